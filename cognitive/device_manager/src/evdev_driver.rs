@@ -35,6 +35,112 @@ struct InputEvent {
 
 // -------------------------------------------------------------------------------------------------
 
+mod ioctl {
+    use libc;
+
+    /// Information about absolute axis as defined in `linux/input.h`.
+    #[repr(C)]
+    pub struct AbsInfo {
+        pub value: libc::int32_t,
+        pub minimum: libc::int32_t,
+        pub maximum: libc::int32_t,
+        pub fuzz: libc::int32_t,
+        pub flat: libc::int32_t,
+        pub resolution: libc::int32_t,
+    }
+
+    // `EVIOCGABS` for `ABS_X`, `ABS_Y`, `ABS_MT_POSITION_X` and `ABS_MT_POSITION_Y`.
+    ioctl!(read get_abs_x with b'E', 0x40 + 0x00; AbsInfo);
+    ioctl!(read get_abs_y with b'E', 0x40 + 0x01; AbsInfo);
+    ioctl!(read get_abs_mt_position_x with b'E', 0x40 + 0x35; AbsInfo);
+    ioctl!(read get_abs_mt_position_y with b'E', 0x40 + 0x36; AbsInfo);
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Change of touch point state accumulated until synchronization event.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum TouchChange {
+    None,
+    Down,
+    Motion,
+    Up,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// State of one multitouch slot.
+#[derive(Clone, Copy, Debug)]
+struct TouchSlot {
+    is_active: bool,
+    x: i32,
+    y: i32,
+    change: TouchChange,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl TouchSlot {
+    /// Constructs new inactive `TouchSlot`.
+    fn new() -> Self {
+        TouchSlot {
+            is_active: false,
+            x: 0,
+            y: 0,
+            change: TouchChange::None,
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Signature of `ioctl` reading information about absolute axis.
+type AbsInfoGetter = unsafe fn(libc::c_int, *mut ioctl::AbsInfo) -> ::nix::Result<libc::c_int>;
+
+/// Range of absolute axis values.
+#[derive(Clone, Copy, Debug)]
+struct AxisRange {
+    minimum: i32,
+    maximum: i32,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl AxisRange {
+    /// Reads range of multitouch axis from device falling back to single-touch axis.
+    fn read(fd: io::RawFd, mt_getter: AbsInfoGetter, getter: AbsInfoGetter) -> Self {
+        let mut info: ioctl::AbsInfo = unsafe { mem::zeroed() };
+        let result = unsafe { mt_getter(fd, &mut info).or_else(|_| getter(fd, &mut info)) };
+        match result {
+            Ok(_) => {
+                AxisRange {
+                    minimum: info.minimum,
+                    maximum: info.maximum,
+                }
+            }
+            Err(err) => {
+                log_warn2!("Failed to read range of touchscreen axis: {:?}", err);
+                AxisRange {
+                    minimum: 0,
+                    maximum: 1,
+                }
+            }
+        }
+    }
+
+    /// Converts given value to fraction of the range.
+    fn normalize(&self, value: i32) -> f32 {
+        let span = self.maximum - self.minimum;
+        if span > 0 {
+            (value - self.minimum) as f32 / span as f32
+        } else {
+            0.0
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
 /// Structure representing evdev input device driver.
 pub struct Evdev {
     fd: io::RawFd,
@@ -42,6 +148,19 @@ pub struct Evdev {
     config: InputConfig,
     gateway: Arc<Mutex<InputForwarding>>,
     pressure: i32,
+
+    /// Currently modified multitouch slot.
+    slot: usize,
+
+    /// States of multitouch slots.
+    slots: Vec<TouchSlot>,
+
+    /// Tells if device reported any multitouch axis. Single-touch axes of such devices duplicate
+    /// the first contact and are ignored.
+    is_multitouch: bool,
+
+    /// Ranges of touchscreen axes used to map positions to output area.
+    ranges: Option<(AxisRange, AxisRange)>,
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -86,12 +205,23 @@ impl Evdev {
            config: InputConfig,
            gateway: Arc<Mutex<InputForwarding>>)
            -> Self {
+        let ranges = if device_kind == DeviceKind::Touchscreen {
+            Some((AxisRange::read(fd, ioctl::get_abs_mt_position_x, ioctl::get_abs_x),
+                  AxisRange::read(fd, ioctl::get_abs_mt_position_y, ioctl::get_abs_y)))
+        } else {
+            None
+        };
+
         Evdev {
             fd: fd,
             device_kind: device_kind,
             config: config,
             gateway: gateway,
             pressure: 0,
+            slot: 0,
+            slots: vec![TouchSlot::new()],
+            is_multitouch: false,
+            ranges: ranges,
         }
     }
 
@@ -105,8 +235,14 @@ impl Evdev {
                     DeviceKind::Keyboard => self.process_keyboard_event(&ev),
                     DeviceKind::Mouse => self.process_mouse_event(&ev),
                     DeviceKind::Touchpad => self.process_touchpad_event(&ev),
+                    DeviceKind::Touchscreen => self.process_touchscreen_event(&ev),
                     DeviceKind::Unknown => panic!("Received event from device of unknown type"),
                 }
+
+                // Any input from user counts as activity.
+                if ev.kind != codes::EV_SYN {
+                    self.gateway.lock().unwrap().emit_user_activity_event();
+                }
             }
             Err(err) => log_warn2!("Error during reading input: {:?}", err),
         }
@@ -193,6 +329,112 @@ impl Evdev {
                        ev.value);
         }
     }
+
+    /// Helper method for processing touchscreen events.
+    ///
+    /// Multitouch events (protocol B) are accumulated per slot and emitted on synchronization.
+    /// Single-touch devices are handled as having only one slot.
+    fn process_touchscreen_event(&mut self, ev: &InputEvent) {
+        if ev.kind == codes::EV_SYN {
+            if ev.code == codes::SYN_REPORT {
+                self.flush_touch_slots();
+            }
+        } else if ev.kind == codes::EV_KEY {
+            if ev.code == codes::BTN_TOUCH {
+                // Multitouch devices report contacts using tracking IDs
+                if self.is_single_touch() {
+                    self.update_touch_slot_activity(ev.value != 0);
+                }
+            } else {
+                log_nyimp!("Unhandled touchscreen key event (code: {}, value: {})",
+                           ev.code,
+                           ev.value);
+            }
+        } else if ev.kind == codes::EV_ABS {
+            if ev.code == codes::ABS_MT_SLOT {
+                self.is_multitouch = true;
+                self.slot = if ev.value > 0 { ev.value as usize } else { 0 };
+                if self.slots.len() <= self.slot {
+                    self.slots.resize(self.slot + 1, TouchSlot::new());
+                }
+            } else if ev.code == codes::ABS_MT_TRACKING_ID {
+                self.is_multitouch = true;
+                self.update_touch_slot_activity(ev.value != -1);
+            } else if ev.code == codes::ABS_MT_POSITION_X {
+                self.is_multitouch = true;
+                self.slots[self.slot].x = ev.value;
+                self.mark_touch_slot_moved();
+            } else if ev.code == codes::ABS_MT_POSITION_Y {
+                self.is_multitouch = true;
+                self.slots[self.slot].y = ev.value;
+                self.mark_touch_slot_moved();
+            } else if (ev.code == codes::ABS_X) && self.is_single_touch() {
+                self.slots[self.slot].x = ev.value;
+                self.mark_touch_slot_moved();
+            } else if (ev.code == codes::ABS_Y) && self.is_single_touch() {
+                self.slots[self.slot].y = ev.value;
+                self.mark_touch_slot_moved();
+            }
+        } else {
+            log_nyimp!("Unhandled touchscreen event (type: {}, code: {}, value: {})",
+                       ev.kind,
+                       ev.code,
+                       ev.value);
+        }
+    }
+
+    /// Checks if device reports only single-touch axes.
+    fn is_single_touch(&self) -> bool {
+        !self.is_multitouch && self.slots.len() == 1
+    }
+
+    /// Marks current touch slot as touched or released.
+    fn update_touch_slot_activity(&mut self, is_active: bool) {
+        let slot = &mut self.slots[self.slot];
+        if is_active && !slot.is_active {
+            slot.change = TouchChange::Down;
+        } else if !is_active && slot.is_active {
+            slot.change = TouchChange::Up;
+        }
+        slot.is_active = is_active;
+    }
+
+    /// Marks current touch slot as moved unless it was just touched or released.
+    fn mark_touch_slot_moved(&mut self) {
+        let slot = &mut self.slots[self.slot];
+        if slot.change == TouchChange::None {
+            slot.change = TouchChange::Motion;
+        }
+    }
+
+    /// Emits events for all touch slots changed since last synchronization.
+    fn flush_touch_slots(&mut self) {
+        let (x_range, y_range) = match self.ranges {
+            Some(ranges) => ranges,
+            None => return,
+        };
+
+        let mut gateway = self.gateway.lock().unwrap();
+        let mut changed = false;
+        for (id, slot) in self.slots.iter_mut().enumerate() {
+            let id = id as i32;
+            let x = x_range.normalize(slot.x);
+            let y = y_range.normalize(slot.y);
+            let change = slot.change;
+            slot.change = TouchChange::None;
+            match change {
+                TouchChange::Down => gateway.emit_touch_down(id, x, y),
+                TouchChange::Motion if slot.is_active => gateway.emit_touch_motion(id, x, y),
+                TouchChange::Up => gateway.emit_touch_up(id),
+                _ => continue,
+            }
+            changed = true;
+        }
+
+        if changed {
+            gateway.emit_touch_frame();
+        }
+    }
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -109,10 +109,35 @@ impl InputForwarding for InputGateway {
         self.forwarder.emit_position_reset();
     }
 
+    /// Emits touch down event.
+    fn emit_touch_down(&mut self, id: i32, x: f32, y: f32) {
+        self.forwarder.emit_touch_down(id, x, y);
+    }
+
+    /// Emits touch motion event.
+    fn emit_touch_motion(&mut self, id: i32, x: f32, y: f32) {
+        self.forwarder.emit_touch_motion(id, x, y);
+    }
+
+    /// Emits touch up event.
+    fn emit_touch_up(&mut self, id: i32) {
+        self.forwarder.emit_touch_up(id);
+    }
+
+    /// Emits touch frame event.
+    fn emit_touch_frame(&mut self) {
+        self.forwarder.emit_touch_frame();
+    }
+
     /// Emits system activity event.
     fn emit_system_activity_event(&mut self) {
         self.forwarder.emit_system_activity_event();
     }
+
+    /// Emits user activity event.
+    fn emit_user_activity_event(&mut self) {
+        self.forwarder.emit_user_activity_event();
+    }
 }
 
 // -------------------------------------------------------------------------------------------------
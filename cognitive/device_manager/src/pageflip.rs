@@ -9,7 +9,7 @@ use std::os::unix::io;
 use libdrm::drm;
 
 use dharma::{EventHandler, EventKind, event_kind};
-use qualia::StatePublishing;
+use qualia::{PresentationTime, StatePublishing};
 
 // -------------------------------------------------------------------------------------------------
 
@@ -43,7 +43,8 @@ impl<P> drm::EventContext for PageFlipContext<P>
 
     #[allow(unused_variables)]
     fn page_flip_handler(&mut self, fd: io::RawFd, sequence: u32, sec: u32, usec: u32, data: i32) {
-        self.state_publisher.emit_page_flip(data);
+        let time = PresentationTime::new(sec as u64, 1000 * usec, sequence as u64);
+        self.state_publisher.emit_page_flip(data, time);
     }
 }
 
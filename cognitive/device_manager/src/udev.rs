@@ -17,6 +17,7 @@ use qualia::DeviceKind;
 
 const INPUT_MOUSE: &'static str = "ID_INPUT_MOUSE";
 const INPUT_TOUCHPAD: &'static str = "ID_INPUT_TOUCHPAD";
+const INPUT_TOUCHSCREEN: &'static str = "ID_INPUT_TOUCHSCREEN";
 const INPUT_KEYBOARD: &'static str = "ID_INPUT_KEYBOARD";
 
 // -------------------------------------------------------------------------------------------------
@@ -111,6 +112,8 @@ pub fn determine_device_kind(device: &libudev::Device) -> DeviceKind {
             return DeviceKind::Mouse;
         } else if property.name() == INPUT_TOUCHPAD {
             return DeviceKind::Touchpad;
+        } else if property.name() == INPUT_TOUCHSCREEN {
+            return DeviceKind::Touchscreen;
         } else if property.name() == INPUT_KEYBOARD {
             return DeviceKind::Keyboard;
         }
@@ -70,4 +70,4 @@ pub use system::{block_signals, unblock_signals, SignalEventHandler};
 /// Timer functionality
 ///
 pub mod timer;
-pub use timer::Timer;
+pub use timer::{Timer, TimerController};
@@ -6,6 +6,7 @@
 // -------------------------------------------------------------------------------------------------
 
 use std::os::unix::io::{RawFd, AsRawFd};
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
 use timerfd::{TimerFd, TimerState};
@@ -22,7 +23,7 @@ pub struct Timer<F>
     callback: F,
 
     /// TimerFd.
-    timer_fd: TimerFd,
+    timer_fd: Arc<Mutex<TimerFd>>,
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -40,12 +41,17 @@ impl<F> Timer<F>
                                });
             Ok(Timer {
                    callback: callback,
-                   timer_fd: timer_fd,
+                   timer_fd: Arc::new(Mutex::new(timer_fd)),
                })
         } else {
             Err(())
         }
     }
+
+    /// Returns controller allowing to rearm the timer after it was added to dispatcher.
+    pub fn get_controller(&self) -> TimerController {
+        TimerController { timer_fd: self.timer_fd.clone() }
+    }
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -54,15 +60,43 @@ impl<F> EventHandler for Timer<F>
     where F: FnMut() -> ()
 {
     fn get_fd(&self) -> RawFd {
-        self.timer_fd.as_raw_fd()
+        self.timer_fd.lock().unwrap().as_raw_fd()
     }
 
     fn process_event(&mut self, event_kind: EventKind) {
         if event_kind.intersects(event_kind::READ) {
-            self.timer_fd.read();
-            (self.callback)();
+            // Timer could have been disarmed after it expired.
+            let expirations = self.timer_fd.lock().unwrap().read();
+            if expirations > 0 {
+                (self.callback)();
+            }
         }
     }
 }
 
 // -------------------------------------------------------------------------------------------------
+
+/// Helper structure for changing state of `Timer` from other places.
+#[derive(Clone)]
+pub struct TimerController {
+    timer_fd: Arc<Mutex<TimerFd>>,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl TimerController {
+    /// Makes the timer expire first after `delay` and then periodically with given `interval`.
+    pub fn arm(&self, delay: Duration, interval: Duration) {
+        self.timer_fd.lock().unwrap().set_state(TimerState::Periodic {
+                                                    current: delay,
+                                                    interval: interval,
+                                                });
+    }
+
+    /// Stops the timer.
+    pub fn disarm(&self) {
+        self.timer_fd.lock().unwrap().set_state(TimerState::Disarmed);
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
@@ -7,14 +7,16 @@
 // -------------------------------------------------------------------------------------------------
 
 use std;
+use std::collections::HashMap;
 
 use timber;
 use qualia::{Action, Area, Command, Direction, Position, Size, Vector};
 use qualia::{SurfaceId, CompositorConfig, ExhibitorCoordinationTrait};
+use qualia::{SurfaceAccess, resize_edge, surface_state};
 
 use surface_history::SurfaceHistory;
-use frames::{Frame, Geometry, Side};
-use frames::{Converting, Searching, Settling};
+use frames::{Frame, Geometry, Mobility, Mode, Side};
+use frames::{Converting, Packing, Searching, Settling};
 
 use strategist::Strategist;
 
@@ -64,6 +66,80 @@ impl std::fmt::Display for CommandResult {
 
 // -------------------------------------------------------------------------------------------------
 
+/// Wrapper for `SurfaceAccess` adding extra state flags when reconfiguring given surface, e.g.
+/// `RESIZING` for surface being interactively resized or `FULLSCREEN` for fullscreen surface.
+struct StatefulSurfaceAccess<'a> {
+    sid: SurfaceId,
+    state_flags: surface_state::SurfaceState,
+    sa: &'a mut SurfaceAccess,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl<'a> SurfaceAccess for StatefulSurfaceAccess<'a> {
+    fn reconfigure(&mut self,
+                   sid: SurfaceId,
+                   size: Size,
+                   mut state_flags: surface_state::SurfaceState) {
+        if sid == self.sid {
+            state_flags.insert(self.state_flags);
+        }
+        self.sa.reconfigure(sid, size, state_flags);
+    }
+
+    fn take_surfaces_damage(&mut self) -> HashMap<SurfaceId, Vec<Area>> {
+        self.sa.take_surfaces_damage()
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Describes how frame was lifted out of the frame layout.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Lift {
+    /// Frame covers whole workspace.
+    Maximized,
+
+    /// Frame covers whole display (optionally display with given ID) including docks.
+    Fullscreen(Option<i32>),
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Information needed to restore frame lifted out of the frame layout.
+struct LiftedFrame {
+    /// Kind of lift.
+    lift: Lift,
+
+    /// Mobility of the frame before lifting.
+    mobility: Mobility,
+
+    /// Area of the frame before lifting.
+    area: Area,
+
+    /// Title of workspace containing the frame before lifting.
+    workspace: Option<String>,
+
+    /// Surface ID of anchored neighbour and side on which frame should be restored relatively to
+    /// it.
+    neighbour: Option<(Side, SurfaceId)>,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl Lift {
+    /// Checks if lifts are of the same kind regardless of their parameters.
+    fn is_like(&self, other: Lift) -> bool {
+        match (*self, other) {
+            (Lift::Maximized, Lift::Maximized) => true,
+            (Lift::Fullscreen(_), Lift::Fullscreen(_)) => true,
+            _ => false,
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
 /// Compositor main structure.
 pub struct Compositor<C>
     where C: ExhibitorCoordinationTrait
@@ -71,9 +147,12 @@ pub struct Compositor<C>
     history: SurfaceHistory,
     coordinator: C,
     root: Frame,
+    stash: Frame,
     selection: Frame,
     strategist: Strategist,
     config: CompositorConfig,
+    lifted: HashMap<SurfaceId, LiftedFrame>,
+    pending_lifts: HashMap<SurfaceId, Lift>,
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -89,9 +168,12 @@ impl<C> Compositor<C>
             history: SurfaceHistory::new(),
             coordinator: coordinator,
             root: root.clone(),
+            stash: Frame::new_stash(),
             selection: root,
             strategist: strategist,
             config: config,
+            lifted: HashMap::new(),
+            pending_lifts: HashMap::new(),
         }
     }
 
@@ -155,6 +237,10 @@ impl<C> Compositor<C>
             Action::Resize => self.resize_frame(&mut frame, command.direction, command.magnitude),
             Action::Move => self.move_frame(&mut frame, command.direction, command.magnitude),
             Action::Anchor => self.anchorize(frame),
+            Action::Maximize => self.toggle_lift(frame, Lift::Maximized),
+            Action::Fullscreen => self.toggle_lift(frame, Lift::Fullscreen(None)),
+            Action::Minimize => self.minimize(frame),
+            Action::Restore => self.restore(),
             _ => CommandResult::NotHandled,
         };
 
@@ -187,7 +273,12 @@ impl<C> Compositor<C>
             let mut frame = Frame::new_leaf(sid, decision.geometry);
             frame.settle(&mut decision.target, area, &mut self.coordinator);
             if decision.selection {
-                self.select(frame);
+                self.select(frame.clone());
+            }
+
+            // Apply maximization or fullscreen requested before the surface was ready
+            if let Some(lift) = self.pending_lifts.remove(&sid) {
+                self.lift(frame, lift);
             }
 
             // Finalize
@@ -218,12 +309,15 @@ impl<C> Compositor<C>
 
     /// Handles destruction of surface. Removes it from history and frame free.
     pub fn unmanage_surface(&mut self, sid: SurfaceId) {
-        if let Some(ref mut frame) = self.root.find_with_sid(sid) {
+        self.lifted.remove(&sid);
+        self.pending_lifts.remove(&sid);
+        let frame = self.root.find_with_sid(sid).or_else(|| self.stash.find_with_sid(sid));
+        if let Some(mut frame) = frame {
             self.history.remove(sid);
             if self.selection.get_sid() == sid {
                 let new_selection = {
-                    if let Some(previous_sid) = self.history.get_nth(0) {
-                        self.root.find_with_sid(previous_sid).expect("Find previous frame")
+                    if let Some(previous) = self.find_most_recent_in_layout() {
+                        previous
                     } else {
                         self.selection.find_buildable().expect("Find buildable")
                     }
@@ -253,6 +347,14 @@ impl<C> Compositor<C>
         }
     }
 
+    /// Gives keyboard focus back to selected surface, e.g. after surface outside of frame layout
+    /// lost it.
+    pub fn restore_keyboard_focus(&mut self) {
+        if self.selection.get_sid().is_valid() {
+            self.coordinator.set_keyboard_focus(self.selection.get_sid());
+        }
+    }
+
     /// Moves given surface by given vector. Only floating surfaces are moved. This movement is
     /// associated with interaction with user and `pin_point` describes point on surface by which
     /// the surface is dragged. If `pin_point` changes display the surface frame is resettled to
@@ -283,6 +385,108 @@ impl<C> Compositor<C>
         }
     }
 
+    /// Resizes given surface by moving given edges by given vector. This resizing is associated
+    /// with interaction with user. Floating frames simply change their size while anchored frames
+    /// are resized in frame layout at the expense of their neighbours.
+    pub fn resize_globally(&mut self,
+                           sid: SurfaceId,
+                           edges: resize_edge::ResizeEdge,
+                           vector: Vector) {
+        if let Some(mut frame) = self.root.find_with_sid(sid) {
+            let mut sa = StatefulSurfaceAccess {
+                sid: sid,
+                state_flags: surface_state::RESIZING,
+                sa: &mut self.coordinator,
+            };
+
+            match frame.get_mobility() {
+                Mobility::Floating => {
+                    let mut resize_vector = Vector::default();
+                    if edges.contains(resize_edge::LEFT) {
+                        resize_vector.x = -vector.x;
+                    } else if edges.contains(resize_edge::RIGHT) {
+                        resize_vector.x = vector.x;
+                    }
+                    if edges.contains(resize_edge::TOP) {
+                        resize_vector.y = -vector.y;
+                    } else if edges.contains(resize_edge::BOTTOM) {
+                        resize_vector.y = vector.y;
+                    }
+
+                    // Size may be clamped so move by the actual change to keep opposite edges
+                    // in place.
+                    let old_size = frame.get_size();
+                    frame.change_size(resize_vector, &mut sa);
+                    let new_size = frame.get_size();
+                    let mut move_vector = Vector::default();
+                    if edges.contains(resize_edge::LEFT) {
+                        move_vector.x = old_size.width as isize - new_size.width as isize;
+                    }
+                    if edges.contains(resize_edge::TOP) {
+                        move_vector.y = old_size.height as isize - new_size.height as isize;
+                    }
+                    frame.move_with_contents(move_vector);
+                }
+                Mobility::Anchored => {
+                    if vector.x != 0 {
+                        if edges.contains(resize_edge::LEFT) {
+                            frame.resize(Direction::West, -vector.x, &mut sa);
+                        } else if edges.contains(resize_edge::RIGHT) {
+                            frame.resize(Direction::East, vector.x, &mut sa);
+                        }
+                    }
+                    if vector.y != 0 {
+                        if edges.contains(resize_edge::TOP) {
+                            frame.resize(Direction::North, -vector.y, &mut sa);
+                        } else if edges.contains(resize_edge::BOTTOM) {
+                            frame.resize(Direction::South, vector.y, &mut sa);
+                        }
+                    }
+                }
+                Mobility::Docked => {}
+            }
+        }
+    }
+
+    /// Informs the surface that interactive resize has ended.
+    pub fn finish_resizing(&mut self, sid: SurfaceId) {
+        let surface = try_get_surface!(self, sid);
+        let mut state_flags = surface.state_flags;
+        state_flags.remove(surface_state::RESIZING);
+        self.coordinator.reconfigure(sid, surface.desired_size, state_flags);
+        self.log_frames();
+    }
+
+    /// Hides given surface in stash removing it from frame layout.
+    pub fn minimize_surface(&mut self, sid: SurfaceId) {
+        if let Some(frame) = self.root.find_with_sid(sid) {
+            self.minimize(frame);
+            self.coordinator.notify();
+            self.log_frames();
+        }
+    }
+
+    /// Lifts given surface out of the frame layout to cover whole workspace.
+    pub fn maximize_surface(&mut self, sid: SurfaceId) {
+        self.lift_surface(sid, Lift::Maximized);
+    }
+
+    /// Brings maximized surface back to its place in frame layout.
+    pub fn unmaximize_surface(&mut self, sid: SurfaceId) {
+        self.unlift_surface(sid, Lift::Maximized);
+    }
+
+    /// Lifts given surface out of the frame layout to cover whole display (including docks). If
+    /// display ID is not given the display the surface is currently placed on is used.
+    pub fn fullscreen_surface(&mut self, sid: SurfaceId, display_id: Option<i32>) {
+        self.lift_surface(sid, Lift::Fullscreen(display_id));
+    }
+
+    /// Brings fullscreen surface back to its place in frame layout.
+    pub fn unfullscreen_surface(&mut self, sid: SurfaceId) {
+        self.unlift_surface(sid, Lift::Fullscreen(None));
+    }
+
     /// Returns root frame.
     pub fn get_root(&self) -> Frame {
         self.root.clone()
@@ -540,6 +744,261 @@ impl<C> Compositor<C>
             frame.resettle(&mut target, None, &mut self.coordinator);
         }
     }
+
+    /// Hides given frame in stash. If the frame was selected, most recently used frame from the
+    /// same workspace becomes selected.
+    fn minimize(&mut self, mut frame: Frame) -> CommandResult {
+        if !frame.get_sid().is_valid() || frame.get_mobility().is_docked() {
+            return CommandResult::WrongFrame;
+        }
+
+        let workspace = frame.find_top().expect("minimized frame must have workspace");
+        frame.stash(&mut self.stash, &mut self.coordinator);
+        if self.selection.equals_exact(&frame) {
+            let most_recent = self.find_most_recent(workspace);
+            self.select(most_recent);
+        }
+        CommandResult::Ok
+    }
+
+    /// Brings back most recently used frame from stash and settles it in current workspace.
+    fn restore(&mut self) -> CommandResult {
+        let mut stashed = None;
+        for sid in self.history.iter() {
+            if let Some(frame) = self.stash.find_with_sid(sid) {
+                stashed = Some(frame);
+                break;
+            }
+        }
+
+        if let Some(mut frame) = stashed {
+            let mut workspace = self.find_current_workspace();
+            frame.unstash(&mut workspace, &mut self.coordinator);
+            self.pop_surface(frame.get_sid());
+        }
+        CommandResult::Ok
+    }
+
+    /// Handles maximization and fullscreen commands. Lifts the frame if it is not lifted the same
+    /// way yet, brings it back to frame layout otherwise.
+    fn toggle_lift(&mut self, frame: Frame, lift: Lift) -> CommandResult {
+        let sid = frame.get_sid();
+        if !sid.is_valid() {
+            return CommandResult::WrongFrame;
+        }
+
+        let is_lifted = self.lifted.get(&sid).map_or(false, |info| info.lift.is_like(lift));
+        if is_lifted {
+            self.unlift(frame, lift);
+        } else {
+            self.lift(frame, lift);
+        }
+        CommandResult::Ok
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Private methods related to maximization and fullscreen.
+impl<C> Compositor<C>
+    where C: ExhibitorCoordinationTrait
+{
+    /// Lifts surface with given ID or remembers the request if the surface is not managed yet.
+    fn lift_surface(&mut self, sid: SurfaceId, lift: Lift) {
+        if let Some(frame) = self.root.find_with_sid(sid) {
+            self.lift(frame, lift);
+            self.coordinator.notify();
+            self.log_frames();
+        } else {
+            self.pending_lifts.insert(sid, lift);
+        }
+    }
+
+    /// Brings back surface with given ID if it was lifted with given kind of lift.
+    fn unlift_surface(&mut self, sid: SurfaceId, lift: Lift) {
+        if self.pending_lifts.get(&sid).map_or(false, |pending| pending.is_like(lift)) {
+            self.pending_lifts.remove(&sid);
+        }
+
+        let is_lifted = self.lifted.get(&sid).map_or(false, |info| info.lift.is_like(lift));
+        if is_lifted {
+            if let Some(frame) = self.root.find_with_sid(sid) {
+                self.unlift(frame, lift);
+                self.coordinator.notify();
+                self.log_frames();
+            }
+        }
+    }
+
+    /// Lifts the frame out of the frame layout making it floating directly in workspace and
+    /// covering whole workspace or display. Information needed to bring the frame back is stored
+    /// only when the frame is lifted for the first time.
+    fn lift(&mut self, mut frame: Frame, lift: Lift) {
+        let sid = frame.get_sid();
+        if !sid.is_valid() || frame.get_mobility().is_docked() {
+            return;
+        }
+
+        // Choose target workspace
+        let mut workspace = {
+            let chosen = if let Lift::Fullscreen(Some(display_id)) = lift {
+                self.find_active_workspace_on_display(display_id)
+            } else {
+                None
+            };
+            match chosen.or_else(|| frame.find_top()) {
+                Some(workspace) => workspace,
+                None => return,
+            }
+        };
+
+        // Remember how to restore the frame
+        if let Some(info) = self.lifted.get_mut(&sid) {
+            info.lift = lift;
+        }
+        if !self.lifted.contains_key(&sid) {
+            let info = LiftedFrame {
+                lift: lift,
+                mobility: frame.get_mobility(),
+                area: frame.get_area(),
+                workspace: frame.find_top().map(|workspace| workspace.get_title()),
+                neighbour: Self::find_anchored_neighbour(&frame),
+            };
+            self.lifted.insert(sid, info);
+        }
+
+        // Choose area to cover
+        let area = match lift {
+            Lift::Maximized => Area::new(Position::default(), workspace.get_size()),
+            Lift::Fullscreen(_) => {
+                let display = Self::find_display(&workspace).expect("workspace must be on display");
+                let position = display.calculate_global_position() -
+                               workspace.calculate_global_position();
+                Area::new(position, display.get_size())
+            }
+        };
+        let state_flags = match lift {
+            Lift::Maximized => surface_state::MAXIMIZED,
+            Lift::Fullscreen(_) => surface_state::FULLSCREEN,
+        };
+
+        // Place the frame directly in workspace
+        let parent = frame.get_parent().expect("lifted frame must have parent");
+        if frame.get_mobility().is_anchored() || !parent.equals_exact(&workspace) {
+            frame.remove_self(&mut self.coordinator);
+            workspace.prepend(&mut frame);
+        }
+        frame.set_plumbing_mobility(Mobility::Floating);
+
+        {
+            let mut sa = StatefulSurfaceAccess {
+                sid: sid,
+                state_flags: state_flags,
+                sa: &mut self.coordinator,
+            };
+            frame.set_size(area.size, &mut sa);
+        }
+        frame.set_plumbing_position(area.pos);
+        self.select(frame);
+    }
+
+    /// Brings the frame back to the place it occupied before lifting.
+    fn unlift(&mut self, mut frame: Frame, lift: Lift) {
+        let sid = frame.get_sid();
+        let info = match self.lifted.remove(&sid) {
+            Some(info) => info,
+            None => return,
+        };
+
+        if !info.lift.is_like(lift) {
+            self.lifted.insert(sid, info);
+            return;
+        }
+
+        // Restore into the workspace the frame was lifted from if it still exists
+        let mut workspace = info.workspace
+            .as_ref()
+            .and_then(|title| self.find_workspace(title))
+            .or_else(|| frame.find_top())
+            .expect("lifted frame must have workspace");
+
+        match info.mobility {
+            Mobility::Anchored => {
+                let neighbour = info.neighbour.and_then(|(side, neighbour_sid)| {
+                    self.root
+                        .find_with_sid(neighbour_sid)
+                        .and_then(|neighbour| if neighbour.get_mobility().is_anchored() {
+                                      Some((side, neighbour))
+                                  } else {
+                                      None
+                                  })
+                });
+
+                frame.remove_self(&mut self.coordinator);
+                if let Some((side, mut neighbour)) = neighbour {
+                    frame.set_plumbing_mobility(Mobility::Anchored);
+                    frame.jumpin(side, &mut neighbour, &mut self.coordinator);
+                } else {
+                    frame.settle(&mut workspace, None, &mut self.coordinator);
+                }
+            }
+            Mobility::Floating | Mobility::Docked => {
+                let is_in_workspace = frame.get_parent()
+                    .map_or(false, |parent| parent.equals_exact(&workspace));
+                if !is_in_workspace {
+                    frame.remove_self(&mut self.coordinator);
+                    workspace.prepend(&mut frame);
+                }
+                frame.set_size(info.area.size, &mut self.coordinator);
+                frame.set_plumbing_position(info.area.pos);
+            }
+        }
+        self.select(frame);
+    }
+
+    /// Finds nearest leaf sibling of the frame anchored in the same container. Returns side on
+    /// which the frame lies relatively to the sibling.
+    fn find_anchored_neighbour(frame: &Frame) -> Option<(Side, SurfaceId)> {
+        if !frame.get_mobility().is_anchored() {
+            return None;
+        }
+
+        let is_anchored_leaf = |frame: &Frame| {
+            frame.get_mobility().is_anchored() && frame.get_sid().is_valid()
+        };
+
+        if let Some(prev) = frame.get_prev_space() {
+            if is_anchored_leaf(&prev) {
+                return Some((Side::After, prev.get_sid()));
+            }
+        }
+        if let Some(next) = frame.get_next_space() {
+            if is_anchored_leaf(&next) {
+                return Some((Side::Before, next.get_sid()));
+            }
+        }
+        None
+    }
+
+    /// Finds display containing given frame.
+    fn find_display(frame: &Frame) -> Option<Frame> {
+        let mut current = Some(frame.clone());
+        while let Some(frame) = current {
+            if frame.get_mode().is_display() {
+                return Some(frame);
+            }
+            current = frame.get_parent();
+        }
+        None
+    }
+
+    /// Finds active workspace on display with given ID.
+    fn find_active_workspace_on_display(&self, display_id: i32) -> Option<Frame> {
+        let display_matcher = |frame: &Frame| frame.get_mode() == Mode::Display { id: display_id };
+        let workspace_matcher =
+            |frame: &Frame| frame.get_mode() == Mode::Workspace { is_active: true };
+        self.root.find(&display_matcher).and_then(|display| display.find(&workspace_matcher))
+    }
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -565,6 +1024,16 @@ impl<C> Compositor<C>
         }
         reference
     }
+
+    /// Finds most recently focused frame placed in frame layout. Stashed frames are skipped.
+    fn find_most_recent_in_layout(&self) -> Option<Frame> {
+        for sid in self.history.iter() {
+            if let Some(frame) = self.root.find_with_sid(sid) {
+                return Some(frame);
+            }
+        }
+        None
+    }
 }
 
 // -------------------------------------------------------------------------------------------------
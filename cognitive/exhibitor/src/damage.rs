@@ -0,0 +1,148 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! This module contains logic for tracking damaged areas of display in order to repaint only parts
+//! of the screen which changed.
+
+// -------------------------------------------------------------------------------------------------
+
+use std;
+use std::collections::VecDeque;
+
+use qualia::{Area, DecorationContext, SurfaceId};
+
+// -------------------------------------------------------------------------------------------------
+
+/// Number of drawn frames for which damage is remembered. Buffers older than that will be redrawn
+/// entirely.
+const MAX_HISTORY_LENGTH: usize = 3;
+
+// -------------------------------------------------------------------------------------------------
+
+/// Surface drawn in frame together with area it covered.
+pub type SceneEntry = (SurfaceId, Area);
+
+// -------------------------------------------------------------------------------------------------
+
+/// Tracks damage of display between frames.
+///
+/// All areas are in logical coordinates relative to the display.
+pub struct Damage {
+    /// Area of the whole display.
+    bounds: Area,
+
+    /// Damage accumulated since last frame was drawn.
+    current: Vec<Area>,
+
+    /// Damage of recently drawn frames starting from the most recent one.
+    history: VecDeque<Vec<Area>>,
+
+    /// Surfaces drawn in last frame in drawing order.
+    scene: Vec<SceneEntry>,
+
+    /// Decorations drawn in last frame.
+    decorations: Vec<DecorationContext>,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl Damage {
+    /// Constructs new `Damage`.
+    pub fn new() -> Self {
+        Damage {
+            bounds: Area::default(),
+            current: Vec::new(),
+            history: VecDeque::with_capacity(MAX_HISTORY_LENGTH),
+            scene: Vec::new(),
+            decorations: Vec::new(),
+        }
+    }
+
+    /// Sets area of the display. If it changed whole display is damaged.
+    pub fn set_bounds(&mut self, bounds: Area) {
+        if self.bounds != bounds {
+            self.bounds = bounds;
+            self.reset();
+        }
+    }
+
+    /// Forgets history and damages whole display.
+    pub fn reset(&mut self) {
+        self.history.clear();
+        self.current = vec![self.bounds];
+    }
+
+    /// Adds given area to damage. Parts of the area outside the display are ignored.
+    pub fn add(&mut self, area: Area) {
+        if let Some(area) = area.intersection(&self.bounds) {
+            self.current.push(area);
+        }
+    }
+
+    /// Compares given scene with the one from previous frame. Areas of surfaces which appeared,
+    /// disappeared, were moved, resized or changed stacking order are damaged.
+    pub fn update_scene(&mut self, scene: Vec<SceneEntry>) {
+        let old_scene = std::mem::replace(&mut self.scene, scene);
+        for i in 0..std::cmp::max(old_scene.len(), self.scene.len()) {
+            let old_entry = old_scene.get(i).cloned();
+            let new_entry = self.scene.get(i).cloned();
+            if old_entry != new_entry {
+                if let Some((_, area)) = old_entry {
+                    self.add(area);
+                }
+                if let Some((_, area)) = new_entry {
+                    self.add(area);
+                }
+            }
+        }
+    }
+
+    /// Compares given decorations with the ones from previous frame. Areas of decorations which
+    /// appeared, disappeared, were moved, resized or changed title or focus are damaged.
+    pub fn update_decorations(&mut self, decorations: Vec<DecorationContext>) {
+        let old_decorations = std::mem::replace(&mut self.decorations, decorations);
+        let mut damage = Vec::new();
+        for decoration in old_decorations.iter() {
+            if !self.decorations.contains(decoration) {
+                damage.push(decoration.area);
+            }
+        }
+        for decoration in self.decorations.iter() {
+            if !old_decorations.contains(decoration) && !damage.contains(&decoration.area) {
+                damage.push(decoration.area);
+            }
+        }
+        for area in damage {
+            self.add(area);
+        }
+    }
+
+    /// Checks if anything was damaged since last frame was drawn.
+    pub fn is_empty(&self) -> bool {
+        self.current.is_empty()
+    }
+
+    /// Returns areas which have to be repainted in buffer of given age in order to bring it up to
+    /// date. Age 0 means contents of the buffer are undefined.
+    pub fn get_repaint_region(&self, buffer_age: u32) -> Vec<Area> {
+        let age = buffer_age as usize;
+        if (age == 0) || (age > self.history.len() + 1) {
+            return vec![self.bounds];
+        }
+
+        let mut region = self.current.clone();
+        for damage in self.history.iter().take(age - 1) {
+            region.extend(damage.iter().cloned());
+        }
+        region
+    }
+
+    /// Moves current damage to history. Should be called after the frame was drawn.
+    pub fn on_frame_drawn(&mut self) {
+        let damage = std::mem::replace(&mut self.current, Vec::new());
+        self.history.push_front(damage);
+        self.history.truncate(MAX_HISTORY_LENGTH);
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
@@ -7,14 +7,19 @@
 
 use std::rc::Rc;
 use std::cell::RefCell;
+use std::collections::HashMap;
 use std::time::Instant;
 
 use qualia::{Buffer, Illusion, Milliseconds, OutputInfo, perceptron, Perceptron, Position};
-use qualia::{ExhibitorCoordinationTrait, SurfaceContext, SurfaceId};
+use qualia::{Layer, LayerProperties, PresentationTime, surface_state};
+use qualia::{Area, ExhibitorCoordinationTrait, SurfaceContext, SurfaceId, SurfaceInfo};
+use qualia::{DecorationContext, Size};
 
-use frames::{Frame, Converting};
+use frames::{Frame, Converting, Mode, Packing, Searching};
 use outputs::Output;
 
+use damage::{Damage, SceneEntry};
+use layers::Layers;
 use pointer::Pointer;
 
 // -------------------------------------------------------------------------------------------------
@@ -28,6 +33,10 @@ pub struct Display<C>
     pointer: Rc<RefCell<Pointer<C>>>,
     output: Box<Output>,
     frame: Frame,
+    layers: Layers,
+    damage: Damage,
+    presented_sids: Vec<SurfaceId>,
+    global_positions: HashMap<SurfaceId, Position>,
     redraw_needed: bool,
     page_flip_scheduled: bool,
     background_sid: SurfaceId,
@@ -51,6 +60,10 @@ impl<C> Display<C>
             pointer: pointer,
             output: output,
             frame: frame,
+            layers: Layers::new(),
+            damage: Damage::new(),
+            presented_sids: Vec::new(),
+            global_positions: HashMap::new(),
             redraw_needed: true,
             page_flip_scheduled: false,
             background_sid: SurfaceId::invalid(),
@@ -83,19 +96,28 @@ impl<C> Display<C>
         Ok(())
     }
 
-    /// Handle page flip: redraw everything.
-    pub fn on_pageflip(&mut self) {
+    /// Handle page flip: notify about presentation of surfaces drawn in last frame and redraw
+    /// everything.
+    pub fn on_pageflip(&mut self, time: PresentationTime) {
         self.page_flip_scheduled = false;
+        let display_id = self.output.get_info().id;
+        for sid in self.presented_sids.drain(..) {
+            let presented = Perceptron::SurfacePresented(sid, display_id, time);
+            self.coordinator.emit(perceptron::SURFACE_PRESENTED, presented);
+        }
+
         if self.redraw_needed {
             self.redraw_all();
         }
     }
 
-    /// Handle notification about needed redraw.
+    /// Handle notification about needed redraw. `damage` contains areas of surfaces (in surface
+    /// coordinates) changed since last notification.
     ///
     /// This will cause display redraw. If page flip is already scheduled, display will be redraw
     /// again after page flip.
-    pub fn on_notify(&mut self) {
+    pub fn on_notify(&mut self, damage: &HashMap<SurfaceId, Vec<Area>>) {
+        self.add_surfaces_damage(damage);
         if !self.redraw_needed {
             if !self.page_flip_scheduled {
                 self.redraw_all();
@@ -109,9 +131,20 @@ impl<C> Display<C>
     pub fn on_wakeup(&mut self) {
         let output = self.output.recreate();
         match output {
-            Ok(output) => {
+            Ok(mut output) => {
+                let old_info = self.output.get_info();
+                output.set_position(old_info.area.pos);
+                output.set_scale(old_info.scale);
+                output.set_transform(old_info.transform);
                 self.output = output;
+                self.damage.reset();
+                self.arrange_layers();
                 self.redraw_all();
+
+                // Output may have been changed while sleeping.
+                let info = self.output.get_info();
+                self.coordinator.emit(perceptron::DISPLAY_UPDATED,
+                                      Perceptron::DisplayUpdated(info));
             }
             Err(err) => {
                 log_error!("Failed to reset output after wakeup: {:?}", err);
@@ -119,10 +152,71 @@ impl<C> Display<C>
         }
     }
 
-    /// Prepare rendering context for layover.
+    /// Places given surface in layer or updates its properties. Layer surfaces are rearranged and
+    /// frames resized to avoid their exclusive zones.
+    pub fn configure_layer_surface(&mut self, sid: SurfaceId, properties: LayerProperties) {
+        let is_new = self.layers.configure(sid, properties);
+        let desired_size = self.coordinator.get_surface(sid).map(|info| info.desired_size);
+        self.arrange_layers();
+
+        // Client waits for configuration after placing surface in layer even if its size did not
+        // change.
+        if is_new && (desired_size == self.coordinator.get_surface(sid).map(|i| i.desired_size)) {
+            self.coordinator.emit(perceptron::SURFACE_RECONFIGURED,
+                                  Perceptron::SurfaceReconfigured(sid));
+        }
+    }
+
+    /// Marks given layer surface as ready to be drawn. Returns its properties or `None` if the
+    /// surface is not placed in layers on this display.
+    pub fn map_layer_surface(&mut self, sid: SurfaceId) -> Option<LayerProperties> {
+        let properties = self.layers.map(sid);
+        if properties.is_some() {
+            self.arrange_layers();
+        }
+        properties
+    }
+
+    /// Removes given surface from layers. Returns `true` if the surface was placed in layers on
+    /// this display.
+    pub fn remove_layer_surface(&mut self, sid: SurfaceId) -> bool {
+        let removed = self.layers.remove(sid);
+        if removed {
+            self.arrange_layers();
+        }
+        removed
+    }
+
+    /// Returns properties of given layer surface or `None` if the surface is not placed in layers
+    /// on this display.
+    pub fn get_layer_properties(&self, sid: SurfaceId) -> Option<LayerProperties> {
+        self.layers.get_properties(sid)
+    }
+
+    /// Finds topmost surface at given position relative to the display. Returns its ID and the
+    /// position relative to the surface.
+    pub fn find_surface(&self, position: Position) -> Option<(SurfaceId, Position)> {
+        for context in self.prepare_surfaces_context().iter().rev() {
+            if let Some(info) = self.coordinator.get_surface(context.id) {
+                let surface_position = position - context.pos + info.offset;
+                if info.accepts_input_at(&surface_position) {
+                    return Some((context.id, surface_position));
+                }
+            }
+        }
+        None
+    }
+
+    /// Prepare rendering context for layover. Drag icon is drawn below the cursor.
     pub fn prepare_layover_context(&self, display_position: Position) -> Vec<SurfaceContext> {
-        vec![SurfaceContext::new(self.pointer.borrow().get_cursor_sid(),
-                                 self.pointer.borrow().get_global_position() - display_position)]
+        let pointer = self.pointer.borrow();
+        let position = pointer.get_global_position() - display_position;
+        let mut layover = Vec::new();
+        if pointer.get_drag_icon_sid().is_valid() {
+            layover.push(SurfaceContext::new(pointer.get_drag_icon_sid(), position));
+        }
+        layover.push(SurfaceContext::new(pointer.get_cursor_sid(), position));
+        layover
     }
 
     /// Prepare rendering context for layunder.
@@ -134,31 +228,205 @@ impl<C> Display<C>
         }
     }
 
-    /// Draw the scene and then schedule page flip.
-    ///
-    /// TODO: Benchmark drawing.
+    /// Prepare rendering context for frames and layer surfaces in drawing order. Background and
+    /// bottom layers are placed below frames, top and overlay layers above them. If active
+    /// workspace has fullscreen surface frames are placed above top layer.
+    fn prepare_surfaces_context(&self) -> Vec<SurfaceContext> {
+        let (lower_layers, upper_layers) = if self.has_fullscreen_surface() {
+            (vec![Layer::Background, Layer::Bottom, Layer::Top], vec![Layer::Overlay])
+        } else {
+            (vec![Layer::Background, Layer::Bottom], vec![Layer::Top, Layer::Overlay])
+        };
+
+        let mut surfaces = self.layers.prepare_context(&lower_layers, &self.coordinator);
+        let mut frames = self.frame.to_array(Position::default(), &self.coordinator);
+        frames.reverse();
+        surfaces.append(&mut frames);
+        surfaces.append(&mut self.layers.prepare_context(&upper_layers, &self.coordinator));
+        surfaces
+    }
+
+    /// Checks if any surface placed directly in active workspace of this display is fullscreen.
+    fn has_fullscreen_surface(&self) -> bool {
+        let matcher = |frame: &Frame| frame.get_mode() == Mode::Workspace { is_active: true };
+        if let Some(workspace) = self.frame.find(&matcher) {
+            for frame in workspace.time_iter() {
+                if let Some(info) = self.coordinator.get_surface(frame.get_sid()) {
+                    if info.state_flags.contains(surface_state::FULLSCREEN) {
+                        return true;
+                    }
+                }
+            }
+        }
+        false
+    }
+
+    /// Calculates areas of layer surfaces, reconfigures them and resizes frames to avoid exclusive
+    /// zones.
+    fn arrange_layers(&mut self) {
+        self.layers.arrange(self.output.get_info().area.size);
+        for (sid, area) in self.layers.get_areas() {
+            self.coordinator.reconfigure(sid, area.size, surface_state::REGULAR);
+        }
+        self.frame.set_margins(self.layers.get_margins(), &mut self.coordinator);
+        self.coordinator.notify();
+    }
+
+    /// Calculates area covered by surface drawn in given context.
+    fn calculate_surface_area(context: &SurfaceContext, info: &SurfaceInfo) -> Area {
+        // Surfaces without buffer are not drawn, but it is safer to assume they cover the size
+        // they requested.
+        let size = info.get_contents_size().unwrap_or(info.requested_size);
+        Area::new(context.pos - info.offset, size)
+    }
+
+    /// Prepare list of decorations of surfaces from `contexts` which are decorated by compositor.
+    /// Decorations surround the area covered by the surface.
+    fn prepare_decorations(&self, contexts: &Vec<SurfaceContext>) -> Vec<DecorationContext> {
+        let focused_sid = self.coordinator.get_keyboard_focused_sid();
+        let mut decorations = Vec::new();
+        for context in contexts.iter() {
+            if let Some(info) = self.coordinator.get_surface(context.id) {
+                if info.has_decorations() {
+                    let margins = info.get_decoration_margins();
+                    let area = Self::calculate_surface_area(context, &info);
+                    let pos = area.pos - Position::new(margins.left as isize, margins.top as isize);
+                    let size = Size::new(area.size.width + margins.left + margins.right,
+                                         area.size.height + margins.top + margins.bottom);
+                    decorations.push(DecorationContext::new(context.id,
+                                                            Area::new(pos, size),
+                                                            info.title.clone(),
+                                                            context.id == focused_sid));
+                }
+            }
+        }
+        decorations
+    }
+
+    /// Prepare list of drawn surfaces with areas they cover, in drawing order.
+    fn prepare_scene(&self, layers: &[&Vec<SurfaceContext>]) -> Vec<SceneEntry> {
+        let mut scene = Vec::new();
+        for contexts in layers.iter() {
+            for context in contexts.iter() {
+                if let Some(info) = self.coordinator.get_surface(context.id) {
+                    scene.push((context.id, Self::calculate_surface_area(context, &info)));
+                }
+            }
+        }
+        scene
+    }
+
+    /// Returns contexts of surfaces which are not entirely covered by `opaque` areas. `contexts`
+    /// are expected to be in drawing order. Opaque regions of visible surfaces are added to
+    /// `opaque` so the method should be called for layers from the topmost one.
+    fn cull_occluded(&self,
+                     contexts: &Vec<SurfaceContext>,
+                     opaque: &mut Vec<Area>)
+                     -> Vec<SurfaceContext> {
+        let mut visible = Vec::with_capacity(contexts.len());
+        for context in contexts.iter().rev() {
+            if let Some(info) = self.coordinator.get_surface(context.id) {
+                let area = Self::calculate_surface_area(context, &info);
+                if !area.is_covered_by(opaque) {
+                    for region in info.opaque_region.iter() {
+                        let region = Area::new(area.pos + region.pos, region.size);
+                        if let Some(region) = region.intersection(&area) {
+                            opaque.push(region);
+                        }
+                    }
+                    visible.push(context.clone());
+                }
+            }
+        }
+        visible.reverse();
+        visible
+    }
+
+    /// Translates damage of surfaces visible on this display to display coordinates.
+    fn add_surfaces_damage(&mut self, damage: &HashMap<SurfaceId, Vec<Area>>) {
+        if damage.is_empty() {
+            return;
+        }
+
+        let info = self.output.get_info();
+        let surfaces = self.prepare_surfaces_context();
+        let layover = self.prepare_layover_context(info.area.pos);
+        let layunder = self.prepare_layunder_context();
+        for (sid, surface_area) in self.prepare_scene(&[&layunder, &surfaces, &layover]) {
+            if let Some(areas) = damage.get(&sid) {
+                for area in areas.iter() {
+                    self.damage.add(Area::new(surface_area.pos + area.pos, area.size));
+                }
+            }
+        }
+    }
+
+    /// Draw the damaged part of the scene and then schedule page flip. If nothing was damaged
+    /// drawing is skipped, but frame notifications are still sent on page flip. Surfaces hidden
+    /// behind opaque ones get no frame notifications and their presentation feedback is discarded.
     fn redraw_all(&mut self) {
         let info = self.output.get_info();
 
-        let mut surfaces = self.frame.to_array(Position::default(), &self.coordinator);
-        surfaces.reverse();
+        let surfaces = self.prepare_surfaces_context();
         let layover = self.prepare_layover_context(info.area.pos);
         let layunder = self.prepare_layunder_context();
         self.pointer.borrow_mut().update_hover_state(info.area, &surfaces);
 
-        if let Err(err) = self.output.draw(&layunder, &surfaces, &layover, &self.coordinator) {
-            log_error!("Display: {}", err);
+        // Frontends need to know where surfaces are to keep their pop-ups on screen. Pass only
+        // positions which changed since previous redraw.
+        let mut global_positions = HashMap::with_capacity(surfaces.len());
+        for context in surfaces.iter() {
+            let position = info.area.pos + context.pos;
+            if self.global_positions.get(&context.id) != Some(&position) {
+                self.coordinator.set_surface_global_position(context.id, position);
+            }
+            global_positions.insert(context.id, position);
         }
+        self.global_positions = global_positions;
 
-        if let Err(err) = self.output.swap_buffers() {
-            log_error!("Display: {}", err);
+        self.damage.set_bounds(info.area.rebased());
+        let scene = self.prepare_scene(&[&layunder, &surfaces, &layover]);
+        self.damage.update_scene(scene);
+        let decorations = self.prepare_decorations(&surfaces);
+        self.damage.update_decorations(decorations.clone());
+
+        // Skip surfaces hidden behind opaque ones
+        let mut opaque = Vec::new();
+        let visible_surfaces = self.cull_occluded(&surfaces, &mut opaque);
+        let visible_layunder = self.cull_occluded(&layunder, &mut opaque);
+
+        if !self.damage.is_empty() {
+            let region = self.damage.get_repaint_region(self.output.get_buffer_age());
+            if let Err(err) = self.output.draw(&visible_layunder,
+                                               &visible_surfaces,
+                                               &decorations,
+                                               &layover,
+                                               &region,
+                                               &self.coordinator) {
+                log_error!("Display: {}", err);
+            }
+
+            if let Err(err) = self.output.swap_buffers() {
+                log_error!("Display: {}", err);
+            }
+            self.damage.on_frame_drawn();
         }
 
         // Send frame notifications
-        for context in surfaces {
+        self.presented_sids.clear();
+        for context in visible_surfaces.iter() {
             let ms = Milliseconds::elapsed_from(&self.reference_time);
             let frame = Perceptron::SurfaceFrame(context.id, ms);
             self.coordinator.emit(perceptron::SURFACE_FRAME, frame);
+            self.presented_sids.push(context.id);
+        }
+
+        // Contents of culled surfaces will not be presented
+        for context in surfaces.iter() {
+            if !self.presented_sids.contains(&context.id) {
+                let discarded = Perceptron::SurfaceDiscarded(context.id);
+                self.coordinator.emit(perceptron::SURFACE_DISCARDED, discarded);
+            }
         }
 
         self.redraw_needed = false;
@@ -7,26 +7,44 @@
 
 use std::rc::Rc;
 use std::cell::RefCell;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::time::Instant;
 
 use qualia::{SurfaceId, Button, InteractionMode, Command, OptionalPosition, Position, Size, Vector};
-use qualia::{perceptron, Perceptron};
+use qualia::{Layer, LayerProperties, Milliseconds, PresentationTime, Touch};
+use qualia::{perceptron, Perceptron, resize_edge};
 use qualia::{CompositorConfig, ExhibitorCoordinationTrait};
 use outputs::Output;
 use frames::Frame;
 
 use compositor::Compositor;
 use pointer::Pointer;
+use touch::Touchscreen;
 use display::Display;
 use strategist::Strategist;
 
 // -------------------------------------------------------------------------------------------------
 
+/// Describes what happens to dragged surface.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum DragKind {
+    /// Surface is moved along with the pointer.
+    Move,
+
+    /// Given edges of surface are moved along with the pointer.
+    Resize(resize_edge::ResizeEdge),
+}
+
+// -------------------------------------------------------------------------------------------------
+
 /// Helper structure for dragging surfaces.
 struct SurfaceDragger {
     sid: SurfaceId,
     position: Position,
+    kind: DragKind,
+
+    /// Dragging requested by client lasts until pointer button is released.
+    requested_by_client: bool,
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -37,8 +55,10 @@ pub struct Exhibitor<C>
 {
     compositor: Compositor<C>,
     pointer: Rc<RefCell<Pointer<C>>>,
+    touchscreen: Touchscreen<C>,
     displays: HashMap<i32, Display<C>>,
     dragger: Option<SurfaceDragger>,
+    pressed_buttons: HashSet<u16>,
     reference_time: Instant,
     coordinator: C,
 }
@@ -58,8 +78,10 @@ impl<C> Exhibitor<C>
         Exhibitor {
             compositor: Compositor::new(coordinator.clone(), strategist, compositor_config),
             pointer: Rc::new(RefCell::new(Pointer::new(reference_time, coordinator.clone()))),
+            touchscreen: Touchscreen::new(coordinator.clone()),
             displays: HashMap::new(),
             dragger: None,
+            pressed_buttons: HashSet::new(),
             reference_time: reference_time,
             coordinator: coordinator,
         }
@@ -74,8 +96,9 @@ impl<C> Exhibitor<C>
 {
     /// Handles notification about needed redraw.
     pub fn on_notify(&mut self) {
+        let damage = self.coordinator.take_surfaces_damage();
         for ref mut display in self.displays.values_mut() {
-            display.on_notify();
+            display.on_notify(&damage);
         }
     }
 
@@ -115,11 +138,11 @@ impl<C> Exhibitor<C>
     }
 
     /// This method is called when pageflip occurred.
-    /// `id` is ID of output that scheduled the pageflip.
-    pub fn on_pageflip(&mut self, id: i32) {
+    /// `id` is ID of output that scheduled the pageflip, `time` describes when it occurred.
+    pub fn on_pageflip(&mut self, id: i32, time: PresentationTime) {
         // Pass notification to associated display
         if let Some(ref mut display) = self.displays.get_mut(&id) {
-            display.on_pageflip();
+            display.on_pageflip(time);
         }
     }
 
@@ -134,6 +157,11 @@ impl<C> Exhibitor<C>
         self.pointer.borrow_mut().on_surface_change(sid);
     }
 
+    /// This method is called when changing drag icon surface was requested.
+    pub fn on_drag_icon_surface_change(&mut self, sid: SurfaceId) {
+        self.pointer.borrow_mut().on_drag_icon_change(sid);
+    }
+
     /// This method is called when changing background surface was requested.
     ///
     /// TODO: Make change background request be display specific.
@@ -143,9 +171,52 @@ impl<C> Exhibitor<C>
         }
     }
 
-    /// This method is called when new surface is ready to be managed.
+    /// This method is called when new surface is ready to be managed. Surfaces placed in layers
+    /// are shown outside of frame layout.
     pub fn on_surface_ready(&mut self, sid: SurfaceId) {
-        self.compositor.manage_surface(sid);
+        let mut properties = None;
+        for display in self.displays.values_mut() {
+            if let Some(props) = display.map_layer_surface(sid) {
+                properties = Some(props);
+                break;
+            }
+        }
+
+        if let Some(properties) = properties {
+            if properties.keyboard_interactive && (properties.layer >= Layer::Top) {
+                self.coordinator.set_keyboard_focus(sid);
+            }
+        } else {
+            self.compositor.manage_surface(sid);
+        }
+    }
+
+    /// Places given surface in layer on given display. If display is not specified the surface
+    /// stays on its current display or is placed on the display under the pointer.
+    pub fn on_layer_surface(&mut self,
+                            sid: SurfaceId,
+                            properties: LayerProperties,
+                            display_id: Option<i32>) {
+        let display_id = display_id.filter(|id| self.displays.contains_key(id))
+            .or_else(|| self.find_layer_surface_display(sid))
+            .or_else(|| {
+                let position = self.pointer.borrow().get_global_position();
+                self.displays
+                    .iter()
+                    .find(|&(_, display)| display.get_info().area.contains(&position))
+                    .map(|(id, _)| *id)
+            })
+            .or_else(|| self.displays.keys().next().cloned());
+
+        if let Some(display_id) = display_id {
+            for (id, display) in self.displays.iter_mut() {
+                if *id == display_id {
+                    display.configure_layer_surface(sid, properties);
+                } else {
+                    display.remove_layer_surface(sid);
+                }
+            }
+        }
     }
 
     /// Dock given surface with given size on given display.
@@ -158,15 +229,81 @@ impl<C> Exhibitor<C>
         }
     }
 
+    /// This method is called when client requested interactive move of surface. The request is
+    /// refused if no button is pressed as nothing would stop the move.
+    pub fn on_surface_move_requested(&mut self, sid: SurfaceId) {
+        if !self.pressed_buttons.is_empty() {
+            self.start_surface_drag(sid, DragKind::Move, true);
+        }
+    }
+
+    /// This method is called when client requested interactive resize of surface. The request is
+    /// refused if no button is pressed as nothing would stop the resize.
+    pub fn on_surface_resize_requested(&mut self, sid: SurfaceId, edges: resize_edge::ResizeEdge) {
+        if edges != resize_edge::NONE && !self.pressed_buttons.is_empty() {
+            self.start_surface_drag(sid, DragKind::Resize(edges), true);
+        }
+    }
+
+    /// This method is called when client requested to maximize or unmaximize surface.
+    pub fn on_surface_maximization_requested(&mut self, sid: SurfaceId, maximized: bool) {
+        if maximized {
+            self.compositor.maximize_surface(sid);
+        } else {
+            self.compositor.unmaximize_surface(sid);
+        }
+    }
+
+    /// This method is called when client requested to make surface fullscreen or to bring it back
+    /// from fullscreen.
+    pub fn on_surface_fullscreen_requested(&mut self,
+                                           sid: SurfaceId,
+                                           fullscreen: bool,
+                                           display_id: Option<i32>) {
+        if fullscreen {
+            self.compositor.fullscreen_surface(sid, display_id);
+        } else {
+            self.compositor.unfullscreen_surface(sid);
+        }
+    }
+
+    /// This method is called when client requested to minimize surface.
+    pub fn on_surface_minimization_requested(&mut self, sid: SurfaceId) {
+        self.compositor.minimize_surface(sid);
+    }
+
     /// This method is called when surface was destroyed.
     pub fn on_surface_destroyed(&mut self, sid: SurfaceId) {
-        self.compositor.unmanage_surface(sid);
+        if self.dragger.as_ref().map_or(false, |dragger| dragger.sid == sid) {
+            self.dragger = None;
+        }
+        let mut was_layer_surface = false;
+        for display in self.displays.values_mut() {
+            was_layer_surface |= display.remove_layer_surface(sid);
+        }
+        if was_layer_surface {
+            if self.coordinator.get_keyboard_focused_sid() == sid {
+                self.compositor.restore_keyboard_focus();
+            }
+        } else {
+            self.compositor.unmanage_surface(sid);
+        }
         self.pointer.borrow_mut().on_surface_destroyed(sid);
+        self.touchscreen.on_surface_destroyed(sid);
+    }
+
+    /// This method is called when title or application ID of surface changed. Displays are
+    /// notified so title bars of decorated surfaces get redrawn.
+    pub fn on_surface_description_changed(&mut self, _sid: SurfaceId) {
+        self.coordinator.notify();
     }
 
     /// This method is called when keyboard focus changed.
     pub fn on_keyboard_focus_changed(&mut self, sid: SurfaceId) {
         self.pointer.borrow_mut().on_keyboard_focus_changed(sid);
+
+        // Decorations of focused surfaces look different
+        self.coordinator.notify();
     }
 
     /// This method is called when screenshot was requested.
@@ -202,11 +339,17 @@ impl<C> Exhibitor<C>
     /// Handle pointer button event.
     pub fn on_button(&mut self, button: Button) {
         // TODO: Be more specific about button codes and values.
+        if button.value != 0 {
+            self.pressed_buttons.insert(button.code);
+        } else {
+            self.pressed_buttons.remove(&button.code);
+        }
+
         if button.value != 0 {
             let pfsid = self.pointer.borrow_mut().get_pointer_focussed_sid();
-            if self.pointer.borrow_mut().get_keyboard_focussed_sid() != pfsid {
-                self.compositor.pop_surface(pfsid);
-            }
+            self.activate_surface(pfsid);
+        } else if self.dragger.as_ref().map_or(false, |dragger| dragger.requested_by_client) {
+            self.deactivate_surface_drag();
         }
     }
 
@@ -215,6 +358,29 @@ impl<C> Exhibitor<C>
         self.pointer.borrow_mut().reset_position()
     }
 
+    /// Handle touch down event. Touched surface is brought to front like after clicking it.
+    pub fn on_touch_down(&mut self, touch: Touch) {
+        let sid = self.touchscreen.on_down(touch.id, touch.position, touch.time, &self.displays);
+        if sid.is_valid() {
+            self.activate_surface(sid);
+        }
+    }
+
+    /// Handle touch motion event.
+    pub fn on_touch_motion(&mut self, touch: Touch) {
+        self.touchscreen.on_motion(touch.id, touch.position, touch.time, &self.displays);
+    }
+
+    /// Handle touch up event.
+    pub fn on_touch_up(&mut self, id: i32, time: Milliseconds) {
+        self.touchscreen.on_up(id, time);
+    }
+
+    /// Handle touch frame event.
+    pub fn on_touch_frame(&mut self) {
+        self.touchscreen.on_frame();
+    }
+
     /// Handles interaction mode switch.
     ///
     /// Exhibitor is concerned only about visual mode. When switched on it starts dragging surface
@@ -254,26 +420,42 @@ impl<C> Exhibitor<C>
     where C: ExhibitorCoordinationTrait
 {
     fn activate_surface_drag(&mut self) {
-        let pointer = self.pointer.borrow();
-        let position = pointer.get_global_position();
-        let sid = pointer.get_pointer_focussed_sid();
+        let sid = self.pointer.borrow().get_pointer_focussed_sid();
+        self.start_surface_drag(sid, DragKind::Move, false);
+    }
+
+    fn start_surface_drag(&mut self, sid: SurfaceId, kind: DragKind, requested_by_client: bool) {
+        self.deactivate_surface_drag();
         if sid.is_valid() {
             self.dragger = Some(SurfaceDragger {
-                                    position: position,
+                                    position: self.pointer.borrow().get_global_position(),
                                     sid: sid,
+                                    kind: kind,
+                                    requested_by_client: requested_by_client,
                                 })
         }
     }
 
     fn deactivate_surface_drag(&mut self) {
-        self.dragger = None;
+        if let Some(dragger) = self.dragger.take() {
+            if let DragKind::Resize(_) = dragger.kind {
+                self.compositor.finish_resizing(dragger.sid);
+            }
+        }
     }
 
     fn drag_surface_if_needed(&mut self) {
         if let Some(ref mut dragger) = self.dragger {
             let new_position = self.pointer.borrow().get_global_position();
             let vector = new_position - dragger.position;
-            self.compositor.move_globally(dragger.sid, vector, new_position);
+            match dragger.kind {
+                DragKind::Move => {
+                    self.compositor.move_globally(dragger.sid, vector, new_position);
+                }
+                DragKind::Resize(edges) => {
+                    self.compositor.resize_globally(dragger.sid, edges, vector);
+                }
+            }
             dragger.position = new_position;
         }
     }
@@ -285,6 +467,31 @@ impl<C> Exhibitor<C>
 impl<C> Exhibitor<C>
     where C: ExhibitorCoordinationTrait
 {
+    /// Brings clicked or touched surface to front. Layer surfaces are never popped; they receive
+    /// keyboard focus only if they requested it.
+    fn activate_surface(&mut self, sid: SurfaceId) {
+        if self.pointer.borrow().get_keyboard_focussed_sid() == sid {
+            return;
+        }
+
+        let properties = self.displays.values().filter_map(|d| d.get_layer_properties(sid)).next();
+        if let Some(properties) = properties {
+            if properties.keyboard_interactive {
+                self.coordinator.set_keyboard_focus(sid);
+            }
+        } else {
+            self.compositor.pop_surface(sid);
+        }
+    }
+
+    /// Returns ID of display on which given surface is placed in layers.
+    fn find_layer_surface_display(&self, sid: SurfaceId) -> Option<i32> {
+        self.displays
+            .iter()
+            .find(|&(_, display)| display.get_layer_properties(sid).is_some())
+            .map(|(id, _)| *id)
+    }
+
     /// Chooses new display position.
     ///
     /// New position is always chosen to be right to most right display.
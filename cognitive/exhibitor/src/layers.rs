@@ -0,0 +1,227 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! This module contains logic for placing surfaces in layers outside of frame layout (like panels,
+//! launchers, notifications or backgrounds provided by clients).
+
+// -------------------------------------------------------------------------------------------------
+
+use qualia::{Area, Margins, Position, Size, SurfaceContext, SurfaceId, SurfaceListing};
+use qualia::{layer_anchor, Layer, LayerProperties};
+
+// -------------------------------------------------------------------------------------------------
+
+/// Surface placed in layer together with its area.
+struct LayerSurface {
+    sid: SurfaceId,
+    properties: LayerProperties,
+
+    /// Area relative to the display.
+    area: Area,
+
+    /// Surfaces are drawn and reserve their exclusive zones only after they are ready.
+    is_mapped: bool,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Keeps track of layer surfaces placed on one display.
+pub struct Layers {
+    /// Surfaces in order of placing them in layers.
+    surfaces: Vec<LayerSurface>,
+
+    /// Space along edges of the display reserved by exclusive zones of surfaces.
+    margins: Margins,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl Layers {
+    /// Constructs new `Layers`.
+    pub fn new() -> Self {
+        Layers {
+            surfaces: Vec::new(),
+            margins: Margins::default(),
+        }
+    }
+
+    /// Places given surface in layer or updates its properties. Returns `true` if the surface was
+    /// not placed in layers before.
+    pub fn configure(&mut self, sid: SurfaceId, properties: LayerProperties) -> bool {
+        if let Some(surface) = self.surfaces.iter_mut().find(|surface| surface.sid == sid) {
+            surface.properties = properties;
+            return false;
+        }
+
+        self.surfaces.push(LayerSurface {
+                               sid: sid,
+                               properties: properties,
+                               area: Area::default(),
+                               is_mapped: false,
+                           });
+        true
+    }
+
+    /// Marks given surface as ready to be drawn. Returns its properties or `None` if the surface is
+    /// not placed in layers.
+    pub fn map(&mut self, sid: SurfaceId) -> Option<LayerProperties> {
+        self.surfaces.iter_mut().find(|surface| surface.sid == sid).map(|surface| {
+            surface.is_mapped = true;
+            surface.properties
+        })
+    }
+
+    /// Removes given surface from layers. Returns `true` if the surface was placed in layers.
+    pub fn remove(&mut self, sid: SurfaceId) -> bool {
+        let len = self.surfaces.len();
+        self.surfaces.retain(|surface| surface.sid != sid);
+        self.surfaces.len() != len
+    }
+
+    /// Returns properties of given surface or `None` if the surface is not placed in layers.
+    pub fn get_properties(&self, sid: SurfaceId) -> Option<LayerProperties> {
+        self.surfaces.iter().find(|surface| surface.sid == sid).map(|surface| surface.properties)
+    }
+
+    /// Returns areas (relative to the display) of all surfaces placed in layers.
+    pub fn get_areas(&self) -> Vec<(SurfaceId, Area)> {
+        self.surfaces.iter().map(|surface| (surface.sid, surface.area)).collect()
+    }
+
+    /// Returns space along edges of the display reserved by exclusive zones.
+    pub fn get_margins(&self) -> Margins {
+        self.margins
+    }
+
+    /// Calculates areas of surfaces and margins reserved by exclusive zones for display of given
+    /// size.
+    ///
+    /// Surfaces with exclusive zones are placed first, starting from the topmost layer. Each of
+    /// them is placed inside area not reserved by previous ones and then reserves its own zone.
+    /// Other surfaces are placed inside the area left by all exclusive zones or, if their exclusive
+    /// zone is negative, inside the whole display.
+    pub fn arrange(&mut self, size: Size) {
+        let bounds = Area::new(Position::default(), size);
+        let mut usable = bounds;
+
+        let mut order: Vec<usize> = (0..self.surfaces.len()).collect();
+        order.sort_by(|a, b| {
+            self.surfaces[*b].properties.layer.cmp(&self.surfaces[*a].properties.layer)
+        });
+
+        for &i in order.iter() {
+            let surface = &mut self.surfaces[i];
+            let edge = surface.properties.get_exclusive_edge();
+            if edge != layer_anchor::NONE {
+                surface.area = Self::place(&surface.properties, usable);
+                if surface.is_mapped {
+                    usable = usable.shrunk(&Self::get_reserved(&surface.properties, edge));
+                }
+            }
+        }
+
+        for surface in self.surfaces.iter_mut() {
+            let properties = &surface.properties;
+            if properties.get_exclusive_edge() == layer_anchor::NONE {
+                let area = if properties.exclusive_zone < 0 { bounds } else { usable };
+                surface.area = Self::place(properties, area);
+            }
+        }
+
+        // Exclusive zones may not fit inside the display
+        let usable = usable.intersection(&bounds).unwrap_or_default();
+        let right = size.width as isize - (usable.pos.x + usable.size.width as isize);
+        let bottom = size.height as isize - (usable.pos.y + usable.size.height as isize);
+        self.margins = Margins::new(usable.pos.y as usize,
+                                    right as usize,
+                                    bottom as usize,
+                                    usable.pos.x as usize);
+    }
+
+    /// Prepares rendering context of mapped surfaces in given layers in drawing order.
+    pub fn prepare_context(&self,
+                           layers: &[Layer],
+                           listing: &SurfaceListing)
+                           -> Vec<SurfaceContext> {
+        let mut result = Vec::new();
+        for layer in layers.iter() {
+            for surface in self.surfaces.iter() {
+                if surface.is_mapped && (surface.properties.layer == *layer) {
+                    if let Some(array) = listing.get_renderer_context(surface.sid) {
+                        for context in array.iter() {
+                            result.push(context.moved(surface.area.pos));
+                        }
+                    }
+                }
+            }
+        }
+        result
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Private helper methods.
+impl Layers {
+    /// Calculates area of surface with given properties placed inside given bounds.
+    fn place(properties: &LayerProperties, bounds: Area) -> Area {
+        let anchor = properties.anchor;
+        let margins = &properties.margins;
+        let (x, width) = Self::place_along(bounds.pos.x,
+                                           bounds.size.width,
+                                           properties.size.width,
+                                           (margins.left, anchor.contains(layer_anchor::LEFT)),
+                                           (margins.right, anchor.contains(layer_anchor::RIGHT)));
+        let (y, height) = Self::place_along(bounds.pos.y,
+                                            bounds.size.height,
+                                            properties.size.height,
+                                            (margins.top, anchor.contains(layer_anchor::TOP)),
+                                            (margins.bottom,
+                                             anchor.contains(layer_anchor::BOTTOM)));
+        Area::create(x, y, width, height)
+    }
+
+    /// Calculates position and length of surface along one axis. `before` and `after` describe
+    /// margin and whether the surface is anchored to the edge. Surfaces anchored to both edges or
+    /// to none of them are centered and zero `length` means the surface should be stretched.
+    fn place_along(start: isize,
+                   bounds_length: usize,
+                   length: usize,
+                   before: (usize, bool),
+                   after: (usize, bool))
+                   -> (isize, usize) {
+        let available = bounds_length as isize - (before.0 + after.0) as isize;
+        let length = if length == 0 && available > 0 {
+            available as usize
+        } else {
+            length
+        };
+
+        let offset = match (before.1, after.1) {
+            (true, false) => before.0 as isize,
+            (false, true) => bounds_length as isize - (after.0 + length) as isize,
+            (true, true) => before.0 as isize + (available - length as isize) / 2,
+            (false, false) => (bounds_length as isize - length as isize) / 2,
+        };
+        (start + offset, length)
+    }
+
+    /// Returns margins reserved by exclusive zone of surface with given properties on given edge.
+    fn get_reserved(properties: &LayerProperties, edge: layer_anchor::LayerAnchor) -> Margins {
+        let zone = properties.exclusive_zone as usize;
+        let margins = &properties.margins;
+        let mut reserved = Margins::default();
+        if edge == layer_anchor::TOP {
+            reserved.top = zone + margins.top;
+        } else if edge == layer_anchor::BOTTOM {
+            reserved.bottom = zone + margins.bottom;
+        } else if edge == layer_anchor::LEFT {
+            reserved.left = zone + margins.left;
+        } else if edge == layer_anchor::RIGHT {
+            reserved.right = zone + margins.right;
+        }
+        reserved
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
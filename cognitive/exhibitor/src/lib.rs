@@ -22,9 +22,15 @@ pub use compositor::Compositor;
 mod pointer;
 pub use pointer::Pointer;
 
+mod damage;
+mod layers;
+
 mod display;
 pub use display::Display;
 
+mod touch;
+pub use touch::Touchscreen;
+
 mod exhibitor;
 pub use exhibitor::Exhibitor;
 
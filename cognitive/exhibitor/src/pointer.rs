@@ -10,6 +10,7 @@ use std::time::Instant;
 
 use qualia::{Area, Milliseconds, OptionalPosition, Position, Vector};
 use qualia::{perceptron, Perceptron, SurfaceContext, SurfaceId, ExhibitorCoordinationTrait};
+use qualia::PointerConstraintKind;
 
 use display::Display;
 
@@ -34,12 +35,24 @@ pub struct Pointer<C>
     /// Surface ID of cursor surface.
     csid: SurfaceId,
 
+    /// Surface ID of icon of dragged data.
+    dsid: SurfaceId,
+
     /// Surface ID of pointer-focused surface.
     pfsid: SurfaceId,
 
     /// Surface ID of keyboard-focused surface.
     kfsid: SurfaceId,
 
+    /// Surface ID of surface which currently constrains pointer movement.
+    constraint_sid: SurfaceId,
+
+    /// Kind of currently active constraint.
+    constraint_kind: PointerConstraintKind,
+
+    /// Region in global coordinates pointer can not leave while confined.
+    confinement: Vec<Area>,
+
     /// Reference time for obtaining event timestamps.
     reference_time: Instant,
 
@@ -60,8 +73,12 @@ impl<C> Pointer<C>
             last_surface_relative: Position::default(),
             display_area: Area::default(),
             csid: SurfaceId::invalid(),
+            dsid: SurfaceId::invalid(),
             pfsid: SurfaceId::invalid(),
             kfsid: SurfaceId::invalid(),
+            constraint_sid: SurfaceId::invalid(),
+            constraint_kind: PointerConstraintKind::Lock,
+            confinement: Vec::new(),
             reference_time: reference_time,
             coordinator: coordinator,
         }
@@ -90,6 +107,11 @@ impl<C> Pointer<C>
         self.csid
     }
 
+    /// Returns ID of the drag icon surface.
+    pub fn get_drag_icon_sid(&self) -> SurfaceId {
+        self.dsid
+    }
+
     /// Return ID of the surface with keyboard focus.
     pub fn get_keyboard_focussed_sid(&self) -> SurfaceId {
         self.kfsid
@@ -109,9 +131,25 @@ impl<C> Pointer<C>
 {
     /// `Pointer` constructor.
     /// Move pointer and cast to correct output.
+    ///
+    /// Pointer-focused surface is informed about the motion even if the pointer is locked or
+    /// confined.
     pub fn move_and_cast(&mut self, vector: Vector, displays: &HashMap<i32, Display<C>>) {
+        if self.pfsid.is_valid() && !vector.is_zero() {
+            let ms = Milliseconds::elapsed_from(&self.reference_time);
+            self.coordinator.emit(perceptron::POINTER_DELTA,
+                                  Perceptron::PointerDelta(self.pfsid, vector, ms));
+        }
+
+        self.validate_constraint();
         let moved = self.position.clone() + vector.clone();
-        self.position = self.cast(moved, displays);
+        if self.constraint_sid.is_valid() {
+            if self.constraint_kind == PointerConstraintKind::Confine {
+                self.position = self.confine(moved);
+            }
+        } else {
+            self.position = self.cast(moved, displays);
+        }
     }
 
     /// Change position of the pointer and cast to correct output.
@@ -153,15 +191,17 @@ impl<C> Pointer<C>
 
         let mut sid = SurfaceId::invalid();
         let mut surface_relative = Position::default();
+        let mut surface_origin = Position::default();
         let display_relative = self.position - display_area.pos;
 
         // Find surface pointer hovers
         for context in surfaces.iter().rev() {
             if let Some(info) = self.coordinator.get_surface(context.id) {
-                let surface_area = Area::new(context.pos, info.requested_size);
-                if surface_area.contains(&display_relative) {
+                let position = display_relative - context.pos.clone() + info.offset;
+                if info.accepts_input_at(&position) {
                     sid = context.id;
-                    surface_relative = display_relative - context.pos.clone() + info.offset;
+                    surface_relative = position;
+                    surface_origin = display_area.pos + context.pos - info.offset;
                     break;
                 }
             }
@@ -179,6 +219,8 @@ impl<C> Pointer<C>
             self.coordinator.emit(perceptron::POINTER_RELATIVE_MOTION,
                                   Perceptron::PointerRelativeMotion(sid, surface_relative, ms));
         }
+
+        self.update_constraint(surface_origin);
     }
 }
 
@@ -188,22 +230,36 @@ impl<C> Pointer<C>
 impl<C> Pointer<C>
     where C: ExhibitorCoordinationTrait
 {
-    /// Handles destruction of cursor surface.
+    /// Handles destruction of cursor, drag icon or constraining surface.
     pub fn on_surface_destroyed(&mut self, sid: SurfaceId) {
+        if self.constraint_sid == sid {
+            self.deactivate_constraint();
+        }
         if self.csid == sid {
             self.csid = SurfaceId::invalid();
         }
+        if self.dsid == sid {
+            self.dsid = SurfaceId::invalid();
+        }
     }
 
     /// Sets surface ID of currently keyboard focused surface.
     pub fn on_keyboard_focus_changed(&mut self, sid: SurfaceId) {
         self.kfsid = sid;
+        if self.constraint_sid.is_valid() && (self.constraint_sid != sid) {
+            self.deactivate_constraint();
+        }
     }
 
     /// Handles cursor surface change request.
     pub fn on_surface_change(&mut self, sid: SurfaceId) {
         self.csid = sid;
     }
+
+    /// Handles drag icon surface change request.
+    pub fn on_drag_icon_change(&mut self, sid: SurfaceId) {
+        self.dsid = sid;
+    }
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -237,6 +293,79 @@ impl<C> Pointer<C>
         }
         position
     }
+
+    /// Cast position to confinement region:
+    /// - if position is in one of areas of the region - return it without change
+    /// - otherwise cast it to the area containing current position.
+    fn confine(&self, position: Position) -> Position {
+        if self.confinement.iter().any(|area| area.contains(&position)) {
+            position
+        } else if let Some(area) = self.confinement.iter().find(|a| a.contains(&self.position)) {
+            position.casted(area)
+        } else {
+            self.position
+        }
+    }
+
+    /// Activates constraint of pointer-focused surface if the surface has also keyboard focus and
+    /// pointer is inside its region. Updates region of already active constraint or deactivates it
+    /// if pointer focus changed or the constraint was removed.
+    ///
+    /// `surface_origin` is position of pointer-focused surface in global coordinates.
+    fn update_constraint(&mut self, surface_origin: Position) {
+        if self.constraint_sid.is_valid() && (self.constraint_sid != self.pfsid) {
+            self.deactivate_constraint();
+        }
+
+        let info = if let Some(info) = self.coordinator.get_surface(self.pfsid) {
+            info
+        } else {
+            return;
+        };
+
+        if let Some(ref constraint) = info.pointer_constraint {
+            let region: Vec<Area> = info.get_pointer_constraint_region()
+                .iter()
+                .map(|area| Area::new(surface_origin + area.pos, area.size))
+                .collect();
+
+            if self.constraint_sid.is_valid() {
+                self.confinement = region;
+            } else if (self.pfsid == self.kfsid) &&
+                      region.iter().any(|area| area.contains(&self.position)) {
+                self.constraint_sid = self.pfsid;
+                self.constraint_kind = constraint.kind;
+                self.confinement = region;
+                self.coordinator.emit(perceptron::POINTER_CONSTRAINT_CHANGED,
+                                      Perceptron::PointerConstraintChanged(self.pfsid, true));
+            }
+        } else if self.constraint_sid.is_valid() {
+            self.deactivate_constraint();
+        }
+    }
+
+    /// Deactivates active constraint if it was removed or replaced by constraint of other kind
+    /// since last hover state update.
+    fn validate_constraint(&mut self) {
+        if self.constraint_sid.is_valid() {
+            let kind = self.coordinator
+                .get_surface(self.constraint_sid)
+                .and_then(|info| info.pointer_constraint)
+                .map(|constraint| constraint.kind);
+            if kind != Some(self.constraint_kind) {
+                self.deactivate_constraint();
+            }
+        }
+    }
+
+    /// Deactivates currently active constraint.
+    fn deactivate_constraint(&mut self) {
+        let sid = self.constraint_sid;
+        self.constraint_sid = SurfaceId::invalid();
+        self.confinement.clear();
+        self.coordinator.emit(perceptron::POINTER_CONSTRAINT_CHANGED,
+                              Perceptron::PointerConstraintChanged(sid, false));
+    }
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -0,0 +1,158 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! This module contains functionality related to touchscreen like tracking touch points and
+//! finding touched surfaces.
+
+// -------------------------------------------------------------------------------------------------
+
+use std::collections::HashMap;
+
+use qualia::{Milliseconds, OutputInfo, Position, Slide, SurfaceId, Vector};
+use qualia::{perceptron, Perceptron, ExhibitorCoordinationTrait};
+
+use display::Display;
+
+// -------------------------------------------------------------------------------------------------
+
+/// State of single touch point.
+struct TouchPoint {
+    /// ID of touched surface. Touch point stays bound to this surface until it is released.
+    sid: SurfaceId,
+
+    /// Difference between position relative to touched surface and global position.
+    offset: Vector,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// State of the touchscreen.
+///
+/// Touchscreen is mapped to area of display with the lowest ID.
+pub struct Touchscreen<C>
+    where C: ExhibitorCoordinationTrait
+{
+    /// Currently active touch points.
+    points: HashMap<i32, TouchPoint>,
+
+    /// Tells if any event was sent to frontends since last frame.
+    is_frame_pending: bool,
+
+    /// Coordinator.
+    coordinator: C,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl<C> Touchscreen<C>
+    where C: ExhibitorCoordinationTrait
+{
+    /// `Touchscreen` constructor.
+    pub fn new(coordinator: C) -> Self {
+        Touchscreen {
+            points: HashMap::new(),
+            is_frame_pending: false,
+            coordinator: coordinator,
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Input handlers.
+impl<C> Touchscreen<C>
+    where C: ExhibitorCoordinationTrait
+{
+    /// Handles touch down. Returns ID of touched surface.
+    pub fn on_down(&mut self,
+                   id: i32,
+                   position: Slide,
+                   time: Milliseconds,
+                   displays: &HashMap<i32, Display<C>>)
+                   -> SurfaceId {
+        let mut sid = SurfaceId::invalid();
+        if let Some(display) = displays.keys().min().and_then(|id| displays.get(id)) {
+            let info = display.get_info();
+            let global = Self::map(position, &info);
+            let display_relative = global - info.area.pos;
+            if let Some((touched_sid, surface_relative)) = display.find_surface(display_relative) {
+                sid = touched_sid;
+                self.points.insert(id,
+                                   TouchPoint {
+                                       sid: sid,
+                                       offset: surface_relative - global,
+                                   });
+                self.is_frame_pending = true;
+                self.coordinator.emit(perceptron::TOUCH_DOWN,
+                                      Perceptron::TouchDown(sid, id, surface_relative, time));
+            }
+        }
+        sid
+    }
+
+    /// Handles motion of touch point.
+    pub fn on_motion(&mut self,
+                     id: i32,
+                     position: Slide,
+                     time: Milliseconds,
+                     displays: &HashMap<i32, Display<C>>) {
+        if let Some(point) = self.points.get(&id) {
+            if let Some(display) = displays.keys().min().and_then(|id| displays.get(id)) {
+                let global = Self::map(position, &display.get_info());
+                let motion = Perceptron::TouchMotion(point.sid, id, global + point.offset, time);
+                self.is_frame_pending = true;
+                self.coordinator.emit(perceptron::TOUCH_MOTION, motion);
+            }
+        }
+    }
+
+    /// Handles release of touch point.
+    pub fn on_up(&mut self, id: i32, time: Milliseconds) {
+        if let Some(point) = self.points.remove(&id) {
+            self.is_frame_pending = true;
+            self.coordinator.emit(perceptron::TOUCH_UP, Perceptron::TouchUp(point.sid, id, time));
+        }
+    }
+
+    /// Handles end of set of touch events.
+    pub fn on_frame(&mut self) {
+        if self.is_frame_pending {
+            self.is_frame_pending = false;
+            self.coordinator.emit(perceptron::TOUCH_FRAME, Perceptron::TouchFrame);
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Other requests.
+impl<C> Touchscreen<C>
+    where C: ExhibitorCoordinationTrait
+{
+    /// Handles destruction of surface. Touch points bound to it are forgotten.
+    pub fn on_surface_destroyed(&mut self, sid: SurfaceId) {
+        self.points.retain(|_, point| point.sid != sid);
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Helper methods
+impl<C> Touchscreen<C>
+    where C: ExhibitorCoordinationTrait
+{
+    /// Maps position given as fraction of touchscreen size to global position inside area of
+    /// given output. Touchscreen is attached to the panel so its position has to be transformed
+    /// the same way as output contents.
+    fn map(position: Slide, info: &OutputInfo) -> Position {
+        let area = info.area;
+        let (x, y) = info.transform
+            .inverted()
+            .map_normalized(position.x.max(0.0).min(1.0), position.y.max(0.0).min(1.0));
+        let width = (area.size.width as f32 - 1.0).max(0.0);
+        let height = (area.size.height as f32 - 1.0).max(0.0);
+        area.pos + Position::new((x * width) as isize, (y * height) as isize)
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
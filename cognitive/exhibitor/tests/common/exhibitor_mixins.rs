@@ -51,6 +51,18 @@ pub trait ExhibitorCommandShorthands {
 
     /// Executes jump to workspace command.
     fn jump_to_workspace(&mut self, workspace_name: &str);
+
+    /// Executes toggle maximization command.
+    fn toggle_maximization(&mut self);
+
+    /// Executes toggle fullscreen command.
+    fn toggle_fullscreen(&mut self);
+
+    /// Executes minimize command.
+    fn minimize(&mut self);
+
+    /// Executes restore command.
+    fn restore(&mut self);
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -225,6 +237,54 @@ impl<C> ExhibitorCommandShorthands for Exhibitor<C>
 
         self.on_command(command);
     }
+
+    /// Executes toggle maximization command.
+    fn toggle_maximization(&mut self) {
+        let command = Command {
+            action: Action::Maximize,
+            direction: Direction::None,
+            magnitude: 0,
+            string: String::default(),
+        };
+
+        self.on_command(command);
+    }
+
+    /// Executes toggle fullscreen command.
+    fn toggle_fullscreen(&mut self) {
+        let command = Command {
+            action: Action::Fullscreen,
+            direction: Direction::None,
+            magnitude: 0,
+            string: String::default(),
+        };
+
+        self.on_command(command);
+    }
+
+    /// Executes minimize command.
+    fn minimize(&mut self) {
+        let command = Command {
+            action: Action::Minimize,
+            direction: Direction::None,
+            magnitude: 0,
+            string: String::default(),
+        };
+
+        self.on_command(command);
+    }
+
+    /// Executes restore command.
+    fn restore(&mut self) {
+        let command = Command {
+            action: Action::Restore,
+            direction: Direction::None,
+            magnitude: 0,
+            string: String::default(),
+        };
+
+        self.on_command(command);
+    }
 }
 
 // -------------------------------------------------------------------------------------------------
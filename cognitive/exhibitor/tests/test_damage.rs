@@ -0,0 +1,151 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! Unit tests of damage tracking and partial repaint.
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+
+extern crate cognitive_qualia as qualia;
+extern crate cognitive_outputs as outputs;
+extern crate cognitive_frames as frames;
+extern crate cognitive_exhibitor as exhibitor;
+
+mod common;
+
+use qualia::{Area, OutputInfo, Position, PresentationTime, Size, SurfaceId, SurfaceManagement};
+use qualia::coordinator_mock::CoordinatorMock;
+use outputs::output_mock::OutputMock;
+use exhibitor::{Exhibitor, Strategist};
+use common::exhibitor_mixins::ExhibitorCommandShorthands;
+
+// -------------------------------------------------------------------------------------------------
+
+struct Environment {
+    exhibitor: Exhibitor<CoordinatorMock>,
+    coordinator: CoordinatorMock,
+    output: OutputMock,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl Environment {
+    pub fn create() -> Self {
+        let mut config = common::configurations::strategist();
+        config.choose_target = "anchored_but_popups".to_owned();
+        let strategist = Strategist::new_from_config(config);
+        let output_info = OutputInfo::new(1,
+                                          Area::new(Position::new(0, 0), Size::new(100, 100)),
+                                          Size::new(100, 100),
+                                          60,
+                                          "test_make".to_owned(),
+                                          "test_model".to_owned());
+
+        let output = OutputMock::new(output_info);
+        let coordinator = CoordinatorMock::new();
+        let mut exhibitor = Exhibitor::new(coordinator.clone(),
+                                           std::time::Instant::now(),
+                                           strategist,
+                                           common::configurations::compositor());
+
+        exhibitor.on_output_found(Box::new(output.clone()));
+
+        Environment {
+            exhibitor: exhibitor,
+            coordinator: coordinator,
+            output: output,
+        }
+    }
+
+    pub fn add_surface(&mut self, sid: SurfaceId) {
+        self.coordinator.add_surface(sid);
+        self.exhibitor.on_surface_ready(sid);
+    }
+
+    pub fn redraw(&mut self) -> Option<Vec<Area>> {
+        self.exhibitor.on_notify();
+        self.exhibitor.on_pageflip(1, PresentationTime::default());
+        self.output.take_drawn_damage()
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if whole display is drawn when display is created.
+#[test]
+fn test_drawing_whole_display_initially() {
+    let mut e = Environment::create();
+
+    assert_eq!(e.output.take_drawn_damage(), Some(vec![Area::create(0, 0, 100, 100)]));
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if nothing is drawn when nothing changed.
+#[test]
+fn test_skipping_drawing_without_damage() {
+    let mut e = Environment::create();
+    e.output.take_drawn_damage();
+
+    assert_eq!(e.redraw(), None);
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if damage of surface is translated to display coordinates and clipped to display size.
+#[test]
+fn test_drawing_damaged_area_of_surface() {
+    let mut e = Environment::create();
+    e.add_surface(SurfaceId::new(1));
+    e.exhibitor.verticalize();
+    e.add_surface(SurfaceId::new(2));
+    e.redraw();
+
+    e.coordinator.damage_surface(SurfaceId::new(2), Area::create(10, 10, 5, 5));
+    e.coordinator.damage_surface(SurfaceId::new(2), Area::create(90, 40, 20, 20));
+    assert_eq!(e.redraw(), Some(vec![Area::create(10, 60, 5, 5), Area::create(90, 90, 10, 10)]));
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if old and new areas of surfaces are damaged when layout changes.
+#[test]
+fn test_damaging_moved_surfaces() {
+    let mut e = Environment::create();
+    e.add_surface(SurfaceId::new(1));
+    e.exhibitor.verticalize();
+    e.add_surface(SurfaceId::new(2));
+    e.redraw();
+
+    e.exhibitor.on_surface_destroyed(SurfaceId::new(2));
+    assert_eq!(e.redraw(), Some(vec![Area::create(0,  0, 100,  50),
+                                     Area::create(0,  0, 100, 100),
+                                     Area::create(0, 50, 100,  50)]));
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if damage of previous frames is repainted when buffer is older than one frame and if
+/// whole display is repainted when age of buffer is unknown or too old.
+#[test]
+fn test_repainting_damage_according_to_buffer_age() {
+    let mut e = Environment::create();
+    e.add_surface(SurfaceId::new(1));
+    e.redraw();
+
+    e.coordinator.damage_surface(SurfaceId::new(1), Area::create(10, 10, 5, 5));
+    assert_eq!(e.redraw(), Some(vec![Area::create(10, 10, 5, 5)]));
+
+    e.output.set_buffer_age(2);
+    e.coordinator.damage_surface(SurfaceId::new(1), Area::create(20, 20, 5, 5));
+    assert_eq!(e.redraw(), Some(vec![Area::create(20, 20, 5, 5), Area::create(10, 10, 5, 5)]));
+
+    e.output.set_buffer_age(0);
+    e.coordinator.damage_surface(SurfaceId::new(1), Area::create(30, 30, 5, 5));
+    assert_eq!(e.redraw(), Some(vec![Area::create(0, 0, 100, 100)]));
+
+    e.output.set_buffer_age(10);
+    e.coordinator.damage_surface(SurfaceId::new(1), Area::create(40, 40, 5, 5));
+    assert_eq!(e.redraw(), Some(vec![Area::create(0, 0, 100, 100)]));
+}
+
+// -------------------------------------------------------------------------------------------------
@@ -0,0 +1,144 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! Unit tests of decorations drawn by compositor.
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+
+extern crate cognitive_qualia as qualia;
+extern crate cognitive_outputs as outputs;
+extern crate cognitive_frames as frames;
+extern crate cognitive_exhibitor as exhibitor;
+
+mod common;
+
+use qualia::{Area, DecorationContext, OutputInfo, Position, PresentationTime, Size, SurfaceId};
+use qualia::{SurfaceControl, SurfaceViewer};
+use qualia::coordinator_mock::CoordinatorMock;
+use outputs::output_mock::OutputMock;
+use exhibitor::{Exhibitor, Strategist};
+use common::exhibitor_mixins::ExhibitorCommandShorthands;
+
+// -------------------------------------------------------------------------------------------------
+
+struct Environment {
+    exhibitor: Exhibitor<CoordinatorMock>,
+    coordinator: CoordinatorMock,
+    output: OutputMock,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl Environment {
+    pub fn create() -> Self {
+        let mut config = common::configurations::strategist();
+        config.choose_target = "anchored_but_popups".to_owned();
+        let strategist = Strategist::new_from_config(config);
+        let output_info = OutputInfo::new(1,
+                                          Area::new(Position::new(0, 0), Size::new(100, 100)),
+                                          Size::new(100, 100),
+                                          60,
+                                          "test_make".to_owned(),
+                                          "test_model".to_owned());
+
+        let output = OutputMock::new(output_info);
+        let coordinator = CoordinatorMock::new();
+        let mut exhibitor = Exhibitor::new(coordinator.clone(),
+                                           std::time::Instant::now(),
+                                           strategist,
+                                           common::configurations::compositor());
+
+        exhibitor.on_output_found(Box::new(output.clone()));
+
+        Environment {
+            exhibitor: exhibitor,
+            coordinator: coordinator,
+            output: output,
+        }
+    }
+
+    pub fn add_surface(&mut self, sid: SurfaceId, title: Option<&str>) {
+        self.coordinator.add_surface(sid);
+        if let Some(title) = title {
+            self.coordinator.set_surface_title(sid, title.to_owned());
+            self.coordinator.set_surface_decorated(sid, true);
+        }
+        self.exhibitor.on_surface_ready(sid);
+    }
+
+    pub fn redraw(&mut self) -> Option<Vec<Area>> {
+        self.exhibitor.on_notify();
+        self.exhibitor.on_pageflip(1, PresentationTime::default());
+        self.output.take_drawn_damage()
+    }
+
+    pub fn get_drawn_position(&self, sid: SurfaceId) -> Option<Position> {
+        self.output.get_drawn_surfaces().iter().find(|c| c.id == sid).map(|c| c.pos)
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if decorations are drawn only around decorated surfaces, if they cover whole frame and if
+/// decorated surfaces are moved and shrunk to make space for them.
+#[test]
+fn test_drawing_decorations_around_decorated_surfaces() {
+    let mut e = Environment::create();
+    e.add_surface(SurfaceId::new(1), Some("Terminal"));
+    e.exhibitor.verticalize();
+    e.add_surface(SurfaceId::new(2), None);
+    e.redraw();
+
+    let info = e.coordinator.get_surface(SurfaceId::new(1)).unwrap();
+    assert_eq!(info.desired_size, Size::new(100, 50));
+    assert_eq!(info.requested_size, Size::new(96, 32));
+
+    assert_eq!(e.output.get_drawn_decorations(),
+               vec![DecorationContext::new(SurfaceId::new(1),
+                                           Area::create(0, 0, 100, 50),
+                                           "Terminal".to_owned(),
+                                           false)]);
+    assert_eq!(e.get_drawn_position(SurfaceId::new(1)), Some(Position::new(2, 16)));
+    assert_eq!(e.get_drawn_position(SurfaceId::new(2)), Some(Position::new(0, 50)));
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if area of decoration is damaged when title of decorated surface changes.
+#[test]
+fn test_damaging_decoration_on_title_change() {
+    let mut e = Environment::create();
+    e.add_surface(SurfaceId::new(1), None);
+    e.exhibitor.verticalize();
+    e.add_surface(SurfaceId::new(2), Some("Editor"));
+    e.redraw();
+
+    e.coordinator.set_surface_title(SurfaceId::new(2), "Editor*".to_owned());
+    assert_eq!(e.redraw(), Some(vec![Area::create(0, 50, 100, 50)]));
+    assert_eq!(e.output.get_drawn_decorations()[0].title, "Editor*".to_owned());
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if fullscreen surfaces are not decorated and get back their decorations after leaving
+/// fullscreen.
+#[test]
+fn test_not_decorating_fullscreen_surfaces() {
+    let mut e = Environment::create();
+    let sid = SurfaceId::new(1);
+    e.add_surface(sid, Some("Video"));
+
+    e.exhibitor.on_surface_fullscreen_requested(sid, true, None);
+    e.redraw();
+    assert_eq!(e.coordinator.get_surface(sid).unwrap().requested_size, Size::new(100, 100));
+    assert_eq!(e.output.get_drawn_decorations(), Vec::new());
+    assert_eq!(e.get_drawn_position(sid), Some(Position::new(0, 0)));
+
+    e.exhibitor.on_surface_fullscreen_requested(sid, false, None);
+    e.redraw();
+    assert_eq!(e.coordinator.get_surface(sid).unwrap().requested_size, Size::new(96, 82));
+    assert_eq!(e.output.get_drawn_decorations().len(), 1);
+    assert_eq!(e.get_drawn_position(sid), Some(Position::new(2, 16)));
+}
+
+// -------------------------------------------------------------------------------------------------
@@ -0,0 +1,218 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! Unit tests of moving and resizing surfaces interactively on client request.
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+
+extern crate cognitive_qualia as qualia;
+extern crate cognitive_outputs as outputs;
+extern crate cognitive_frames as frames;
+extern crate cognitive_exhibitor as exhibitor;
+
+mod common;
+
+use qualia::{OutputInfo, SurfaceId, SurfaceViewer};
+use qualia::{Area, Button, Milliseconds, Position, Size, Vector};
+use qualia::{resize_edge, surface_state};
+use qualia::coordinator_mock::CoordinatorMock;
+use outputs::output_mock::OutputMock;
+use frames::Searching;
+use exhibitor::{Exhibitor, Strategist};
+use common::exhibitor_mixins::ExhibitorCommandShorthands;
+
+// -------------------------------------------------------------------------------------------------
+
+struct Environment {
+    exhibitor: Exhibitor<CoordinatorMock>,
+    coordinator: CoordinatorMock,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl Environment {
+    pub fn create(strategist: Strategist) -> Self {
+        let output_info = OutputInfo::new(1,
+                                          Area::new(Position::new(0, 0), Size::new(100, 100)),
+                                          Size::new(100, 100),
+                                          60,
+                                          "test_make".to_owned(),
+                                          "test_model".to_owned());
+
+        let output = Box::new(OutputMock::new(output_info.clone()));
+        let coordinator = CoordinatorMock::new();
+        let mut exhibitor = Exhibitor::new(coordinator.clone(),
+                                           std::time::Instant::now(),
+                                           strategist,
+                                           common::configurations::compositor());
+
+        exhibitor.on_output_found(output);
+
+        Environment {
+            exhibitor: exhibitor,
+            coordinator: coordinator,
+        }
+    }
+
+    pub fn add_surface(&mut self, sid: SurfaceId) {
+        self.coordinator.add_surface(sid);
+        self.exhibitor.on_surface_ready(sid);
+    }
+
+    pub fn get_area(&self, sid: SurfaceId) -> Area {
+        self.exhibitor.get_root().find_with_sid(sid).expect("find frame").get_area()
+    }
+
+    pub fn press_button(&mut self) {
+        let time = Milliseconds::elapsed_from(&std::time::Instant::now());
+        self.exhibitor.on_button(Button::new(0x110, 1, time));
+    }
+
+    pub fn release_button(&mut self) {
+        let time = Milliseconds::elapsed_from(&std::time::Instant::now());
+        self.exhibitor.on_button(Button::new(0x110, 0, time));
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if floating surface follows the pointer after client requested move and stops following
+/// it when button is released.
+#[test]
+fn test_moving_floating_surface() {
+    let mut e = Environment::create(Strategist::default());
+    let sid = SurfaceId::new(1);
+    e.add_surface(sid);
+    let area = e.get_area(sid);
+
+    e.press_button();
+    e.exhibitor.on_surface_move_requested(sid);
+    e.exhibitor.on_motion(Vector::new(5, 7));
+    assert_eq!(e.get_area(sid), Area::new(area.pos + Vector::new(5, 7), area.size));
+
+    e.release_button();
+    e.exhibitor.on_motion(Vector::new(5, 7));
+    assert_eq!(e.get_area(sid), Area::new(area.pos + Vector::new(5, 7), area.size));
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if floating surface is resized by bottom-right edges and `RESIZING` state is set only
+/// until button is released.
+#[test]
+fn test_resizing_floating_surface_by_bottom_right_edges() {
+    let mut e = Environment::create(Strategist::default());
+    let sid = SurfaceId::new(1);
+    e.add_surface(sid);
+    let area = e.get_area(sid);
+
+    e.press_button();
+    e.exhibitor.on_surface_resize_requested(sid, resize_edge::BOTTOM | resize_edge::RIGHT);
+    e.exhibitor.on_motion(Vector::new(5, 7));
+    let expected_size = Size::new(area.size.width + 5, area.size.height + 7);
+    assert_eq!(e.get_area(sid), Area::new(area.pos, expected_size));
+
+    let info = e.coordinator.get_surface(sid).unwrap();
+    assert_eq!(info.desired_size, expected_size);
+    assert!(info.state_flags.contains(surface_state::RESIZING));
+
+    e.release_button();
+    let info = e.coordinator.get_surface(sid).unwrap();
+    assert_eq!(info.desired_size, expected_size);
+    assert!(!info.state_flags.contains(surface_state::RESIZING));
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if dragging top-left edges of floating surface resizes and moves it so the bottom-right
+/// corner stays in place.
+#[test]
+fn test_resizing_floating_surface_by_top_left_edges() {
+    let mut e = Environment::create(Strategist::default());
+    let sid = SurfaceId::new(1);
+    e.add_surface(sid);
+    let area = e.get_area(sid);
+
+    e.press_button();
+    e.exhibitor.on_surface_resize_requested(sid, resize_edge::TOP | resize_edge::LEFT);
+    e.exhibitor.on_motion(Vector::new(-5, -7));
+    let expected_size = Size::new(area.size.width + 5, area.size.height + 7);
+    let expected_position = area.pos - Vector::new(5, 7);
+    assert_eq!(e.get_area(sid), Area::new(expected_position, expected_size));
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if surface stops moving when it reached minimal size while resizing by top-left edges.
+#[test]
+fn test_resizing_floating_surface_by_top_left_edges_below_minimal_size() {
+    let mut e = Environment::create(Strategist::default());
+    let sid = SurfaceId::new(1);
+    e.add_surface(sid);
+    let area = e.get_area(sid);
+
+    e.press_button();
+    e.exhibitor.on_surface_resize_requested(sid, resize_edge::TOP | resize_edge::LEFT);
+    let vector = Vector::new(area.size.width as isize + 10, area.size.height as isize + 10);
+    e.exhibitor.on_motion(vector);
+    let new_area = e.get_area(sid);
+    assert_eq!(new_area.size, Size::new(1, 1));
+    assert_eq!(new_area.pos + Vector::new(1, 1), area.pos + Vector::new(area.size.width as isize,
+                                                                        area.size.height as isize));
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if move and resize requested by client are refused when no button is pressed.
+#[test]
+fn test_refusing_move_and_resize_without_pressed_button() {
+    let mut e = Environment::create(Strategist::default());
+    let sid = SurfaceId::new(1);
+    e.add_surface(sid);
+    let area = e.get_area(sid);
+
+    e.exhibitor.on_surface_move_requested(sid);
+    e.exhibitor.on_motion(Vector::new(5, 7));
+    assert_eq!(e.get_area(sid), area);
+
+    e.exhibitor.on_surface_resize_requested(sid, resize_edge::BOTTOM | resize_edge::RIGHT);
+    e.exhibitor.on_motion(Vector::new(5, 7));
+    assert_eq!(e.get_area(sid), area);
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if resizing anchored surface changes size of its neighbour.
+#[test]
+fn test_resizing_anchored_surface() {
+    let mut config = common::configurations::strategist();
+    config.choose_target = "anchored_but_popups".to_owned();
+    let mut e = Environment::create(Strategist::new_from_config(config));
+
+    let sid1 = SurfaceId::new(1);
+    let sid2 = SurfaceId::new(2);
+    e.add_surface(sid1);
+    e.add_surface(sid2);
+    e.exhibitor.horizontalize();
+
+    let (left, right) = if e.get_area(sid1).pos.x == 0 { (sid1, sid2) } else { (sid2, sid1) };
+    assert_eq!(e.get_area(left), Area::new(Position::new(0, 0), Size::new(50, 100)));
+    assert_eq!(e.get_area(right), Area::new(Position::new(50, 0), Size::new(50, 100)));
+
+    e.press_button();
+    e.exhibitor.on_surface_resize_requested(left, resize_edge::RIGHT);
+    e.exhibitor.on_motion(Vector::new(10, 0));
+    assert_eq!(e.get_area(left), Area::new(Position::new(0, 0), Size::new(60, 100)));
+    assert_eq!(e.get_area(right), Area::new(Position::new(60, 0), Size::new(40, 100)));
+
+    let info = e.coordinator.get_surface(left).unwrap();
+    assert!(info.state_flags.contains(surface_state::RESIZING));
+    let info = e.coordinator.get_surface(right).unwrap();
+    assert!(!info.state_flags.contains(surface_state::RESIZING));
+
+    e.release_button();
+    let info = e.coordinator.get_surface(left).unwrap();
+    assert!(!info.state_flags.contains(surface_state::RESIZING));
+}
+
+// -------------------------------------------------------------------------------------------------
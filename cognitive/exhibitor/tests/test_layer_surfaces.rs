@@ -0,0 +1,179 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! Unit tests of placing surfaces in layers.
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+
+extern crate cognitive_qualia as qualia;
+extern crate cognitive_outputs as outputs;
+extern crate cognitive_frames as frames;
+extern crate cognitive_exhibitor as exhibitor;
+
+mod common;
+
+use qualia::{Area, Margins, OutputInfo, Position, PresentationTime, Size, SurfaceId};
+use qualia::SurfaceViewer;
+use qualia::{layer_anchor, Layer, LayerProperties};
+use qualia::coordinator_mock::CoordinatorMock;
+use outputs::output_mock::OutputMock;
+use exhibitor::{Exhibitor, Strategist};
+
+// -------------------------------------------------------------------------------------------------
+
+struct Environment {
+    exhibitor: Exhibitor<CoordinatorMock>,
+    coordinator: CoordinatorMock,
+    output: OutputMock,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl Environment {
+    pub fn create() -> Self {
+        let mut config = common::configurations::strategist();
+        config.choose_target = "anchored_but_popups".to_owned();
+        let strategist = Strategist::new_from_config(config);
+        let output_info = OutputInfo::new(1,
+                                          Area::new(Position::new(0, 0), Size::new(100, 100)),
+                                          Size::new(100, 100),
+                                          60,
+                                          "test_make".to_owned(),
+                                          "test_model".to_owned());
+
+        let output = OutputMock::new(output_info);
+        let coordinator = CoordinatorMock::new();
+        let mut exhibitor = Exhibitor::new(coordinator.clone(),
+                                           std::time::Instant::now(),
+                                           strategist,
+                                           common::configurations::compositor());
+
+        exhibitor.on_output_found(Box::new(output.clone()));
+
+        Environment {
+            exhibitor: exhibitor,
+            coordinator: coordinator,
+            output: output,
+        }
+    }
+
+    pub fn add_surface(&mut self, sid: SurfaceId) {
+        self.coordinator.add_surface(sid);
+        self.exhibitor.on_surface_ready(sid);
+    }
+
+    pub fn add_layer_surface(&mut self, sid: SurfaceId, properties: LayerProperties) {
+        self.coordinator.add_surface(sid);
+        self.exhibitor.on_layer_surface(sid, properties, None);
+        self.exhibitor.on_surface_ready(sid);
+    }
+
+    pub fn get_size(&self, sid: SurfaceId) -> Size {
+        self.coordinator.get_surface(sid).expect("Get surface").desired_size
+    }
+
+    pub fn get_drawn_sids(&mut self) -> Vec<SurfaceId> {
+        self.exhibitor.on_notify();
+        self.exhibitor.on_pageflip(1, PresentationTime::default());
+        self.output.get_drawn_surfaces().iter().map(|c| c.id).collect()
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if panel anchored to top edge is stretched along the edge and if its exclusive zone
+/// shrinks area of workspace.
+#[test]
+fn test_reserving_exclusive_zone() {
+    let mut e = Environment::create();
+    e.add_surface(SurfaceId::new(1));
+    assert_eq!(e.get_size(SurfaceId::new(1)), Size::new(100, 100));
+
+    let mut panel = LayerProperties::new(Layer::Top);
+    panel.anchor = layer_anchor::TOP | layer_anchor::LEFT | layer_anchor::RIGHT;
+    panel.size = Size::new(0, 10);
+    panel.exclusive_zone = 10;
+    e.add_layer_surface(SurfaceId::new(2), panel);
+
+    assert_eq!(e.get_size(SurfaceId::new(2)), Size::new(100, 10));
+    assert_eq!(e.get_size(SurfaceId::new(1)), Size::new(100, 90));
+    assert_eq!(e.exhibitor.get_selection().calculate_global_position(),
+               Position::new(0, 10));
+
+    e.exhibitor.on_surface_destroyed(SurfaceId::new(2));
+    assert_eq!(e.get_size(SurfaceId::new(1)), Size::new(100, 100));
+    assert_eq!(e.exhibitor.get_selection().calculate_global_position(),
+               Position::new(0, 0));
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if margins of exclusive surface are reserved too and if surfaces without exclusive zone
+/// are placed outside of reserved area unless their exclusive zone is negative.
+#[test]
+fn test_placing_surfaces_around_exclusive_zones() {
+    let mut e = Environment::create();
+    e.add_surface(SurfaceId::new(1));
+
+    let mut dock = LayerProperties::new(Layer::Bottom);
+    dock.anchor = layer_anchor::LEFT;
+    dock.size = Size::new(10, 50);
+    dock.margins = Margins::new(0, 0, 0, 5);
+    dock.exclusive_zone = 10;
+    e.add_layer_surface(SurfaceId::new(2), dock);
+
+    let mut launcher = LayerProperties::new(Layer::Overlay);
+    launcher.anchor = layer_anchor::TOP | layer_anchor::BOTTOM | layer_anchor::LEFT;
+    launcher.size = Size::new(30, 0);
+    e.add_layer_surface(SurfaceId::new(3), launcher);
+
+    let mut background = LayerProperties::new(Layer::Background);
+    background.anchor = layer_anchor::TOP | layer_anchor::BOTTOM |
+                        layer_anchor::LEFT | layer_anchor::RIGHT;
+    background.exclusive_zone = -1;
+    e.add_layer_surface(SurfaceId::new(4), background);
+
+    assert_eq!(e.get_size(SurfaceId::new(1)), Size::new(85, 100));
+    assert_eq!(e.get_size(SurfaceId::new(2)), Size::new(10, 50));
+    assert_eq!(e.get_size(SurfaceId::new(3)), Size::new(30, 100));
+    assert_eq!(e.get_size(SurfaceId::new(4)), Size::new(100, 100));
+    assert_eq!(e.exhibitor.get_selection().calculate_global_position(),
+               Position::new(15, 0));
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if fullscreen surface is drawn above top layer but below overlay layer and if it goes back
+/// below top layer after leaving fullscreen.
+#[test]
+fn test_drawing_fullscreen_surface_between_top_and_overlay_layers() {
+    let mut e = Environment::create();
+    e.add_surface(SurfaceId::new(1));
+
+    let mut panel = LayerProperties::new(Layer::Top);
+    panel.anchor = layer_anchor::TOP | layer_anchor::LEFT | layer_anchor::RIGHT;
+    panel.size = Size::new(0, 10);
+    e.add_layer_surface(SurfaceId::new(2), panel);
+
+    let mut launcher = LayerProperties::new(Layer::Overlay);
+    launcher.size = Size::new(30, 30);
+    e.add_layer_surface(SurfaceId::new(3), launcher);
+
+    let mut background = LayerProperties::new(Layer::Background);
+    background.anchor = layer_anchor::TOP | layer_anchor::BOTTOM |
+                        layer_anchor::LEFT | layer_anchor::RIGHT;
+    e.add_layer_surface(SurfaceId::new(4), background);
+
+    assert_eq!(e.get_drawn_sids(),
+               vec![SurfaceId::new(4), SurfaceId::new(1), SurfaceId::new(2), SurfaceId::new(3)]);
+
+    e.exhibitor.on_surface_fullscreen_requested(SurfaceId::new(1), true, None);
+    assert_eq!(e.get_drawn_sids(),
+               vec![SurfaceId::new(4), SurfaceId::new(2), SurfaceId::new(1), SurfaceId::new(3)]);
+
+    e.exhibitor.on_surface_fullscreen_requested(SurfaceId::new(1), false, None);
+    assert_eq!(e.get_drawn_sids(),
+               vec![SurfaceId::new(4), SurfaceId::new(1), SurfaceId::new(2), SurfaceId::new(3)]);
+}
+
+// -------------------------------------------------------------------------------------------------
@@ -0,0 +1,220 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! Unit tests of maximizing surfaces and making them fullscreen.
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+
+extern crate cognitive_qualia as qualia;
+extern crate cognitive_outputs as outputs;
+extern crate cognitive_frames as frames;
+extern crate cognitive_exhibitor as exhibitor;
+
+mod common;
+
+use qualia::{OutputInfo, SurfaceId, SurfaceViewer};
+use qualia::{Area, Position, Size};
+use qualia::surface_state;
+use qualia::coordinator_mock::CoordinatorMock;
+use outputs::output_mock::OutputMock;
+use frames::{Mobility, Searching};
+use exhibitor::{Exhibitor, Strategist};
+use common::exhibitor_mixins::ExhibitorCommandShorthands;
+
+// -------------------------------------------------------------------------------------------------
+
+struct Environment {
+    exhibitor: Exhibitor<CoordinatorMock>,
+    coordinator: CoordinatorMock,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl Environment {
+    pub fn create(strategist: Strategist) -> Self {
+        let output_info = OutputInfo::new(1,
+                                          Area::new(Position::new(0, 0), Size::new(100, 100)),
+                                          Size::new(100, 100),
+                                          60,
+                                          "test_make".to_owned(),
+                                          "test_model".to_owned());
+
+        let output = Box::new(OutputMock::new(output_info.clone()));
+        let coordinator = CoordinatorMock::new();
+        let mut exhibitor = Exhibitor::new(coordinator.clone(),
+                                           std::time::Instant::now(),
+                                           strategist,
+                                           common::configurations::compositor());
+
+        exhibitor.on_output_found(output);
+
+        Environment {
+            exhibitor: exhibitor,
+            coordinator: coordinator,
+        }
+    }
+
+    pub fn create_anchored() -> Self {
+        let mut config = common::configurations::strategist();
+        config.choose_target = "anchored_but_popups".to_owned();
+        Self::create(Strategist::new_from_config(config))
+    }
+
+    pub fn add_surface(&mut self, sid: SurfaceId) {
+        self.coordinator.add_surface(sid);
+        self.exhibitor.on_surface_ready(sid);
+    }
+
+    pub fn get_area(&self, sid: SurfaceId) -> Area {
+        self.exhibitor.get_root().find_with_sid(sid).expect("find frame").get_area()
+    }
+
+    pub fn get_global_area(&self, sid: SurfaceId) -> Area {
+        let frame = self.exhibitor.get_root().find_with_sid(sid).expect("find frame");
+        Area::new(frame.calculate_global_position(), frame.get_size())
+    }
+
+    pub fn get_mobility(&self, sid: SurfaceId) -> Mobility {
+        self.exhibitor.get_root().find_with_sid(sid).expect("find frame").get_mobility()
+    }
+
+    pub fn get_state(&self, sid: SurfaceId) -> surface_state::SurfaceState {
+        self.coordinator.get_surface(sid).expect("get surface").state_flags
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if maximized anchored surface covers whole workspace and after unmaximizing returns to
+/// its place in frame layout.
+#[test]
+fn test_maximizing_anchored_surface() {
+    let mut e = Environment::create_anchored();
+    let sid1 = SurfaceId::new(1);
+    let sid2 = SurfaceId::new(2);
+    e.add_surface(sid1);
+    e.add_surface(sid2);
+    e.exhibitor.horizontalize();
+
+    let (left, right) = if e.get_area(sid1).pos.x == 0 { (sid1, sid2) } else { (sid2, sid1) };
+    let left_area = Area::new(Position::new(0, 0), Size::new(50, 100));
+    let right_area = Area::new(Position::new(50, 0), Size::new(50, 100));
+    assert_eq!(e.get_area(left), left_area);
+    assert_eq!(e.get_area(right), right_area);
+
+    e.exhibitor.on_surface_maximization_requested(left, true);
+    assert_eq!(e.get_global_area(left), Area::new(Position::new(0, 0), Size::new(100, 100)));
+    assert_eq!(e.get_mobility(left), Mobility::Floating);
+    assert_eq!(e.exhibitor.get_selection().get_sid(), left);
+    assert!(e.get_state(left).contains(surface_state::MAXIMIZED));
+
+    e.exhibitor.on_surface_maximization_requested(left, false);
+    assert_eq!(e.get_area(left), left_area);
+    assert_eq!(e.get_area(right), right_area);
+    assert_eq!(e.get_mobility(left), Mobility::Anchored);
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if fullscreen surface covers whole display including dock and after leaving fullscreen
+/// returns to its previous area.
+#[test]
+fn test_fullscreen_floating_surface_above_dock() {
+    let mut e = Environment::create(Strategist::default());
+    let sid = SurfaceId::new(1);
+    e.add_surface(sid);
+    e.exhibitor.on_dock_surface(SurfaceId::new(2), Size::new(100, 10), 1);
+    let area = e.get_area(sid);
+
+    e.exhibitor.on_surface_fullscreen_requested(sid, true, None);
+    assert_eq!(e.get_global_area(sid), Area::new(Position::new(0, 0), Size::new(100, 100)));
+    assert_eq!(e.coordinator.get_surface(sid).unwrap().desired_size, Size::new(100, 100));
+    assert!(e.get_state(sid).contains(surface_state::FULLSCREEN));
+
+    e.exhibitor.on_surface_fullscreen_requested(sid, false, None);
+    assert_eq!(e.get_area(sid), area);
+    assert!(!e.get_state(sid).contains(surface_state::FULLSCREEN));
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if unmaximizing fullscreen surface does not bring it back from fullscreen.
+#[test]
+fn test_unmaximizing_fullscreen_surface() {
+    let mut e = Environment::create(Strategist::default());
+    let sid = SurfaceId::new(1);
+    e.add_surface(sid);
+    let area = e.get_area(sid);
+
+    e.exhibitor.on_surface_fullscreen_requested(sid, true, Some(1));
+    e.exhibitor.on_surface_maximization_requested(sid, false);
+    assert_eq!(e.get_area(sid), Area::new(Position::new(0, 0), Size::new(100, 100)));
+    assert!(e.get_state(sid).contains(surface_state::FULLSCREEN));
+
+    e.exhibitor.on_surface_fullscreen_requested(sid, false, None);
+    assert_eq!(e.get_area(sid), area);
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if maximization requested before surface was ready is applied when it becomes ready.
+#[test]
+fn test_maximizing_surface_before_it_is_ready() {
+    let mut e = Environment::create(Strategist::default());
+    let sid = SurfaceId::new(1);
+    e.coordinator.add_surface(sid);
+    e.exhibitor.on_surface_maximization_requested(sid, true);
+    e.exhibitor.on_surface_ready(sid);
+
+    assert_eq!(e.get_area(sid), Area::new(Position::new(0, 0), Size::new(100, 100)));
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if maximization and fullscreen commands toggle state of selected surface.
+#[test]
+fn test_toggling_maximization_and_fullscreen() {
+    let mut e = Environment::create(Strategist::default());
+    let sid = SurfaceId::new(1);
+    e.add_surface(sid);
+    let area = e.get_area(sid);
+
+    e.exhibitor.toggle_maximization();
+    assert_eq!(e.get_area(sid), Area::new(Position::new(0, 0), Size::new(100, 100)));
+    e.exhibitor.toggle_maximization();
+    assert_eq!(e.get_area(sid), area);
+
+    e.exhibitor.toggle_fullscreen();
+    assert!(e.get_state(sid).contains(surface_state::FULLSCREEN));
+    e.exhibitor.toggle_fullscreen();
+    assert!(!e.get_state(sid).contains(surface_state::FULLSCREEN));
+    assert_eq!(e.get_area(sid), area);
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if surface made fullscreen on other display returns to the workspace it was lifted from.
+#[test]
+fn test_leaving_fullscreen_on_other_display() {
+    let mut e = Environment::create(Strategist::default());
+    let output_info = OutputInfo::new(2,
+                                      Area::new(Position::new(100, 0), Size::new(100, 100)),
+                                      Size::new(100, 100),
+                                      60,
+                                      "test_make".to_owned(),
+                                      "test_model".to_owned());
+    e.exhibitor.on_output_found(Box::new(OutputMock::new(output_info)));
+
+    let sid = SurfaceId::new(1);
+    e.add_surface(sid);
+    let area = e.get_global_area(sid);
+    let (other_id, other_x) = if area.pos.x < 100 { (2, 100) } else { (1, 0) };
+
+    e.exhibitor.on_surface_fullscreen_requested(sid, true, Some(other_id));
+    assert_eq!(e.get_global_area(sid), Area::new(Position::new(other_x, 0), Size::new(100, 100)));
+
+    e.exhibitor.on_surface_fullscreen_requested(sid, false, None);
+    assert_eq!(e.get_global_area(sid), area);
+}
+
+// -------------------------------------------------------------------------------------------------
@@ -0,0 +1,149 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! Unit tests of minimizing surfaces and restoring them from stash.
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+
+extern crate cognitive_qualia as qualia;
+extern crate cognitive_outputs as outputs;
+extern crate cognitive_frames as frames;
+extern crate cognitive_exhibitor as exhibitor;
+
+mod common;
+
+use qualia::{OutputInfo, SurfaceId};
+use qualia::{Area, Position, Size};
+use qualia::coordinator_mock::CoordinatorMock;
+use outputs::output_mock::OutputMock;
+use frames::Searching;
+use exhibitor::{Exhibitor, Strategist};
+use common::exhibitor_mixins::ExhibitorCommandShorthands;
+
+// -------------------------------------------------------------------------------------------------
+
+struct Environment {
+    exhibitor: Exhibitor<CoordinatorMock>,
+    coordinator: CoordinatorMock,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl Environment {
+    pub fn create(strategist: Strategist) -> Self {
+        let output_info = OutputInfo::new(1,
+                                          Area::new(Position::new(0, 0), Size::new(100, 100)),
+                                          Size::new(100, 100),
+                                          60,
+                                          "test_make".to_owned(),
+                                          "test_model".to_owned());
+
+        let output = Box::new(OutputMock::new(output_info.clone()));
+        let coordinator = CoordinatorMock::new();
+        let mut exhibitor = Exhibitor::new(coordinator.clone(),
+                                           std::time::Instant::now(),
+                                           strategist,
+                                           common::configurations::compositor());
+
+        exhibitor.on_output_found(output);
+
+        Environment {
+            exhibitor: exhibitor,
+            coordinator: coordinator,
+        }
+    }
+
+    pub fn create_anchored() -> Self {
+        let mut config = common::configurations::strategist();
+        config.choose_target = "anchored_but_popups".to_owned();
+        Self::create(Strategist::new_from_config(config))
+    }
+
+    pub fn add_surface(&mut self, sid: SurfaceId) {
+        self.coordinator.add_surface(sid);
+        self.exhibitor.on_surface_ready(sid);
+    }
+
+    pub fn get_area(&self, sid: SurfaceId) -> Area {
+        self.exhibitor.get_root().find_with_sid(sid).expect("find frame").get_area()
+    }
+
+    pub fn is_in_layout(&self, sid: SurfaceId) -> bool {
+        self.exhibitor.get_root().find_with_sid(sid).is_some()
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if minimized anchored surface is removed from layout letting its neighbour take its space
+/// and after restoring it is placed back in workspace and selected.
+#[test]
+fn test_minimizing_and_restoring_anchored_surface() {
+    let mut e = Environment::create_anchored();
+    let sid1 = SurfaceId::new(1);
+    let sid2 = SurfaceId::new(2);
+    e.add_surface(sid1);
+    e.add_surface(sid2);
+    e.exhibitor.horizontalize();
+    assert_eq!(e.exhibitor.get_selection().get_sid(), sid2);
+
+    e.exhibitor.minimize();
+    assert!(!e.is_in_layout(sid2));
+    assert_eq!(e.get_area(sid1), Area::new(Position::new(0, 0), Size::new(100, 100)));
+    assert_eq!(e.exhibitor.get_selection().get_sid(), sid1);
+
+    e.exhibitor.restore();
+    assert!(e.is_in_layout(sid2));
+    assert_eq!(e.exhibitor.get_selection().get_sid(), sid2);
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if minimized surfaces are restored starting from the most recently used one and floating
+/// surfaces keep their area.
+#[test]
+fn test_restoring_in_history_order() {
+    let mut e = Environment::create(Strategist::default());
+    let sid1 = SurfaceId::new(1);
+    let sid2 = SurfaceId::new(2);
+    let sid3 = SurfaceId::new(3);
+    e.add_surface(sid1);
+    e.add_surface(sid2);
+    e.add_surface(sid3);
+    let area2 = e.get_area(sid2);
+
+    e.exhibitor.on_surface_minimization_requested(sid2);
+    e.exhibitor.on_surface_minimization_requested(sid3);
+    assert!(!e.is_in_layout(sid2));
+    assert!(!e.is_in_layout(sid3));
+    assert_eq!(e.exhibitor.get_selection().get_sid(), sid1);
+
+    e.exhibitor.restore();
+    assert!(e.is_in_layout(sid3));
+    assert!(!e.is_in_layout(sid2));
+
+    e.exhibitor.restore();
+    assert!(e.is_in_layout(sid2));
+    assert_eq!(e.get_area(sid2), area2);
+    assert_eq!(e.exhibitor.get_selection().get_sid(), sid2);
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if destroying minimized surface removes it from stash.
+#[test]
+fn test_destroying_minimized_surface() {
+    let mut e = Environment::create(Strategist::default());
+    let sid1 = SurfaceId::new(1);
+    let sid2 = SurfaceId::new(2);
+    e.add_surface(sid1);
+    e.add_surface(sid2);
+
+    e.exhibitor.minimize();
+    e.exhibitor.on_surface_destroyed(sid2);
+    e.exhibitor.restore();
+    assert!(!e.is_in_layout(sid2));
+    assert_eq!(e.exhibitor.get_selection().get_sid(), sid1);
+}
+
+// -------------------------------------------------------------------------------------------------
@@ -0,0 +1,151 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! Unit tests of skipping drawing of surfaces covered by opaque surfaces.
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+
+extern crate cognitive_qualia as qualia;
+extern crate cognitive_outputs as outputs;
+extern crate cognitive_frames as frames;
+extern crate cognitive_exhibitor as exhibitor;
+
+mod common;
+
+use qualia::{Area, OutputInfo, Position, Size, SurfaceContext, SurfaceId, SurfaceManagement};
+use qualia::{perceptron, Perceptron, PresentationTime, SignalId};
+use qualia::coordinator_mock::CoordinatorMock;
+use outputs::output_mock::OutputMock;
+use exhibitor::{Exhibitor, Strategist};
+
+// -------------------------------------------------------------------------------------------------
+
+struct Environment {
+    exhibitor: Exhibitor<CoordinatorMock>,
+    coordinator: CoordinatorMock,
+    output: OutputMock,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl Environment {
+    pub fn create() -> Self {
+        let mut config = common::configurations::strategist();
+        config.choose_target = "anchored_but_popups".to_owned();
+        let strategist = Strategist::new_from_config(config);
+        let output_info = OutputInfo::new(1,
+                                          Area::new(Position::new(0, 0), Size::new(100, 100)),
+                                          Size::new(100, 100),
+                                          60,
+                                          "test_make".to_owned(),
+                                          "test_model".to_owned());
+
+        let output = OutputMock::new(output_info);
+        let coordinator = CoordinatorMock::new();
+        let mut exhibitor = Exhibitor::new(coordinator.clone(),
+                                           std::time::Instant::now(),
+                                           strategist,
+                                           common::configurations::compositor());
+
+        exhibitor.on_output_found(Box::new(output.clone()));
+
+        Environment {
+            exhibitor: exhibitor,
+            coordinator: coordinator,
+            output: output,
+        }
+    }
+
+    pub fn add_surface(&mut self, sid: SurfaceId) {
+        self.coordinator.add_surface(sid);
+        self.exhibitor.on_surface_ready(sid);
+    }
+
+    pub fn redraw(&mut self) -> Vec<SurfaceContext> {
+        self.exhibitor.on_notify();
+        self.exhibitor.on_pageflip(1, PresentationTime::default());
+        self.output.get_drawn_surfaces()
+    }
+
+    pub fn context(id: u64) -> SurfaceContext {
+        SurfaceContext::new(SurfaceId::new(id), Position::new(0, 0))
+    }
+
+    /// Returns IDs of surfaces for which given signal was emitted.
+    pub fn get_signaled_sids(signals: &Vec<(SignalId, Perceptron)>,
+                             signal_id: SignalId)
+                             -> Vec<SurfaceId> {
+        signals.iter()
+            .filter(|&&(id, _)| id == signal_id)
+            .filter_map(|&(_, ref package)| match *package {
+                Perceptron::SurfaceFrame(sid, _) => Some(sid),
+                Perceptron::SurfaceDiscarded(sid) => Some(sid),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if surface entirely covered by opaque surface is not drawn.
+#[test]
+fn test_culling_surface_covered_by_opaque_surface() {
+    let mut e = Environment::create();
+    e.add_surface(SurfaceId::new(1));
+    e.add_surface(SurfaceId::new(2));
+    e.coordinator.set_surface_opaque_region(SurfaceId::new(2), vec![Area::create(0, 0, 100, 100)]);
+
+    assert_eq!(e.redraw(), vec![Environment::context(2)]);
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if surface covered by union of opaque areas is not drawn.
+#[test]
+fn test_culling_surface_covered_by_many_opaque_areas() {
+    let mut e = Environment::create();
+    e.add_surface(SurfaceId::new(1));
+    e.add_surface(SurfaceId::new(2));
+    e.coordinator.set_surface_opaque_region(SurfaceId::new(2), vec![Area::create(0,  0, 100, 50),
+                                                                    Area::create(0, 50, 100, 50)]);
+
+    assert_eq!(e.redraw(), vec![Environment::context(2)]);
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if surfaces only partially covered by opaque surfaces or covered by translucent surfaces
+/// are drawn.
+#[test]
+fn test_drawing_partially_covered_surface() {
+    let mut e = Environment::create();
+    e.add_surface(SurfaceId::new(1));
+    e.add_surface(SurfaceId::new(2));
+    assert_eq!(e.redraw(), vec![Environment::context(1), Environment::context(2)]);
+
+    e.coordinator.set_surface_opaque_region(SurfaceId::new(2), vec![Area::create(0, 0, 100, 99)]);
+    e.coordinator.damage_surface(SurfaceId::new(2), Area::create(0, 0, 100, 100));
+    assert_eq!(e.redraw(), vec![Environment::context(1), Environment::context(2)]);
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if frame notifications are sent only for drawn surfaces and culled surfaces are reported
+/// as discarded.
+#[test]
+fn test_notifying_only_drawn_surfaces() {
+    let mut e = Environment::create();
+    e.add_surface(SurfaceId::new(1));
+    e.add_surface(SurfaceId::new(2));
+    e.coordinator.set_surface_opaque_region(SurfaceId::new(2), vec![Area::create(0, 0, 100, 100)]);
+
+    e.redraw();
+    let signals = e.coordinator.take_signals();
+    assert_eq!(Environment::get_signaled_sids(&signals, perceptron::SURFACE_FRAME),
+               vec![SurfaceId::new(2)]);
+    assert_eq!(Environment::get_signaled_sids(&signals, perceptron::SURFACE_DISCARDED),
+               vec![SurfaceId::new(1)]);
+}
+
+// -------------------------------------------------------------------------------------------------
@@ -0,0 +1,175 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! Unit tests of locking and confining pointer.
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+
+extern crate cognitive_qualia as qualia;
+extern crate cognitive_outputs as outputs;
+extern crate cognitive_frames as frames;
+extern crate cognitive_exhibitor as exhibitor;
+
+mod common;
+
+use qualia::{Area, OutputInfo, Position, PresentationTime, Size, SurfaceId, Vector};
+use qualia::{PointerConstraint, PointerConstraintKind, SurfaceAccess, SurfaceControl};
+use qualia::surface_state;
+use qualia::coordinator_mock::CoordinatorMock;
+use outputs::output_mock::OutputMock;
+use exhibitor::{Exhibitor, Strategist};
+
+// -------------------------------------------------------------------------------------------------
+
+struct Environment {
+    exhibitor: Exhibitor<CoordinatorMock>,
+    coordinator: CoordinatorMock,
+    output: OutputMock,
+    csid: SurfaceId,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl Environment {
+    pub fn create() -> Self {
+        let mut config = common::configurations::strategist();
+        config.choose_target = "anchored_but_popups".to_owned();
+        let strategist = Strategist::new_from_config(config);
+        let output_info = OutputInfo::new(1,
+                                          Area::new(Position::new(0, 0), Size::new(100, 100)),
+                                          Size::new(100, 100),
+                                          60,
+                                          "test_make".to_owned(),
+                                          "test_model".to_owned());
+
+        let output = OutputMock::new(output_info);
+        let mut coordinator = CoordinatorMock::new();
+        let mut exhibitor = Exhibitor::new(coordinator.clone(),
+                                           std::time::Instant::now(),
+                                           strategist,
+                                           common::configurations::compositor());
+
+        exhibitor.on_output_found(Box::new(output.clone()));
+        let csid = SurfaceId::new(100);
+        coordinator.add_surface(csid);
+        coordinator.reconfigure(csid, Size::new(1, 1), surface_state::REGULAR);
+
+        Environment {
+            exhibitor: exhibitor,
+            coordinator: coordinator,
+            output: output,
+            csid: csid,
+        }
+    }
+
+    /// Adds surface covering whole display, focuses it and sets cursor surface after pointer
+    /// entered it.
+    pub fn add_surface(&mut self, sid: SurfaceId) {
+        self.coordinator.add_surface(sid);
+        self.exhibitor.on_surface_ready(sid);
+        self.exhibitor.on_keyboard_focus_changed(sid);
+        self.redraw();
+        self.exhibitor.on_cursor_surface_change(self.csid);
+        self.redraw();
+    }
+
+    pub fn constrain(&mut self, sid: SurfaceId, kind: PointerConstraintKind, region: Option<Area>) {
+        let constraint = PointerConstraint::new(kind, region.map(|area| vec![area]));
+        self.coordinator.set_surface_pointer_constraint(sid, Some(constraint));
+        self.redraw();
+    }
+
+    pub fn unconstrain(&mut self, sid: SurfaceId) {
+        self.coordinator.set_surface_pointer_constraint(sid, None);
+    }
+
+    pub fn move_pointer(&mut self, x: isize, y: isize) -> Position {
+        self.exhibitor.on_motion(Vector::new(x, y));
+        self.redraw();
+        let csid = self.csid;
+        self.output.get_drawn_layover().iter()
+            .find(|c| c.id == csid)
+            .expect("Get cursor")
+            .pos
+    }
+
+    pub fn redraw(&mut self) {
+        self.exhibitor.on_notify();
+        self.exhibitor.on_pageflip(1, PresentationTime::default());
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if locked pointer does not move and if it moves again after the constraint was removed.
+#[test]
+fn test_locking_pointer() {
+    let mut e = Environment::create();
+    e.add_surface(SurfaceId::new(1));
+    assert_eq!(e.move_pointer(0, 0), Position::new(50, 50));
+
+    e.constrain(SurfaceId::new(1), PointerConstraintKind::Lock, None);
+    assert_eq!(e.move_pointer(10, 10), Position::new(50, 50));
+    assert_eq!(e.move_pointer(-20, 5), Position::new(50, 50));
+
+    e.unconstrain(SurfaceId::new(1));
+    assert_eq!(e.move_pointer(10, 10), Position::new(60, 60));
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if confined pointer can move only inside the region of the constraint.
+#[test]
+fn test_confining_pointer() {
+    let mut e = Environment::create();
+    e.add_surface(SurfaceId::new(1));
+
+    let region = Area::new(Position::new(40, 40), Size::new(20, 20));
+    e.constrain(SurfaceId::new(1), PointerConstraintKind::Confine, Some(region));
+    assert_eq!(e.move_pointer(5, 5), Position::new(55, 55));
+    assert_eq!(e.move_pointer(30, -5), Position::new(59, 50));
+    assert_eq!(e.move_pointer(-5, -30), Position::new(54, 40));
+    assert_eq!(e.move_pointer(-30, 30), Position::new(40, 59));
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if constraint is activated only when pointer enters its region and if it is released when
+/// the surface is destroyed.
+#[test]
+fn test_activating_and_releasing_constraint() {
+    let mut e = Environment::create();
+    e.add_surface(SurfaceId::new(1));
+
+    let region = Area::new(Position::new(0, 0), Size::new(20, 20));
+    e.constrain(SurfaceId::new(1), PointerConstraintKind::Lock, Some(region));
+    assert_eq!(e.move_pointer(-20, -20), Position::new(30, 30));
+    assert_eq!(e.move_pointer(-15, -15), Position::new(15, 15));
+    assert_eq!(e.move_pointer(-5, -5), Position::new(15, 15));
+
+    e.exhibitor.on_surface_destroyed(SurfaceId::new(1));
+    assert_eq!(e.move_pointer(5, 5), Position::new(20, 20));
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if constraint is activated only when the surface has keyboard focus and if it is released
+/// when keyboard focus moves to other surface.
+#[test]
+fn test_activating_constraint_only_for_keyboard_focused_surface() {
+    let mut e = Environment::create();
+    e.add_surface(SurfaceId::new(1));
+    e.exhibitor.on_keyboard_focus_changed(SurfaceId::new(2));
+
+    e.constrain(SurfaceId::new(1), PointerConstraintKind::Lock, None);
+    assert_eq!(e.move_pointer(10, 10), Position::new(60, 60));
+
+    e.exhibitor.on_keyboard_focus_changed(SurfaceId::new(1));
+    assert_eq!(e.move_pointer(0, 0), Position::new(60, 60));
+    assert_eq!(e.move_pointer(10, 10), Position::new(60, 60));
+
+    e.exhibitor.on_keyboard_focus_changed(SurfaceId::new(2));
+    assert_eq!(e.move_pointer(-20, -20), Position::new(40, 40));
+}
+
+// -------------------------------------------------------------------------------------------------
@@ -13,7 +13,7 @@ extern crate cognitive_exhibitor as exhibitor;
 mod common;
 
 use qualia::{InteractionMode, OutputInfo, SurfaceId};
-use qualia::{Area, Position, Size, Vector};
+use qualia::{Area, Position, PresentationTime, Size, Vector};
 use qualia::coordinator_mock::CoordinatorMock;
 use outputs::output_mock::OutputMock;
 use frames::Geometry::{Stacked, Vertical};
@@ -72,8 +72,8 @@ impl Environment {
 
     pub fn redraw(&mut self) {
         self.exhibitor.on_notify();
-        self.exhibitor.on_pageflip(1);
-        self.exhibitor.on_pageflip(2);
+        self.exhibitor.on_pageflip(1, PresentationTime::default());
+        self.exhibitor.on_pageflip(2, PresentationTime::default());
     }
 
     pub fn create_surface(&mut self, id: u64) {
@@ -0,0 +1,166 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! Unit tests of touchscreen input.
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+
+extern crate cognitive_qualia as qualia;
+extern crate cognitive_outputs as outputs;
+extern crate cognitive_frames as frames;
+extern crate cognitive_exhibitor as exhibitor;
+
+mod common;
+
+use std::time::Duration;
+
+use qualia::{OutputInfo, Milliseconds, Slide, SurfaceId, Touch, Transform};
+use qualia::{Area, Position, Size, SurfaceManagement};
+use qualia::coordinator_mock::CoordinatorMock;
+use outputs::output_mock::OutputMock;
+use exhibitor::{Exhibitor, Strategist};
+use common::exhibitor_mixins::ExhibitorCommandShorthands;
+
+// -------------------------------------------------------------------------------------------------
+
+struct Environment {
+    exhibitor: Exhibitor<CoordinatorMock>,
+    coordinator: CoordinatorMock,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl Environment {
+    pub fn create() -> Self {
+        Self::create_with_transform(Transform::Normal)
+    }
+
+    pub fn create_with_transform(transform: Transform) -> Self {
+        let mut config = common::configurations::strategist();
+        config.choose_target = "anchored_but_popups".to_owned();
+        let strategist = Strategist::new_from_config(config);
+        let mut output_info = OutputInfo::new(1,
+                                              Area::new(Position::new(0, 0), Size::new(100, 100)),
+                                              Size::new(100, 100),
+                                              60,
+                                              "test_make".to_owned(),
+                                              "test_model".to_owned());
+        output_info.transform = transform;
+
+        let output = Box::new(OutputMock::new(output_info.clone()));
+        let coordinator = CoordinatorMock::new();
+        let mut exhibitor = Exhibitor::new(coordinator.clone(),
+                                           std::time::Instant::now(),
+                                           strategist,
+                                           common::configurations::compositor());
+
+        exhibitor.on_output_found(output);
+
+        Environment {
+            exhibitor: exhibitor,
+            coordinator: coordinator,
+        }
+    }
+
+    pub fn add_surface(&mut self, sid: SurfaceId) {
+        self.coordinator.add_surface(sid);
+        self.exhibitor.on_surface_ready(sid);
+    }
+
+    pub fn time() -> Milliseconds {
+        Milliseconds::from_duration(&Duration::from_millis(0))
+    }
+
+    pub fn touch(&self, id: i32, x: f32, y: f32) -> Touch {
+        Touch::new(id, Slide::new(x, y), Self::time())
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if touched surface gets selected and selection does not change when touch point moves
+/// over other surface.
+#[test]
+fn test_touching_selects_touched_surface() {
+    let mut e = Environment::create();
+    let sid1 = SurfaceId::new(1);
+    let sid2 = SurfaceId::new(2);
+    e.add_surface(sid1);
+    e.add_surface(sid2);
+    e.exhibitor.horizontalize();
+    assert_eq!(e.exhibitor.get_selection().get_sid(), sid2);
+
+    let touch = e.touch(0, 0.75, 0.5);
+    e.exhibitor.on_touch_down(touch);
+    e.exhibitor.on_touch_frame();
+    assert_eq!(e.exhibitor.get_selection().get_sid(), sid1);
+
+    let touch = e.touch(0, 0.25, 0.5);
+    e.exhibitor.on_touch_motion(touch);
+    e.exhibitor.on_touch_frame();
+    assert_eq!(e.exhibitor.get_selection().get_sid(), sid1);
+
+    e.exhibitor.on_touch_up(0, Environment::time());
+    e.exhibitor.on_touch_frame();
+
+    let touch = e.touch(1, 0.25, 0.5);
+    e.exhibitor.on_touch_down(touch);
+    assert_eq!(e.exhibitor.get_selection().get_sid(), sid2);
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if touch position is transformed the same way as contents of rotated output.
+#[test]
+fn test_touching_rotated_output() {
+    let mut e = Environment::create_with_transform(Transform::Rotated90);
+    let sid1 = SurfaceId::new(1);
+    let sid2 = SurfaceId::new(2);
+    e.add_surface(sid1);
+    e.add_surface(sid2);
+    e.exhibitor.horizontalize();
+    assert_eq!(e.exhibitor.get_selection().get_sid(), sid2);
+
+    // Top of the panel is the right side of rotated output.
+    let touch = e.touch(0, 0.5, 0.25);
+    e.exhibitor.on_touch_down(touch);
+    e.exhibitor.on_touch_up(0, Environment::time());
+    e.exhibitor.on_touch_frame();
+    assert_eq!(e.exhibitor.get_selection().get_sid(), sid1);
+
+    // Bottom of the panel is the left side of rotated output.
+    let touch = e.touch(1, 0.5, 0.75);
+    e.exhibitor.on_touch_down(touch);
+    e.exhibitor.on_touch_up(1, Environment::time());
+    e.exhibitor.on_touch_frame();
+    assert_eq!(e.exhibitor.get_selection().get_sid(), sid2);
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if touches outside input region of surface are ignored.
+#[test]
+fn test_touching_outside_input_region() {
+    let mut e = Environment::create();
+    let sid1 = SurfaceId::new(1);
+    let sid2 = SurfaceId::new(2);
+    e.add_surface(sid1);
+    e.add_surface(sid2);
+    e.exhibitor.horizontalize();
+    e.coordinator.set_surface_input_region(sid1, Some(vec![Area::create(0, 0, 25, 100)]));
+    assert_eq!(e.exhibitor.get_selection().get_sid(), sid2);
+
+    let touch = e.touch(0, 0.9, 0.5);
+    e.exhibitor.on_touch_down(touch);
+    e.exhibitor.on_touch_up(0, Environment::time());
+    e.exhibitor.on_touch_frame();
+    assert_eq!(e.exhibitor.get_selection().get_sid(), sid2);
+
+    let touch = e.touch(1, 0.6, 0.5);
+    e.exhibitor.on_touch_down(touch);
+    e.exhibitor.on_touch_up(1, Environment::time());
+    e.exhibitor.on_touch_frame();
+    assert_eq!(e.exhibitor.get_selection().get_sid(), sid1);
+}
+
+// -------------------------------------------------------------------------------------------------
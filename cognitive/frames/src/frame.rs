@@ -8,7 +8,7 @@
 use std::{fmt, mem, ptr};
 use std::default::Default;
 
-use qualia::{SurfaceId, Area, Position, Size};
+use qualia::{SurfaceId, Area, Margins, Position, Size};
 
 // -------------------------------------------------------------------------------------------------
 
@@ -128,6 +128,7 @@ pub enum Mode {
     Root,
     Display { id: i32 },
     Workspace { is_active: bool },
+    Stash,
     Container,
     Leaf,
 }
@@ -153,6 +154,11 @@ impl Mode {
         }
     }
 
+    /// Returns `true` if mode is `Stash`, `false` otherwise.
+    pub fn is_stash(&self) -> bool {
+        *self == Mode::Stash
+    }
+
     /// Returns `true` if mode is `Leaf`, `false` otherwise.
     pub fn is_leaf(&self) -> bool {
         *self == Mode::Leaf
@@ -196,6 +202,9 @@ pub struct Parameters {
     /// Size.
     pub size: Size,
 
+    /// Space along edges not covered by children.
+    pub margins: Margins,
+
     /// Title.
     pub title: String,
 }
@@ -212,6 +221,7 @@ impl Parameters {
             mode: Mode::Root,
             pos: Position::default(),
             size: Size::default(),
+            margins: Margins::default(),
             title: "root".to_owned(),
         }
     }
@@ -225,6 +235,7 @@ impl Parameters {
             mode: Mode::Display { id: id },
             pos: area.pos,
             size: area.size,
+            margins: Margins::default(),
             title: title,
         }
     }
@@ -238,10 +249,25 @@ impl Parameters {
             mode: Mode::Workspace { is_active: active },
             pos: Position::default(),
             size: Size::default(),
+            margins: Margins::default(),
             title: title,
         }
     }
 
+    /// Creates new parameters for stash frame.
+    pub fn new_stash() -> Self {
+        Parameters {
+            sid: SurfaceId::invalid(),
+            geometry: Geometry::Stacked,
+            mobility: Mobility::Floating,
+            mode: Mode::Stash,
+            pos: Position::default(),
+            size: Size::default(),
+            margins: Margins::default(),
+            title: "stash".to_owned(),
+        }
+    }
+
     /// Creates new parameters for container frame.
     pub fn new_container(geometry: Geometry) -> Self {
         Parameters {
@@ -251,6 +277,7 @@ impl Parameters {
             mode: Mode::Container,
             pos: Position::default(),
             size: Size::default(),
+            margins: Margins::default(),
             title: "".to_owned(),
         }
     }
@@ -267,6 +294,7 @@ impl Parameters {
             mode: Mode::Leaf,
             pos: Position::default(),
             size: Size::default(),
+            margins: Margins::default(),
             title: "".to_owned(),
         }
     }
@@ -313,6 +341,7 @@ impl Frame {
                                mode: mode,
                                pos: pos,
                                size: size,
+                               margins: Margins::default(),
                                title: title,
                            },
                            node: Node::default(),
@@ -343,6 +372,15 @@ impl Frame {
                        })
     }
 
+    /// Creates new stash frame. Stash is not part of frame layout. It keeps frames removed from
+    /// layout without destroying them.
+    pub fn new_stash() -> Self {
+        Self::allocate(InnerFrame {
+                           params: Parameters::new_stash(),
+                           node: Node::default(),
+                       })
+    }
+
     /// Creates new container frame.
     pub fn new_container(geometry: Geometry) -> Self {
         Self::allocate(InnerFrame {
@@ -439,6 +477,12 @@ impl Frame {
         Area::new(self.get_position(), self.get_size())
     }
 
+    /// Gets margins.
+    #[inline]
+    pub fn get_margins(&self) -> Margins {
+        unsafe { (*self.inner).params.margins }
+    }
+
     /// Gets title.
     pub fn get_title(&self) -> String {
         unsafe { (*self.inner).params.title.clone() }
@@ -505,6 +549,14 @@ impl Frame {
         }
     }
 
+    /// Sets margins without adjusting any sizes an positions.
+    #[inline]
+    pub fn set_plumbing_margins(&mut self, margins: Margins) {
+        unsafe {
+            (*self.inner).params.margins = margins;
+        }
+    }
+
     /// Sets geometry without adjusting any sizes an positions.
     #[inline]
     pub fn set_plumbing_geometry(&mut self, geometry: Geometry) {
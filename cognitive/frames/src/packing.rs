@@ -5,7 +5,7 @@
 
 // -------------------------------------------------------------------------------------------------
 
-use qualia::{Position, Size, Vector};
+use qualia::{Area, Margins, Position, Size, Vector};
 use qualia::{SurfaceAccess, surface_state};
 
 use frame::{Frame, Geometry, Mobility};
@@ -27,6 +27,10 @@ pub trait Packing {
     /// Set size of the frame and resize its subframe accordingly.
     fn set_size(&mut self, size: Size, sa: &mut SurfaceAccess);
 
+    /// Set margins of the frame and resize and reposition its subframes so they do not cover
+    /// them.
+    fn set_margins(&mut self, margins: Margins, sa: &mut SurfaceAccess);
+
     /// Remove given frame and relax old parent.
     fn remove_self(&mut self, sa: &mut SurfaceAccess);
 }
@@ -45,11 +49,12 @@ impl Packing for Frame {
         }
 
         // Decide how to resize and move twigs
+        let inner = Area::new(Position::default(), self.get_size()).shrunk(&self.get_margins());
         let mut size = Size::new(0, 0);
         let mut increment = Vector::new(0, 0);
         match self.get_geometry() {
             Geometry::Stacked => {
-                size = self.get_size();
+                size = inner.size;
             }
             Geometry::Vertical => {
                 let mut docked_height = 0;
@@ -58,8 +63,8 @@ impl Packing for Frame {
                         docked_height += frame.get_size().height;
                     }
                 }
-                size.width = self.get_size().width;
-                size.height = (self.get_size().height - docked_height) / len;
+                size.width = inner.size.width;
+                size.height = inner.size.height.saturating_sub(docked_height) / len;
                 increment.y = size.height as isize;
             }
             Geometry::Horizontal => {
@@ -69,14 +74,14 @@ impl Packing for Frame {
                         docked_width += frame.get_size().width;
                     }
                 }
-                size.height = self.get_size().height;
-                size.width = (self.get_size().width - docked_width) / len;
+                size.height = inner.size.height;
+                size.width = inner.size.width.saturating_sub(docked_width) / len;
                 increment.x = size.width as isize;
             }
         }
 
         // Resize and reposition all subframes recursively
-        let mut pos = Position::default();
+        let mut pos = inner.pos;
         for mut frame in self.space_iter() {
             match frame.get_mobility() {
                 Mobility::Anchored => {
@@ -87,8 +92,14 @@ impl Packing for Frame {
                 Mobility::Docked => {
                     match self.get_geometry() {
                         Geometry::Stacked => {}
-                        Geometry::Vertical => pos.y += frame.get_size().height as isize,
-                        Geometry::Horizontal => pos.x += frame.get_size().width as isize,
+                        Geometry::Vertical => {
+                            frame.set_plumbing_position(pos);
+                            pos.y += frame.get_size().height as isize;
+                        }
+                        Geometry::Horizontal => {
+                            frame.set_plumbing_position(pos);
+                            pos.x += frame.get_size().width as isize;
+                        }
                     }
                 }
                 Mobility::Floating => {}
@@ -108,12 +119,13 @@ impl Packing for Frame {
         sa.reconfigure(self.get_sid(), size.clone(), surface_state::MAXIMIZED);
 
         // Set size to frames children.
+        let inner_size = Area::new(Position::default(), size).shrunk(&self.get_margins()).size;
         match self.get_geometry() {
             Geometry::Horizontal => {
                 if old_size.width == size.width {
                     for mut frame in self.space_iter() {
                         let mut frame_size = frame.get_size();
-                        frame_size.height = size.height;
+                        frame_size.height = inner_size.height;
                         frame.set_size(frame_size, sa);
                     }
                 } else {
@@ -124,7 +136,7 @@ impl Packing for Frame {
                 if old_size.height == size.height {
                     for mut frame in self.space_iter() {
                         let mut frame_size = frame.get_size();
-                        frame_size.width = size.width;
+                        frame_size.width = inner_size.width;
                         frame.set_size(frame_size, sa);
                     }
                 } else {
@@ -134,13 +146,20 @@ impl Packing for Frame {
             Geometry::Stacked => {
                 for mut frame in self.space_iter() {
                     if !frame.get_mobility().is_floating() {
-                        frame.set_size(size.clone(), sa);
+                        frame.set_size(inner_size, sa);
                     }
                 }
             }
         }
     }
 
+    fn set_margins(&mut self, margins: Margins, sa: &mut SurfaceAccess) {
+        if self.get_margins() != margins {
+            self.set_plumbing_margins(margins);
+            self.relax(sa);
+        }
+    }
+
     fn remove_self(&mut self, sa: &mut SurfaceAccess) {
         if let Some(ref mut parent) = self.get_parent() {
             self.remove();
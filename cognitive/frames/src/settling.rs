@@ -5,7 +5,7 @@
 
 // -------------------------------------------------------------------------------------------------
 
-use qualia::{Area, Direction, Position, Vector, Size, SurfaceAccess, SurfaceId};
+use qualia::{Area, Direction, Margins, Position, Vector, Size, SurfaceAccess, SurfaceId};
 
 use frame::{Frame, Geometry, Mobility, Mode, Side};
 use searching::Searching;
@@ -75,6 +75,14 @@ pub trait Settling {
     /// Move the frame and all subframes by given vector.
     fn move_with_contents(&mut self, vector: Vector);
 
+    /// Removes frame `self` from frame layout and places it in `stash` without destroying it.
+    /// Mobility, position and size of the frame are preserved.
+    fn stash(&mut self, stash: &mut Frame, sa: &mut SurfaceAccess);
+
+    /// Takes frame `self` out of stash and settles it on given target. Floating frame keeps its
+    /// area.
+    fn unstash(&mut self, target: &mut Frame, sa: &mut SurfaceAccess);
+
     /// Removes frame `self`, relaxes old parent and destroys the frame.
     fn destroy_self(&mut self, sa: &mut SurfaceAccess);
 }
@@ -178,10 +186,12 @@ impl Settling for Frame {
                                        self.get_position(),
                                        self.get_size(),
                                        self.get_title());
+        distancer.set_plumbing_margins(self.get_margins());
         self.prejoin(&mut distancer);
         self.remove();
         self.set_plumbing_mobility(Mobility::Anchored);
         self.set_plumbing_mode(frame_mode);
+        self.set_plumbing_margins(Margins::default());
         self.set_plumbing_position(Position::default());
         distancer.prepend(self);
         distancer
@@ -347,8 +357,29 @@ impl Settling for Frame {
         self.set_plumbing_position(new_position);
     }
 
-    fn destroy_self(&mut self, sa: &mut SurfaceAccess) {
+    fn stash(&mut self, stash: &mut Frame, sa: &mut SurfaceAccess) {
         self.remove_self(sa);
+        stash.prepend(self);
+    }
+
+    fn unstash(&mut self, target: &mut Frame, sa: &mut SurfaceAccess) {
+        let area = if self.get_mobility().is_floating() {
+            Some(self.get_area())
+        } else {
+            None
+        };
+
+        // NOTE: Stash is not part of frame layout so there is no need to relax it.
+        self.remove();
+        self.settle(target, area, sa);
+    }
+
+    fn destroy_self(&mut self, sa: &mut SurfaceAccess) {
+        if self.get_parent().map_or(false, |parent| parent.get_mode().is_stash()) {
+            self.remove();
+        } else {
+            self.remove_self(sa);
+        }
         self.destroy();
     }
 }
@@ -5,7 +5,9 @@
 
 // -------------------------------------------------------------------------------------------------
 
-use qualia::{SurfaceAccess, SurfaceId, Size, surface_state};
+use std::collections::HashMap;
+
+use qualia::{Area, SurfaceAccess, SurfaceId, Size, surface_state};
 
 // -------------------------------------------------------------------------------------------------
 
@@ -31,6 +33,10 @@ impl SurfaceAccess for SurfaceAccessMock {
                    _size: Size,
                    _state_flags: surface_state::SurfaceState) {
     }
+
+    fn take_surfaces_damage(&mut self) -> HashMap<SurfaceId, Vec<Area>> {
+        HashMap::new()
+    }
 }
 
 // -------------------------------------------------------------------------------------------------
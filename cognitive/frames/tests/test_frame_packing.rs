@@ -12,7 +12,7 @@ extern crate cognitive_frames as frames;
 
 mod common;
 
-use qualia::{Position, Size};
+use qualia::{Margins, Position, Size};
 use frames::Packing;
 use common::{assertions, layouts, surface_access_mock};
 
@@ -89,6 +89,48 @@ fn test_homogenizing_horizontal_with_docked() {
 
 // -------------------------------------------------------------------------------------------------
 
+/// Test if setting margins makes subframes avoid them while docked and floating frames keep their
+/// sizes.
+#[test]
+fn test_setting_margins() {
+    let mut sa = surface_access_mock::SurfaceAccessMock::new();
+    let (mut r, a, b, c, d, e) = layouts::make_sized_for_homogenizing_vertical_with_docked();
+
+    r.set_margins(Margins::new(5, 10, 15, 20), &mut sa);
+
+    assertions::assert_area(&r, Position::new( 0,   0), Size::new(100, 130));
+    assertions::assert_area(&a, Position::new(20,   5), Size::new(100,  10));
+    assertions::assert_area(&b, Position::new(20,  15), Size::new( 70,  40));
+    assertions::assert_area(&c, Position::new(20,  55), Size::new( 70,  40));
+    assertions::assert_area(&d, Position::new(20,  95), Size::new(100,  20));
+    assertions::assert_area(&e, Position::new( 8,   9), Size::new( 12,  13));
+
+    r.destroy();
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Test if setting margins leaving less space than docked frames take does not overflow and
+/// shrinks anchored frames to zero.
+#[test]
+fn test_setting_margins_larger_than_space_for_docked() {
+    let mut sa = surface_access_mock::SurfaceAccessMock::new();
+    let (mut r, a, b, c, d, e) = layouts::make_sized_for_homogenizing_vertical_with_docked();
+
+    r.set_margins(Margins::new(60, 0, 60, 0), &mut sa);
+
+    assertions::assert_area(&r, Position::new(0,  0), Size::new(100, 130));
+    assertions::assert_area(&a, Position::new(0, 60), Size::new(100,  10));
+    assertions::assert_area(&b, Position::new(0, 70), Size::new(100,   0));
+    assertions::assert_area(&c, Position::new(0, 70), Size::new(100,   0));
+    assertions::assert_area(&d, Position::new(0, 70), Size::new(100,  20));
+    assertions::assert_area(&e, Position::new(8,  9), Size::new( 12,  13));
+
+    r.destroy();
+}
+
+// -------------------------------------------------------------------------------------------------
+
 /// Test if removing frame with siblings works correctly.
 #[test]
 fn test_removing_self_with_siblings() {
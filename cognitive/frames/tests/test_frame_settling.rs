@@ -14,8 +14,9 @@ mod common;
 
 use qualia::{Position, Size, SurfaceId};
 use qualia::Direction::{North, East, South, West};
-use frames::{Frame, Parameters, Settling};
+use frames::{Frame, Parameters, Searching, Settling};
 use frames::Geometry::{Horizontal, Stacked, Vertical};
+use frames::Mobility::{Anchored, Floating};
 use frames::Side::{Before, On, After};
 use frames::representation::FrameRepresentation;
 use common::{assertions, layouts, surface_access_mock};
@@ -661,3 +662,60 @@ fn test_resizing_horizontal_anchored() {
 }
 
 // -------------------------------------------------------------------------------------------------
+
+/// Tests stashing and unstashing floating frame.
+///
+/// Stashed frame should be removed from layout. After unstashing it should keep its area.
+#[test]
+fn test_stashing_floating() {
+    let mut sa = surface_access_mock::SurfaceAccessMock::new();
+    let (mut r, _, _, _, _, _, _, _, _, _, _, _, _, _, _, mut z) =
+        layouts::make_sized_for_homogenizing();
+    let mut stash = Frame::new_stash();
+    let area = z.get_area();
+
+    z.stash(&mut stash, &mut sa);
+    assert!(r.find_with_sid(SurfaceId::new(9)).is_none());
+    assert!(stash.find_with_sid(SurfaceId::new(9)).is_some());
+    assert_eq!(r.count_children(), 1);
+
+    z.unstash(&mut r, &mut sa);
+    assert_eq!(stash.count_children(), 0);
+    assert!(z.get_parent().unwrap().equals_exact(&r));
+    assert_eq!(z.get_mobility(), Floating);
+    assertions::assert_area(&z, area.pos, area.size);
+
+    r.destroy();
+    stash.destroy();
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Tests stashing and unstashing anchored frame.
+///
+/// Neighbours of stashed frame should take its space. After unstashing the frame should be
+/// anchored in target frame.
+#[test]
+fn test_stashing_anchored() {
+    let mut sa = surface_access_mock::SurfaceAccessMock::new();
+    let (r, abcdefghi, mut hi, abcdef, _, _, _, _, _, _, _, _, mut g, _, _, _) =
+        layouts::make_sized_for_homogenizing();
+    let mut stash = Frame::new_stash();
+
+    g.stash(&mut stash, &mut sa);
+    assert_eq!(abcdefghi.count_children(), 2);
+    assert!(stash.find_with_sid(SurfaceId::new(7)).is_some());
+    assertions::assert_area(&abcdef, Position::new(0, 0), Size::new(180, 361));
+    assertions::assert_area(&hi, Position::new(180, 0), Size::new(180, 361));
+
+    g.unstash(&mut hi, &mut sa);
+    assert_eq!(stash.count_children(), 0);
+    assert_eq!(hi.count_children(), 3);
+    assert!(g.get_parent().unwrap().equals_exact(&hi));
+    assert_eq!(g.get_mobility(), Anchored);
+
+    r.destroy();
+    stash.destroy();
+}
+
+// -------------------------------------------------------------------------------------------------
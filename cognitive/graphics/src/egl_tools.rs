@@ -68,6 +68,7 @@ pub mod ext {
     // Extension names
     pub const IMAGE_BASE_EXT: &'static str = "EGL_KHR_image_base";
     pub const IMAGE_EXTERNAL_EXT: &'static str = "GL_OES_EGL_image_external";
+    pub const BUFFER_AGE_EXT: &'static str = "EGL_EXT_buffer_age";
 
     pub const DRM_BUFFER_USE_SHARE_MESA: egl::EGLint = 0x00000002;
     pub const OPENGL_ES3_BIT_KHR: egl::EGLint = 0x00000040;
@@ -80,6 +81,8 @@ pub mod ext {
 
     pub const PLATFORM_GBM_KHR: egl::EGLenum = 0x31D7;
 
+    pub const BUFFER_AGE: egl::EGLint = 0x313D;
+
     pub const LINUX_DMA_BUF_EXT: egl::EGLenum = 0x3270;
     pub const LINUX_DRM_FOURCC_EXT: egl::EGLint = 0x3271;
     pub const DMA_BUF_PLANE0_FD_EXT: egl::EGLint = 0x3272;
@@ -512,6 +515,17 @@ impl EglContext {
                                            egl::get_error())))
         }
     }
+
+    /// Returns age of back buffer (number of frames since its contents were drawn, 0 if they are
+    /// undefined) or `None` if age could not be queried. Requires `EGL_EXT_buffer_age` extension.
+    pub fn get_buffer_age(&self) -> Option<u32> {
+        let mut age = 0;
+        if egl::query_surface(self.egl.display, self.egl.surface, ext::BUFFER_AGE, &mut age) {
+            Some(age as u32)
+        } else {
+            None
+        }
+    }
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -8,12 +8,14 @@
 
 use libgbm;
 use libdrm::drm_mode;
+use libdrm::ffi::drm_mode::DRM_MODE_TYPE_PREFERRED;
+use libdrm::ffi::xf86drm_mode::{drmModeGetConnector, drmModeFreeConnector, drmModeSubPixel};
 use std::collections::HashMap;
 use std::collections::VecDeque;
 
 use graphics::{egl_tools, gbm_tools};
-use qualia::{Buffer, DrmBundle, Illusion, SurfaceContext, SurfaceViewer};
-use qualia::{Area, OutputInfo, Position, Size};
+use qualia::{Buffer, DecorationContext, DrmBundle, Illusion, SurfaceContext, SurfaceViewer};
+use qualia::{Area, OutputInfo, OutputMode, Position, Size, Subpixel, Transform};
 use renderer_gl::RendererGl;
 
 use output::Output;
@@ -32,6 +34,12 @@ pub struct DrmOutput {
     /// Size of the output in pixels.
     size: Size,
 
+    /// Factor by which size in pixels is bigger than size in logical coordinates.
+    scale: u32,
+
+    /// Rotation and flipping of output contents.
+    transform: Transform,
+
     /// Size of the output in millimeters.
     physical_size: Size,
 
@@ -53,6 +61,12 @@ pub struct DrmOutput {
     /// DRM mode.
     mode: drm_mode::ModeInfo,
 
+    /// All modes supported by the output. The first one is currently used.
+    modes: Vec<OutputMode>,
+
+    /// Subpixel layout of the output.
+    subpixel: Subpixel,
+
     /// Renderer.
     renderer: RendererGl,
 
@@ -76,7 +90,9 @@ impl DrmOutput {
         let size;
         let modes;
         let physical_size;
+        let name;
         if let Some(connector) = drm_mode::get_connector(drm.fd, drm.connector_id) {
+            name = Self::make_connector_name(&connector);
             modes = connector.get_modes();
             mode = modes.get(0).unwrap().clone();
             size = Size::new(mode.get_hdisplay() as usize, mode.get_vdisplay() as usize);
@@ -96,14 +112,31 @@ impl DrmOutput {
         // Create renderer
         let renderer = RendererGl::new(egl, size.clone());
 
+        // Gather informations about modes
+        let output_modes = modes.iter()
+            .enumerate()
+            .map(|(i, mode)| {
+                let is_preferred = (mode.get_mode_type() & DRM_MODE_TYPE_PREFERRED as u32) != 0;
+                OutputMode::new(Size::new(mode.get_hdisplay() as usize,
+                                          mode.get_vdisplay() as usize),
+                                1000 * mode.get_vrefresh() as usize,
+                                i == 0,
+                                is_preferred)
+            })
+            .collect();
+
         // Create output
         let mut mine = DrmOutput {
             id: id,
             position: Position::default(),
             size: size,
+            scale: 1,
+            transform: Transform::Normal,
             physical_size: physical_size,
-            name: "".to_owned(),
+            name: name,
             renderer: renderer,
+            modes: output_modes,
+            subpixel: Self::read_subpixel(&drm),
             mode: mode,
             drm: drm,
             gbm: gbm,
@@ -128,10 +161,17 @@ impl Output for DrmOutput {
     fn draw(&mut self,
             layunder: &Vec<SurfaceContext>,
             surfaces: &Vec<SurfaceContext>,
+            decorations: &Vec<DecorationContext>,
             layover: &Vec<SurfaceContext>,
+            damage: &Vec<Area>,
             viewer: &SurfaceViewer)
             -> Result<(), Illusion> {
-        self.renderer.draw(layunder, surfaces, layover, viewer)
+        self.renderer.draw(layunder, surfaces, decorations, layover, damage, viewer)
+    }
+
+    /// Returns age of the buffer to be drawn next.
+    fn get_buffer_age(&self) -> u32 {
+        self.renderer.get_buffer_age()
     }
 
     /// Takes screenshot. Returns `Buffer` containing image data.
@@ -142,14 +182,25 @@ impl Output for DrmOutput {
     /// Returns info about output.
     fn get_info(&self) -> OutputInfo {
         // TODO: Make Output aware of its position.
-        let area = Area::new(self.position, self.size);
-
-        OutputInfo::new(self.id,
-                        area,
-                        self.physical_size,
-                        60, // TODO: make output aware of its refresh rate.
-                        self.name.clone(),
-                        self.name.clone())
+        let mut size = Size::new(self.size.width / self.scale as usize,
+                                 self.size.height / self.scale as usize);
+        if self.transform.swaps_dimensions() {
+            size = Size::new(size.height, size.width);
+        }
+        let area = Area::new(self.position, size);
+
+        let mut info = OutputInfo::new(self.id,
+                                       area,
+                                       self.physical_size,
+                                       self.mode.get_vrefresh() as usize,
+                                       self.name.clone(),
+                                       self.name.clone());
+        info.name = self.name.clone();
+        info.modes = self.modes.clone();
+        info.subpixel = self.subpixel;
+        info.scale = self.scale;
+        info.transform = self.transform;
+        info
     }
 
     /// Sets global position.
@@ -157,6 +208,18 @@ impl Output for DrmOutput {
         self.position = position;
     }
 
+    /// Sets scale.
+    fn set_scale(&mut self, scale: u32) {
+        self.scale = std::cmp::max(scale, 1);
+        self.renderer.set_scale(self.scale);
+    }
+
+    /// Sets transform.
+    fn set_transform(&mut self, transform: Transform) {
+        self.transform = transform;
+        self.renderer.set_transform(transform);
+    }
+
     /// Swaps renderers and devices buffers.
     fn swap_buffers(&mut self) -> Result<u32, Illusion> {
         self.renderer.swap_buffers()?;
@@ -191,6 +254,53 @@ impl Output for DrmOutput {
 
 // Private methods
 impl DrmOutput {
+    /// Makes name of the output from type of connector the same way as kernel does (e.g.
+    /// "HDMI-A-1"). `drm_mode::Connector::get_type_name` uses different naming.
+    fn make_connector_name(connector: &drm_mode::Connector) -> String {
+        let type_name = match connector.get_connector_type() {
+            1 => "VGA",
+            2 => "DVI-I",
+            3 => "DVI-D",
+            4 => "DVI-A",
+            5 => "Composite",
+            6 => "SVIDEO",
+            7 => "LVDS",
+            8 => "Component",
+            9 => "DIN",
+            10 => "DP",
+            11 => "HDMI-A",
+            12 => "HDMI-B",
+            13 => "TV",
+            14 => "eDP",
+            15 => "Virtual",
+            16 => "DSI",
+            17 => "DPI",
+            _ => "Unknown",
+        };
+        format!("{}-{}", type_name, connector.get_connector_type_id())
+    }
+
+    /// Reads subpixel layout of the connector. `drm_mode::Connector` does not provide it.
+    fn read_subpixel(drm: &DrmBundle) -> Subpixel {
+        unsafe {
+            let connector = drmModeGetConnector(drm.fd, drm.connector_id);
+            if connector.is_null() {
+                return Subpixel::Unknown;
+            }
+
+            let subpixel = match (*connector).subpixel {
+                drmModeSubPixel::DRM_MODE_SUBPIXEL_UNKNOWN => Subpixel::Unknown,
+                drmModeSubPixel::DRM_MODE_SUBPIXEL_HORIZONTAL_RGB => Subpixel::HorizontalRgb,
+                drmModeSubPixel::DRM_MODE_SUBPIXEL_HORIZONTAL_BGR => Subpixel::HorizontalBgr,
+                drmModeSubPixel::DRM_MODE_SUBPIXEL_VERTICAL_RGB => Subpixel::VerticalRgb,
+                drmModeSubPixel::DRM_MODE_SUBPIXEL_VERTICAL_BGR => Subpixel::VerticalBgr,
+                drmModeSubPixel::DRM_MODE_SUBPIXEL_NONE => Subpixel::None,
+            };
+            drmModeFreeConnector(connector);
+            subpixel
+        }
+    }
+
     /// Swap device buffers.
     /// Create buffer if necessary.
     fn swap_gbm_buffers(&mut self) -> Result<u32, Illusion> {
@@ -3,20 +3,29 @@
 
 //! This module contains interface for all output devices or mocks.
 
-use qualia::{Buffer, Illusion, OutputInfo, Position, SurfaceContext, SurfaceViewer};
+use qualia::{Area, Buffer, Illusion, OutputInfo, Position, SurfaceContext, SurfaceViewer};
+use qualia::{DecorationContext, Transform};
 
 // -------------------------------------------------------------------------------------------------
 
 /// `Output` is representation of physical output device.
 pub trait Output {
-    /// Draws passed scene using renderer.
+    /// Draws passed scene using renderer. Only `damage` (list of areas in logical coordinates
+    /// relative to the output) is redrawn, rest of the buffer is left untouched. `decorations` are
+    /// drawn directly below corresponding `surfaces`.
     fn draw(&mut self,
             layunder: &Vec<SurfaceContext>,
             surfaces: &Vec<SurfaceContext>,
+            decorations: &Vec<DecorationContext>,
             layover: &Vec<SurfaceContext>,
+            damage: &Vec<Area>,
             viewer: &SurfaceViewer)
             -> Result<(), Illusion>;
 
+    /// Returns age of the buffer to be drawn next: number of frames since it was drawn or 0 if its
+    /// contents are undefined.
+    fn get_buffer_age(&self) -> u32;
+
     /// Takes screenshot. Returns `Buffer` containing image data.
     fn take_screenshot(&self) -> Result<Buffer, Illusion>;
 
@@ -26,6 +35,12 @@ pub trait Output {
     /// Sets global position.
     fn set_position(&mut self, position: Position);
 
+    /// Sets scale. Size of output in logical coordinates is its size in pixels divided by scale.
+    fn set_scale(&mut self, scale: u32);
+
+    /// Sets rotation and flipping of output contents.
+    fn set_transform(&mut self, transform: Transform);
+
     /// Swaps buffers.
     fn swap_buffers(&mut self) -> Result<u32, Illusion>;
 
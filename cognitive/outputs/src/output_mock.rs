@@ -6,7 +6,8 @@
 use std::cell::RefCell;
 use std::rc::Rc;
 
-use qualia::{Buffer, Illusion, OutputInfo, Position, SurfaceContext, SurfaceViewer};
+use qualia::{Area, Buffer, Illusion, OutputInfo, Position, SurfaceContext, SurfaceViewer};
+use qualia::{DecorationContext, Transform};
 
 use output::Output;
 
@@ -15,13 +16,25 @@ use output::Output;
 /// Mock of `Output`.
 pub struct InnerOutputMock {
     info: OutputInfo,
+    buffer_age: u32,
+    damage: Option<Vec<Area>>,
+    surfaces: Vec<SurfaceContext>,
+    decorations: Vec<DecorationContext>,
+    layover: Vec<SurfaceContext>,
 }
 
 // -------------------------------------------------------------------------------------------------
 
 impl InnerOutputMock {
     pub fn new(info: OutputInfo) -> Self {
-        InnerOutputMock { info: info }
+        InnerOutputMock {
+            info: info,
+            buffer_age: 1,
+            damage: None,
+            surfaces: Vec::new(),
+            decorations: Vec::new(),
+            layover: Vec::new(),
+        }
     }
 }
 
@@ -39,6 +52,37 @@ impl OutputMock {
     pub fn new(info: OutputInfo) -> Self {
         OutputMock { mock: Rc::new(RefCell::new(InnerOutputMock::new(info))) }
     }
+
+    /// Sets age of buffers returned by `get_buffer_age`.
+    pub fn set_buffer_age(&mut self, age: u32) {
+        let mut mine = self.mock.borrow_mut();
+        mine.buffer_age = age;
+    }
+
+    /// Returns damage passed to last call to `draw` or `None` if `draw` was not called since last
+    /// call to this method.
+    pub fn take_drawn_damage(&mut self) -> Option<Vec<Area>> {
+        let mut mine = self.mock.borrow_mut();
+        mine.damage.take()
+    }
+
+    /// Returns surfaces passed to last call to `draw`.
+    pub fn get_drawn_surfaces(&self) -> Vec<SurfaceContext> {
+        let mine = self.mock.borrow();
+        mine.surfaces.clone()
+    }
+
+    /// Returns decorations passed to last call to `draw`.
+    pub fn get_drawn_decorations(&self) -> Vec<DecorationContext> {
+        let mine = self.mock.borrow();
+        mine.decorations.clone()
+    }
+
+    /// Returns layover passed to last call to `draw`.
+    pub fn get_drawn_layover(&self) -> Vec<SurfaceContext> {
+        let mine = self.mock.borrow();
+        mine.layover.clone()
+    }
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -48,12 +92,24 @@ impl Output for OutputMock {
     fn draw(&mut self,
             layunder: &Vec<SurfaceContext>,
             surfaces: &Vec<SurfaceContext>,
+            decorations: &Vec<DecorationContext>,
             layover: &Vec<SurfaceContext>,
+            damage: &Vec<Area>,
             viewer: &SurfaceViewer)
             -> Result<(), Illusion> {
+        let mut mine = self.mock.borrow_mut();
+        mine.damage = Some(damage.clone());
+        mine.surfaces = surfaces.clone();
+        mine.decorations = decorations.clone();
+        mine.layover = layover.clone();
         Ok(())
     }
 
+    fn get_buffer_age(&self) -> u32 {
+        let mine = self.mock.borrow();
+        mine.buffer_age
+    }
+
     fn take_screenshot(&self) -> Result<Buffer, Illusion> {
         panic!("Taking screenshot not supported in unit test");
     }
@@ -65,6 +121,16 @@ impl Output for OutputMock {
 
     fn set_position(&mut self, _position: Position) {}
 
+    fn set_scale(&mut self, scale: u32) {
+        let mut mine = self.mock.borrow_mut();
+        mine.info.scale = scale;
+    }
+
+    fn set_transform(&mut self, transform: Transform) {
+        let mut mine = self.mock.borrow_mut();
+        mine.info.transform = transform;
+    }
+
     fn swap_buffers(&mut self) -> Result<u32, Illusion> {
         Ok(u32::default())
     }
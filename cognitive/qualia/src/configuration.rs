@@ -3,8 +3,11 @@
 
 //! Default configuration for `cognitive` entities.
 
+use std::collections::HashMap;
 use std::path::PathBuf;
 
+use enums::Transform;
+
 // -------------------------------------------------------------------------------------------------
 
 /// Configuration of aesthetics.
@@ -36,6 +39,9 @@ pub struct ExhibitorConfig {
 
     /// Configuration of strategist.
     pub strategist: StrategistConfig,
+
+    /// Configurations of outputs indexed by output name (e.g. "HDMI-A-1").
+    pub outputs: HashMap<String, OutputConfig>,
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -62,6 +68,24 @@ pub struct InputConfig {
 pub struct KeyboardConfig {
     pub layout: String,
     pub variant: String,
+
+    /// Number of key repeats per second. Zero disables repeating.
+    pub repeat_rate: u32,
+
+    /// Delay in milliseconds after pressing a key before repeating starts.
+    pub repeat_delay: u32,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Configuration of single output.
+#[derive(Clone, Debug, Serialize)]
+pub struct OutputConfig {
+    /// Factor by which size of output in pixels is bigger than its size in logical coordinates.
+    pub scale: u32,
+
+    /// Rotation and flipping of the output.
+    pub transform: Transform,
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -3,18 +3,22 @@
 
 //! This module contains wanna-be-mock of `Coordinator`. Currently it is more a stub.
 
+use std;
 use std::cell::RefCell;
 use std::collections::HashMap;
 use std::rc::Rc;
 
-use defs::{DrmBundle, Position, SignalId, Size, Vector, WorkspaceState};
+use defs::{Area, DrmBundle, Position, SignalId, Size, Vector, WorkspaceState};
 use defs::{DmabufId, EglImageId, MemoryPoolId, MemoryViewId};
-use surface::{DataSource, SurfaceContext, SurfaceId, SurfaceInfo, surface_state, show_reason};
+use enums::Transform;
+use surface::{DataSource, LayerProperties, SurfaceContext, SurfaceId, SurfaceInfo, ViewportSource};
+use surface::{resize_edge, surface_state, show_reason, PointerConstraint};
 use surface::{SurfaceManagement, SurfaceControl, SurfaceViewer};
 use surface::{SurfaceAccess, SurfaceListing, SurfaceFocusing};
 use memory::{Buffer, Memory};
 use image::PixelFormat;
 use perceptron::Perceptron;
+use timing::PresentationTime;
 use traits::{StatePublishing, Screenshooting, MemoryManagement, WindowManagement};
 use traits::ExhibitorCoordinationTrait;
 
@@ -23,13 +27,19 @@ use traits::ExhibitorCoordinationTrait;
 /// Mock of `Coordinator`.
 pub struct InnerCoordinatorMock {
     surfaces: HashMap<SurfaceId, SurfaceInfo>,
+    damage: HashMap<SurfaceId, Vec<Area>>,
+    signals: Vec<(SignalId, Perceptron)>,
 }
 
 // -------------------------------------------------------------------------------------------------
 
 impl InnerCoordinatorMock {
     pub fn new() -> Self {
-        InnerCoordinatorMock { surfaces: HashMap::new() }
+        InnerCoordinatorMock {
+            surfaces: HashMap::new(),
+            damage: HashMap::new(),
+            signals: Vec::new(),
+        }
     }
 
     pub fn add_surface(&mut self, sid: SurfaceId) {
@@ -41,10 +51,67 @@ impl InnerCoordinatorMock {
             requested_size: Size::default(),
             state_flags: surface_state::REGULAR,
             data_source: DataSource::None,
+            buffer_scale: 1,
+            buffer_transform: Transform::Normal,
+            viewport_source: None,
+            viewport_destination: None,
+            opaque_region: Vec::new(),
+            input_region: None,
+            title: String::new(),
+            app_id: String::new(),
+            is_decorated: false,
+            pointer_constraint: None,
+            global_position: None,
         };
 
         self.surfaces.insert(sid, info);
     }
+
+    pub fn set_surface_opaque_region(&mut self, sid: SurfaceId, region: Vec<Area>) {
+        if let Some(info) = self.surfaces.get_mut(&sid) {
+            info.opaque_region = region;
+        }
+    }
+
+    pub fn set_surface_input_region(&mut self, sid: SurfaceId, region: Option<Vec<Area>>) {
+        if let Some(info) = self.surfaces.get_mut(&sid) {
+            info.input_region = region;
+        }
+    }
+
+    pub fn damage_surface(&mut self, sid: SurfaceId, area: Area) {
+        self.damage.entry(sid).or_insert_with(Vec::new).push(area);
+    }
+
+    pub fn set_surface_title(&mut self, sid: SurfaceId, title: String) {
+        if let Some(info) = self.surfaces.get_mut(&sid) {
+            info.title = title;
+        }
+    }
+
+    pub fn set_surface_decorated(&mut self, sid: SurfaceId, is_decorated: bool) {
+        if let Some(info) = self.surfaces.get_mut(&sid) {
+            info.is_decorated = is_decorated;
+        }
+    }
+
+    pub fn set_surface_pointer_constraint(&mut self,
+                                          sid: SurfaceId,
+                                          constraint: Option<PointerConstraint>) {
+        if let Some(info) = self.surfaces.get_mut(&sid) {
+            info.pointer_constraint = constraint;
+        }
+    }
+
+    pub fn set_surface_global_position(&mut self, sid: SurfaceId, position: Position) {
+        if let Some(info) = self.surfaces.get_mut(&sid) {
+            info.global_position = Some(position);
+        }
+    }
+
+    pub fn take_signals(&mut self) -> Vec<(SignalId, Perceptron)> {
+        std::mem::replace(&mut self.signals, Vec::new())
+    }
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -66,6 +133,11 @@ impl CoordinatorMock {
         let mut mock = self.mock.borrow_mut();
         mock.add_surface(sid);
     }
+
+    pub fn take_signals(&mut self) -> Vec<(SignalId, Perceptron)> {
+        let mut mock = self.mock.borrow_mut();
+        mock.take_signals()
+    }
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -77,6 +149,23 @@ impl SurfaceManagement for CoordinatorMock {
     fn attach_shm(&self, _mvid: MemoryViewId, _sid: SurfaceId) {}
     fn attach_egl_image(&self, _eiid: EglImageId, _sid: SurfaceId) {}
     fn attach_dmabuf(&self, _dbid: DmabufId, _sid: SurfaceId) {}
+    fn set_surface_buffer_scale(&self, _sid: SurfaceId, _scale: u32) {}
+    fn set_surface_buffer_transform(&self, _sid: SurfaceId, _transform: Transform) {}
+    fn set_surface_viewport_source(&self, _sid: SurfaceId, _source: Option<ViewportSource>) {}
+    fn set_surface_viewport_destination(&self, _sid: SurfaceId, _destination: Option<Size>) {}
+    fn set_surface_opaque_region(&self, sid: SurfaceId, region: Vec<Area>) {
+        let mut mock = self.mock.borrow_mut();
+        mock.set_surface_opaque_region(sid, region);
+    }
+    fn set_surface_input_region(&self, sid: SurfaceId, region: Option<Vec<Area>>) {
+        let mut mock = self.mock.borrow_mut();
+        mock.set_surface_input_region(sid, region);
+    }
+    fn damage_surface(&self, sid: SurfaceId, area: Area) {
+        let mut mock = self.mock.borrow_mut();
+        mock.damage_surface(sid, area);
+    }
+    fn damage_surface_buffer(&self, _sid: SurfaceId, _area: Area) {}
     fn detach_surface(&self, _sid: SurfaceId) {}
     fn commit_surface(&self, _sid: SurfaceId) {}
     fn destroy_surface(&self, _sid: SurfaceId) {}
@@ -87,12 +176,48 @@ impl SurfaceManagement for CoordinatorMock {
 impl SurfaceControl for CoordinatorMock {
     fn show_surface(&self, _sid: SurfaceId, _reason: show_reason::ShowReason) {}
     fn dock_surface(&self, _sid: SurfaceId, _size: Size, _display_id: i32) {}
+    fn set_surface_layer(&self,
+                         _sid: SurfaceId,
+                         _properties: LayerProperties,
+                         _display_id: Option<i32>) {
+    }
     fn hide_surface(&self, _sid: SurfaceId, _reason: show_reason::ShowReason) {}
     fn set_surface_offset(&self, _sid: SurfaceId, _offset: Vector) {}
     fn set_surface_requested_size(&self, _sid: SurfaceId, _size: Size) {}
     fn set_surface_relative_position(&self, _sid: SurfaceId, _offset: Vector) {}
     fn relate_surfaces(&self, _sid: SurfaceId, _parent_sid: SurfaceId) {}
     fn unrelate_surface(&self, _sid: SurfaceId) {}
+    fn place_surface_above(&self, _sid: SurfaceId, _sibling_sid: SurfaceId) {}
+    fn place_surface_below(&self, _sid: SurfaceId, _sibling_sid: SurfaceId) {}
+    fn set_surface_synchronized(&self, _sid: SurfaceId, _is_synchronized: bool) {}
+    fn set_surface_title(&self, sid: SurfaceId, title: String) {
+        let mut mock = self.mock.borrow_mut();
+        mock.set_surface_title(sid, title);
+    }
+    fn set_surface_app_id(&self, _sid: SurfaceId, _app_id: String) {}
+    fn set_surface_decorated(&self, sid: SurfaceId, is_decorated: bool) {
+        let mut mock = self.mock.borrow_mut();
+        mock.set_surface_decorated(sid, is_decorated);
+    }
+    fn set_surface_pointer_constraint(&self,
+                                      sid: SurfaceId,
+                                      constraint: Option<PointerConstraint>) {
+        let mut mock = self.mock.borrow_mut();
+        mock.set_surface_pointer_constraint(sid, constraint);
+    }
+    fn set_surface_global_position(&self, sid: SurfaceId, position: Position) {
+        let mut mock = self.mock.borrow_mut();
+        mock.set_surface_global_position(sid, position);
+    }
+    fn request_surface_move(&self, _sid: SurfaceId) {}
+    fn request_surface_resize(&self, _sid: SurfaceId, _edges: resize_edge::ResizeEdge) {}
+    fn request_surface_maximization(&self, _sid: SurfaceId, _maximized: bool) {}
+    fn request_surface_fullscreen(&self,
+                                  _sid: SurfaceId,
+                                  _fullscreen: bool,
+                                  _display_id: Option<i32>) {
+    }
+    fn request_surface_minimization(&self, _sid: SurfaceId) {}
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -113,6 +238,17 @@ impl SurfaceViewer for CoordinatorMock {
                      requested_size: Size::default(),
                      state_flags: surface_state::REGULAR,
                      data_source: DataSource::None,
+                     buffer_scale: 1,
+                     buffer_transform: Transform::Normal,
+                     viewport_source: None,
+                     viewport_destination: None,
+                     opaque_region: Vec::new(),
+                     input_region: None,
+                     title: String::new(),
+                     app_id: String::new(),
+                     is_decorated: false,
+                     pointer_constraint: None,
+                     global_position: None,
                  })
         }
     }
@@ -127,11 +263,18 @@ impl SurfaceAccess for CoordinatorMock {
                    state_flags: surface_state::SurfaceState) {
         let mut mock = self.mock.borrow_mut();
         if let Some(mut info) = mock.surfaces.get_mut(&sid) {
-            info.desired_size = size;
-            info.requested_size = size;
+            // Client is expected to leave space for decorations.
             info.state_flags = state_flags;
+            let margins = info.get_decoration_margins();
+            info.desired_size = size;
+            info.requested_size = Area::new(Position::default(), size).shrunk(&margins).size;
         }
     }
+
+    fn take_surfaces_damage(&mut self) -> HashMap<SurfaceId, Vec<Area>> {
+        let mut mock = self.mock.borrow_mut();
+        std::mem::replace(&mut mock.damage, HashMap::new())
+    }
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -140,7 +283,9 @@ impl SurfaceListing for CoordinatorMock {
     fn get_renderer_context(&self, sid: SurfaceId) -> Option<Vec<SurfaceContext>> {
         let mock = self.mock.borrow();
         if let Some(info) = mock.surfaces.get(&sid) {
-            Some(vec![SurfaceContext::new(sid, info.offset)])
+            let margins = info.get_decoration_margins();
+            let offset = info.offset + Vector::new(margins.left as isize, margins.top as isize);
+            Some(vec![SurfaceContext::new(sid, offset)])
         } else {
             panic!("Trying to get renderer context for not existing surface {:?}", sid);
         }
@@ -163,7 +308,10 @@ impl SurfaceFocusing for CoordinatorMock {
 // -------------------------------------------------------------------------------------------------
 
 impl StatePublishing for CoordinatorMock {
-    fn emit(&mut self, _id: SignalId, _package: Perceptron) {}
+    fn emit(&mut self, id: SignalId, package: Perceptron) {
+        let mut mock = self.mock.borrow_mut();
+        mock.signals.push((id, package));
+    }
     fn suspend(&mut self) {}
     fn wakeup(&mut self) {}
     fn input_devices_changed(&mut self) {}
@@ -171,7 +319,7 @@ impl StatePublishing for CoordinatorMock {
     fn notify(&mut self) {}
     fn publish_output(&mut self, _drm_bundle: DrmBundle) {}
     fn emit_vblank(&mut self, _display_id: i32) {}
-    fn emit_page_flip(&mut self, _display_id: i32) {}
+    fn emit_page_flip(&mut self, _display_id: i32, _time: PresentationTime) {}
 }
 
 // -------------------------------------------------------------------------------------------------
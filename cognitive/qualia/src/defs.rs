@@ -354,6 +354,19 @@ impl Area {
         }
     }
 
+    /// Returns new `Area` with given margins cut off from its edges. If margins do not fit inside
+    /// the area, the resulting size is zero.
+    pub fn shrunk(&self, margins: &Margins) -> Self {
+        let width = self.size.width as isize - (margins.left + margins.right) as isize;
+        let height = self.size.height as isize - (margins.top + margins.bottom) as isize;
+        Area {
+            pos: Position::new(self.pos.x + margins.left as isize,
+                               self.pos.y + margins.top as isize),
+            size: Size::new(if width > 0 { width as usize } else { 0 },
+                            if height > 0 { height as usize } else { 0 }),
+        }
+    }
+
     /// Check if `Area` has zero area.
     pub fn is_zero(&self) -> bool {
         self.size.is_zero()
@@ -402,6 +415,68 @@ impl Area {
             self.size.height += diff as usize;
         }
     }
+
+    /// Returns common part of this `Area` and passed `area` or `None` if they do not overlap.
+    pub fn intersection(&self, area: &Area) -> Option<Area> {
+        let left = std::cmp::max(self.pos.x, area.pos.x);
+        let top = std::cmp::max(self.pos.y, area.pos.y);
+        let right = std::cmp::min(self.pos.x + self.size.width as isize,
+                                  area.pos.x + area.size.width as isize);
+        let bottom = std::cmp::min(self.pos.y + self.size.height as isize,
+                                   area.pos.y + area.size.height as isize);
+
+        if (left < right) && (top < bottom) {
+            Some(Area::create(left, top, (right - left) as usize, (bottom - top) as usize))
+        } else {
+            None
+        }
+    }
+
+    /// Returns parts of this `Area` not covered by passed `area` as list of at most four
+    /// non-overlapping areas.
+    pub fn subtract(&self, area: &Area) -> Vec<Area> {
+        let common = if let Some(common) = self.intersection(area) {
+            common
+        } else {
+            return vec![*self];
+        };
+
+        let right = self.pos.x + self.size.width as isize;
+        let bottom = self.pos.y + self.size.height as isize;
+        let common_right = common.pos.x + common.size.width as isize;
+        let common_bottom = common.pos.y + common.size.height as isize;
+
+        let mut result = Vec::with_capacity(4);
+        if common.pos.y > self.pos.y {
+            let height = (common.pos.y - self.pos.y) as usize;
+            result.push(Area::create(self.pos.x, self.pos.y, self.size.width, height));
+        }
+        if common_bottom < bottom {
+            let height = (bottom - common_bottom) as usize;
+            result.push(Area::create(self.pos.x, common_bottom, self.size.width, height));
+        }
+        if common.pos.x > self.pos.x {
+            let width = (common.pos.x - self.pos.x) as usize;
+            result.push(Area::create(self.pos.x, common.pos.y, width, common.size.height));
+        }
+        if common_right < right {
+            let width = (right - common_right) as usize;
+            result.push(Area::create(common_right, common.pos.y, width, common.size.height));
+        }
+        result
+    }
+
+    /// Checks if this `Area` is entirely covered by union of passed areas.
+    pub fn is_covered_by(&self, areas: &[Area]) -> bool {
+        let mut uncovered = vec![*self];
+        for area in areas.iter() {
+            uncovered = uncovered.iter().flat_map(|a| a.subtract(area)).collect();
+            if uncovered.is_empty() {
+                return true;
+            }
+        }
+        self.is_zero()
+    }
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -417,6 +492,76 @@ impl std::default::Default for Area {
 
 // -------------------------------------------------------------------------------------------------
 
+/// Type defining space reserved along edges of an area.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Margins {
+    pub top: usize,
+    pub right: usize,
+    pub bottom: usize,
+    pub left: usize,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl Margins {
+    /// `Margins` constructor.
+    pub fn new(top: usize, right: usize, bottom: usize, left: usize) -> Self {
+        Margins {
+            top: top,
+            right: right,
+            bottom: bottom,
+            left: left,
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl std::default::Default for Margins {
+    fn default() -> Self {
+        Margins {
+            top: 0,
+            right: 0,
+            bottom: 0,
+            left: 0,
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Set of informations about mode of output.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct OutputMode {
+    /// Size of the mode in pixels.
+    pub size: Size,
+
+    /// Refresh rate in millihertz.
+    pub refresh_rate: usize,
+
+    /// Tells if the mode is currently used.
+    pub is_current: bool,
+
+    /// Tells if the mode is preferred by the output.
+    pub is_preferred: bool,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl OutputMode {
+    /// Constructs new `OutputMode`.
+    pub fn new(size: Size, refresh_rate: usize, is_current: bool, is_preferred: bool) -> Self {
+        OutputMode {
+            size: size,
+            refresh_rate: refresh_rate,
+            is_current: is_current,
+            is_preferred: is_preferred,
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
 /// Set of informations about output.
 #[derive(Clone, Debug)]
 pub struct OutputInfo {
@@ -426,6 +571,21 @@ pub struct OutputInfo {
     pub refresh_rate: usize,
     pub make: String,
     pub model: String,
+
+    /// Name of the output (e.g. derived from connector).
+    pub name: String,
+
+    /// List of all modes supported by the output.
+    pub modes: Vec<OutputMode>,
+
+    /// Subpixel layout of the output.
+    pub subpixel: enums::Subpixel,
+
+    /// Rotation and flipping of the output.
+    pub transform: enums::Transform,
+
+    /// Scale factor of the output.
+    pub scale: u32,
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -446,8 +606,14 @@ impl OutputInfo {
             refresh_rate: refresh_rate,
             make: make,
             model: model,
+            name: String::new(),
+            modes: vec![OutputMode::new(area.size, 1000 * refresh_rate, true, false)],
+            subpixel: enums::Subpixel::Unknown,
+            transform: enums::Transform::Normal,
+            scale: 1,
         }
     }
+
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -15,6 +15,7 @@ pub enum DeviceKind {
     Keyboard,
     Mouse,
     Touchpad,
+    Touchscreen,
     Unknown,
 }
 
@@ -26,6 +27,7 @@ impl fmt::Debug for DeviceKind {
             DeviceKind::Keyboard => write!(f, "keyboard"),
             DeviceKind::Mouse => write!(f, "mouse"),
             DeviceKind::Touchpad => write!(f, "touchpad"),
+            DeviceKind::Touchscreen => write!(f, "touchscreen"),
             DeviceKind::Unknown => write!(f, "unknown device"),
         }
     }
@@ -71,6 +73,18 @@ pub enum Action {
 
     /// Change size.
     Resize,
+
+    /// Maximize; unmaximize.
+    Maximize,
+
+    /// Make fullscreen; bring back from fullscreen.
+    Fullscreen,
+
+    /// Hide in stash.
+    Minimize,
+
+    /// Bring back from stash.
+    Restore,
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -152,3 +166,87 @@ pub enum InteractionMode {
 }
 
 // -------------------------------------------------------------------------------------------------
+
+/// Describes subpixel layout of output. Values are the same as used in Wayland protocol.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Subpixel {
+    Unknown = 0,
+    None = 1,
+    HorizontalRgb = 2,
+    HorizontalBgr = 3,
+    VerticalRgb = 4,
+    VerticalBgr = 5,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Describes rotation and flipping of output or buffer contents. Rotations are counter-clockwise.
+/// Values are the same as used in Wayland protocol.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize)]
+pub enum Transform {
+    Normal = 0,
+    Rotated90 = 1,
+    Rotated180 = 2,
+    Rotated270 = 3,
+    Flipped = 4,
+    Flipped90 = 5,
+    Flipped180 = 6,
+    Flipped270 = 7,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl Transform {
+    /// Constructs `Transform` from value used in Wayland protocol.
+    pub fn from_wayland(value: i32) -> Option<Self> {
+        match value {
+            0 => Some(Transform::Normal),
+            1 => Some(Transform::Rotated90),
+            2 => Some(Transform::Rotated180),
+            3 => Some(Transform::Rotated270),
+            4 => Some(Transform::Flipped),
+            5 => Some(Transform::Flipped90),
+            6 => Some(Transform::Flipped180),
+            7 => Some(Transform::Flipped270),
+            _ => None,
+        }
+    }
+
+    /// Checks if transform rotates by quarter of full angle, i.e. if width and height of
+    /// transformed contents are swapped.
+    pub fn swaps_dimensions(&self) -> bool {
+        match *self {
+            Transform::Rotated90 |
+            Transform::Rotated270 |
+            Transform::Flipped90 |
+            Transform::Flipped270 => true,
+            _ => false,
+        }
+    }
+
+    /// Returns transform reverting this one.
+    pub fn inverted(&self) -> Self {
+        match *self {
+            Transform::Rotated90 => Transform::Rotated270,
+            Transform::Rotated270 => Transform::Rotated90,
+            transform => transform,
+        }
+    }
+
+    /// Maps point from transformed contents to untransformed ones (e.g. from surface to buffer or
+    /// from logical output space to framebuffer). Coordinates are normalized to range [0, 1].
+    pub fn map_normalized(&self, x: f32, y: f32) -> (f32, f32) {
+        match *self {
+            Transform::Normal => (x, y),
+            Transform::Rotated90 => (y, 1.0 - x),
+            Transform::Rotated180 => (1.0 - x, 1.0 - y),
+            Transform::Rotated270 => (1.0 - y, x),
+            Transform::Flipped => (1.0 - x, y),
+            Transform::Flipped90 => (y, x),
+            Transform::Flipped180 => (x, 1.0 - y),
+            Transform::Flipped270 => (1.0 - y, 1.0 - x),
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
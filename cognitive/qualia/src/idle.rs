@@ -0,0 +1,93 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! This module contains functionality related to tracking user inactivity.
+
+// -------------------------------------------------------------------------------------------------
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use defs::SurfaceId;
+
+// -------------------------------------------------------------------------------------------------
+
+/// Tracks time elapsed since last user activity and surfaces inhibiting the system from becoming
+/// idle.
+///
+/// Time is passed explicitly to all methods so the tracker does not depend on a clock.
+#[derive(Clone, Debug)]
+pub struct IdleTracker {
+    /// Moment of last user activity.
+    last_activity: Instant,
+
+    /// Surfaces requesting inhibition together with number of their inhibitors.
+    inhibitors: HashMap<SurfaceId, usize>,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl IdleTracker {
+    /// `IdleTracker` constructor. `now` is treated as moment of last activity.
+    pub fn new(now: Instant) -> Self {
+        IdleTracker {
+            last_activity: now,
+            inhibitors: HashMap::new(),
+        }
+    }
+
+    /// Registers user activity.
+    pub fn on_activity(&mut self, now: Instant) {
+        if now > self.last_activity {
+            self.last_activity = now;
+        }
+    }
+
+    /// Adds inhibitor for given surface.
+    pub fn inhibit(&mut self, sid: SurfaceId) {
+        *self.inhibitors.entry(sid).or_insert(0) += 1;
+    }
+
+    /// Removes one inhibitor of given surface. Removing inhibitor counts as activity so the
+    /// system does not become idle right after inhibition ends.
+    pub fn uninhibit(&mut self, sid: SurfaceId, now: Instant) {
+        let is_last = if let Some(count) = self.inhibitors.get_mut(&sid) {
+            *count -= 1;
+            *count == 0
+        } else {
+            false
+        };
+
+        if is_last {
+            self.inhibitors.remove(&sid);
+            if self.inhibitors.is_empty() {
+                self.on_activity(now);
+            }
+        }
+    }
+
+    /// Checks if any surface inhibits idleness.
+    pub fn is_inhibited(&self) -> bool {
+        !self.inhibitors.is_empty()
+    }
+
+    /// Returns time elapsed since last activity regardless of inhibitors.
+    pub fn get_input_idle_time(&self, now: Instant) -> Duration {
+        if now > self.last_activity {
+            now.duration_since(self.last_activity)
+        } else {
+            Duration::default()
+        }
+    }
+
+    /// Returns time elapsed since last activity or zero if idleness is inhibited.
+    pub fn get_idle_time(&self, now: Instant) -> Duration {
+        if self.is_inhibited() {
+            Duration::default()
+        } else {
+            self.get_input_idle_time(now)
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
@@ -125,6 +125,34 @@ impl Axis {
 
 // -------------------------------------------------------------------------------------------------
 
+/// Data for touch event.
+#[derive(Clone, Copy, Debug)]
+pub struct Touch {
+    /// ID of touch point. It is the same for all events from touch down to touch up.
+    pub id: i32,
+
+    /// Position of touch point as fraction of touch device size (both coordinates are in range
+    /// from `0.0` to `1.0`).
+    pub position: Slide,
+
+    pub time: timing::Milliseconds,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl Touch {
+    /// Constructs `Touch`.
+    pub fn new(id: i32, position: Slide, time: timing::Milliseconds) -> Self {
+        Touch {
+            id: id,
+            position: position,
+            time: time,
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
 /// Trait implemented by handlers of input events like key strokes.
 pub trait InputHandling: Send {
     /// Catches and processes the keyboard event.
@@ -167,8 +195,24 @@ pub trait InputForwarding: Send {
     /// Emits position reset event.
     fn emit_position_reset(&mut self);
 
+    /// Emits touch down event. Position is given as fraction of touch device size.
+    fn emit_touch_down(&mut self, id: i32, x: f32, y: f32);
+
+    /// Emits touch motion event. Position is given as fraction of touch device size.
+    fn emit_touch_motion(&mut self, id: i32, x: f32, y: f32);
+
+    /// Emits touch up event.
+    fn emit_touch_up(&mut self, id: i32);
+
+    /// Emits touch frame event ending set of touch events sent at the same time.
+    fn emit_touch_frame(&mut self);
+
     /// Emits system activity event.
     fn emit_system_activity_event(&mut self);
+
+    /// Emits user activity event. It should be emitted on every user input so idleness can be
+    /// tracked.
+    fn emit_user_activity_event(&mut self);
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -21,7 +21,7 @@ extern crate dharma;
 extern crate cognitive_graphics as graphics;
 
 pub mod enums;
-pub use enums::{DeviceKind, KeyState, Action, Direction, InteractionMode};
+pub use enums::{DeviceKind, KeyState, Action, Direction, InteractionMode, Subpixel, Transform};
 
 pub mod errors;
 pub use errors::Illusion;
@@ -30,15 +30,15 @@ pub use errors::Illusion;
 pub mod macros;
 
 pub mod timing;
-pub use timing::Milliseconds;
+pub use timing::{Milliseconds, PresentationTime};
 
 pub mod defs;
-pub use defs::{Area, Point, Position, OptionalPosition, Size, Slide, Vector};
-pub use defs::{Command, OutputInfo, DrmBundle, WorkspaceState, WorkspaceInfo};
+pub use defs::{Area, Margins, Point, Position, OptionalPosition, Size, Slide, Vector};
+pub use defs::{Command, OutputInfo, OutputMode, DrmBundle, WorkspaceState, WorkspaceInfo};
 pub use defs::{DmabufId, EglImageId, MemoryPoolId, MemoryViewId, SignalId};
 
 pub mod input;
-pub use input::{Axis, Button, Binding, Key, CatchResult, InputCode, InputValue, modifier};
+pub use input::{Axis, Button, Binding, Key, Touch, CatchResult, InputCode, InputValue, modifier};
 pub use input::{InputForwarding, InputHandling};
 
 pub mod image;
@@ -49,16 +49,21 @@ pub use memory::{Buffer, Memory, MemoryPool, MemoryView};
 
 pub mod configuration;
 pub use configuration::{AestheticsConfig, CompositorConfig, ExhibitorConfig};
-pub use configuration::{KeyboardConfig, InputConfig, StrategistConfig};
+pub use configuration::{KeyboardConfig, InputConfig, OutputConfig, StrategistConfig};
 
 pub mod surface;
 pub use surface::{SurfaceContext, SurfaceId, SurfaceIdType, SurfaceInfo, DataSource};
+pub use surface::{DecorationContext, Layer, LayerProperties, ViewportSource};
+pub use surface::{PointerConstraint, PointerConstraintKind};
 pub use surface::{SurfaceManagement, SurfaceControl, SurfaceViewer};
 pub use surface::{SurfaceAccess, SurfaceListing, SurfaceFocusing};
-pub use surface::{show_reason, surface_state};
+pub use surface::{decoration, show_reason, layer_anchor, resize_edge, surface_state};
+
+pub mod idle;
+pub use idle::IdleTracker;
 
 pub mod transfer;
-pub use transfer::Transfer;
+pub use transfer::{Transfer, dnd_action};
 
 pub mod perceptron;
 pub use perceptron::Perceptron;
@@ -12,11 +12,12 @@ use std::os::unix::io::RawFd;
 
 use dharma::SignalId;
 
-use timing::Milliseconds;
+use timing::{Milliseconds, PresentationTime};
 use enums::InteractionMode;
 use defs::{Command, OutputInfo, SurfaceId};
+use surface::{resize_edge, LayerProperties};
 use defs::{Position, OptionalPosition, Vector, Size, DrmBundle};
-use input::{Axis, Button, Key};
+use input::{Axis, Button, Key, Touch};
 
 // -------------------------------------------------------------------------------------------------
 
@@ -28,6 +29,7 @@ pub const PAGE_FLIP: SignalId = 4;
 pub const INPUTS_CHANGED: SignalId = 5;
 pub const OUTPUTS_CHANGED: SignalId = 6;
 pub const OUTPUT_FOUND: SignalId = 7;
+pub const DISPLAY_UPDATED: SignalId = 8;
 pub const DISPLAY_CREATED: SignalId = 9;
 pub const INPUT_POINTER_MOTION: SignalId = 10;
 pub const INPUT_POINTER_POSITION: SignalId = 11;
@@ -35,18 +37,41 @@ pub const INPUT_POINTER_BUTTON: SignalId = 12;
 pub const INPUT_POINTER_AXIS: SignalId = 13;
 pub const INPUT_POINTER_POSITION_RESET: SignalId = 14;
 pub const INPUT_KEYBOARD: SignalId = 15;
+pub const INPUT_TOUCH_DOWN: SignalId = 16;
+pub const INPUT_TOUCH_MOTION: SignalId = 17;
+pub const INPUT_TOUCH_UP: SignalId = 18;
+pub const INPUT_TOUCH_FRAME: SignalId = 19;
 pub const SURFACE_READY: SignalId = 20;
 pub const SURFACE_DESTROYED: SignalId = 21;
 pub const SURFACE_RECONFIGURED: SignalId = 22;
 pub const DOCK_SURFACE: SignalId = 23;
+pub const SURFACE_DESCRIPTION_CHANGED: SignalId = 24;
 pub const CURSOR_SURFACE_CHANGE: SignalId = 25;
 pub const BACKGROUND_SURFACE_CHANGE: SignalId = 26;
+pub const SURFACE_MOVE_REQUESTED: SignalId = 27;
+pub const SURFACE_RESIZE_REQUESTED: SignalId = 28;
+pub const SURFACE_MAXIMIZATION_REQUESTED: SignalId = 29;
 pub const SURFACE_FRAME: SignalId = 30;
 pub const POINTER_FOCUS_CHANGED: SignalId = 31;
 pub const POINTER_RELATIVE_MOTION: SignalId = 32;
 pub const KEYBOARD_FOCUS_CHANGED: SignalId = 33;
+pub const SURFACE_FULLSCREEN_REQUESTED: SignalId = 34;
+pub const SURFACE_MINIMIZATION_REQUESTED: SignalId = 35;
+pub const DRAG_ICON_SURFACE_CHANGE: SignalId = 36;
+pub const TOUCH_DOWN: SignalId = 37;
+pub const TOUCH_MOTION: SignalId = 38;
+pub const TOUCH_UP: SignalId = 39;
+pub const TOUCH_FRAME: SignalId = 40;
 pub const TRANSFER_OFFERED: SignalId = 41;
 pub const TRANSFER_REQUESTED: SignalId = 42;
+pub const PRIMARY_TRANSFER_OFFERED: SignalId = 43;
+pub const PRIMARY_TRANSFER_REQUESTED: SignalId = 44;
+pub const SURFACE_PRESENTED: SignalId = 45;
+pub const LAYER_SURFACE: SignalId = 46;
+pub const POINTER_DELTA: SignalId = 47;
+pub const POINTER_CONSTRAINT_CHANGED: SignalId = 48;
+pub const SYSTEM_ACTIVITY: SignalId = 49;
+pub const SURFACE_DISCARDED: SignalId = 50;
 pub const COMMAND: SignalId = 51;
 pub const MODE: SignalId = 52;
 pub const TAKE_SCREENSHOT: SignalId = 101;
@@ -71,32 +96,56 @@ pub enum Perceptron {
     CustomEmpty,
     CustomId(u64),
     VerticalBlank(i32),
-    PageFlip(i32),
+    PageFlip(i32, PresentationTime),
     OutputFound(DrmBundle),
     DisplayCreated(OutputInfo),
+    DisplayUpdated(OutputInfo),
     InputPointerMotion(Vector),
     InputPointerPosition(OptionalPosition),
     InputPointerButton(Button),
     InputPointerAxis(Axis),
     InputPointerPositionReset,
     InputKeyboard(Key),
+    InputTouchDown(Touch),
+    InputTouchMotion(Touch),
+    InputTouchUp(i32, Milliseconds),
+    InputTouchFrame,
     SurfaceReady(SurfaceId),
     SurfaceDestroyed(SurfaceId),
     SurfaceReconfigured(SurfaceId),
     DockSurface(SurfaceId, Size, i32),
+    LayerSurface(SurfaceId, LayerProperties, Option<i32>),
+    SurfaceDescriptionChanged(SurfaceId),
     CursorSurfaceChange(SurfaceId),
     BackgroundSurfaceChange(SurfaceId),
+    DragIconSurfaceChange(SurfaceId),
+    SurfaceMoveRequested(SurfaceId),
+    SurfaceResizeRequested(SurfaceId, resize_edge::ResizeEdge),
+    SurfaceMaximizationRequested(SurfaceId, bool),
+    SurfaceFullscreenRequested(SurfaceId, bool, Option<i32>),
+    SurfaceMinimizationRequested(SurfaceId),
     SurfaceFrame(SurfaceId, Milliseconds),
+    SurfacePresented(SurfaceId, i32, PresentationTime),
+    SurfaceDiscarded(SurfaceId),
     PointerFocusChanged(SurfaceId, SurfaceId, Position),
     PointerRelativeMotion(SurfaceId, Position, Milliseconds),
+    PointerDelta(SurfaceId, Vector, Milliseconds),
+    PointerConstraintChanged(SurfaceId, bool),
     KeyboardFocusChanged(SurfaceId, SurfaceId),
+    TouchDown(SurfaceId, i32, Position, Milliseconds),
+    TouchMotion(SurfaceId, i32, Position, Milliseconds),
+    TouchUp(SurfaceId, i32, Milliseconds),
+    TouchFrame,
     TransferOffered,
     TransferRequested(String, RawFd),
+    PrimaryTransferOffered,
+    PrimaryTransferRequested(String, RawFd),
     Command(Command),
     Mode { active: bool, mode: InteractionMode },
     TakeScreenshot(i32),
     ScreenshotDone,
     WorkspaceStateChanged,
+    SystemActivity,
     Timer500,
 }
 
@@ -113,9 +162,12 @@ impl std::fmt::Debug for Perceptron {
             Perceptron::CustomEmpty => write!(f, "CustomEmpty"),
             Perceptron::CustomId(ref id) => write!(f, "CustomId({:?})", id),
             Perceptron::VerticalBlank(ref data) => write!(f, "VerticalBlank({:?})", data),
-            Perceptron::PageFlip(ref data) => write!(f, "PageFlip({:?})", data),
+            Perceptron::PageFlip(ref data, ref time) => {
+                write!(f, "PageFlip({:?}, {:?})", data, time)
+            }
             Perceptron::OutputFound(ref bundle) => write!(f, "OutputFound({:?})", bundle),
             Perceptron::DisplayCreated(ref info) => write!(f, "DisplayCreated({:?})", info),
+            Perceptron::DisplayUpdated(ref info) => write!(f, "DisplayUpdated({:?})", info),
             Perceptron::InputPointerMotion(ref vector) => {
                 write!(f, "InputPointerMotion({:?})", vector)
             }
@@ -126,6 +178,12 @@ impl std::fmt::Debug for Perceptron {
             Perceptron::InputPointerAxis(ref axis) => write!(f, "InputPointerAxis({:?})", axis),
             Perceptron::InputPointerPositionReset => write!(f, "InputPointerPositionReset"),
             Perceptron::InputKeyboard(ref key) => write!(f, "InputKeyboard({:?})", key),
+            Perceptron::InputTouchDown(ref touch) => write!(f, "InputTouchDown({:?})", touch),
+            Perceptron::InputTouchMotion(ref touch) => write!(f, "InputTouchMotion({:?})", touch),
+            Perceptron::InputTouchUp(ref id, ref time) => {
+                write!(f, "InputTouchUp({:?}, {:?})", id, time.get_value())
+            }
+            Perceptron::InputTouchFrame => write!(f, "InputTouchFrame"),
 
             Perceptron::SurfaceReady(ref sid) => write!(f, "SurfaceReady({})", sid),
             Perceptron::SurfaceDestroyed(ref sid) => write!(f, "SurfaceDestroyed({})", sid),
@@ -133,26 +191,78 @@ impl std::fmt::Debug for Perceptron {
             Perceptron::DockSurface(ref sid, ref size, display_id) => {
                 write!(f, "DockSurface({}, {:?}, {:?})", sid, size, display_id)
             }
+            Perceptron::LayerSurface(ref sid, ref properties, display_id) => {
+                write!(f, "LayerSurface({}, {:?}, {:?})", sid, properties, display_id)
+            }
+            Perceptron::SurfaceDescriptionChanged(ref sid) => {
+                write!(f, "SurfaceDescriptionChanged({})", sid)
+            }
             Perceptron::CursorSurfaceChange(ref sid) => write!(f, "CursorSurfaceChange({})", sid),
             Perceptron::BackgroundSurfaceChange(ref sid) => {
                 write!(f, "BackgroundSurfaceChange({})", sid)
             }
+            Perceptron::DragIconSurfaceChange(ref sid) => {
+                write!(f, "DragIconSurfaceChange({})", sid)
+            }
+            Perceptron::SurfaceMoveRequested(ref sid) => write!(f, "SurfaceMoveRequested({})", sid),
+            Perceptron::SurfaceResizeRequested(ref sid, ref edges) => {
+                write!(f, "SurfaceResizeRequested({}, {:?})", sid, edges)
+            }
+            Perceptron::SurfaceMaximizationRequested(ref sid, maximized) => {
+                write!(f, "SurfaceMaximizationRequested({}, {:?})", sid, maximized)
+            }
+            Perceptron::SurfaceFullscreenRequested(ref sid, fullscreen, ref display_id) => {
+                write!(f,
+                       "SurfaceFullscreenRequested({}, {:?}, {:?})",
+                       sid,
+                       fullscreen,
+                       display_id)
+            }
+            Perceptron::SurfaceMinimizationRequested(ref sid) => {
+                write!(f, "SurfaceMinimizationRequested({})", sid)
+            }
             Perceptron::SurfaceFrame(sid, time) => {
                 write!(f, "SurfaceFrame(sid: {}, milliseconds: {})", sid, time.get_value())
             }
+            Perceptron::SurfacePresented(sid, display_id, ref time) => {
+                write!(f, "SurfacePresented(sid: {}, display: {}, {:?})", sid, display_id, time)
+            }
+            Perceptron::SurfaceDiscarded(sid) => {
+                write!(f, "SurfaceDiscarded({})", sid)
+            }
             Perceptron::PointerFocusChanged(ref old_sid, ref new_sid, ref pos) => {
                 write!(f, "PointerFocusChanged(old: {:?}, new: {:?}, {:?})", old_sid, new_sid, pos)
             }
             Perceptron::PointerRelativeMotion(ref sid, ref pos, ref time) => {
                 write!(f, "PointerRelativeMotion({:?}, {:?}, {:?})", sid, pos, time.get_value())
             }
+            Perceptron::PointerDelta(ref sid, ref vector, ref time) => {
+                write!(f, "PointerDelta({:?}, {:?}, {:?})", sid, vector, time.get_value())
+            }
+            Perceptron::PointerConstraintChanged(ref sid, is_active) => {
+                write!(f, "PointerConstraintChanged({:?}, {:?})", sid, is_active)
+            }
             Perceptron::KeyboardFocusChanged(ref old_sid, ref new_sid) => {
                 write!(f, "KeyboardFocusChanged({:?}, {:?})", old_sid, new_sid)
             }
+            Perceptron::TouchDown(ref sid, ref id, ref pos, ref time) => {
+                write!(f, "TouchDown({:?}, {:?}, {:?}, {:?})", sid, id, pos, time.get_value())
+            }
+            Perceptron::TouchMotion(ref sid, ref id, ref pos, ref time) => {
+                write!(f, "TouchMotion({:?}, {:?}, {:?}, {:?})", sid, id, pos, time.get_value())
+            }
+            Perceptron::TouchUp(ref sid, ref id, ref time) => {
+                write!(f, "TouchUp({:?}, {:?}, {:?})", sid, id, time.get_value())
+            }
+            Perceptron::TouchFrame => write!(f, "TouchFrame"),
             Perceptron::TransferOffered => write!(f, "TransferOffered"),
             Perceptron::TransferRequested(ref mime_type, fd) => {
                 write!(f, "TransferRequested('{:?}', fd: {:?})", mime_type, fd)
             }
+            Perceptron::PrimaryTransferOffered => write!(f, "PrimaryTransferOffered"),
+            Perceptron::PrimaryTransferRequested(ref mime_type, fd) => {
+                write!(f, "PrimaryTransferRequested('{:?}', fd: {:?})", mime_type, fd)
+            }
             Perceptron::Command(ref command) => write!(f, "Command({:?})", command),
             Perceptron::Mode { active, mode } => {
                 write!(f, "Mode(active: {:?}, mode: {:?})", active, mode)
@@ -160,6 +270,7 @@ impl std::fmt::Debug for Perceptron {
             Perceptron::TakeScreenshot(ref id) => write!(f, "TakeScreenshot({:?})", id),
             Perceptron::ScreenshotDone => write!(f, "ScreenshotDone"),
             Perceptron::WorkspaceStateChanged => write!(f, "WorkspaceStateChanged"),
+            Perceptron::SystemActivity => write!(f, "SystemActivity"),
             Perceptron::Timer500 => write!(f, "Timer500"),
         }
     }
@@ -5,6 +5,8 @@
 
 // -------------------------------------------------------------------------------------------------
 
+use std;
+use std::collections::HashMap;
 use std::time::Instant;
 
 use graphics::attributes::{EglAttributes, DmabufAttributes};
@@ -12,7 +14,8 @@ use graphics::attributes::{EglAttributes, DmabufAttributes};
 use image::Image;
 use memory::MemoryView;
 use defs::{DmabufId, EglImageId, MemoryViewId};
-use defs::{Position, Size, Vector};
+use defs::{Area, Margins, Position, Size, Vector};
+use enums::Transform;
 pub use defs::{SurfaceId, SurfaceIdType};
 
 // -------------------------------------------------------------------------------------------------
@@ -40,6 +43,93 @@ impl SurfaceContext {
 
 // -------------------------------------------------------------------------------------------------
 
+/// Geometry of decorations drawn by compositor around surfaces which requested them.
+pub mod decoration {
+    use defs::Margins;
+
+    /// Width of border drawn around decorated surface.
+    pub const BORDER_WIDTH: usize = 2;
+
+    /// Height of title bar drawn between top border and decorated surface.
+    pub const TITLE_BAR_HEIGHT: usize = 14;
+
+    /// Returns space taken by decorations along edges of decorated surface.
+    pub fn get_margins() -> Margins {
+        Margins::new(BORDER_WIDTH + TITLE_BAR_HEIGHT, BORDER_WIDTH, BORDER_WIDTH, BORDER_WIDTH)
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// This structure defines how decoration of the surface should be drawn.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DecorationContext {
+    /// ID of decorated surface.
+    pub id: SurfaceId,
+
+    /// Area covered by the surface together with its decorations.
+    pub area: Area,
+
+    /// Title shown in title bar.
+    pub title: String,
+
+    /// Decorations of focused surface are drawn in different color.
+    pub is_focused: bool,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl DecorationContext {
+    /// `DecorationContext` constructor.
+    pub fn new(id: SurfaceId, area: Area, title: String, is_focused: bool) -> Self {
+        DecorationContext {
+            id: id,
+            area: area,
+            title: title,
+            is_focused: is_focused,
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Kind of restriction imposed on pointer movement by a surface.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PointerConstraintKind {
+    /// Pointer does not move while the constraint is active.
+    Lock,
+
+    /// Pointer can not leave the region of the constraint.
+    Confine,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Restriction of pointer movement requested for a surface. Constraint becomes active when the
+/// surface has pointer focus and the pointer is inside its region.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PointerConstraint {
+    /// Kind of the constraint.
+    pub kind: PointerConstraintKind,
+
+    /// Region in surface coordinates. `None` means the whole surface.
+    pub region: Option<Vec<Area>>,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl PointerConstraint {
+    /// `PointerConstraint` constructor.
+    pub fn new(kind: PointerConstraintKind, region: Option<Vec<Area>>) -> Self {
+        PointerConstraint {
+            kind: kind,
+            region: region,
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
 /// These flags describe readiness of `Surface` to be displayed.
 pub mod show_reason {
     bitflags!(
@@ -68,6 +158,134 @@ pub mod surface_state {
 
 // -------------------------------------------------------------------------------------------------
 
+/// These flags describe which edges of surface are dragged during interactive resize. Values are
+/// the same as used in Wayland shell protocols.
+pub mod resize_edge {
+    bitflags!(
+        pub flags ResizeEdge: u32 {
+            const NONE = 0b0000,
+            const TOP = 0b0001,
+            const BOTTOM = 0b0010,
+            const LEFT = 0b0100,
+            const RIGHT = 0b1000,
+        }
+    );
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// These flags describe to which edges of display layer surface is anchored. Values are the same
+/// as used in Wayland layer shell protocol.
+pub mod layer_anchor {
+    bitflags!(
+        pub flags LayerAnchor: u32 {
+            const NONE = 0b0000,
+            const TOP = 0b0001,
+            const BOTTOM = 0b0010,
+            const LEFT = 0b0100,
+            const RIGHT = 0b1000,
+        }
+    );
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Layers in which surfaces can be placed outside of frame layout. Layers are listed from the
+/// bottom to the top. Background and bottom layers are drawn below frames, top and overlay layers
+/// above them.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Layer {
+    Background,
+    Bottom,
+    Top,
+    Overlay,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Describes placement of surface in layer.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LayerProperties {
+    /// Layer in which the surface is placed.
+    pub layer: Layer,
+
+    /// Edges of display to which the surface is anchored.
+    pub anchor: layer_anchor::LayerAnchor,
+
+    /// Edge on which exclusive zone is reserved. If `NONE` the edge is deduced from anchor.
+    pub exclusive_edge: layer_anchor::LayerAnchor,
+
+    /// Requested size. Zero dimension means the surface should be stretched between opposite
+    /// edges it is anchored to.
+    pub size: Size,
+
+    /// Distance from anchored edges.
+    pub margins: Margins,
+
+    /// Size of area along the edge which other surfaces should not cover. Zero means the surface
+    /// should not cover exclusive zones of other surfaces and negative value means it does not
+    /// care about them.
+    pub exclusive_zone: i32,
+
+    /// Whether the surface wants to receive keyboard input.
+    pub keyboard_interactive: bool,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl LayerProperties {
+    /// Constructs new `LayerProperties` for surface in given layer.
+    pub fn new(layer: Layer) -> Self {
+        LayerProperties {
+            layer: layer,
+            anchor: layer_anchor::NONE,
+            exclusive_edge: layer_anchor::NONE,
+            size: Size::default(),
+            margins: Margins::default(),
+            exclusive_zone: 0,
+            keyboard_interactive: false,
+        }
+    }
+
+    /// Checks if the surface is anchored to both opposite edges along every axis in which its
+    /// requested size is zero.
+    pub fn is_size_valid(&self) -> bool {
+        let horizontal = layer_anchor::LEFT | layer_anchor::RIGHT;
+        let vertical = layer_anchor::TOP | layer_anchor::BOTTOM;
+        ((self.size.width != 0) || self.anchor.contains(horizontal)) &&
+        ((self.size.height != 0) || self.anchor.contains(vertical))
+    }
+
+    /// Returns edge on which exclusive zone should be reserved or `NONE` if the zone should be
+    /// ignored. Without explicitly chosen edge the surface must be anchored to exactly one edge or
+    /// to one edge and both perpendicular ones.
+    pub fn get_exclusive_edge(&self) -> layer_anchor::LayerAnchor {
+        if self.exclusive_zone <= 0 {
+            return layer_anchor::NONE;
+        }
+
+        if self.exclusive_edge != layer_anchor::NONE {
+            return self.exclusive_edge;
+        }
+
+        let horizontal = layer_anchor::LEFT | layer_anchor::RIGHT;
+        let vertical = layer_anchor::TOP | layer_anchor::BOTTOM;
+        for edge in [layer_anchor::TOP, layer_anchor::BOTTOM].iter() {
+            if (self.anchor == *edge) || (self.anchor == *edge | horizontal) {
+                return *edge;
+            }
+        }
+        for edge in [layer_anchor::LEFT, layer_anchor::RIGHT].iter() {
+            if (self.anchor == *edge) || (self.anchor == *edge | vertical) {
+                return *edge;
+            }
+        }
+        layer_anchor::NONE
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
 /// This enum gathers information about source of data to be used when drawing surface.
 ///
 /// Must be easy to copy and ensure data is not destroyed during use.
@@ -144,6 +362,32 @@ impl DataSource {
 
 // -------------------------------------------------------------------------------------------------
 
+/// Source rectangle of surface viewport in surface coordinates before cropping and scaling (after
+/// applying buffer transform and scale).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ViewportSource {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl ViewportSource {
+    /// Constructs new `ViewportSource`.
+    pub fn new(x: f32, y: f32, width: f32, height: f32) -> Self {
+        ViewportSource {
+            x: x,
+            y: y,
+            width: width,
+            height: height,
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
 /// Structure containing public information about surface.
 #[derive(Clone)]
 pub struct SurfaceInfo {
@@ -154,6 +398,125 @@ pub struct SurfaceInfo {
     pub requested_size: Size,
     pub state_flags: surface_state::SurfaceState,
     pub data_source: DataSource,
+    pub buffer_scale: u32,
+    pub buffer_transform: Transform,
+    pub viewport_source: Option<ViewportSource>,
+    pub viewport_destination: Option<Size>,
+    pub opaque_region: Vec<Area>,
+    pub input_region: Option<Vec<Area>>,
+    pub title: String,
+    pub app_id: String,
+    pub is_decorated: bool,
+    pub pointer_constraint: Option<PointerConstraint>,
+    pub global_position: Option<Position>,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl SurfaceInfo {
+    /// Returns size of attached buffer in surface coordinates before applying viewport (after
+    /// applying buffer transform and scale) or `None` if no buffer is attached.
+    pub fn get_scaled_buffer_size(&self) -> Option<Size> {
+        self.data_source.as_image().map(|image| {
+            let size = image.get_size();
+            let size = if self.buffer_transform.swaps_dimensions() {
+                Size::new(size.height, size.width)
+            } else {
+                size
+            };
+            let scale = std::cmp::max(self.buffer_scale, 1) as usize;
+            Size::new(size.width / scale, size.height / scale)
+        })
+    }
+
+    /// Checks if source rectangle of viewport lays inside attached buffer in surface coordinates.
+    /// Returns `true` if there is no source rectangle or no buffer is attached.
+    pub fn is_viewport_source_in_buffer(&self) -> bool {
+        match (self.viewport_source, self.data_source.as_image()) {
+            (Some(source), Some(image)) => {
+                let size = image.get_size();
+                let size = if self.buffer_transform.swaps_dimensions() {
+                    Size::new(size.height, size.width)
+                } else {
+                    size
+                };
+                let scale = std::cmp::max(self.buffer_scale, 1) as f32;
+                (source.x + source.width <= size.width as f32 / scale) &&
+                (source.y + source.height <= size.height as f32 / scale)
+            }
+            _ => true,
+        }
+    }
+
+    /// Returns size of surface contents in logical coordinates (after applying buffer transform,
+    /// scale and viewport) or `None` if no buffer is attached.
+    pub fn get_contents_size(&self) -> Option<Size> {
+        self.get_scaled_buffer_size().map(|size| {
+            match (self.viewport_destination, self.viewport_source) {
+                (Some(destination), _) => destination,
+                (None, Some(source)) => Size::new(source.width as usize, source.height as usize),
+                (None, None) => size,
+            }
+        })
+    }
+
+    /// Checks if given position (relative to origin of the buffer) lays inside input region of the
+    /// surface. Input region is clipped to surface bounds and if not set covers whole surface.
+    /// Surfaces without buffer are assumed to cover their requested size.
+    pub fn accepts_input_at(&self, position: &Position) -> bool {
+        let size = self.get_contents_size().unwrap_or(self.requested_size);
+        if !Area::new(Position::default(), size).contains(position) {
+            return false;
+        }
+
+        if let Some(ref region) = self.input_region {
+            region.iter().any(|area| area.contains(position))
+        } else {
+            true
+        }
+    }
+
+    /// Checks if compositor should draw decorations for the surface. Fullscreen surfaces are never
+    /// decorated.
+    pub fn has_decorations(&self) -> bool {
+        self.is_decorated && !self.state_flags.contains(surface_state::FULLSCREEN)
+    }
+
+    /// Returns space taken by decorations along edges of the surface or zero margins if the
+    /// surface is not decorated by compositor.
+    pub fn get_decoration_margins(&self) -> Margins {
+        if self.has_decorations() {
+            decoration::get_margins()
+        } else {
+            Margins::default()
+        }
+    }
+
+    /// Returns region of pointer constraint clipped to surface bounds or empty list if there is
+    /// no constraint.
+    pub fn get_pointer_constraint_region(&self) -> Vec<Area> {
+        if let Some(ref constraint) = self.pointer_constraint {
+            let size = self.get_contents_size().unwrap_or(self.requested_size);
+            let bounds = Area::new(Position::default(), size);
+            if let Some(ref region) = constraint.region {
+                region.iter().filter_map(|area| area.intersection(&bounds)).collect()
+            } else {
+                vec![bounds]
+            }
+        } else {
+            Vec::new()
+        }
+    }
+
+    /// Checks if opaque region covers whole attached buffer.
+    pub fn is_opaque(&self) -> bool {
+        if let Some(size) = self.get_contents_size() {
+            let area = Area::new(Position::default(), size);
+            !area.is_zero() && area.is_covered_by(&self.opaque_region)
+        } else {
+            false
+        }
+    }
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -172,6 +535,32 @@ pub trait SurfaceManagement {
     /// Sets given dmabuf as pending for given surface.
     fn attach_dmabuf(&self, dmid: DmabufId, sid: SurfaceId);
 
+    /// Sets pending scale of buffers attached to given surface.
+    fn set_surface_buffer_scale(&self, sid: SurfaceId, scale: u32);
+
+    /// Sets pending transform of buffers attached to given surface.
+    fn set_surface_buffer_transform(&self, sid: SurfaceId, transform: Transform);
+
+    /// Sets pending opaque region (list of areas in surface coordinates) of given surface.
+    fn set_surface_opaque_region(&self, sid: SurfaceId, region: Vec<Area>);
+
+    /// Sets pending source rectangle of viewport of given surface. `None` means whole buffer.
+    fn set_surface_viewport_source(&self, sid: SurfaceId, source: Option<ViewportSource>);
+
+    /// Sets pending destination size of viewport of given surface. `None` means size of source
+    /// rectangle.
+    fn set_surface_viewport_destination(&self, sid: SurfaceId, destination: Option<Size>);
+
+    /// Sets pending input region (list of areas in surface coordinates) of given surface. `None`
+    /// means the whole surface accepts input.
+    fn set_surface_input_region(&self, sid: SurfaceId, region: Option<Vec<Area>>);
+
+    /// Adds given area (in surface coordinates) to pending damage of given surface.
+    fn damage_surface(&self, sid: SurfaceId, area: Area);
+
+    /// Adds given area (in buffer coordinates) to pending damage of given surface.
+    fn damage_surface_buffer(&self, sid: SurfaceId, area: Area);
+
     /// Informs other parts of application the surface is now not visible.
     fn detach_surface(&self, sid: SurfaceId);
 
@@ -192,6 +581,13 @@ pub trait SurfaceControl {
     /// Docks given surface.
     fn dock_surface(&self, sid: SurfaceId, size: Size, display_id: i32);
 
+    /// Places given surface in layer on given display or on display chosen by exhibitor if
+    /// `display_id` is `None`.
+    fn set_surface_layer(&self,
+                         sid: SurfaceId,
+                         properties: LayerProperties,
+                         display_id: Option<i32>);
+
     /// Subtracts given show reason flag from set of surfaces show reason.
     fn hide_surface(&self, sid: SurfaceId, reason: show_reason::ShowReason);
 
@@ -209,6 +605,49 @@ pub trait SurfaceControl {
 
     /// Unrelates two surfaces.
     fn unrelate_surface(&self, sid: SurfaceId);
+
+    /// Places surface directly above its sibling or parent. Takes effect after parent is committed.
+    fn place_surface_above(&self, sid: SurfaceId, sibling_sid: SurfaceId);
+
+    /// Places surface directly below its sibling or parent. Takes effect after parent is committed.
+    fn place_surface_below(&self, sid: SurfaceId, sibling_sid: SurfaceId);
+
+    /// Sets synchronization mode of subsurface. State committed by synchronized subsurface is
+    /// applied after its parent is committed.
+    fn set_surface_synchronized(&self, sid: SurfaceId, is_synchronized: bool);
+
+    /// Sets title of given surface.
+    fn set_surface_title(&self, sid: SurfaceId, title: String);
+
+    /// Sets application ID (or class) of given surface.
+    fn set_surface_app_id(&self, sid: SurfaceId, app_id: String);
+
+    /// Sets whether given surface should be decorated by compositor.
+    fn set_surface_decorated(&self, sid: SurfaceId, is_decorated: bool);
+
+    /// Sets or removes constraint of pointer movement over given surface.
+    fn set_surface_pointer_constraint(&self,
+                                      sid: SurfaceId,
+                                      constraint: Option<PointerConstraint>);
+
+    /// Sets position of given surface in global coordinates as it was last drawn.
+    fn set_surface_global_position(&self, sid: SurfaceId, position: Position);
+
+    /// Requests interactive move of given surface driven by pointer.
+    fn request_surface_move(&self, sid: SurfaceId);
+
+    /// Requests interactive resize of given surface driven by pointer.
+    fn request_surface_resize(&self, sid: SurfaceId, edges: resize_edge::ResizeEdge);
+
+    /// Requests to maximize or unmaximize given surface.
+    fn request_surface_maximization(&self, sid: SurfaceId, maximized: bool);
+
+    /// Requests to make given surface fullscreen (optionally on display with given ID) or bring
+    /// it back from fullscreen.
+    fn request_surface_fullscreen(&self, sid: SurfaceId, fullscreen: bool, display_id: Option<i32>);
+
+    /// Requests to minimize given surface.
+    fn request_surface_minimization(&self, sid: SurfaceId);
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -228,6 +667,10 @@ pub trait SurfaceAccess {
                    sid: SurfaceId,
                    size: Size,
                    state_flags: surface_state::SurfaceState);
+
+    /// Returns damage of all surfaces committed since last call and forgets it. Areas are in
+    /// surface coordinates.
+    fn take_surfaces_damage(&mut self) -> HashMap<SurfaceId, Vec<Area>>;
 }
 
 // -------------------------------------------------------------------------------------------------
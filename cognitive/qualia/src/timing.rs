@@ -30,3 +30,32 @@ impl Milliseconds {
 }
 
 // -------------------------------------------------------------------------------------------------
+
+/// This structure represents moment when new contents of display were presented (page flip
+/// occurred). Time is measured by `CLOCK_MONOTONIC`.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PresentationTime {
+    /// Seconds part of timestamp.
+    pub seconds: u64,
+
+    /// Nanoseconds part of timestamp.
+    pub nanoseconds: u32,
+
+    /// Counter of vertical blanks.
+    pub sequence: u64,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl PresentationTime {
+    /// Constructs new `PresentationTime`.
+    pub fn new(seconds: u64, nanoseconds: u32, sequence: u64) -> Self {
+        PresentationTime {
+            seconds: seconds,
+            nanoseconds: nanoseconds,
+            sequence: sequence,
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
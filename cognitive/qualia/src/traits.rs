@@ -17,6 +17,7 @@ use memory::{Buffer, Memory};
 use perceptron::Perceptron;
 use surface::{SurfaceManagement, SurfaceControl, SurfaceViewer};
 use surface::{SurfaceAccess, SurfaceListing, SurfaceFocusing};
+use timing::PresentationTime;
 use transfer::Transfer;
 
 // -------------------------------------------------------------------------------------------------
@@ -28,6 +29,9 @@ pub trait AppearanceManagement {
 
     /// Sets given surface as background.
     fn set_surface_as_background(&self, sid: SurfaceId);
+
+    /// Sets given surface as icon of dragged data. Invalid surface ID removes the icon.
+    fn set_surface_as_drag_icon(&self, sid: SurfaceId);
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -42,6 +46,21 @@ pub trait DataTransferring {
 
     /// Requests start of data transfer to requesting client.
     fn request_transfer(&mut self, mime_type: String, fd: RawFd);
+
+    /// Sets transfer offered by drag-and-drop. `None` ends dragging.
+    fn set_drag_transfer(&mut self, transfer: Option<Transfer>);
+
+    /// Returns transfer offered by drag-and-drop.
+    fn get_drag_transfer(&self) -> Option<Transfer>;
+
+    /// Sets primary selection transfer offer.
+    fn set_primary_transfer(&mut self, transfer: Option<Transfer>);
+
+    /// Returns primary selection transfer offer.
+    fn get_primary_transfer(&self) -> Option<Transfer>;
+
+    /// Requests start of primary selection data transfer to requesting client.
+    fn request_primary_transfer(&mut self, mime_type: String, fd: RawFd);
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -89,8 +108,8 @@ pub trait StatePublishing {
     /// Notifies about V-blank.
     fn emit_vblank(&mut self, display_id: i32);
 
-    /// Notifies about page flip.
-    fn emit_page_flip(&mut self, display_id: i32);
+    /// Notifies about page flip. `time` describes when new contents of the display were presented.
+    fn emit_page_flip(&mut self, display_id: i32, time: PresentationTime);
 }
 
 // -------------------------------------------------------------------------------------------------
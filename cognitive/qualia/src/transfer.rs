@@ -5,10 +5,26 @@
 
 // -------------------------------------------------------------------------------------------------
 
+/// These flags describe actions which can be performed on data after drag-and-drop. Values are the
+/// same as used in Wayland protocol.
+pub mod dnd_action {
+    bitflags!(
+        pub flags DndAction: u32 {
+            const NONE = 0b0000,
+            const COPY = 0b0001,
+            const MOVE = 0b0010,
+            const ASK = 0b0100,
+        }
+    );
+}
+
+// -------------------------------------------------------------------------------------------------
+
 /// Data related to transferring data.
 #[derive(Clone)]
 pub struct Transfer {
     pub mime_types: Vec<String>,
+    pub dnd_actions: dnd_action::DndAction,
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -16,7 +32,10 @@ pub struct Transfer {
 impl Transfer {
     /// Constructs new `Transfer`.
     pub fn new() -> Self {
-        Transfer { mime_types: Vec::new() }
+        Transfer {
+            mime_types: Vec::new(),
+            dnd_actions: dnd_action::NONE,
+        }
     }
 
     /// Adds offered mime type.
@@ -28,6 +47,37 @@ impl Transfer {
     pub fn get_mime_types(&self) -> &Vec<String> {
         &self.mime_types
     }
+
+    /// Sets drag-and-drop actions supported by source.
+    pub fn set_dnd_actions(&mut self, dnd_actions: dnd_action::DndAction) {
+        self.dnd_actions = dnd_actions;
+    }
+
+    /// Returns drag-and-drop actions supported by source.
+    pub fn get_dnd_actions(&self) -> dnd_action::DndAction {
+        self.dnd_actions
+    }
+
+    /// Chooses drag-and-drop action supported by both source and destination. Action preferred by
+    /// destination is chosen if possible, otherwise the first supported one in order: copy, move,
+    /// ask.
+    pub fn choose_dnd_action(&self,
+                             dnd_actions: dnd_action::DndAction,
+                             preferred_action: dnd_action::DndAction)
+                             -> dnd_action::DndAction {
+        let common = self.dnd_actions & dnd_actions;
+        if !preferred_action.is_empty() && common.contains(preferred_action) {
+            preferred_action
+        } else if common.contains(dnd_action::COPY) {
+            dnd_action::COPY
+        } else if common.contains(dnd_action::MOVE) {
+            dnd_action::MOVE
+        } else if common.contains(dnd_action::ASK) {
+            dnd_action::ASK
+        } else {
+            dnd_action::NONE
+        }
+    }
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -7,7 +7,7 @@
 
 extern crate cognitive_qualia;
 
-use cognitive_qualia::defs::{Area, Position, Size};
+use cognitive_qualia::defs::{Area, Margins, Position, Size};
 
 // -------------------------------------------------------------------------------------------------
 
@@ -145,3 +145,71 @@ fn should_inflate_area() {
 }
 
 // -------------------------------------------------------------------------------------------------
+
+/// Check if intersection of areas is correctly calculated.
+#[test]
+fn should_intersect_areas() {
+    #[cfg_attr(rustfmt, rustfmt_skip)]
+    let tests: [(Area, Option<Area>); 6] = [
+        (Area::create( 0,  0, 20, 20), Some(Area::create(10, 10, 10, 10))),
+        (Area::create(20, 20, 10, 10), Some(Area::create(20, 20, 10, 10))),
+        (Area::create(30, 30, 20, 20), Some(Area::create(30, 30, 10, 10))),
+        (Area::create( 0,  0, 50, 50), Some(Area::create(10, 10, 30, 30))),
+        (Area::create( 0,  0, 10, 10), None),
+        (Area::create(40, 10, 10, 30), None),
+    ];
+
+    for test in &tests {
+        let area = Area::create(10, 10, 30, 30);
+        assert!(area.intersection(&test.0) == test.1,
+                "Intersection with {:?} should be {:?}, is {:?}",
+                test.0,
+                test.1,
+                area.intersection(&test.0));
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if subtracting areas leaves correct parts uncovered.
+#[test]
+fn should_subtract_areas() {
+    let area = Area::create(10, 10, 30, 30);
+
+    assert_eq!(area.subtract(&Area::create(50, 50, 10, 10)), vec![area]);
+    assert_eq!(area.subtract(&Area::create(0, 0, 50, 50)), vec![]);
+    assert_eq!(area.subtract(&Area::create(20, 20, 10, 10)),
+               vec![Area::create(10, 10, 30, 10),
+                    Area::create(10, 30, 30, 10),
+                    Area::create(10, 20, 10, 10),
+                    Area::create(30, 20, 10, 10)]);
+    assert_eq!(area.subtract(&Area::create(0, 0, 20, 50)), vec![Area::create(20, 10, 20, 30)]);
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if area is correctly reported as covered by other areas.
+#[test]
+fn should_check_if_area_is_covered() {
+    let area = Area::create(10, 10, 30, 30);
+
+    assert!(area.is_covered_by(&[Area::create(0, 0, 50, 50)]));
+    assert!(area.is_covered_by(&[Area::create(0, 0, 50, 20), Area::create(0, 20, 50, 30)]));
+    assert!(!area.is_covered_by(&[Area::create(0, 0, 50, 20), Area::create(0, 21, 50, 30)]));
+    assert!(!area.is_covered_by(&[]));
+    assert!(Area::create(10, 10, 0, 0).is_covered_by(&[]));
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if area is correctly shrunk by margins.
+#[test]
+fn should_shrink_area() {
+    let area = Area::create(10, 10, 30, 30);
+
+    assert_eq!(area.shrunk(&Margins::default()), area);
+    assert_eq!(area.shrunk(&Margins::new(1, 2, 3, 4)), Area::create(14, 11, 24, 26));
+    assert_eq!(area.shrunk(&Margins::new(20, 0, 20, 0)), Area::create(10, 30, 30, 0));
+}
+
+// -------------------------------------------------------------------------------------------------
@@ -0,0 +1,79 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! Tests for enum helpers.
+
+// -------------------------------------------------------------------------------------------------
+
+extern crate cognitive_qualia;
+
+use cognitive_qualia::enums::Transform;
+
+// -------------------------------------------------------------------------------------------------
+
+const ALL_TRANSFORMS: [Transform; 8] = [Transform::Normal,
+                                        Transform::Rotated90,
+                                        Transform::Rotated180,
+                                        Transform::Rotated270,
+                                        Transform::Flipped,
+                                        Transform::Flipped90,
+                                        Transform::Flipped180,
+                                        Transform::Flipped270];
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if transforms are correctly constructed from Wayland values.
+#[test]
+fn should_construct_transform_from_wayland_value() {
+    for transform in ALL_TRANSFORMS.iter() {
+        assert_eq!(Transform::from_wayland(*transform as i32), Some(*transform));
+    }
+    assert_eq!(Transform::from_wayland(-1), None);
+    assert_eq!(Transform::from_wayland(8), None);
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if corners of contents are mapped to correct corners for rotations.
+#[test]
+fn should_map_corners_for_rotations() {
+    assert_eq!(Transform::Normal.map_normalized(0.0, 0.0), (0.0, 0.0));
+    assert_eq!(Transform::Rotated90.map_normalized(0.0, 0.0), (0.0, 1.0));
+    assert_eq!(Transform::Rotated180.map_normalized(0.0, 0.0), (1.0, 1.0));
+    assert_eq!(Transform::Rotated270.map_normalized(0.0, 0.0), (1.0, 0.0));
+    assert_eq!(Transform::Flipped.map_normalized(0.0, 0.0), (1.0, 0.0));
+    assert_eq!(Transform::Flipped90.map_normalized(1.0, 0.0), (0.0, 1.0));
+    assert_eq!(Transform::Flipped180.map_normalized(0.0, 0.0), (0.0, 1.0));
+    assert_eq!(Transform::Flipped270.map_normalized(0.0, 0.0), (1.0, 1.0));
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check if inverted transform maps points back to original positions.
+#[test]
+fn should_revert_transform_with_inverted_one() {
+    let points = [(0.0, 0.0), (1.0, 0.0), (0.0, 1.0), (1.0, 1.0), (0.25, 0.75)];
+    for transform in ALL_TRANSFORMS.iter() {
+        for &(x, y) in points.iter() {
+            let (tx, ty) = transform.map_normalized(x, y);
+            let result = transform.inverted().map_normalized(tx, ty);
+            assert_eq!(result, (x, y), "Transform {:?} for point ({}, {})", transform, x, y);
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Check which transforms swap width and height.
+#[test]
+fn should_tell_if_transform_swaps_dimensions() {
+    let swapping = [Transform::Rotated90,
+                    Transform::Rotated270,
+                    Transform::Flipped90,
+                    Transform::Flipped270];
+    for transform in ALL_TRANSFORMS.iter() {
+        assert_eq!(transform.swaps_dimensions(), swapping.contains(transform));
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
@@ -0,0 +1,66 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! Tests for tracking user inactivity.
+
+// -------------------------------------------------------------------------------------------------
+
+extern crate cognitive_qualia;
+
+use std::time::{Duration, Instant};
+
+use cognitive_qualia::{IdleTracker, SurfaceId};
+
+// -------------------------------------------------------------------------------------------------
+
+/// Test if idle time is measured from last activity.
+#[test]
+fn should_measure_idle_time_from_last_activity() {
+    let start = Instant::now();
+    let mut tracker = IdleTracker::new(start);
+    assert_eq!(tracker.get_idle_time(start + Duration::from_secs(3)), Duration::from_secs(3));
+
+    tracker.on_activity(start + Duration::from_secs(2));
+    assert_eq!(tracker.get_idle_time(start + Duration::from_secs(3)), Duration::from_secs(1));
+    assert_eq!(tracker.get_idle_time(start + Duration::from_secs(1)), Duration::from_secs(0));
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Test if inhibitors stop idle time only until the last of them is removed and if input idle time
+/// ignores them.
+#[test]
+fn should_not_become_idle_while_inhibited() {
+    let start = Instant::now();
+    let mut tracker = IdleTracker::new(start);
+    tracker.inhibit(SurfaceId::new(1));
+    tracker.inhibit(SurfaceId::new(1));
+    tracker.inhibit(SurfaceId::new(2));
+
+    let now = start + Duration::from_secs(5);
+    assert!(tracker.is_inhibited());
+    assert_eq!(tracker.get_idle_time(now), Duration::from_secs(0));
+    assert_eq!(tracker.get_input_idle_time(now), Duration::from_secs(5));
+
+    tracker.uninhibit(SurfaceId::new(1), start + Duration::from_secs(6));
+    tracker.uninhibit(SurfaceId::new(2), start + Duration::from_secs(7));
+    assert!(tracker.is_inhibited());
+
+    tracker.uninhibit(SurfaceId::new(1), start + Duration::from_secs(8));
+    assert!(!tracker.is_inhibited());
+    assert_eq!(tracker.get_idle_time(start + Duration::from_secs(10)), Duration::from_secs(2));
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Test if removing unknown inhibitor is ignored.
+#[test]
+fn should_ignore_unknown_inhibitors() {
+    let start = Instant::now();
+    let mut tracker = IdleTracker::new(start);
+    tracker.uninhibit(SurfaceId::new(1), start + Duration::from_secs(1));
+    assert!(!tracker.is_inhibited());
+    assert_eq!(tracker.get_idle_time(start + Duration::from_secs(2)), Duration::from_secs(2));
+}
+
+// -------------------------------------------------------------------------------------------------
@@ -0,0 +1,49 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! Tests for data transfer.
+
+// -------------------------------------------------------------------------------------------------
+
+extern crate cognitive_qualia;
+
+use cognitive_qualia::{Transfer, dnd_action};
+
+// -------------------------------------------------------------------------------------------------
+
+/// Test if preferred action is chosen when it is supported by both sides.
+#[test]
+fn should_choose_preferred_action_if_supported_by_both_sides() {
+    let mut transfer = Transfer::new();
+    transfer.set_dnd_actions(dnd_action::COPY | dnd_action::MOVE | dnd_action::ASK);
+
+    let action = transfer.choose_dnd_action(dnd_action::COPY | dnd_action::MOVE, dnd_action::MOVE);
+    assert_eq!(action, dnd_action::MOVE);
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Test if first common action is chosen when preferred action is not supported by source.
+#[test]
+fn should_fall_back_to_common_action_if_preferred_not_supported() {
+    let mut transfer = Transfer::new();
+    transfer.set_dnd_actions(dnd_action::MOVE | dnd_action::ASK);
+
+    let action = transfer.choose_dnd_action(dnd_action::COPY | dnd_action::MOVE | dnd_action::ASK,
+                                            dnd_action::COPY);
+    assert_eq!(action, dnd_action::MOVE);
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Test if no action is chosen when source and destination have no common actions.
+#[test]
+fn should_choose_no_action_if_nothing_in_common() {
+    let mut transfer = Transfer::new();
+    transfer.set_dnd_actions(dnd_action::COPY);
+
+    let action = transfer.choose_dnd_action(dnd_action::MOVE | dnd_action::ASK, dnd_action::MOVE);
+    assert_eq!(action, dnd_action::NONE);
+}
+
+// -------------------------------------------------------------------------------------------------
@@ -4,8 +4,14 @@
 
 varying highp vec2 v_texcoords;
 uniform sampler2D texture;
+uniform bool use_fill_color;
+uniform highp vec4 fill_color;
 
 void main(void)
 {
-    gl_FragColor = texture2D(texture, v_texcoords);
+    if (use_fill_color) {
+        gl_FragColor = fill_color;
+    } else {
+        gl_FragColor = texture2D(texture, v_texcoords);
+    }
 }
@@ -4,9 +4,15 @@
 
 in highp vec2 v_texcoords;
 uniform sampler2D texture;
+uniform bool use_fill_color;
+uniform highp vec4 fill_color;
 out highp vec4 color;
 
 void main(void)
 {
-    color = texture2D(texture, v_texcoords);
+    if (use_fill_color) {
+        color = fill_color;
+    } else {
+        color = texture2D(texture, v_texcoords);
+    }
 }
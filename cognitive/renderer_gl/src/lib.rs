@@ -19,6 +19,7 @@ extern crate timber;
 extern crate cognitive_qualia as qualia;
 
 mod cache_gl;
+mod font;
 
 pub mod renderer_gl;
 
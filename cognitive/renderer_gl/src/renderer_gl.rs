@@ -6,6 +6,7 @@
 // -------------------------------------------------------------------------------------------------
 
 use std;
+use std::collections::HashMap;
 use std::time::Instant;
 
 use gl;
@@ -13,10 +14,12 @@ use egl;
 
 use cognitive_graphics::{egl_tools, gl_tools};
 use cognitive_graphics::attributes::{DmabufAttributes, EglAttributes};
-use qualia::{SurfaceViewer, SurfaceContext, Illusion, Size, PixelFormat, SurfaceId};
+use qualia::{SurfaceViewer, SurfaceContext, Illusion, Size, PixelFormat, SurfaceId, Transform};
+use qualia::{Area, DecorationContext, Position, ViewportSource, decoration};
 use qualia::{Buffer, DataSource, Image, MemoryView, Pixmap};
 
 use cache_gl::CacheGl;
+use font;
 
 // -------------------------------------------------------------------------------------------------
 
@@ -32,12 +35,28 @@ const VERTEX_SHADER_300: &'static str = include_str!("vertex.300.glsl");
 /// Fragment shader source code for OpenGL ES 3.0 (GLSL ES 300)
 const FRAGMENT_SHADER_300: &'static str = include_str!("fragment.300.glsl");
 
+/// Corners of drawn rectangle (as two triangles) in normalized coordinates.
+const CORNERS: [(f32, f32); 6] = [(0.0, 0.0), (1.0, 0.0), (0.0, 1.0),
+                                  (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)];
+
+/// Color of decorations of focused surface.
+const FOCUSED_DECORATION_COLOR: [f32; 4] = [0.2, 0.45, 0.7, 1.0];
+
+/// Color of decorations of not focused surfaces.
+const UNFOCUSED_DECORATION_COLOR: [f32; 4] = [0.3, 0.3, 0.3, 1.0];
+
+/// Horizontal distance between left border and the title in logical pixels.
+const TITLE_PADDING: usize = 4;
+
 // -------------------------------------------------------------------------------------------------
 
 /// GL renderer.
 pub struct RendererGl {
     egl: egl_tools::EglBucket,
     size: Size,
+    scale: u32,
+    transform: Transform,
+    has_buffer_age: bool,
     cache: CacheGl,
 
     // GL rendering
@@ -46,9 +65,14 @@ pub struct RendererGl {
     loc_texcoords: gl::types::GLint,
     loc_texture: gl::types::GLint,
     loc_screen_size: gl::types::GLint,
+    loc_use_fill_color: gl::types::GLint,
+    loc_fill_color: gl::types::GLint,
     vbo_vertices: gl::types::GLuint,
     vbo_texcoords: gl::types::GLuint,
 
+    // Textures with rendered titles of decorated surfaces
+    titles: HashMap<SurfaceId, (String, gl::types::GLuint, Size)>,
+
     // Pointers to extension functions
     image_target_texture: Option<egl_tools::ImageTargetTexture2DOesFn>,
 }
@@ -61,14 +85,20 @@ impl RendererGl {
         RendererGl {
             egl: egl,
             size: size,
+            scale: 1,
+            transform: Transform::Normal,
+            has_buffer_age: false,
             cache: CacheGl::new(),
             program: gl::types::GLuint::default(),
             loc_vertices: gl::types::GLint::default(),
             loc_texcoords: gl::types::GLint::default(),
             loc_texture: gl::types::GLint::default(),
             loc_screen_size: gl::types::GLint::default(),
+            loc_use_fill_color: gl::types::GLint::default(),
+            loc_fill_color: gl::types::GLint::default(),
             vbo_vertices: gl::types::GLuint::default(),
             vbo_texcoords: gl::types::GLuint::default(),
+            titles: HashMap::new(),
             image_target_texture: None,
         }
     }
@@ -103,6 +133,10 @@ impl RendererGl {
         self.loc_texture = gl_tools::get_uniform_location(self.program, "texture".to_owned())?;
         self.loc_screen_size = gl_tools::get_uniform_location(self.program,
                                                               "screen_size".to_owned())?;
+        self.loc_use_fill_color = gl_tools::get_uniform_location(self.program,
+                                                                 "use_fill_color".to_owned())?;
+        self.loc_fill_color = gl_tools::get_uniform_location(self.program,
+                                                             "fill_color".to_owned())?;
 
         // Generate vertex buffer object
         unsafe {
@@ -112,26 +146,54 @@ impl RendererGl {
 
         // Get needed extension functions
         self.image_target_texture = egl_tools::get_proc_addr_of_image_target_texture_2d_oes();
+        self.has_buffer_age = egl_tools::has_extension(self.egl.display,
+                                                       egl_tools::ext::BUFFER_AGE_EXT);
 
         Ok(())
     }
 
-    /// Draw passed frame scene.
+    /// Sets scale of the output. Positions of surfaces passed to `draw` are in logical coordinates
+    /// which are multiplied by this scale to get position in pixels.
+    pub fn set_scale(&mut self, scale: u32) {
+        self.scale = scale;
+    }
+
+    /// Sets rotation and flipping of the output. Positions of surfaces passed to `draw` are
+    /// relative to transformed output.
+    pub fn set_transform(&mut self, transform: Transform) {
+        self.transform = transform;
+    }
+
+    /// Draw passed frame scene. Drawing is limited to bounding box of `damage` given in logical
+    /// coordinates. Decorations are drawn directly below surfaces they belong to.
     pub fn draw(&mut self,
                 layunder: &Vec<SurfaceContext>,
                 surfaces: &Vec<SurfaceContext>,
+                decorations: &Vec<DecorationContext>,
                 layover: &Vec<SurfaceContext>,
+                damage: &Vec<Area>,
                 viewer: &SurfaceViewer)
                 -> Result<(), Illusion> {
         let _context = self.egl.make_current()?;
-        self.prepare_view();
+        self.prepare_view(damage);
         self.draw_surfaces(layunder, viewer);
-        self.draw_surfaces(surfaces, viewer);
+        self.draw_decorated_surfaces(surfaces, decorations, viewer);
         self.draw_surfaces(layover, viewer);
         self.release_view();
+        self.release_titles(decorations);
         Ok(())
     }
 
+    /// Returns age of the buffer to be drawn next or 0 if it is unknown.
+    pub fn get_buffer_age(&self) -> u32 {
+        if self.has_buffer_age {
+            if let Ok(context) = self.egl.make_current() {
+                return context.get_buffer_age().unwrap_or(0);
+            }
+        }
+        0
+    }
+
     /// Swap buffers.
     pub fn swap_buffers(&mut self) -> Result<(), Illusion> {
         let context = self.egl.make_current()?;
@@ -174,9 +236,16 @@ impl RendererGl {
 
 /// Drawing helpers.
 impl RendererGl {
-    /// Prepare view for drawing.
-    fn prepare_view(&self) {
+    /// Prepare view for drawing. Everything outside damaged area is left untouched.
+    fn prepare_view(&self, damage: &Vec<Area>) {
+        let scissor = self.calculate_scissor(damage);
         unsafe {
+            gl::Enable(gl::SCISSOR_TEST);
+            gl::Scissor(scissor.pos.x as gl::types::GLint,
+                        scissor.pos.y as gl::types::GLint,
+                        scissor.size.width as gl::types::GLsizei,
+                        scissor.size.height as gl::types::GLsizei);
+
             gl::ClearColor(0.0, 0.3, 0.5, 1.0);
             gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT);
 
@@ -188,6 +257,54 @@ impl RendererGl {
         }
     }
 
+    /// Returns size of the output in pixels as seen after transforming.
+    fn get_transformed_size(&self) -> Size {
+        if self.transform.swaps_dimensions() {
+            Size::new(self.size.height, self.size.width)
+        } else {
+            self.size
+        }
+    }
+
+    /// Calculates scissor box containing all damaged areas. Resulting area is in framebuffer
+    /// pixels with origin in bottom left corner as expected by GL.
+    fn calculate_scissor(&self, damage: &Vec<Area>) -> Area {
+        let mut bounds = if let Some(area) = damage.first() {
+            *area
+        } else {
+            return Area::default();
+        };
+        for area in damage.iter() {
+            bounds.inflate(area);
+        }
+
+        let output_size = self.get_transformed_size();
+        let scale = self.scale as f32;
+        let (x1, y1) = self.transform
+            .map_normalized(scale * bounds.pos.x as f32 / output_size.width as f32,
+                            scale * bounds.pos.y as f32 / output_size.height as f32);
+        let (x2, y2) = self.transform
+            .map_normalized(scale * (bounds.pos.x + bounds.size.width as isize) as f32 /
+                            output_size.width as f32,
+                            scale * (bounds.pos.y + bounds.size.height as isize) as f32 /
+                            output_size.height as f32);
+
+        let width = self.size.width as f32;
+        let height = self.size.height as f32;
+        let left = (x1.min(x2) * width).floor().max(0.0);
+        let top = (y1.min(y2) * height).floor().max(0.0);
+        let right = (x1.max(x2) * width).ceil().min(width);
+        let bottom = (y1.max(y2) * height).ceil().min(height);
+        if (left < right) && (top < bottom) {
+            Area::create(left as isize,
+                         (height - bottom) as isize,
+                         (right - left) as usize,
+                         (bottom - top) as usize)
+        } else {
+            Area::default()
+        }
+    }
+
     /// Loads memory buffer as texture. Returns dimensions of the buffer.
     fn load_buffer_as_texture(&mut self,
                               sid: SurfaceId,
@@ -300,12 +417,35 @@ impl RendererGl {
         }
     }
 
-    /// Load textures and prepare vertices.
+    /// Prepares vertices of rectangle covering given area. Area is given in logical coordinates
+    /// relative to transformed output.
+    fn prepare_vertices(&self, area: &Area, vertices: &mut [gl::types::GLfloat]) {
+        let output_size = self.get_transformed_size();
+        let scale = self.scale as gl::types::GLfloat;
+        let left = scale * area.pos.x as gl::types::GLfloat;
+        let top = scale * area.pos.y as gl::types::GLfloat;
+        let width = scale * area.size.width as gl::types::GLfloat;
+        let height = scale * area.size.height as gl::types::GLfloat;
+
+        // TODO: Use element buffer.
+        for (i, &(x, y)) in CORNERS.iter().enumerate() {
+            // Map corner from transformed output to framebuffer
+            let (fx, fy) = self.transform
+                .map_normalized((left + x * width) / output_size.width as f32,
+                                (top + y * height) / output_size.height as f32);
+            vertices[2 * i] = fx * self.size.width as gl::types::GLfloat;
+            vertices[2 * i + 1] = fy * self.size.height as gl::types::GLfloat;
+        }
+    }
+
+    /// Load textures and prepare vertices. Returns `true` if surface is opaque and can be drawn
+    /// without blending, `false` otherwise.
     fn load_texture_and_prepare_vertices(&mut self,
                                          viewer: &SurfaceViewer,
                                          context: &SurfaceContext,
                                          vertices: &mut [gl::types::GLfloat],
-                                         texcoords: &mut [gl::types::GLfloat]) {
+                                         texcoords: &mut [gl::types::GLfloat])
+                                         -> bool {
         if let Some(ref surface) = viewer.get_surface(context.id) {
             let size = {
                 match surface.data_source {
@@ -323,114 +463,289 @@ impl RendererGl {
             };
 
             if let Some(size) = size {
-                let left = (context.pos.x - surface.offset.x) as gl::types::GLfloat;
-                let top = (context.pos.y - surface.offset.y) as gl::types::GLfloat;
-                let right = left + size.width as gl::types::GLfloat;
-                let bottom = top + size.height as gl::types::GLfloat;
-
-                vertices[0] = left;
-                vertices[1] = top;
-                vertices[2] = right;
-                vertices[3] = top;
-                vertices[4] = left;
-                vertices[5] = bottom;
-                vertices[6] = right;
-                vertices[7] = top;
-                vertices[8] = right;
-                vertices[9] = bottom;
-                vertices[10] = left;
-                vertices[11] = bottom;
-
-                // TODO: Use element buffer.
-                texcoords[0] = 0.0;
-                texcoords[1] = 0.0;
-                texcoords[2] = 1.0;
-                texcoords[3] = 0.0;
-                texcoords[4] = 0.0;
-                texcoords[5] = 1.0;
-                texcoords[6] = 1.0;
-                texcoords[7] = 0.0;
-                texcoords[8] = 1.0;
-                texcoords[9] = 1.0;
-                texcoords[10] = 0.0;
-                texcoords[11] = 1.0;
+                // Size of the surface in buffer pixels.
+                let size = if surface.buffer_transform.swaps_dimensions() {
+                    Size::new(size.height, size.width)
+                } else {
+                    size
+                };
+
+                // Size of the buffer in surface coordinates before cropping and scaling.
+                let buffer_scale = surface.buffer_scale as gl::types::GLfloat;
+                let buffer_width = size.width as gl::types::GLfloat / buffer_scale;
+                let buffer_height = size.height as gl::types::GLfloat / buffer_scale;
+
+                // Part of the buffer to be shown and size it is scaled to.
+                let source = surface.viewport_source
+                    .unwrap_or(ViewportSource::new(0.0, 0.0, buffer_width, buffer_height));
+                let contents_size = surface.get_contents_size().unwrap_or(Size::default());
+
+                // Positions and sizes are in logical coordinates.
+                let pos = Position::new(context.pos.x - surface.offset.x,
+                                        context.pos.y - surface.offset.y);
+                let area = Area::new(pos, contents_size);
+                self.prepare_vertices(&area, vertices);
+
+                for (i, &(x, y)) in CORNERS.iter().enumerate() {
+                    // Map corner from surface through source rectangle to buffer
+                    let sx = (source.x + x * source.width) / buffer_width;
+                    let sy = (source.y + y * source.height) / buffer_height;
+                    let (u, v) = surface.buffer_transform.map_normalized(sx, sy);
+                    texcoords[2 * i] = u;
+                    texcoords[2 * i + 1] = v;
+                }
+                surface.is_opaque()
             } else {
                 log_warn3!("Renderer: No buffer for surface {}", context.id);
+                false
             }
         } else {
             log_warn3!("Renderer: No info for surface {}", context.id);
+            false
         }
     }
 
+    /// Draws surfaces interleaved with their decorations. Decorations of surfaces not present in
+    /// `surfaces` are drawn first.
+    fn draw_decorated_surfaces(&mut self,
+                               surfaces: &Vec<SurfaceContext>,
+                               decorations: &Vec<DecorationContext>,
+                               viewer: &SurfaceViewer) {
+        for decoration in decorations.iter() {
+            if !surfaces.iter().any(|context| context.id == decoration.id) {
+                self.draw_decoration(decoration);
+            }
+        }
+
+        let mut begin = 0;
+        for (i, context) in surfaces.iter().enumerate() {
+            if let Some(decoration) = decorations.iter().find(|d| d.id == context.id) {
+                self.draw_surfaces(&surfaces[begin..i], viewer);
+                self.draw_decoration(decoration);
+                begin = i;
+            }
+        }
+        self.draw_surfaces(&surfaces[begin..], viewer);
+    }
+
+    /// Draws borders and title bar of single surface.
+    fn draw_decoration(&mut self, decoration: &DecorationContext) {
+        let area = decoration.area;
+        let margins = decoration::get_margins();
+        let inner_height = area.size.height.saturating_sub(margins.top + margins.bottom);
+        let bands = [Area::new(area.pos, Size::new(area.size.width, margins.top)),
+                     Area::create(area.pos.x,
+                                  area.pos.y + margins.top as isize,
+                                  margins.left,
+                                  inner_height),
+                     Area::create(area.pos.x +
+                                  area.size.width.saturating_sub(margins.right) as isize,
+                                  area.pos.y + margins.top as isize,
+                                  margins.right,
+                                  inner_height),
+                     Area::create(area.pos.x,
+                                  area.pos.y +
+                                  area.size.height.saturating_sub(margins.bottom) as isize,
+                                  area.size.width,
+                                  margins.bottom)];
+
+        let mut vertices = vec![0.0; 12 * (bands.len() + 1)];
+        let mut texcoords = vec![0.0; 12 * (bands.len() + 1)];
+        for (i, band) in bands.iter().enumerate() {
+            self.prepare_vertices(band, &mut vertices[12 * i..12 * i + 12]);
+        }
+
+        let max_width = area.size
+            .width
+            .saturating_sub(margins.left + margins.right + 2 * TITLE_PADDING);
+        let title = self.load_title_as_texture(decoration.id, &decoration.title, max_width);
+        if let Some(size) = title {
+            let dy = decoration::BORDER_WIDTH +
+                     decoration::TITLE_BAR_HEIGHT.saturating_sub(size.height) / 2;
+            let dx = margins.left + TITLE_PADDING;
+            let pos = area.pos + Position::new(dx as isize, dy as isize);
+            let i = bands.len();
+            self.prepare_vertices(&Area::new(pos, size), &mut vertices[12 * i..12 * i + 12]);
+            for (j, &(x, y)) in CORNERS.iter().enumerate() {
+                texcoords[12 * i + 2 * j] = x;
+                texcoords[12 * i + 2 * j + 1] = y;
+            }
+        }
+
+        let color = if decoration.is_focused {
+            FOCUSED_DECORATION_COLOR
+        } else {
+            UNFOCUSED_DECORATION_COLOR
+        };
+
+        unsafe {
+            self.upload_vertices(&vertices, &texcoords);
+
+            gl::Disable(gl::BLEND);
+            gl::Uniform1i(self.loc_use_fill_color, 1);
+            gl::Uniform4f(self.loc_fill_color, color[0], color[1], color[2], color[3]);
+            gl::DrawArrays(gl::TRIANGLES, 0, 6 * bands.len() as i32);
+            gl::Uniform1i(self.loc_use_fill_color, 0);
+            gl::Enable(gl::BLEND);
+
+            if title.is_some() {
+                gl::Uniform1i(self.loc_texture, 0);
+                gl::DrawArrays(gl::TRIANGLES, 6 * bands.len() as i32, 6);
+            }
+
+            self.release_vertices();
+        }
+    }
+
+    /// Renders title and loads it as texture bound to the first texture unit. Texture is reused
+    /// while the title does not change. Returns logical size of the title.
+    fn load_title_as_texture(&mut self,
+                             sid: SurfaceId,
+                             title: &String,
+                             max_width: usize)
+                             -> Option<Size> {
+        unsafe { gl::ActiveTexture(gl::TEXTURE0) };
+        if let Some(&(ref cached_title, texture, size)) = self.titles.get(&sid) {
+            if (cached_title == title) && (size.width <= max_width) {
+                unsafe { gl::BindTexture(gl::TEXTURE_2D, texture) };
+                return Some(size);
+            }
+        }
+
+        self.release_title(sid);
+        if let Some(buffer) = font::render_text(title, max_width) {
+            let mut texture: gl::types::GLuint = 0;
+            unsafe {
+                gl::GenTextures(1, &mut texture);
+                gl::BindTexture(gl::TEXTURE_2D, texture);
+                gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::NEAREST as i32);
+                gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::NEAREST as i32);
+                gl::TexImage2D(gl::TEXTURE_2D,
+                               0,
+                               gl::RGBA as gl::types::GLint,
+                               buffer.get_width() as gl::types::GLint,
+                               buffer.get_height() as gl::types::GLint,
+                               0,
+                               gl::RGBA,
+                               gl::UNSIGNED_BYTE,
+                               buffer.as_ptr() as *const _);
+            }
+            let size = buffer.get_size();
+            self.titles.insert(sid, (title.clone(), texture, size));
+            Some(size)
+        } else {
+            None
+        }
+    }
+
+    /// Deletes texture with title of given surface.
+    fn release_title(&mut self, sid: SurfaceId) {
+        if let Some((_, texture, _)) = self.titles.remove(&sid) {
+            unsafe { gl::DeleteTextures(1, &texture) };
+        }
+    }
+
+    /// Deletes textures with titles of surfaces which are no longer decorated.
+    fn release_titles(&mut self, decorations: &Vec<DecorationContext>) {
+        let sids: Vec<SurfaceId> = self.titles
+            .keys()
+            .filter(|sid| !decorations.iter().any(|d| d.id == **sid))
+            .cloned()
+            .collect();
+        for sid in sids {
+            self.release_title(sid);
+        }
+    }
+
+    /// Uploads positions and texture coordinates to vertex buffer objects.
+    unsafe fn upload_vertices(&self,
+                              vertices: &[gl::types::GLfloat],
+                              texcoords: &[gl::types::GLfloat]) {
+        let vertices_size = vertices.len() * std::mem::size_of::<gl::types::GLfloat>();
+
+        // Upload positions to vertex buffer object
+        gl::BindBuffer(gl::ARRAY_BUFFER, self.vbo_vertices);
+        gl::EnableVertexAttribArray(self.loc_vertices as gl::types::GLuint);
+        gl::VertexAttribPointer(self.loc_vertices as gl::types::GLuint,
+                                2,
+                                gl::FLOAT,
+                                gl::FALSE,
+                                2 * std::mem::size_of::<gl::types::GLfloat>() as gl::types::GLint,
+                                std::ptr::null());
+        gl::BufferData(gl::ARRAY_BUFFER,
+                       vertices_size as isize,
+                       vertices.as_ptr() as *const _,
+                       gl::DYNAMIC_DRAW);
+
+        // Upload texture coordinates to vertex buffer object
+        gl::BindBuffer(gl::ARRAY_BUFFER, self.vbo_texcoords);
+        gl::EnableVertexAttribArray(self.loc_texcoords as gl::types::GLuint);
+        gl::VertexAttribPointer(self.loc_texcoords as gl::types::GLuint,
+                                2,
+                                gl::FLOAT,
+                                gl::FALSE,
+                                2 * std::mem::size_of::<gl::types::GLfloat>() as gl::types::GLint,
+                                std::ptr::null());
+        gl::BufferData(gl::ARRAY_BUFFER,
+                       vertices_size as isize,
+                       texcoords.as_ptr() as *const _,
+                       gl::DYNAMIC_DRAW);
+    }
+
+    /// Disables vertex attribute arrays enabled by `upload_vertices`.
+    unsafe fn release_vertices(&self) {
+        gl::DisableVertexAttribArray(self.loc_texcoords as gl::types::GLuint);
+        gl::DisableVertexAttribArray(self.loc_vertices as gl::types::GLuint);
+    }
+
     /// Draws surfaces.
-    fn draw_surfaces(&mut self, surfaces: &Vec<SurfaceContext>, viewer: &SurfaceViewer) {
+    fn draw_surfaces(&mut self, surfaces: &[SurfaceContext], viewer: &SurfaceViewer) {
         if surfaces.len() == 0 {
             return;
         }
 
         // Prepare vertices positions and upload textures
         let vertices_len = 12 * surfaces.len();
-        let vertices_size = vertices_len * std::mem::size_of::<gl::types::GLfloat>();
         let mut vertices = vec![0.0; vertices_len];
         let mut texcoords = vec![0.0; vertices_len];
+        let mut opaque = vec![false; surfaces.len()];
 
         for i in 0..surfaces.len() {
             // Activate the target texture
             unsafe { gl::ActiveTexture(gl::TEXTURE0 + i as u32) };
 
             // Bind data to texture and prepare vertices
-            self.load_texture_and_prepare_vertices(viewer,
-                                                   &surfaces[i],
-                                                   &mut vertices[12 * i..12 * i + 12],
-                                                   &mut texcoords[12 * i..12 * i + 12]);
+            opaque[i] =
+                self.load_texture_and_prepare_vertices(viewer,
+                                                       &surfaces[i],
+                                                       &mut vertices[12 * i..12 * i + 12],
+                                                       &mut texcoords[12 * i..12 * i + 12]);
         }
 
         unsafe {
-            // Upload positions to vertex buffer object
-            gl::BindBuffer(gl::ARRAY_BUFFER, self.vbo_vertices);
-            gl::EnableVertexAttribArray(self.loc_vertices as gl::types::GLuint);
-            gl::VertexAttribPointer(self.loc_vertices as gl::types::GLuint,
-                                    2,
-                                    gl::FLOAT,
-                                    gl::FALSE,
-                                    2 *
-                                    std::mem::size_of::<gl::types::GLfloat>() as gl::types::GLint,
-                                    std::ptr::null());
-            gl::BufferData(gl::ARRAY_BUFFER,
-                           vertices_size as isize,
-                           vertices.as_ptr() as *const _,
-                           gl::DYNAMIC_DRAW);
-
-            // Upload positions to vertex buffer object
-            gl::BindBuffer(gl::ARRAY_BUFFER, self.vbo_texcoords);
-            gl::EnableVertexAttribArray(self.loc_texcoords as gl::types::GLuint);
-            gl::VertexAttribPointer(self.loc_texcoords as gl::types::GLuint,
-                                    2,
-                                    gl::FLOAT,
-                                    gl::FALSE,
-                                    2 *
-                                    std::mem::size_of::<gl::types::GLfloat>() as gl::types::GLint,
-                                    std::ptr::null());
-            gl::BufferData(gl::ARRAY_BUFFER,
-                           vertices_size as isize,
-                           texcoords.as_ptr() as *const _,
-                           gl::DYNAMIC_DRAW);
-
-            // Redraw everything
-            for i in 0..surfaces.len() as i32 {
-                gl::Uniform1i(self.loc_texture, i);
-                gl::DrawArrays(gl::TRIANGLES, 6 * i, 6);
+            self.upload_vertices(&vertices, &texcoords);
+
+            // Redraw everything. Blending is not needed for opaque surfaces.
+            for i in 0..surfaces.len() {
+                if opaque[i] {
+                    gl::Disable(gl::BLEND);
+                } else {
+                    gl::Enable(gl::BLEND);
+                }
+                gl::Uniform1i(self.loc_texture, i as i32);
+                gl::DrawArrays(gl::TRIANGLES, 6 * i as i32, 6);
             }
+            gl::Enable(gl::BLEND);
 
             // Release resources
-            gl::DisableVertexAttribArray(self.loc_texcoords as gl::types::GLuint);
-            gl::DisableVertexAttribArray(self.loc_vertices as gl::types::GLuint);
+            self.release_vertices();
         }
     }
 
     /// Unbind framebuffer and program.
     fn release_view(&self) {
         unsafe {
+            gl::Disable(gl::SCISSOR_TEST);
             gl::BindFramebuffer(gl::DRAW_FRAMEBUFFER, 0);
             gl::UseProgram(0);
         }
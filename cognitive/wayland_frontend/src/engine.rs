@@ -8,20 +8,21 @@
 use std;
 use std::collections::HashMap;
 use std::os::unix::io::RawFd;
+use std::time::Instant;
 
 use dharma;
 use skylane::server as wl;
 
 use qualia::{Axis, Button, DrmBundle, Milliseconds, OutputInfo, Position, Size};
 use qualia::{Key, KeyboardConfig, Perceptron, Settings};
-use qualia::{surface_state, SurfaceId};
+use qualia::{surface_state, PresentationTime, SurfaceId, Vector};
 use qualia::FrontendsCoordinationTrait;
 use inputs::{KeyboardState, KeyMods};
 
 use protocol;
 use gateway::Gateway;
 use proxy::{Proxy, ProxyRef};
-use mediator::{Mediator, MediatorRef};
+use mediator::{Mediator, MediatorRef, VirtualKeyboardEvent};
 use event_handlers::{ClientEventHandler, DisplayEventHandler};
 use std::path::PathBuf;
 
@@ -35,6 +36,15 @@ struct Client {
 
 // -------------------------------------------------------------------------------------------------
 
+/// Client receiving keyboard input either as input method grabbing keyboard or as regular client.
+#[derive(Clone, Copy, PartialEq)]
+enum KeyReceiver {
+    InputMethod(dharma::EventHandlerId),
+    Client(dharma::EventHandlerId),
+}
+
+// -------------------------------------------------------------------------------------------------
+
 /// This is main structure of `wayland_frontend` crate.
 ///
 /// For information about its role and place among other structures see crate-level documentation.
@@ -45,8 +55,11 @@ pub struct Engine {
     output_infos: Vec<OutputInfo>,
     coordinator: Box<FrontendsCoordinationTrait>,
     settings: Settings,
+    keyboard_config: KeyboardConfig,
     dispatcher: dharma::LocalDispatcher,
     keyboard_state: KeyboardState,
+    pressed_keys: HashMap<u16, KeyReceiver>,
+    pointer_focused_sid: SurfaceId,
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -71,6 +84,9 @@ impl Engine {
             settings: settings,
             dispatcher: dharma::LocalDispatcher::new(),
             keyboard_state: KeyboardState::new(&keyboard_config).expect("creating keyboard state"),
+            keyboard_config: keyboard_config,
+            pressed_keys: HashMap::new(),
+            pointer_focused_sid: SurfaceId::invalid(),
         }
     }
 
@@ -110,20 +126,35 @@ impl Engine {
         let mut proxy = Proxy::new(id,
                                    coordinator,
                                    self.settings.clone(),
+                                   self.keyboard_config.clone(),
                                    self.mediator.clone(),
                                    client_socket.clone());
         proxy.register_global(protocol::shm::get_global());
         proxy.register_global(protocol::compositor::get_global());
         proxy.register_global(protocol::shell::get_global());
         proxy.register_global(protocol::xdg_shell_v6::get_global());
+        proxy.register_global(protocol::xdg_shell::get_global());
+        proxy.register_global(protocol::layer_shell::get_global());
+        proxy.register_global(protocol::xdg_decoration::get_global());
         proxy.register_global(protocol::data_device_manager::get_global());
+        proxy.register_global(protocol::primary_selection_device_manager::get_global());
         proxy.register_global(protocol::seat::get_global());
+        proxy.register_global(protocol::relative_pointer::get_global());
+        proxy.register_global(protocol::pointer_constraints::get_global());
+        proxy.register_global(protocol::idle_inhibit::get_global());
+        proxy.register_global(protocol::idle_notify::get_global());
+        proxy.register_global(protocol::text_input::get_global());
+        proxy.register_global(protocol::input_method::get_global());
+        proxy.register_global(protocol::virtual_keyboard::get_global());
         proxy.register_global(protocol::subcompositor::get_global());
+        proxy.register_global(protocol::viewporter::get_global());
+        proxy.register_global(protocol::presentation::get_global());
+        proxy.register_global(protocol::xdg_output::get_global());
         proxy.register_global(protocol::weston_screenshooter::get_global());
         proxy.register_global(protocol::linux_dmabuf_v1::get_global());
         proxy.register_global(protocol::mesa_drm::get_global());
         for info in self.output_infos.iter() {
-            proxy.register_global(protocol::output::get_global(info.clone()));
+            proxy.on_display_created(info.clone());
         }
         let proxy_ref = ProxyRef::new(proxy);
 
@@ -147,6 +178,7 @@ impl Engine {
             false
         };
 
+        self.pressed_keys.retain(|_, receiver| *receiver != KeyReceiver::Client(id));
         let result2 = if let Some(_client) = self.clients.remove(&id) {
             true
         } else {
@@ -168,6 +200,9 @@ impl Engine {
         } else {
             log_warn1!("Wayland Engine: No client: {}", id);
         }
+
+        self.deliver_virtual_keyboard_events();
+        self.leave_drag_without_source();
     }
 }
 
@@ -202,52 +237,170 @@ impl Engine {
             }
         }
     }
-}
 
-// -------------------------------------------------------------------------------------------------
+    /// Returns ID of client which should receive keyboard input. Client holding pop-up grab
+    /// receives keyboard input regardless of focus.
+    fn get_keyboard_receiver(&self) -> Option<dharma::EventHandlerId> {
+        let mediator = self.mediator.borrow();
+        if let Some(id) = mediator.get_popup_grabber() {
+            Some(id)
+        } else {
+            let sid = self.coordinator.get_keyboard_focused_sid();
+            mediator.get_client_for_sid(sid).cloned()
+        }
+    }
 
-impl Gateway for Engine {
-    fn on_output_found(&mut self, bundle: DrmBundle) {
-        self.mediator.borrow_mut().set_drm_device(bundle.fd, bundle.path);
+    /// Returns input method if it grabs keyboard or otherwise client which should receive keyboard
+    /// input.
+    fn get_key_receiver(&self) -> Option<KeyReceiver> {
+        let input_method = self.mediator.borrow().get_input_method();
+        match input_method {
+            Some(ref input_method) if input_method.keyboard_grab_oid.is_some() => {
+                Some(KeyReceiver::InputMethod(input_method.cid))
+            }
+            _ => self.get_keyboard_receiver().map(KeyReceiver::Client),
+        }
     }
 
-    fn on_display_created(&mut self, output_info: OutputInfo) {
-        self.output_infos.push(output_info.clone());
-        for (_, client) in self.clients.iter() {
-            client.proxy.borrow_mut().on_display_created(output_info.clone());
+    /// Passes keyboard events emulated by input method to client which should receive keyboard
+    /// input.
+    fn deliver_virtual_keyboard_events(&mut self) {
+        let events = self.mediator.borrow_mut().take_virtual_keyboard_events();
+        if events.is_empty() {
+            return;
+        }
+
+        if let Some(id) = self.get_keyboard_receiver() {
+            if let Some(client) = self.clients.get(&id) {
+                let mut proxy = client.proxy.borrow_mut();
+                for event in events {
+                    match event {
+                        VirtualKeyboardEvent::Key(key) => proxy.on_keyboard_input(key, None),
+                        VirtualKeyboardEvent::Modifiers(mods) => proxy.on_keyboard_modifiers(mods),
+                    }
+                }
+            }
         }
     }
 
-    fn on_keyboard_input(&mut self, key: Key, _mods: Option<KeyMods>) {
-        let mods = if self.keyboard_state.update(key.code, key.value) {
-            Some(self.keyboard_state.get_mods())
-        } else {
-            None
+    /// Moves dragged data over given surface sending `leave` and `enter` events to clients if
+    /// surface changed. Returns `true` if the surface already received `enter`.
+    fn drag_over(&mut self, sid: SurfaceId, position: Position) -> bool {
+        let (drag, old_id, new_id) = {
+            let mediator = self.mediator.borrow();
+            if let Some(drag) = mediator.get_drag() {
+                let old_id = mediator.get_client_for_sid(drag.target_sid).cloned();
+                let new_id = mediator.get_client_for_sid(sid).cloned();
+                (drag, old_id, new_id)
+            } else {
+                return false;
+            }
         };
 
-        let sid = self.coordinator.get_keyboard_focused_sid();
-        if let Some(id) = self.mediator.borrow().get_client_for_sid(sid) {
-            if let Some(client) = self.clients.get(&id) {
-                client.proxy.borrow_mut().on_keyboard_input(key, mods);
+        if drag.target_sid.is_valid() && drag.target_sid == sid {
+            self.mediator.borrow_mut().set_drag_target(sid, position);
+            return true;
+        }
+
+        if let Some(client) = old_id.and_then(|id| self.clients.get(&id)) {
+            client.proxy.borrow_mut().leave_drag();
+        }
+
+        // Drag without data source is not shown to other clients.
+        let mut target_sid = SurfaceId::invalid();
+        if let Some(id) = new_id {
+            if drag.source.is_some() || id == drag.origin_cid {
+                if let Some(client) = self.clients.get_mut(&id) {
+                    let mut proxy = client.proxy.borrow_mut();
+                    proxy.enter_drag(&mut client.connection,
+                                     client.proxy.clone(),
+                                     sid,
+                                     position,
+                                     drag.source.clone());
+                    target_sid = sid;
+                }
             }
         }
+
+        self.mediator.borrow_mut().set_drag_target(target_sid, position);
+        false
     }
 
-    fn on_surface_frame(&mut self, sid: SurfaceId, milliseconds: Milliseconds) {
-        if let Some(id) = self.mediator.borrow().get_client_for_sid(sid) {
-            if let Some(client) = self.clients.get(&id) {
-                client.proxy.borrow_mut().on_surface_frame(sid, milliseconds);
+    /// Drops dragged data on current target, informs the source about result and gives pointer
+    /// focus back to surface under pointer.
+    fn drop_drag(&mut self) {
+        let drag = self.mediator.borrow_mut().end_drag();
+        if let Some(drag) = drag {
+            let target_id = self.mediator.borrow().get_client_for_sid(drag.target_sid).cloned();
+            let is_dropped = if let Some(client) = target_id.and_then(|id| self.clients.get(&id)) {
+                client.proxy.borrow_mut().drop_drag()
+            } else {
+                false
+            };
+
+            let position = drag.position;
+            if let Some(client) = self.clients.get(&drag.origin_cid) {
+                client.proxy.borrow_mut().end_drag(drag, is_dropped);
             }
+
+            // Pointer focus was left when dragging started.
+            let sid = self.coordinator.get_pointer_focused_sid();
+            self.pointer_focused_sid = SurfaceId::invalid();
+            self.update_pointer_focus(sid, position);
         }
     }
 
-    fn on_pointer_focus_changed(&self,
-                                old_sid: SurfaceId,
-                                new_sid: SurfaceId,
-                                position: Position) {
+    /// Sends `leave` to target of drag-and-drop if data source of the drag was destroyed. Drag
+    /// without data source continues only within the origin client.
+    fn leave_drag_without_source(&mut self) {
+        if !self.mediator.borrow_mut().take_drag_source_cancelled() {
+            return;
+        }
+
+        let drag = if let Some(drag) = self.mediator.borrow().get_drag() {
+            drag
+        } else {
+            return;
+        };
+
+        let target_id = self.mediator.borrow().get_client_for_sid(drag.target_sid).cloned();
+        if let Some(client) = target_id.and_then(|id| self.clients.get(&id)) {
+            client.proxy.borrow_mut().leave_drag();
+        }
+        self.mediator.borrow_mut().set_drag_target(SurfaceId::invalid(), drag.position);
+    }
+
+    /// Returns ID of client which should receive pointer events over given surface. While pop-up
+    /// grab is active only the grabbing client receives pointer events.
+    fn get_pointer_receiver(&self, sid: SurfaceId) -> Option<dharma::EventHandlerId> {
         let mediator = self.mediator.borrow();
-        let old_client_id = mediator.get_client_for_sid(old_sid);
-        let new_client_id = mediator.get_client_for_sid(new_sid);
+        let id = mediator.get_client_for_sid(sid).cloned();
+        match mediator.get_popup_grabber() {
+            Some(grabber) if id != Some(grabber) => None,
+            _ => id,
+        }
+    }
+
+    /// Moves pointer focus to given surface. Surfaces of clients other than one holding pop-up
+    /// grab do not get focus until the grab is dismissed.
+    fn update_pointer_focus(&mut self, sid: SurfaceId, position: Position) {
+        let old_sid = self.pointer_focused_sid;
+        let new_sid = if self.get_pointer_receiver(sid).is_some() {
+            sid
+        } else {
+            SurfaceId::invalid()
+        };
+
+        if old_sid == new_sid {
+            return;
+        }
+        self.pointer_focused_sid = new_sid;
+
+        let (old_client_id, new_client_id) = {
+            let mediator = self.mediator.borrow();
+            (mediator.get_client_for_sid(old_sid).cloned(),
+             mediator.get_client_for_sid(new_sid).cloned())
+        };
 
         if new_client_id != old_client_id {
             if let Some(client_id) = old_client_id {
@@ -273,10 +426,139 @@ impl Gateway for Engine {
         }
     }
 
-    fn on_pointer_relative_motion(&self,
+    /// Dismisses pop-ups holding grab if given surface is not one of them.
+    fn dismiss_popup_grabs_outside(&self, sid: SurfaceId) {
+        let id = self.mediator.borrow().get_popup_grabber();
+        if let Some(id) = id {
+            if let Some(client) = self.clients.get(&id) {
+                let mut proxy = client.proxy.borrow_mut();
+                if !proxy.is_grabbing_popup(sid) {
+                    proxy.dismiss_popup_grabs();
+                }
+            }
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl Gateway for Engine {
+    fn on_output_found(&mut self, bundle: DrmBundle) {
+        self.mediator.borrow_mut().set_drm_device(bundle.fd, bundle.path);
+    }
+
+    fn on_display_created(&mut self, output_info: OutputInfo) {
+        self.output_infos.push(output_info.clone());
+        for (_, client) in self.clients.iter() {
+            client.proxy.borrow_mut().on_display_created(output_info.clone());
+        }
+    }
+
+    fn on_display_updated(&mut self, output_info: OutputInfo) {
+        for info in self.output_infos.iter_mut().filter(|info| info.id == output_info.id) {
+            *info = output_info.clone();
+        }
+        for (_, client) in self.clients.iter() {
+            client.proxy.borrow_mut().on_display_updated(output_info.clone());
+        }
+    }
+
+    fn on_keyboard_input(&mut self, key: Key, _mods: Option<KeyMods>) {
+        let mods = if self.keyboard_state.update(key.code, key.value) {
+            Some(self.keyboard_state.get_mods())
+        } else {
+            None
+        };
+
+        // Input method grabbing keyboard receives all keyboard input. It passes back keys it does
+        // not handle using virtual keyboard. Keys pressed before the grab started are still
+        // passed to the client which received the press. Keys pressed during the grab are passed to
+        // the focused client after the grab ended as the input method might have passed the press
+        // to it.
+        let receiver = match (self.pressed_keys.get(&key.code), self.get_key_receiver()) {
+            (Some(&KeyReceiver::Client(id)), Some(KeyReceiver::InputMethod(_))) => {
+                Some(KeyReceiver::Client(id))
+            }
+            (_, receiver) => receiver,
+        };
+
+        if key.value == 0 {
+            self.pressed_keys.remove(&key.code);
+        } else if let Some(receiver) = receiver {
+            self.pressed_keys.insert(key.code, receiver);
+        }
+
+        match receiver {
+            Some(KeyReceiver::InputMethod(id)) => {
+                if let Some(client) = self.clients.get(&id) {
+                    client.proxy.borrow_mut().on_input_method_keyboard_input(key, mods);
+                }
+            }
+            Some(KeyReceiver::Client(id)) => {
+                if let Some(client) = self.clients.get(&id) {
+                    client.proxy.borrow_mut().on_keyboard_input(key, mods);
+                }
+            }
+            None => {}
+        }
+    }
+
+    fn on_surface_frame(&mut self, sid: SurfaceId, milliseconds: Milliseconds) {
+        if let Some(id) = self.mediator.borrow().get_client_for_sid(sid) {
+            if let Some(client) = self.clients.get(&id) {
+                client.proxy.borrow_mut().on_surface_frame(sid, milliseconds);
+            }
+        }
+    }
+
+    fn on_surface_presented(&mut self, sid: SurfaceId, display_id: i32, time: PresentationTime) {
+        if let Some(id) = self.mediator.borrow().get_client_for_sid(sid) {
+            if let Some(client) = self.clients.get(&id) {
+                client.proxy.borrow_mut().on_surface_presented(sid, display_id, time);
+            }
+        }
+    }
+
+    fn on_surface_discarded(&mut self, sid: SurfaceId) {
+        if let Some(id) = self.mediator.borrow().get_client_for_sid(sid) {
+            if let Some(client) = self.clients.get(&id) {
+                client.proxy.borrow_mut().on_surface_discarded(sid);
+            }
+        }
+    }
+
+    fn on_pointer_focus_changed(&mut self,
+                                _old_sid: SurfaceId,
+                                new_sid: SurfaceId,
+                                position: Position) {
+        if self.mediator.borrow().is_dragging() {
+            self.drag_over(new_sid, position);
+            return;
+        }
+
+        self.update_pointer_focus(new_sid, position);
+    }
+
+    fn on_pointer_relative_motion(&mut self,
                                   sid: SurfaceId,
                                   position: Position,
                                   milliseconds: Milliseconds) {
+        if self.mediator.borrow().is_dragging() {
+            if self.drag_over(sid, position) {
+                let id = self.mediator.borrow().get_client_for_sid(sid).cloned();
+                if let Some(client) = id.and_then(|id| self.clients.get(&id)) {
+                    client.proxy.borrow().move_drag(position, milliseconds);
+                }
+            }
+            return;
+        }
+
+        // Focus may be pending since pop-up grab was dismissed.
+        self.update_pointer_focus(sid, position);
+        if self.pointer_focused_sid != sid {
+            return;
+        }
+
         if let Some(id) = self.mediator.borrow().get_client_for_sid(sid) {
             if let Some(client) = self.clients.get(&id) {
                 client.proxy.borrow_mut().on_pointer_relative_motion(sid, position, milliseconds);
@@ -284,8 +566,39 @@ impl Gateway for Engine {
         }
     }
 
-    fn on_pointer_button(&self, btn: Button) {
+    fn on_pointer_delta(&mut self, sid: SurfaceId, vector: Vector, milliseconds: Milliseconds) {
+        if let Some(id) = self.get_pointer_receiver(sid) {
+            if let Some(client) = self.clients.get(&id) {
+                client.proxy.borrow_mut().on_pointer_delta(sid, vector, milliseconds);
+            }
+        }
+    }
+
+    fn on_pointer_constraint_changed(&mut self, sid: SurfaceId, is_active: bool) {
+        if let Some(id) = self.mediator.borrow().get_client_for_sid(sid) {
+            if let Some(client) = self.clients.get(&id) {
+                client.proxy.borrow_mut().on_pointer_constraint_changed(sid, is_active);
+            }
+        }
+    }
+
+    fn on_pointer_button(&mut self, btn: Button) {
         let sid = self.coordinator.get_pointer_focused_sid();
+        let drag = self.mediator.borrow().get_drag();
+        if let Some(drag) = drag {
+            if btn.value == 0 && drag.button.map_or(true, |code| code == btn.code) {
+                self.drop_drag();
+            }
+            return;
+        }
+
+        if btn.value != 0 {
+            self.dismiss_popup_grabs_outside(sid);
+        }
+
+        // Button events go only to surface which received pointer enter. Click dismissing pop-up
+        // grab is not passed to other clients.
+        let sid = self.pointer_focused_sid;
         if let Some(id) = self.mediator.borrow().get_client_for_sid(sid) {
             if let Some(client) = self.clients.get(&id) {
                 client.proxy.borrow_mut().on_pointer_button(btn);
@@ -294,7 +607,11 @@ impl Gateway for Engine {
     }
 
     fn on_pointer_axis(&self, axis: Axis) {
-        let sid = self.coordinator.get_pointer_focused_sid();
+        if self.mediator.borrow().is_dragging() {
+            return;
+        }
+
+        let sid = self.pointer_focused_sid;
         if let Some(id) = self.mediator.borrow().get_client_for_sid(sid) {
             if let Some(client) = self.clients.get(&id) {
                 client.proxy.borrow_mut().on_pointer_axis(axis);
@@ -302,11 +619,47 @@ impl Gateway for Engine {
         }
     }
 
-    fn on_keyboard_focus_changed(&mut self, old_sid: SurfaceId, new_sid: SurfaceId) {
-        let mediator = self.mediator.borrow();
-        let old_client_id = mediator.get_client_for_sid(old_sid);
-        let new_client_id = mediator.get_client_for_sid(new_sid);
+    fn on_touch_down(&mut self, sid: SurfaceId, id: i32, position: Position, time: Milliseconds) {
+        if let Some(client_id) = self.mediator.borrow().get_client_for_sid(sid) {
+            if let Some(client) = self.clients.get(&client_id) {
+                client.proxy.borrow_mut().on_touch_down(sid, id, position, time);
+            }
+        }
+    }
+
+    fn on_touch_motion(&mut self,
+                       sid: SurfaceId,
+                       id: i32,
+                       position: Position,
+                       time: Milliseconds) {
+        if let Some(client_id) = self.mediator.borrow().get_client_for_sid(sid) {
+            if let Some(client) = self.clients.get(&client_id) {
+                client.proxy.borrow_mut().on_touch_motion(sid, id, position, time);
+            }
+        }
+    }
+
+    fn on_touch_up(&mut self, sid: SurfaceId, id: i32, time: Milliseconds) {
+        if let Some(client_id) = self.mediator.borrow().get_client_for_sid(sid) {
+            if let Some(client) = self.clients.get(&client_id) {
+                client.proxy.borrow_mut().on_touch_up(sid, id, time);
+            }
+        }
+    }
 
+    fn on_touch_frame(&mut self) {
+        for client in self.clients.values() {
+            client.proxy.borrow_mut().on_touch_frame();
+        }
+    }
+
+    fn on_keyboard_focus_changed(&mut self, old_sid: SurfaceId, new_sid: SurfaceId) {
+        // `Mediator` can not stay borrowed as proxies may update pop-up grab.
+        let (old_client_id, new_client_id) = {
+            let mediator = self.mediator.borrow();
+            (mediator.get_client_for_sid(old_sid).cloned(),
+             mediator.get_client_for_sid(new_sid).cloned())
+        };
 
         if new_client_id != old_client_id {
             if let Some(client_id) = old_client_id {
@@ -319,6 +672,7 @@ impl Gateway for Engine {
                 if let Some(client) = self.clients.get_mut(&client_id) {
                     let mut proxy = client.proxy.borrow_mut();
                     proxy.make_data_offer(&mut client.connection, client.proxy.clone());
+                    proxy.make_primary_offer(&mut client.connection, client.proxy.clone());
                     proxy.on_keyboard_focus_changed(SurfaceId::invalid(), new_sid);
                 }
             }
@@ -350,6 +704,40 @@ impl Gateway for Engine {
         }
     }
 
+    fn on_primary_transfer_offered(&mut self) {
+        for client in self.clients.values() {
+            client.proxy.borrow_mut().on_primary_transfer_offered();
+        }
+
+        let sid = self.coordinator.get_keyboard_focused_sid();
+        let id = self.mediator.borrow().get_client_for_sid(sid).cloned();
+        if let Some(client) = id.and_then(|id| self.clients.get_mut(&id)) {
+            client.proxy.borrow_mut().make_primary_offer(&mut client.connection,
+                                                         client.proxy.clone());
+        }
+    }
+
+    fn on_primary_transfer_requested(&mut self, mime_type: String, fd: RawFd) {
+        if let Some(id) = self.mediator.borrow().get_primary_transfer_offerer() {
+            if let Some(client) = self.clients.get_mut(&id) {
+                client.proxy.borrow_mut().on_primary_transfer_requested(mime_type, fd);
+            }
+        }
+    }
+
+    fn on_system_activity(&mut self) {
+        self.mediator.borrow_mut().get_idle_tracker_mut().on_activity(Instant::now());
+        for client in self.clients.values() {
+            client.proxy.borrow_mut().on_system_activity();
+        }
+    }
+
+    fn on_timer_500(&mut self) {
+        for client in self.clients.values() {
+            client.proxy.borrow_mut().on_timer_500();
+        }
+    }
+
     fn on_surface_reconfigured(&self,
                                sid: SurfaceId,
                                size: Size,
@@ -5,14 +5,21 @@
 
 // -------------------------------------------------------------------------------------------------
 
+use std::cmp;
 use std::path::PathBuf;
 use std::os::unix::io::RawFd;
+use std::time::Duration;
 
 use skylane::server as wl;
+use wayland_protocols::server::text_input_unstable_v3::zwp_text_input_v3;
+use wayland_protocols::server::xdg_shell::xdg_positioner::constraint_adjustment;
 
 use cognitive_graphics::attributes::{EglAttributes, DmabufAttributes};
-use qualia::{Area, Memory, PixelFormat, Size, SurfaceId, Transfer, Vector, show_reason};
+use qualia::{Area, Memory, PixelFormat, Position, Size, SurfaceId, Transfer, Transform, Vector};
+use qualia::{dnd_action, resize_edge, show_reason};
 use qualia::{DmabufId, EglImageId, MemoryPoolId, MemoryViewId};
+use qualia::{Key, LayerProperties, ViewportSource};
+use inputs::KeyMods;
 
 // -------------------------------------------------------------------------------------------------
 
@@ -21,6 +28,97 @@ use qualia::{DmabufId, EglImageId, MemoryPoolId, MemoryViewId};
 pub enum ShellSurfaceOid {
     Shell(wl::ObjectId),
     ZxdgToplevelV6(wl::ObjectId, wl::ObjectId),
+    XdgToplevel(wl::ObjectId, wl::ObjectId),
+    Layer(wl::ObjectId),
+    InputPopup(wl::ObjectId),
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Enum describing type of shell pop-up and its object ID.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PopupOid {
+    Shell(wl::ObjectId),
+    ZxdgPopupV6(wl::ObjectId),
+    XdgPopup(wl::ObjectId),
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Enum describing type of pointer constraint and its object ID.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PointerConstraintOid {
+    Locked(wl::ObjectId),
+    Confined(wl::ObjectId),
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Placement along one axis used by positioners to describe anchor point and gravity.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Placement {
+    Start,
+    Center,
+    End,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl Placement {
+    /// Returns distance of point described by this placement from the beginning of segment of
+    /// given length.
+    fn get_anchor_offset(&self, length: usize) -> isize {
+        match *self {
+            Placement::Start => 0,
+            Placement::Center => (length / 2) as isize,
+            Placement::End => length as isize,
+        }
+    }
+
+    /// Returns distance between anchor point and beginning of segment of given length placed in
+    /// direction described by this placement.
+    fn get_gravity_offset(&self, length: usize) -> isize {
+        match *self {
+            Placement::Start => -(length as isize),
+            Placement::Center => -((length / 2) as isize),
+            Placement::End => 0,
+        }
+    }
+
+    /// Returns placement on the opposite side.
+    fn flipped(&self) -> Placement {
+        match *self {
+            Placement::Start => Placement::End,
+            Placement::Center => Placement::Center,
+            Placement::End => Placement::Start,
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Checks if segment starting at `pos` fits between `min` and `max`.
+fn fits(pos: isize, length: usize, min: isize, max: isize) -> bool {
+    pos >= min && pos + length as isize <= max
+}
+
+/// Moves segment starting at `pos` to fit between `min` and `max`. If the segment is too long it is
+/// aligned to `min`.
+fn slide(pos: isize, length: usize, min: isize, max: isize) -> isize {
+    let pos = if pos + length as isize > max { max - length as isize } else { pos };
+    if pos < min { min } else { pos }
+}
+
+/// Shrinks segment starting at `pos` to its part between `min` and `max`. Segment is left
+/// unchanged if no part of it lays between `min` and `max`.
+fn resize(pos: isize, length: usize, min: isize, max: isize) -> (isize, usize) {
+    let start = cmp::max(pos, min);
+    let end = cmp::min(pos + length as isize, max);
+    if end > start {
+        (start, (end - start) as usize)
+    } else {
+        (pos, length)
+    }
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -31,6 +129,9 @@ pub struct PositionerInfo {
     pub offset: Vector,
     pub size: Size,
     pub anchor: Area,
+    pub anchor_placement: (Placement, Placement),
+    pub gravity: (Placement, Placement),
+    pub constraint_adjustment: u32,
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -41,11 +142,126 @@ impl PositionerInfo {
             offset: Vector::default(),
             size: Size::default(),
             anchor: Area::default(),
+            anchor_placement: (Placement::Center, Placement::Center),
+            gravity: (Placement::Center, Placement::Center),
+            constraint_adjustment: constraint_adjustment::NONE,
         }
     }
 
+    /// Computes area of pop-up relative to its parent.
     pub fn get_area(&self) -> Area {
-        Area::new(self.offset + self.anchor.pos, self.size)
+        let (anchor_x, anchor_y) = self.anchor_placement;
+        let (gravity_x, gravity_y) = self.gravity;
+        let x = self.anchor.pos.x + anchor_x.get_anchor_offset(self.anchor.size.width) +
+                gravity_x.get_gravity_offset(self.size.width);
+        let y = self.anchor.pos.y + anchor_y.get_anchor_offset(self.anchor.size.height) +
+                gravity_y.get_gravity_offset(self.size.height);
+        Area::new(self.offset + Position::new(x, y), self.size)
+    }
+
+    /// Computes area of pop-up relative to its parent adjusted to fit in `bounds` (also relative
+    /// to the parent) as allowed by constraint adjustment. Flipping is tried first, then sliding
+    /// and resizing.
+    pub fn get_constrained_area(&self, bounds: Area) -> Area {
+        let mut area = self.get_area();
+        let adjustment = self.constraint_adjustment;
+
+        let (min_x, max_x) = (bounds.pos.x, bounds.pos.x + bounds.size.width as isize);
+        if !fits(area.pos.x, area.size.width, min_x, max_x) {
+            if adjustment & constraint_adjustment::FLIP_X != 0 {
+                let mut flipped = *self;
+                flipped.anchor_placement.0 = self.anchor_placement.0.flipped();
+                flipped.gravity.0 = self.gravity.0.flipped();
+                flipped.offset.x = -self.offset.x;
+                let flipped_area = flipped.get_area();
+                if fits(flipped_area.pos.x, flipped_area.size.width, min_x, max_x) {
+                    area.pos.x = flipped_area.pos.x;
+                }
+            }
+            if adjustment & constraint_adjustment::SLIDE_X != 0 {
+                area.pos.x = slide(area.pos.x, area.size.width, min_x, max_x);
+            }
+            if adjustment & constraint_adjustment::RESIZE_X != 0 {
+                let (x, width) = resize(area.pos.x, area.size.width, min_x, max_x);
+                area.pos.x = x;
+                area.size.width = width;
+            }
+        }
+
+        let (min_y, max_y) = (bounds.pos.y, bounds.pos.y + bounds.size.height as isize);
+        if !fits(area.pos.y, area.size.height, min_y, max_y) {
+            if adjustment & constraint_adjustment::FLIP_Y != 0 {
+                let mut flipped = *self;
+                flipped.anchor_placement.1 = self.anchor_placement.1.flipped();
+                flipped.gravity.1 = self.gravity.1.flipped();
+                flipped.offset.y = -self.offset.y;
+                let flipped_area = flipped.get_area();
+                if fits(flipped_area.pos.y, flipped_area.size.height, min_y, max_y) {
+                    area.pos.y = flipped_area.pos.y;
+                }
+            }
+            if adjustment & constraint_adjustment::SLIDE_Y != 0 {
+                area.pos.y = slide(area.pos.y, area.size.height, min_y, max_y);
+            }
+            if adjustment & constraint_adjustment::RESIZE_Y != 0 {
+                let (y, height) = resize(area.pos.y, area.size.height, min_y, max_y);
+                area.pos.y = y;
+                area.size.height = height;
+            }
+        }
+
+        area
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// State of text input applied on commit. Input method is informed about it when the text input
+/// is enabled.
+#[derive(Clone, Debug)]
+pub struct TextInputState {
+    pub is_enabled: bool,
+    pub surrounding_text: Option<(String, i32, i32)>,
+    pub change_cause: u32,
+    pub content_hint: u32,
+    pub content_purpose: u32,
+    pub cursor_rectangle: Area,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl TextInputState {
+    pub fn new() -> Self {
+        TextInputState {
+            is_enabled: false,
+            surrounding_text: None,
+            change_cause: zwp_text_input_v3::change_cause::INPUT_METHOD,
+            content_hint: zwp_text_input_v3::content_hint::NONE,
+            content_purpose: zwp_text_input_v3::content_purpose::NORMAL,
+            cursor_rectangle: Area::default(),
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Changes of text requested by input method, applied on commit.
+#[derive(Clone, Debug)]
+pub struct InputMethodState {
+    pub commit_string: Option<String>,
+    pub preedit_string: Option<(String, i32, i32)>,
+    pub delete_surrounding_text: (u32, u32),
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl InputMethodState {
+    pub fn new() -> Self {
+        InputMethodState {
+            commit_string: None,
+            preedit_string: None,
+            delete_surrounding_text: (0, 0),
+        }
     }
 }
 
@@ -94,8 +310,9 @@ pub trait Facade {
     /// Requests destruction of dmabuf.
     fn destroy_dmabuf(&mut self, dbid: DmabufId);
 
-    /// Defines region. Regions may be used to define input area of surface.
-    fn define_region(&mut self, region_oid: wl::ObjectId, region: Area);
+    /// Defines region as union of given areas. Regions may be used to define input or opaque area
+    /// of surface.
+    fn define_region(&mut self, region_oid: wl::ObjectId, region: Vec<Area>);
 
     /// Undefines region.
     fn undefine_region(&mut self, region_oid: wl::ObjectId);
@@ -106,18 +323,36 @@ pub trait Facade {
     /// Removes pointer OID.
     fn remove_pointer_oid(&mut self, pointer_oid: wl::ObjectId);
 
+    /// Adds relative pointer OID.
+    fn add_relative_pointer_oid(&mut self, relative_pointer_oid: wl::ObjectId);
+
+    /// Removes relative pointer OID.
+    fn remove_relative_pointer_oid(&mut self, relative_pointer_oid: wl::ObjectId);
+
     /// Adds keyboard OID.
     fn add_keyboard_oid(&mut self, keyboard_oid: wl::ObjectId);
 
     /// Removes keyboard OID.
     fn remove_keyboard_oid(&mut self, keyboard_oid: wl::ObjectId);
 
-    /// Add data device OID.
-    fn add_data_device_oid(&mut self, data_device_oid: wl::ObjectId);
+    /// Adds touch OID.
+    fn add_touch_oid(&mut self, touch_oid: wl::ObjectId);
+
+    /// Removes touch OID.
+    fn remove_touch_oid(&mut self, touch_oid: wl::ObjectId);
+
+    /// Add data device OID together with version of bound `wl_data_device_manager`.
+    fn add_data_device_oid(&mut self, data_device_oid: wl::ObjectId, version: u32);
 
     /// Removes data device OID.
     fn remove_data_device_oid(&mut self, data_device_oid: wl::ObjectId);
 
+    /// Add primary selection device OID.
+    fn add_primary_device_oid(&mut self, primary_device_oid: wl::ObjectId);
+
+    /// Removes primary selection device OID.
+    fn remove_primary_device_oid(&mut self, primary_device_oid: wl::ObjectId);
+
     /// Sets positioner info.
     fn set_positioner(&mut self, wl::ObjectId, positioner: PositionerInfo);
 
@@ -127,9 +362,16 @@ pub trait Facade {
     /// Removes positioner info.
     fn remove_positioner(&mut self, oid: wl::ObjectId);
 
+    /// Returns area of display containing given surface relative to the surface. Pop-ups of the
+    /// surface should fit in it. Returns `None` if the surface was not drawn yet.
+    fn get_popup_bounds(&self, parent_surface_oid: wl::ObjectId) -> Option<Area>;
+
     /// Sets transfer info.
     fn set_transfer(&mut self, wl::ObjectId, transfer: Transfer);
 
+    /// Relates data source object ID with version of bound `wl_data_device_manager`.
+    fn relate_data_source_oid_with_version(&mut self, oid: wl::ObjectId, version: u32);
+
     /// Gets transfer info.
     fn get_transfer(&mut self, oid: wl::ObjectId) -> Option<Transfer>;
 
@@ -142,24 +384,90 @@ pub trait Facade {
     /// Request start of data transfer to requesting client.
     fn request_transfer(&mut self, mime_type: String, fd: RawFd);
 
-    /// Sets given region as input region of surface.
+    /// Sets primary selection transfer info.
+    fn set_primary_transfer(&mut self, wl::ObjectId, transfer: Transfer);
+
+    /// Gets primary selection transfer info.
+    fn get_primary_transfer(&mut self, oid: wl::ObjectId) -> Option<Transfer>;
+
+    /// Selects given primary selection transfer info as the offered one. `None` unsets primary
+    /// selection.
+    fn select_primary_transfer(&mut self, oid: Option<wl::ObjectId>);
+
+    /// Removes primary selection transfer info.
+    fn remove_primary_transfer(&mut self, oid: wl::ObjectId);
+
+    /// Request start of primary selection data transfer to requesting client.
+    fn request_primary_transfer(&mut self, mime_type: String, fd: RawFd);
+
+    /// Starts drag-and-drop of data from given source over given origin surface. Drag without
+    /// source is allowed only within requesting client.
+    fn start_drag(&mut self,
+                  data_source_oid: Option<wl::ObjectId>,
+                  origin_surface_oid: wl::ObjectId,
+                  icon_surface_oid: Option<wl::ObjectId>);
+
+    /// Sets mime type accepted by destination of drag-and-drop.
+    fn accept_drag_mime_type(&mut self, data_offer_oid: wl::ObjectId, mime_type: Option<String>);
+
+    /// Sets drag-and-drop actions supported by destination and negotiates the resulting action.
+    fn set_drag_actions(&mut self,
+                        data_offer_oid: wl::ObjectId,
+                        dnd_actions: dnd_action::DndAction,
+                        preferred_action: dnd_action::DndAction);
+
+    /// Requests start of drag-and-drop data transfer from source to requesting client.
+    fn request_drag_transfer(&mut self,
+                             data_offer_oid: wl::ObjectId,
+                             mime_type: String,
+                             fd: RawFd);
+
+    /// Informs source that destination finished drag-and-drop.
+    fn finish_drag(&mut self, data_offer_oid: wl::ObjectId);
+
+    /// Removes drag-and-drop data offer.
+    fn remove_drag_offer(&mut self, data_offer_oid: wl::ObjectId);
+
+    /// Sets given region as input region of surface. Null region means the whole surface accepts
+    /// input. This will take effect after `commit`.
     fn set_input_region(&self, sid: SurfaceId, region_oid: wl::ObjectId);
 
+    /// Sets given region as opaque region of surface. This will take effect after `commit`.
+    fn set_opaque_region(&self, sid: SurfaceId, region_oid: wl::ObjectId);
+
     /// Requests creation of surface. Return ID of newly created surface.
     fn create_surface(&mut self, surface_oid: wl::ObjectId) -> SurfaceId;
 
-    /// Requests destruction of surface.
-    fn destroy_surface(&self, sid: SurfaceId);
+    /// Requests destruction of surface. Pop-ups grabbed by or over this surface are dismissed.
+    fn destroy_surface(&mut self, sid: SurfaceId);
 
     /// Attaches memory view to surface. This will take effect after `commit`.
     fn attach(&mut self, buffer_oid: wl::ObjectId, sid: SurfaceId, x: i32, y: i32);
 
+    /// Sets scale of buffers attached to surface. This will take effect after `commit`.
+    fn set_buffer_scale(&self, sid: SurfaceId, scale: u32);
+
+    /// Sets transform of buffers attached to surface. This will take effect after `commit`.
+    fn set_buffer_transform(&self, sid: SurfaceId, transform: Transform);
+
+    /// Marks given area (in surface coordinates) of surface as changed. This will take effect
+    /// after `commit`.
+    fn damage(&self, sid: SurfaceId, area: Area);
+
+    /// Marks given area (in buffer coordinates) of surface as changed. This will take effect after
+    /// `commit`.
+    fn damage_buffer(&self, sid: SurfaceId, area: Area);
+
     /// Commits all requests to surface.
-    fn commit(&self, sid: SurfaceId);
+    fn commit(&mut self, sid: SurfaceId);
 
     /// Requests (one-shot) notification about redrawing of given surface.
     fn set_frame(&mut self, sid: SurfaceId, frame_oid: wl::ObjectId);
 
+    /// Requests (one-shot) feedback about presentation of contents of given surface. This will take
+    /// effect after `commit`.
+    fn add_presentation_feedback(&mut self, surface_oid: wl::ObjectId, feedback_oid: wl::ObjectId);
+
     /// Adds a reason to show given surface on screen.
     fn show(&mut self,
             surface_oid: wl::ObjectId,
@@ -186,11 +494,204 @@ pub trait Facade {
     /// Requests to set offset between related surfaces.
     fn set_relative_position(&self, surface_oid: wl::ObjectId, x: isize, y: isize);
 
+    /// Requests placing related surface directly above its sibling or parent.
+    fn place_above(&self, surface_oid: wl::ObjectId, sibling_oid: wl::ObjectId);
+
+    /// Requests placing related surface directly below its sibling or parent.
+    fn place_below(&self, surface_oid: wl::ObjectId, sibling_oid: wl::ObjectId);
+
+    /// Sets synchronization mode of related surface.
+    fn set_synchronized(&self, surface_oid: wl::ObjectId, is_synchronized: bool);
+
+    /// Marks given surface as having given viewport. Returns `false` if the surface already had
+    /// one.
+    fn add_viewport(&mut self, surface_oid: wl::ObjectId, viewport_oid: wl::ObjectId) -> bool;
+
+    /// Forgets viewport of given surface. Cropping and scaling will be unset after `commit`.
+    fn remove_viewport(&mut self, surface_oid: wl::ObjectId);
+
+    /// Sets source rectangle of viewport of given surface. This will take effect after `commit`.
+    fn set_viewport_source(&self, surface_oid: wl::ObjectId, source: Option<ViewportSource>);
+
+    /// Sets destination size of viewport of given surface. This will take effect after `commit`.
+    fn set_viewport_destination(&self, surface_oid: wl::ObjectId, destination: Option<Size>);
+
+    /// Sets window geometry (visible bounds of the window excluding e.g. shadows) of given
+    /// surface.
+    fn set_window_geometry(&self, surface_oid: wl::ObjectId, area: Area);
+
+    /// Sets title of given surface.
+    fn set_title(&self, surface_oid: wl::ObjectId, title: String);
+
+    /// Sets application ID (or class) of given surface.
+    fn set_app_id(&self, surface_oid: wl::ObjectId, app_id: String);
+
+    /// Requests interactive, pointer-driven move of given surface. `serial` must be the serial of
+    /// button press which is still held over the surface.
+    fn request_move(&self, surface_oid: wl::ObjectId, serial: u32);
+
+    /// Requests interactive, pointer-driven resize of given surface by given edges. `serial` must
+    /// be the serial of button press which is still held over the surface.
+    fn request_resize(&self,
+                      surface_oid: wl::ObjectId,
+                      serial: u32,
+                      edges: resize_edge::ResizeEdge);
+
+    /// Requests to maximize or unmaximize given surface.
+    fn set_maximized(&self, surface_oid: wl::ObjectId, maximized: bool);
+
+    /// Requests to make given surface fullscreen (optionally on given output) or to bring it back
+    /// from fullscreen.
+    fn set_fullscreen(&self,
+                      surface_oid: wl::ObjectId,
+                      fullscreen: bool,
+                      output_oid: Option<wl::ObjectId>);
+
+    /// Requests to minimize given surface.
+    fn set_minimized(&self, surface_oid: wl::ObjectId);
+
+    /// Places given surface in layer (optionally on given output) outside of frame layout.
+    /// Returns `false` if the surface already has a shell role.
+    fn add_layer_surface(&mut self,
+                         surface_oid: wl::ObjectId,
+                         layer_surface_oid: wl::ObjectId,
+                         output_oid: Option<wl::ObjectId>,
+                         properties: LayerProperties)
+                         -> bool;
+
+    /// Sets properties of given layer surface. This will take effect after `commit`.
+    fn set_layer_properties(&mut self, surface_oid: wl::ObjectId, properties: LayerProperties);
+
+    /// Removes given surface from layers and hides it.
+    fn remove_layer_surface(&mut self, surface_oid: wl::ObjectId);
+
+    /// Sets whether surface of given `xdg_toplevel` should be decorated by compositor. Returns ID
+    /// of the `xdg_surface` of the toplevel or `None` if there is no such toplevel.
+    fn set_toplevel_decorated(&mut self,
+                              toplevel_oid: wl::ObjectId,
+                              is_decorated: bool)
+                              -> Option<wl::ObjectId>;
+
+    /// Inhibits idleness while given inhibitor exists.
+    fn add_idle_inhibitor(&mut self, inhibitor_oid: wl::ObjectId, surface_oid: wl::ObjectId);
+
+    /// Removes given idle inhibitor.
+    fn remove_idle_inhibitor(&mut self, inhibitor_oid: wl::ObjectId);
+
+    /// Adds notification about user being idle for at least `timeout`. If `respects_inhibitors`
+    /// is `false` idle inhibitors are ignored.
+    fn add_idle_notification(&mut self,
+                             notification_oid: wl::ObjectId,
+                             timeout: Duration,
+                             respects_inhibitors: bool);
+
+    /// Removes given idle notification.
+    fn remove_idle_notification(&mut self, notification_oid: wl::ObjectId);
+
+    /// Requests locking or confining pointer over given surface. The constraint becomes active
+    /// when pointer enters given region (or the whole surface if region is not defined). Returns
+    /// `false` if the surface is already constrained.
+    fn constrain_pointer(&mut self,
+                         surface_oid: wl::ObjectId,
+                         constraint_oid: PointerConstraintOid,
+                         region_oid: wl::ObjectId,
+                         is_persistent: bool)
+                         -> bool;
+
+    /// Changes region of given pointer constraint.
+    fn set_pointer_constraint_region(&mut self,
+                                     constraint_oid: PointerConstraintOid,
+                                     region_oid: wl::ObjectId);
+
+    /// Removes given pointer constraint.
+    fn unconstrain_pointer(&mut self, constraint_oid: PointerConstraintOid);
+
+    /// Adds new text input. It will be informed about keyboard focus entering and leaving surfaces
+    /// of this client.
+    fn add_text_input(&mut self, text_input_oid: wl::ObjectId);
+
+    /// Applies state of given text input. Input method is activated when the text input gets
+    /// enabled on focused surface and deactivated when it gets disabled.
+    fn commit_text_input(&mut self, text_input_oid: wl::ObjectId, state: TextInputState);
+
+    /// Removes given text input.
+    fn remove_text_input(&mut self, text_input_oid: wl::ObjectId);
+
+    /// Makes this client the input method. Returns `false` if other input method already exists.
+    fn add_input_method(&mut self, input_method_oid: wl::ObjectId) -> bool;
+
+    /// Passes changes of text requested by input method to the active text input.
+    fn commit_input_method(&mut self, input_method_oid: wl::ObjectId, state: InputMethodState);
+
+    /// Removes given input method.
+    fn remove_input_method(&mut self, input_method_oid: wl::ObjectId);
+
+    /// Adds pop-up of input method. It is displayed next to cursor of the active text input.
+    /// Returns `false` if the surface is unknown or already has a role.
+    fn add_input_popup(&mut self, popup_oid: wl::ObjectId, surface_oid: wl::ObjectId) -> bool;
+
+    /// Removes given input method pop-up.
+    fn remove_input_popup(&mut self, popup_oid: wl::ObjectId);
+
+    /// Makes input method receive all keyboard input until the grab is released.
+    fn grab_input_method_keyboard(&mut self,
+                                  input_method_oid: wl::ObjectId,
+                                  grab_oid: wl::ObjectId);
+
+    /// Releases keyboard grab of input method.
+    fn release_input_method_keyboard(&mut self, grab_oid: wl::ObjectId);
+
+    /// Checks if this client may create virtual keyboard. Only input method is allowed to.
+    fn add_virtual_keyboard(&mut self) -> bool;
+
+    /// Passes key emulated by virtual keyboard to client having keyboard focus.
+    fn emit_virtual_key(&mut self, key: Key);
+
+    /// Passes modifiers emulated by virtual keyboard to client having keyboard focus.
+    fn emit_virtual_modifiers(&mut self, mods: KeyMods);
+
+    /// Adds given pop-up on top of chain of grabbing pop-ups. Keyboard input will be directed to
+    /// it and clicking outside of the chain will dismiss all pop-ups. If the parent is not the
+    /// topmost pop-up of the chain the grab is refused and the pop-up dismissed immediately.
+    fn grab_popup(&mut self,
+                  surface_oid: wl::ObjectId,
+                  parent_surface_oid: wl::ObjectId,
+                  popup_oid: PopupOid);
+
+    /// Removes given pop-up from grab chain dismissing all pop-ups grabbed on top of it.
+    fn ungrab_popup(&mut self, surface_oid: wl::ObjectId);
+
+    /// Remembers `xdg_popup` created without parent together with its surface and position
+    /// relative to the parent it may get later.
+    fn add_parentless_popup(&mut self,
+                            popup_oid: wl::ObjectId,
+                            surface_oid: wl::ObjectId,
+                            position: Position);
+
+    /// Forgets given parentless `xdg_popup`.
+    fn remove_parentless_popup(&mut self, popup_oid: wl::ObjectId);
+
+    /// Makes given surface a parent of given parentless `xdg_popup`. Returns `false` if the pop-up
+    /// is not known or already has a parent.
+    fn adopt_popup(&mut self, popup_oid: wl::ObjectId, parent_surface_oid: wl::ObjectId) -> bool;
+
+    /// Returns object ID of parent of given surface.
+    fn get_parent_surface_oid(&self, surface_oid: wl::ObjectId) -> Option<wl::ObjectId>;
+
     /// Requests to use given surface for drawing cursor.
     fn set_as_cursor(&self, surface_oid: wl::ObjectId, hotspot_x: isize, hotspot_x: isize);
 
-    /// Relates output object ID with output ID.
-    fn relate_output_oid_with_id(&mut self, oid: wl::ObjectId, id: i32);
+    /// Relates output object ID with output ID and version of bound `wl_output`.
+    fn relate_output_oid_with_id(&mut self, oid: wl::ObjectId, id: i32, version: u32);
+
+    /// Forgets relation of output object ID with output ID.
+    fn unrelate_output_oid(&mut self, oid: wl::ObjectId);
+
+    /// Relates `zxdg_output_v1` object ID with `wl_output` object ID it describes.
+    fn bind_xdg_output(&mut self, oid: wl::ObjectId, output_oid: wl::ObjectId, version: u32);
+
+    /// Forgets `zxdg_output_v1` object ID.
+    fn unbind_xdg_output(&mut self, oid: wl::ObjectId);
 
     /// Requests taking screenshot.
     fn take_screenshot(&mut self,
@@ -8,7 +8,7 @@
 use std::os::unix::io::RawFd;
 
 use qualia::{Axis, Button, DrmBundle, Key, Milliseconds, OutputInfo, Position, Size};
-use qualia::{SurfaceId, surface_state};
+use qualia::{PresentationTime, SurfaceId, Vector, surface_state};
 use inputs::KeyMods;
 
 // -------------------------------------------------------------------------------------------------
@@ -20,30 +20,57 @@ pub trait Gateway {
     /// Notifies display was created.
     fn on_display_created(&mut self, output_info: OutputInfo);
 
+    /// Notifies information about display changed.
+    fn on_display_updated(&mut self, output_info: OutputInfo);
+
     /// Notifies keyboard key was pressed.
     fn on_keyboard_input(&mut self, key: Key, mods: Option<KeyMods>);
 
     /// Notifies about redrawing surface.
     fn on_surface_frame(&mut self, sid: SurfaceId, milliseconds: Milliseconds);
 
+    /// Notifies that contents of surface drawn in last frame were presented on given display.
+    fn on_surface_presented(&mut self, sid: SurfaceId, display_id: i32, time: PresentationTime);
+
+    /// Notifies that surface was not drawn in last frame because it was hidden.
+    fn on_surface_discarded(&mut self, sid: SurfaceId);
+
     /// Notifies that pointer was moved from above one surface above another.
-    fn on_pointer_focus_changed(&self,
+    fn on_pointer_focus_changed(&mut self,
                                 old_sid: SurfaceId,
                                 new_sid: SurfaceId,
                                 position: Position);
 
     /// Notifies that pointer moved.
-    fn on_pointer_relative_motion(&self,
+    fn on_pointer_relative_motion(&mut self,
                                   sid: SurfaceId,
                                   position: Position,
                                   milliseconds: Milliseconds);
 
+    /// Notifies about pointer motion over surface regardless of pointer being constrained.
+    fn on_pointer_delta(&mut self, sid: SurfaceId, vector: Vector, milliseconds: Milliseconds);
+
+    /// Notifies that pointer constraint of surface was activated or deactivated.
+    fn on_pointer_constraint_changed(&mut self, sid: SurfaceId, is_active: bool);
+
     /// Notifies mouse or touchpad button was pressed.
-    fn on_pointer_button(&self, btn: Button);
+    fn on_pointer_button(&mut self, btn: Button);
 
     /// Notifies about pointer move.
     fn on_pointer_axis(&self, axis: Axis);
 
+    /// Notifies that touch point was put down on surface.
+    fn on_touch_down(&mut self, sid: SurfaceId, id: i32, position: Position, time: Milliseconds);
+
+    /// Notifies that touch point moved.
+    fn on_touch_motion(&mut self, sid: SurfaceId, id: i32, position: Position, time: Milliseconds);
+
+    /// Notifies that touch point was released.
+    fn on_touch_up(&mut self, sid: SurfaceId, id: i32, time: Milliseconds);
+
+    /// Notifies about end of set of touch events.
+    fn on_touch_frame(&mut self);
+
     /// Notifies about keyboard focus change.
     fn on_keyboard_focus_changed(&mut self, old_sid: SurfaceId, new_sid: SurfaceId);
 
@@ -53,6 +80,18 @@ pub trait Gateway {
     /// Handles data transfer request to requesting client.
     fn on_transfer_requested(&mut self, mime_type: String, fd: RawFd);
 
+    /// Handles change of offered primary selection data.
+    fn on_primary_transfer_offered(&mut self);
+
+    /// Handles primary selection data transfer request to requesting client.
+    fn on_primary_transfer_requested(&mut self, mime_type: String, fd: RawFd);
+
+    /// Notifies about user activity.
+    fn on_system_activity(&mut self);
+
+    /// Notifies that half a second passed. Used for checking idle timeouts.
+    fn on_timer_500(&mut self);
+
     /// Notifies about change of size or state of surface.
     fn on_surface_reconfigured(&self,
                                sid: SurfaceId,
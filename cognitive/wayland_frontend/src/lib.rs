@@ -38,6 +38,7 @@ extern crate nix;
 
 extern crate skylane;
 extern crate skylane_protocols;
+extern crate cognitive_wayland_protocols as wayland_protocols;
 
 extern crate dharma;
 extern crate cognitive_graphics;
@@ -56,6 +57,7 @@ mod mediator;
 mod global;
 mod facade;
 mod gateway;
+mod popup_grabs;
 mod proxy;
 mod event_handlers;
 
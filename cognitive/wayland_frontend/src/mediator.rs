@@ -9,12 +9,101 @@ use std;
 use std::collections::HashMap;
 use std::os::unix::io::RawFd;
 use std::path::PathBuf;
+use std::time::Instant;
 
 use libdrm;
 
 use dharma;
+use skylane::server as wl;
 
-use qualia::SurfaceId;
+use qualia::{Area, IdleTracker, Key, Position, SurfaceId};
+use inputs::KeyMods;
+
+// -------------------------------------------------------------------------------------------------
+
+/// Data source of drag-and-drop. Destination client uses it to send requests directly to source
+/// client.
+#[derive(Clone)]
+pub struct DragSource {
+    pub socket: wl::Socket,
+    pub data_source_oid: wl::ObjectId,
+    pub version: u32,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// State of ongoing drag-and-drop.
+#[derive(Clone)]
+pub struct Drag {
+    /// Client which started dragging.
+    pub origin_cid: dharma::EventHandlerId,
+
+    /// Data source. Drag without source is allowed only within origin client.
+    pub source: Option<DragSource>,
+
+    /// Surface which received `enter` event.
+    pub target_sid: SurfaceId,
+
+    /// Last pointer position relative to surface under pointer.
+    pub position: Position,
+
+    /// Button held when dragging started. Its release ends dragging.
+    pub button: Option<u16>,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Text input enabled on surface focused by keyboard. Input method sends text to it.
+#[derive(Clone)]
+pub struct ActiveTextInput {
+    /// Client owning the text input.
+    pub cid: dharma::EventHandlerId,
+
+    /// Connection to the client owning the text input.
+    pub socket: wl::Socket,
+
+    /// Object ID of `zwp_text_input_v3`.
+    pub text_input_oid: wl::ObjectId,
+
+    /// Focused surface the text input was enabled on.
+    pub sid: SurfaceId,
+
+    /// Number of commits of the text input. Used as serial of `done` event.
+    pub commit_count: u32,
+
+    /// Cursor rectangle relative to the focused surface.
+    pub cursor_rectangle: Area,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Input method client. There may be only one input method at a time.
+#[derive(Clone)]
+pub struct InputMethod {
+    /// Client being the input method.
+    pub cid: dharma::EventHandlerId,
+
+    /// Connection to the input method client.
+    pub socket: wl::Socket,
+
+    /// Object ID of `zwp_input_method_v2`.
+    pub input_method_oid: wl::ObjectId,
+
+    /// Object IDs of `zwp_input_popup_surface_v2` together with their surfaces.
+    pub popups: HashMap<wl::ObjectId, SurfaceId>,
+
+    /// Object ID of `zwp_input_method_keyboard_grab_v2`. While it exists all keyboard input is
+    /// sent to the input method instead of focused surface.
+    pub keyboard_grab_oid: Option<wl::ObjectId>,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Keyboard event emulated by input method with `zwp_virtual_keyboard_v1`.
+pub enum VirtualKeyboardEvent {
+    Key(Key),
+    Modifiers(KeyMods),
+}
 
 // -------------------------------------------------------------------------------------------------
 
@@ -24,9 +113,17 @@ use qualia::SurfaceId;
 pub struct Mediator {
     sid_to_cid_dictionary: HashMap<SurfaceId, dharma::EventHandlerId>,
     transfer_offerer: Option<dharma::EventHandlerId>,
+    primary_transfer_offerer: Option<dharma::EventHandlerId>,
+    popup_grabber: Option<dharma::EventHandlerId>,
+    drag: Option<Drag>,
+    is_drag_source_cancelled: bool,
     screenshoter_cid: Option<dharma::EventHandlerId>,
     drm_device_path: Option<PathBuf>,
     drm_device_fd: Option<RawFd>,
+    idle_tracker: IdleTracker,
+    input_method: Option<InputMethod>,
+    active_text_input: Option<ActiveTextInput>,
+    virtual_keyboard_events: Vec<VirtualKeyboardEvent>,
 }
 
 define_ref!(struct Mediator as MediatorRef);
@@ -38,9 +135,17 @@ impl Mediator {
         Mediator {
             sid_to_cid_dictionary: HashMap::new(),
             transfer_offerer: None,
+            primary_transfer_offerer: None,
+            popup_grabber: None,
+            drag: None,
+            is_drag_source_cancelled: false,
             screenshoter_cid: None,
             drm_device_fd: None,
             drm_device_path: None,
+            idle_tracker: IdleTracker::new(Instant::now()),
+            input_method: None,
+            active_text_input: None,
+            virtual_keyboard_events: Vec::new(),
         }
     }
 }
@@ -68,6 +173,70 @@ impl Mediator {
         self.transfer_offerer
     }
 
+    pub fn register_primary_transfer_offerer(&mut self,
+                                             offerer: Option<dharma::EventHandlerId>) {
+        self.primary_transfer_offerer = offerer;
+    }
+
+    pub fn get_primary_transfer_offerer(&self) -> Option<dharma::EventHandlerId> {
+        self.primary_transfer_offerer
+    }
+
+    pub fn register_popup_grabber(&mut self, popup_grabber: Option<dharma::EventHandlerId>) {
+        self.popup_grabber = popup_grabber;
+    }
+
+    pub fn get_popup_grabber(&self) -> Option<dharma::EventHandlerId> {
+        self.popup_grabber
+    }
+
+    pub fn start_drag(&mut self, drag: Drag) {
+        self.drag = Some(drag);
+    }
+
+    pub fn is_dragging(&self) -> bool {
+        self.drag.is_some()
+    }
+
+    pub fn get_drag(&self) -> Option<Drag> {
+        self.drag.clone()
+    }
+
+    pub fn set_drag_target(&mut self, target_sid: SurfaceId, position: Position) {
+        if let Some(ref mut drag) = self.drag {
+            drag.target_sid = target_sid;
+            drag.position = position;
+        }
+    }
+
+    pub fn end_drag(&mut self) -> Option<Drag> {
+        self.drag.take()
+    }
+
+    /// Removes data source from ongoing drag if it is the given source. Returns `true` if it was.
+    pub fn cancel_drag_source(&mut self,
+                              cid: dharma::EventHandlerId,
+                              data_source_oid: wl::ObjectId)
+                              -> bool {
+        if let Some(ref mut drag) = self.drag {
+            let is_source = match drag.source {
+                Some(ref source) => source.data_source_oid == data_source_oid,
+                None => false,
+            };
+            if drag.origin_cid == cid && is_source {
+                drag.source = None;
+                self.is_drag_source_cancelled = true;
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Checks if data source of ongoing drag was cancelled since last call.
+    pub fn take_drag_source_cancelled(&mut self) -> bool {
+        std::mem::replace(&mut self.is_drag_source_cancelled, false)
+    }
+
     pub fn register_screenshoter(&mut self, cid: Option<dharma::EventHandlerId>) {
         self.screenshoter_cid = cid;
     }
@@ -85,6 +254,42 @@ impl Mediator {
         self.drm_device_path.clone()
     }
 
+    pub fn get_idle_tracker(&self) -> &IdleTracker {
+        &self.idle_tracker
+    }
+
+    pub fn get_idle_tracker_mut(&mut self) -> &mut IdleTracker {
+        &mut self.idle_tracker
+    }
+
+    pub fn register_input_method(&mut self, input_method: Option<InputMethod>) {
+        self.input_method = input_method;
+    }
+
+    pub fn get_input_method(&self) -> Option<InputMethod> {
+        self.input_method.clone()
+    }
+
+    pub fn get_input_method_mut(&mut self) -> Option<&mut InputMethod> {
+        self.input_method.as_mut()
+    }
+
+    pub fn set_active_text_input(&mut self, text_input: Option<ActiveTextInput>) {
+        self.active_text_input = text_input;
+    }
+
+    pub fn get_active_text_input(&self) -> Option<ActiveTextInput> {
+        self.active_text_input.clone()
+    }
+
+    pub fn push_virtual_keyboard_event(&mut self, event: VirtualKeyboardEvent) {
+        self.virtual_keyboard_events.push(event);
+    }
+
+    pub fn take_virtual_keyboard_events(&mut self) -> Vec<VirtualKeyboardEvent> {
+        std::mem::replace(&mut self.virtual_keyboard_events, Vec::new())
+    }
+
     pub fn authenticate_drm_device(&self, magic: u32) {
         if let Some(fd) = self.drm_device_fd {
             // TODO: Add safe `drmAuthMagic` to lidrm bindings.
@@ -0,0 +1,150 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! This module contains helper structures for tracking chains of pop-ups holding explicit grab.
+
+// -------------------------------------------------------------------------------------------------
+
+use skylane::server as wl;
+
+use qualia::SurfaceId;
+
+use facade::PopupOid;
+
+// -------------------------------------------------------------------------------------------------
+
+/// Helper structure for aggregating information about pop-up holding explicit grab.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PopupGrab {
+    pub sid: SurfaceId,
+    pub surface_oid: wl::ObjectId,
+    pub parent_surface_oid: wl::ObjectId,
+    pub popup_oid: PopupOid,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Chain of pop-ups holding explicit grab. Every pop-up in chain is child of the previous one.
+pub struct PopupGrabChain {
+    grabs: Vec<PopupGrab>,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl PopupGrabChain {
+    /// Constructs new empty `PopupGrabChain`.
+    pub fn new() -> Self {
+        PopupGrabChain { grabs: Vec::new() }
+    }
+
+    /// Checks if there is no grab.
+    pub fn is_empty(&self) -> bool {
+        self.grabs.is_empty()
+    }
+
+    /// Checks if given surface is one of grabbing pop-ups.
+    pub fn contains(&self, sid: SurfaceId) -> bool {
+        self.grabs.iter().any(|grab| grab.sid == sid)
+    }
+
+    /// Returns the topmost pop-up.
+    pub fn get_topmost(&self) -> Option<&PopupGrab> {
+        self.grabs.last()
+    }
+
+    /// Adds new pop-up on top of chain. Pop-up can be added only if its parent is the topmost
+    /// pop-up or chain is empty. Returns `true` on success.
+    pub fn push(&mut self, grab: PopupGrab) -> bool {
+        let is_parent_topmost = if let Some(topmost) = self.grabs.last() {
+            topmost.surface_oid == grab.parent_surface_oid
+        } else {
+            true
+        };
+
+        if is_parent_topmost {
+            self.grabs.push(grab);
+        }
+        is_parent_topmost
+    }
+
+    /// Returns position in chain of pop-up with given surface.
+    pub fn find(&self, surface_oid: wl::ObjectId) -> Option<usize> {
+        self.grabs.iter().position(|grab| grab.surface_oid == surface_oid)
+    }
+
+    /// Returns position in chain of the lowest pop-up with given surface or parent surface.
+    pub fn find_related(&self, surface_oid: wl::ObjectId) -> Option<usize> {
+        self.grabs.iter().position(|grab| {
+            grab.surface_oid == surface_oid || grab.parent_surface_oid == surface_oid
+        })
+    }
+
+    /// Removes pop-ups starting from given position. Returns removed pop-ups starting from the
+    /// topmost one.
+    pub fn truncate(&mut self, index: usize) -> Vec<PopupGrab> {
+        let mut removed = Vec::new();
+        while self.grabs.len() > index {
+            if let Some(grab) = self.grabs.pop() {
+                removed.push(grab);
+            }
+        }
+        removed
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use skylane::server as wl;
+    use qualia::SurfaceId;
+    use facade::PopupOid;
+    use super::{PopupGrab, PopupGrabChain};
+
+    fn grab(id: u32, parent_id: u32) -> PopupGrab {
+        PopupGrab {
+            sid: SurfaceId::new(id as u64),
+            surface_oid: wl::ObjectId::new(id),
+            parent_surface_oid: wl::ObjectId::new(parent_id),
+            popup_oid: PopupOid::XdgPopup(wl::ObjectId::new(100 + id)),
+        }
+    }
+
+    /// Check if only child of the topmost pop-up can join the chain.
+    #[test]
+    fn test_grabbing_only_from_topmost_popup() {
+        let mut chain = PopupGrabChain::new();
+        assert!(chain.push(grab(2, 1)));
+        assert!(chain.push(grab(3, 2)));
+        assert!(!chain.push(grab(4, 2)));
+        assert!(!chain.push(grab(5, 1)));
+
+        assert!(chain.contains(SurfaceId::new(3)));
+        assert!(!chain.contains(SurfaceId::new(4)));
+        assert_eq!(chain.get_topmost(), Some(&grab(3, 2)));
+    }
+
+    /// Check if dismissing pop-up in the middle of chain dismisses also all pop-ups above it, from
+    /// the topmost one down.
+    #[test]
+    fn test_dismissing_grab_chain() {
+        let mut chain = PopupGrabChain::new();
+        assert!(chain.push(grab(2, 1)));
+        assert!(chain.push(grab(3, 2)));
+        assert!(chain.push(grab(4, 3)));
+
+        let index = chain.find(wl::ObjectId::new(3));
+        assert_eq!(index, Some(1));
+        assert_eq!(chain.truncate(1), vec![grab(4, 3), grab(3, 2)]);
+        assert_eq!(chain.get_topmost(), Some(&grab(2, 1)));
+
+        // Destroying parent of the lowest pop-up breaks the whole chain.
+        assert!(chain.push(grab(5, 2)));
+        let index = chain.find_related(wl::ObjectId::new(1));
+        assert_eq!(index, Some(0));
+        assert_eq!(chain.truncate(0), vec![grab(5, 2), grab(2, 1)]);
+        assert!(chain.is_empty());
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
@@ -5,13 +5,15 @@
 
 use std::rc::Rc;
 
+use skylane::server as wl;
 use skylane::server::{Bundle, Object, ObjectId, Task};
 use skylane_protocols::server::Handler;
+use skylane_protocols::server::wayland::wl_display;
 use skylane_protocols::server::wayland::wl_compositor;
 use skylane_protocols::server::wayland::wl_surface;
 use skylane_protocols::server::wayland::wl_region;
 
-use qualia::{Area, SurfaceId};
+use qualia::{Area, SurfaceId, Transform};
 
 use global::Global;
 use facade::Facade;
@@ -103,8 +105,7 @@ impl Surface {
 #[allow(unused_variables)]
 impl wl_surface::Interface for Surface {
     fn destroy(&mut self, this_object_id: ObjectId, bundle: &mut Bundle) -> Task {
-        let proxy = self.proxy.borrow();
-        proxy.destroy_surface(self.sid);
+        self.proxy.borrow_mut().destroy_surface(self.sid);
         Task::Destroy { id: this_object_id }
     }
 
@@ -128,6 +129,11 @@ impl wl_surface::Interface for Surface {
               width: i32,
               height: i32)
               -> Task {
+        if width > 0 && height > 0 {
+            let area = Area::create(x as isize, y as isize, width as usize, height as usize);
+            let proxy = self.proxy.borrow();
+            proxy.damage(self.sid, area);
+        }
         Task::None
     }
 
@@ -142,8 +148,8 @@ impl wl_surface::Interface for Surface {
                          bundle: &mut Bundle,
                          region_oid: ObjectId)
                          -> Task {
-        let proxy = self.proxy.borrow_mut();
-        proxy.set_input_region(self.sid, region_oid);
+        let proxy = self.proxy.borrow();
+        proxy.set_opaque_region(self.sid, region_oid);
         Task::None
     }
 
@@ -158,7 +164,7 @@ impl wl_surface::Interface for Surface {
     }
 
     fn commit(&mut self, this_object_id: ObjectId, bundle: &mut Bundle) -> Task {
-        let proxy = self.proxy.borrow_mut();
+        let mut proxy = self.proxy.borrow_mut();
         proxy.commit(self.sid);
         Task::None
     }
@@ -168,6 +174,16 @@ impl wl_surface::Interface for Surface {
                             bundle: &mut Bundle,
                             transform: i32)
                             -> Task {
+        if let Some(transform) = Transform::from_wayland(transform) {
+            let proxy = self.proxy.borrow();
+            proxy.set_buffer_transform(self.sid, transform);
+        } else {
+            send!(wl_display::error(&bundle.get_socket(),
+                                    wl::DISPLAY_ID,
+                                    this_object_id,
+                                    wl_surface::error::INVALID_TRANSFORM,
+                                    "Unknown buffer transform"));
+        }
         Task::None
     }
 
@@ -176,6 +192,16 @@ impl wl_surface::Interface for Surface {
                         bundle: &mut Bundle,
                         scale: i32)
                         -> Task {
+        if scale < 1 {
+            send!(wl_display::error(&bundle.get_socket(),
+                                    wl::DISPLAY_ID,
+                                    this_object_id,
+                                    wl_surface::error::INVALID_SCALE,
+                                    "Buffer scale must be positive"));
+        } else {
+            let proxy = self.proxy.borrow();
+            proxy.set_buffer_scale(self.sid, scale as u32);
+        }
         Task::None
     }
 
@@ -187,6 +213,11 @@ impl wl_surface::Interface for Surface {
                      width: i32,
                      height: i32)
                      -> Task {
+        if width > 0 && height > 0 {
+            let area = Area::create(x as isize, y as isize, width as usize, height as usize);
+            let proxy = self.proxy.borrow();
+            proxy.damage_buffer(self.sid, area);
+        }
         Task::None
     }
 }
@@ -196,7 +227,7 @@ impl wl_surface::Interface for Surface {
 /// Wayland `wl_region` object.
 struct Region {
     proxy: ProxyRef,
-    area: Option<Area>,
+    areas: Vec<Area>,
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -205,7 +236,7 @@ impl Region {
     fn new(proxy_ref: ProxyRef) -> Self {
         Region {
             proxy: proxy_ref,
-            area: None,
+            areas: Vec::new(),
         }
     }
 
@@ -233,16 +264,10 @@ impl wl_region::Interface for Region {
            -> Task {
         if width > 0 && height > 0 {
             let area = Area::create(x as isize, y as isize, width as usize, height as usize);
-            if let Some(ref mut region) = self.area {
-                region.inflate(&area);
-            } else {
-                self.area = Some(area);
-            }
-
-            if let Some(region) = self.area {
-                let mut proxy = self.proxy.borrow_mut();
-                proxy.define_region(this_object_id, region);
-            }
+            self.areas.push(area);
+
+            let mut proxy = self.proxy.borrow_mut();
+            proxy.define_region(this_object_id, self.areas.clone());
         } else {
             log_wayl3!("Received region with non-positive width or height");
         }
@@ -250,14 +275,22 @@ impl wl_region::Interface for Region {
     }
 
     fn subtract(&mut self,
-                _this_object_id: ObjectId,
+                this_object_id: ObjectId,
                 _bundle: &mut Bundle,
-                _x: i32,
-                _y: i32,
-                _width: i32,
-                _height: i32)
+                x: i32,
+                y: i32,
+                width: i32,
+                height: i32)
                 -> Task {
-        // Not supported yet
+        if width > 0 && height > 0 {
+            let area = Area::create(x as isize, y as isize, width as usize, height as usize);
+            self.areas = self.areas.iter().flat_map(|a| a.subtract(&area)).collect();
+
+            let mut proxy = self.proxy.borrow_mut();
+            proxy.define_region(this_object_id, self.areas.clone());
+        } else {
+            log_wayl3!("Received region with non-positive width or height");
+        }
         Task::None
     }
 }
@@ -15,7 +15,7 @@ use skylane_protocols::server::wayland::wl_data_source;
 use skylane_protocols::server::wayland::wl_data_device;
 use skylane_protocols::server::wayland::wl_data_offer;
 
-use qualia::Transfer;
+use qualia::{dnd_action, Transfer};
 
 use global::Global;
 use proxy::ProxyRef;
@@ -26,6 +26,7 @@ use facade::Facade;
 /// Wayland `wl_data_device_manager` object.
 struct DataDeviceManager {
     proxy_ref: ProxyRef,
+    version: u32,
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -40,12 +41,16 @@ pub fn get_global() -> Global {
 
 impl DataDeviceManager {
     /// Creates new `DataDeviceManager`.
-    fn new(_oid: ObjectId, proxy_ref: ProxyRef) -> Self {
-        DataDeviceManager { proxy_ref: proxy_ref }
+    fn new(_oid: ObjectId, version: u32, proxy_ref: ProxyRef) -> Self {
+        DataDeviceManager {
+            proxy_ref: proxy_ref,
+            version: version,
+        }
     }
 
-    fn new_object(oid: ObjectId, _version: u32, proxy_ref: ProxyRef) -> Box<Object> {
-        Box::new(Handler::<_, wl_data_device_manager::Dispatcher>::new(Self::new(oid, proxy_ref)))
+    fn new_object(oid: ObjectId, version: u32, proxy_ref: ProxyRef) -> Box<Object> {
+        let manager = Self::new(oid, version, proxy_ref);
+        Box::new(Handler::<_, wl_data_device_manager::Dispatcher>::new(manager))
     }
 }
 
@@ -59,7 +64,9 @@ impl wl_data_device_manager::Interface for DataDeviceManager {
                           -> Task {
         Task::Create {
             id: new_data_source_id,
-            object: DataSource::new_object(new_data_source_id, self.proxy_ref.clone()),
+            object: DataSource::new_object(new_data_source_id,
+                                           self.version,
+                                           self.proxy_ref.clone()),
         }
     }
 
@@ -71,7 +78,9 @@ impl wl_data_device_manager::Interface for DataDeviceManager {
                        -> Task {
         Task::Create {
             id: new_data_device_id,
-            object: DataDevice::new_object(new_data_device_id, self.proxy_ref.clone()),
+            object: DataDevice::new_object(new_data_device_id,
+                                           self.version,
+                                           self.proxy_ref.clone()),
         }
     }
 }
@@ -87,18 +96,20 @@ struct DataSource {
 
 impl DataSource {
     /// Creates new `DataSource`.
-    fn new(oid: ObjectId, proxy_ref: ProxyRef) -> Self {
+    fn new(oid: ObjectId, version: u32, proxy_ref: ProxyRef) -> Self {
         {
             let mut proxy = proxy_ref.borrow_mut();
             let transfer = Transfer::new();
             proxy.set_transfer(oid, transfer);
+            proxy.relate_data_source_oid_with_version(oid, version);
         }
 
         DataSource { proxy_ref: proxy_ref }
     }
 
-    fn new_object(oid: ObjectId, proxy_ref: ProxyRef) -> Box<Object> {
-        Box::new(Handler::<_, wl_data_source::Dispatcher>::new(Self::new(oid, proxy_ref)))
+    fn new_object(oid: ObjectId, version: u32, proxy_ref: ProxyRef) -> Box<Object> {
+        let data_source = Self::new(oid, version, proxy_ref);
+        Box::new(Handler::<_, wl_data_source::Dispatcher>::new(data_source))
     }
 }
 
@@ -121,10 +132,15 @@ impl wl_data_source::Interface for DataSource {
     }
 
     fn set_actions(&mut self,
-                   _this_object_id: ObjectId,
+                   this_object_id: ObjectId,
                    _bundle: &mut Bundle,
-                   _dnd_actions: u32)
+                   dnd_actions: u32)
                    -> Task {
+        let mut proxy = self.proxy_ref.borrow_mut();
+        if let Some(ref mut transfer) = proxy.get_transfer(this_object_id) {
+            transfer.set_dnd_actions(dnd_action::DndAction::from_bits_truncate(dnd_actions));
+            proxy.set_transfer(this_object_id, transfer.clone());
+        }
         Task::None
     }
 }
@@ -140,17 +156,17 @@ struct DataDevice {
 
 impl DataDevice {
     /// Creates new `DataDevice`.
-    fn new(oid: ObjectId, proxy_ref: ProxyRef) -> Self {
+    fn new(oid: ObjectId, version: u32, proxy_ref: ProxyRef) -> Self {
         {
             let mut proxy = proxy_ref.borrow_mut();
-            proxy.add_data_device_oid(oid);
+            proxy.add_data_device_oid(oid, version);
         }
 
         DataDevice { proxy_ref: proxy_ref }
     }
 
-    fn new_object(oid: ObjectId, proxy_ref: ProxyRef) -> Box<Object> {
-        let data_device = Self::new(oid, proxy_ref);
+    fn new_object(oid: ObjectId, version: u32, proxy_ref: ProxyRef) -> Box<Object> {
+        let data_device = Self::new(oid, version, proxy_ref);
         Box::new(Handler::<_, wl_data_device::Dispatcher>::new(data_device))
     }
 }
@@ -161,11 +177,19 @@ impl wl_data_device::Interface for DataDevice {
     fn start_drag(&mut self,
                   _this_object_id: ObjectId,
                   _bundle: &mut Bundle,
-                  _source: ObjectId,
-                  _origin: ObjectId,
-                  _icon: ObjectId,
+                  data_source_oid: ObjectId,
+                  origin_surface_oid: ObjectId,
+                  icon_surface_oid: ObjectId,
                   _serial: u32)
                   -> Task {
+        let data_source_oid = if data_source_oid.is_null() { None } else { Some(data_source_oid) };
+        let icon_surface_oid = if icon_surface_oid.is_null() {
+            None
+        } else {
+            Some(icon_surface_oid)
+        };
+        let mut proxy = self.proxy_ref.borrow_mut();
+        proxy.start_drag(data_source_oid, origin_surface_oid, icon_surface_oid);
         Task::None
     }
 
@@ -192,18 +216,30 @@ impl wl_data_device::Interface for DataDevice {
 /// Wayland `wl_data_offer` object.
 pub struct DataOffer {
     proxy_ref: ProxyRef,
+
+    /// Tells if data is offered by drag-and-drop rather than by selection.
+    is_drag: bool,
 }
 
 // -------------------------------------------------------------------------------------------------
 
 impl DataOffer {
     /// Creates new `DataOffer`.
-    fn new(_oid: ObjectId, proxy_ref: ProxyRef) -> Self {
-        DataOffer { proxy_ref: proxy_ref }
+    fn new(_oid: ObjectId, is_drag: bool, proxy_ref: ProxyRef) -> Self {
+        DataOffer {
+            proxy_ref: proxy_ref,
+            is_drag: is_drag,
+        }
     }
 
+    /// Creates new `DataOffer` for selection.
     pub fn new_object(oid: ObjectId, proxy_ref: ProxyRef) -> Box<Object> {
-        Box::new(Handler::<_, wl_data_offer::Dispatcher>::new(Self::new(oid, proxy_ref)))
+        Box::new(Handler::<_, wl_data_offer::Dispatcher>::new(Self::new(oid, false, proxy_ref)))
+    }
+
+    /// Creates new `DataOffer` for drag-and-drop.
+    pub fn new_drag_object(oid: ObjectId, proxy_ref: ProxyRef) -> Box<Object> {
+        Box::new(Handler::<_, wl_data_offer::Dispatcher>::new(Self::new(oid, true, proxy_ref)))
     }
 }
 
@@ -211,39 +247,62 @@ impl DataOffer {
 
 impl wl_data_offer::Interface for DataOffer {
     fn accept(&mut self,
-              _this_object_id: ObjectId,
+              this_object_id: ObjectId,
               _bundle: &mut Bundle,
               _serial: u32,
-              _mime_type: String)
+              mime_type: String)
               -> Task {
+        if self.is_drag {
+            let mime_type = if mime_type.is_empty() { None } else { Some(mime_type) };
+            let mut proxy = self.proxy_ref.borrow_mut();
+            proxy.accept_drag_mime_type(this_object_id, mime_type);
+        }
         Task::None
     }
 
     fn receive(&mut self,
-               _this_object_id: ObjectId,
+               this_object_id: ObjectId,
                _bundle: &mut Bundle,
                mime_type: String,
                fd: RawFd)
                -> Task {
         let mut proxy = self.proxy_ref.borrow_mut();
-        proxy.request_transfer(mime_type, fd);
+        if self.is_drag {
+            proxy.request_drag_transfer(this_object_id, mime_type, fd);
+        } else {
+            proxy.request_transfer(mime_type, fd);
+        }
         Task::None
     }
 
     fn destroy(&mut self, this_object_id: ObjectId, _bundle: &mut Bundle) -> Task {
+        if self.is_drag {
+            let mut proxy = self.proxy_ref.borrow_mut();
+            proxy.remove_drag_offer(this_object_id);
+        }
         Task::Destroy { id: this_object_id }
     }
 
-    fn finish(&mut self, _this_object_id: ObjectId, _bundle: &mut Bundle) -> Task {
+    fn finish(&mut self, this_object_id: ObjectId, _bundle: &mut Bundle) -> Task {
+        if self.is_drag {
+            let mut proxy = self.proxy_ref.borrow_mut();
+            proxy.finish_drag(this_object_id);
+        }
         Task::None
     }
 
     fn set_actions(&mut self,
-                   _this_object_id: ObjectId,
+                   this_object_id: ObjectId,
                    _bundle: &mut Bundle,
-                   _dnd_actions: u32,
-                   _preferred_action: u32)
+                   dnd_actions: u32,
+                   preferred_action: u32)
                    -> Task {
+        if self.is_drag {
+            let dnd_actions = dnd_action::DndAction::from_bits_truncate(dnd_actions);
+            let preferred_action = dnd_action::DndAction::from_bits_truncate(preferred_action);
+            let mut proxy = self.proxy_ref.borrow_mut();
+            proxy.set_drag_actions(this_object_id, dnd_actions, preferred_action);
+        }
         Task::None
     }
 }
@@ -0,0 +1,95 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! Implementations of Wayland `zwp_idle_inhibit_manager_v1` and `zwp_idle_inhibitor_v1` objects.
+
+use std::rc::Rc;
+
+use skylane::server::{Bundle, Object, ObjectId, Task};
+use wayland_protocols::server::Handler;
+use wayland_protocols::server::idle_inhibit_unstable_v1::{zwp_idle_inhibit_manager_v1,
+                                                          zwp_idle_inhibitor_v1};
+
+use global::Global;
+use facade::Facade;
+use proxy::ProxyRef;
+
+// -------------------------------------------------------------------------------------------------
+
+/// Wayland `zwp_idle_inhibit_manager_v1` object.
+struct IdleInhibitManager {
+    proxy: ProxyRef,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+pub fn get_global() -> Global {
+    Global::new(zwp_idle_inhibit_manager_v1::NAME,
+                zwp_idle_inhibit_manager_v1::VERSION,
+                Rc::new(IdleInhibitManager::new_object))
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl IdleInhibitManager {
+    fn new(proxy_ref: ProxyRef) -> Self {
+        IdleInhibitManager { proxy: proxy_ref }
+    }
+
+    fn new_object(_oid: ObjectId, _version: u32, proxy_ref: ProxyRef) -> Box<Object> {
+        Box::new(Handler::<_, zwp_idle_inhibit_manager_v1::Dispatcher>::new(Self::new(proxy_ref)))
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl zwp_idle_inhibit_manager_v1::Interface for IdleInhibitManager {
+    fn destroy(&mut self, this_object_id: ObjectId, _bundle: &mut Bundle) -> Task {
+        Task::Destroy { id: this_object_id }
+    }
+
+    fn create_inhibitor(&mut self,
+                        _this_object_id: ObjectId,
+                        _bundle: &mut Bundle,
+                        id: ObjectId,
+                        surface: ObjectId)
+                        -> Task {
+        self.proxy.borrow_mut().add_idle_inhibitor(id, surface);
+        Task::Create {
+            id: id,
+            object: IdleInhibitor::new_object(self.proxy.clone()),
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Wayland `zwp_idle_inhibitor_v1` object.
+///
+/// Idleness is inhibited as long as this object exists.
+struct IdleInhibitor {
+    proxy: ProxyRef,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl IdleInhibitor {
+    fn new(proxy_ref: ProxyRef) -> Self {
+        IdleInhibitor { proxy: proxy_ref }
+    }
+
+    fn new_object(proxy_ref: ProxyRef) -> Box<Object> {
+        Box::new(Handler::<_, zwp_idle_inhibitor_v1::Dispatcher>::new(Self::new(proxy_ref)))
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl zwp_idle_inhibitor_v1::Interface for IdleInhibitor {
+    fn destroy(&mut self, this_object_id: ObjectId, _bundle: &mut Bundle) -> Task {
+        self.proxy.borrow_mut().remove_idle_inhibitor(this_object_id);
+        Task::Destroy { id: this_object_id }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
@@ -0,0 +1,117 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! Implementations of Wayland `ext_idle_notifier_v1` and `ext_idle_notification_v1` objects.
+
+use std::rc::Rc;
+use std::time::Duration;
+
+use skylane::server::{Bundle, Object, ObjectId, Task};
+use wayland_protocols::server::Handler;
+use wayland_protocols::server::ext_idle_notify_v1::{ext_idle_notifier_v1,
+                                                    ext_idle_notification_v1};
+
+use global::Global;
+use facade::Facade;
+use proxy::ProxyRef;
+
+// -------------------------------------------------------------------------------------------------
+
+/// Wayland `ext_idle_notifier_v1` object.
+struct IdleNotifier {
+    proxy: ProxyRef,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+pub fn get_global() -> Global {
+    Global::new(ext_idle_notifier_v1::NAME,
+                ext_idle_notifier_v1::VERSION,
+                Rc::new(IdleNotifier::new_object))
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl IdleNotifier {
+    fn new(proxy_ref: ProxyRef) -> Self {
+        IdleNotifier { proxy: proxy_ref }
+    }
+
+    fn new_object(_oid: ObjectId, _version: u32, proxy_ref: ProxyRef) -> Box<Object> {
+        Box::new(Handler::<_, ext_idle_notifier_v1::Dispatcher>::new(Self::new(proxy_ref)))
+    }
+
+    /// Creates new notification object.
+    fn create_notification(&mut self,
+                           id: ObjectId,
+                           timeout: u32,
+                           respects_inhibitors: bool)
+                           -> Task {
+        let timeout = Duration::from_millis(timeout as u64);
+        self.proxy.borrow_mut().add_idle_notification(id, timeout, respects_inhibitors);
+        Task::Create {
+            id: id,
+            object: IdleNotification::new_object(self.proxy.clone()),
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl ext_idle_notifier_v1::Interface for IdleNotifier {
+    fn destroy(&mut self, this_object_id: ObjectId, _bundle: &mut Bundle) -> Task {
+        Task::Destroy { id: this_object_id }
+    }
+
+    fn get_idle_notification(&mut self,
+                             _this_object_id: ObjectId,
+                             _bundle: &mut Bundle,
+                             id: ObjectId,
+                             timeout: u32,
+                             _seat: ObjectId)
+                             -> Task {
+        self.create_notification(id, timeout, true)
+    }
+
+    fn get_input_idle_notification(&mut self,
+                                   _this_object_id: ObjectId,
+                                   _bundle: &mut Bundle,
+                                   id: ObjectId,
+                                   timeout: u32,
+                                   _seat: ObjectId)
+                                   -> Task {
+        self.create_notification(id, timeout, false)
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Wayland `ext_idle_notification_v1` object.
+///
+/// `idled` and `resumed` events are sent by `Proxy`.
+struct IdleNotification {
+    proxy: ProxyRef,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl IdleNotification {
+    fn new(proxy_ref: ProxyRef) -> Self {
+        IdleNotification { proxy: proxy_ref }
+    }
+
+    fn new_object(proxy_ref: ProxyRef) -> Box<Object> {
+        Box::new(Handler::<_, ext_idle_notification_v1::Dispatcher>::new(Self::new(proxy_ref)))
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl ext_idle_notification_v1::Interface for IdleNotification {
+    fn destroy(&mut self, this_object_id: ObjectId, _bundle: &mut Bundle) -> Task {
+        self.proxy.borrow_mut().remove_idle_notification(this_object_id);
+        Task::Destroy { id: this_object_id }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
@@ -0,0 +1,258 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! Implementations of Wayland `zwp_input_method_manager_v2`, `zwp_input_method_v2`,
+//! `zwp_input_popup_surface_v2` and `zwp_input_method_keyboard_grab_v2` objects.
+
+use std::mem;
+use std::rc::Rc;
+
+use skylane::server as wl;
+use skylane::server::{Bundle, Object, ObjectId, Task};
+use skylane_protocols::server::wayland::wl_display;
+use wayland_protocols::server::Handler;
+use wayland_protocols::server::input_method_unstable_v2::{zwp_input_method_manager_v2,
+                                                           zwp_input_method_v2,
+                                                           zwp_input_popup_surface_v2,
+                                                           zwp_input_method_keyboard_grab_v2};
+
+use global::Global;
+use facade::{Facade, InputMethodState};
+use proxy::ProxyRef;
+use protocol::text_input::decode_text;
+
+// -------------------------------------------------------------------------------------------------
+
+/// Wayland `zwp_input_method_manager_v2` object.
+struct InputMethodManager {
+    proxy: ProxyRef,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+pub fn get_global() -> Global {
+    Global::new(zwp_input_method_manager_v2::NAME,
+                zwp_input_method_manager_v2::VERSION,
+                Rc::new(InputMethodManager::new_object))
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl InputMethodManager {
+    fn new(proxy_ref: ProxyRef) -> Self {
+        InputMethodManager { proxy: proxy_ref }
+    }
+
+    fn new_object(_oid: ObjectId, _version: u32, proxy_ref: ProxyRef) -> Box<Object> {
+        Box::new(Handler::<_, zwp_input_method_manager_v2::Dispatcher>::new(Self::new(proxy_ref)))
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl zwp_input_method_manager_v2::Interface for InputMethodManager {
+    fn get_input_method(&mut self,
+                        _this_object_id: ObjectId,
+                        bundle: &mut Bundle,
+                        _seat: ObjectId,
+                        input_method: ObjectId)
+                        -> Task {
+        if !self.proxy.borrow_mut().add_input_method(input_method) {
+            // Only one input method is allowed. The object stays inert.
+            send!(zwp_input_method_v2::unavailable(&bundle.get_socket(), input_method));
+        }
+
+        Task::Create {
+            id: input_method,
+            object: InputMethod::new_object(self.proxy.clone()),
+        }
+    }
+
+    fn destroy(&mut self, this_object_id: ObjectId, _bundle: &mut Bundle) -> Task {
+        Task::Destroy { id: this_object_id }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Wayland `zwp_input_method_v2` object.
+///
+/// Requested changes of text are double-buffered and passed to the active text input on commit.
+struct InputMethod {
+    pending: InputMethodState,
+    proxy: ProxyRef,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl InputMethod {
+    fn new(proxy_ref: ProxyRef) -> Self {
+        InputMethod {
+            pending: InputMethodState::new(),
+            proxy: proxy_ref,
+        }
+    }
+
+    fn new_object(proxy_ref: ProxyRef) -> Box<Object> {
+        Box::new(Handler::<_, zwp_input_method_v2::Dispatcher>::new(Self::new(proxy_ref)))
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl zwp_input_method_v2::Interface for InputMethod {
+    fn commit_string(&mut self,
+                     _this_object_id: ObjectId,
+                     _bundle: &mut Bundle,
+                     text: String)
+                     -> Task {
+        self.pending.commit_string = Some(decode_text(text));
+        Task::None
+    }
+
+    fn set_preedit_string(&mut self,
+                          _this_object_id: ObjectId,
+                          _bundle: &mut Bundle,
+                          text: String,
+                          cursor_begin: i32,
+                          cursor_end: i32)
+                          -> Task {
+        self.pending.preedit_string = Some((decode_text(text), cursor_begin, cursor_end));
+        Task::None
+    }
+
+    fn delete_surrounding_text(&mut self,
+                               _this_object_id: ObjectId,
+                               _bundle: &mut Bundle,
+                               before_length: u32,
+                               after_length: u32)
+                               -> Task {
+        self.pending.delete_surrounding_text = (before_length, after_length);
+        Task::None
+    }
+
+    fn commit(&mut self, this_object_id: ObjectId, _bundle: &mut Bundle, _serial: u32) -> Task {
+        // Pending state is reset after every commit.
+        let state = mem::replace(&mut self.pending, InputMethodState::new());
+        self.proxy.borrow_mut().commit_input_method(this_object_id, state);
+        Task::None
+    }
+
+    fn get_input_popup_surface(&mut self,
+                               this_object_id: ObjectId,
+                               bundle: &mut Bundle,
+                               id: ObjectId,
+                               surface: ObjectId)
+                               -> Task {
+        if self.proxy.borrow_mut().add_input_popup(id, surface) {
+            Task::Create {
+                id: id,
+                object: InputPopupSurface::new_object(self.proxy.clone()),
+            }
+        } else {
+            send!(wl_display::error(&bundle.get_socket(),
+                                    wl::DISPLAY_ID,
+                                    this_object_id,
+                                    wl_display::error::INVALID_OBJECT,
+                                    "Surface is unknown or already has a role"));
+            Task::None
+        }
+    }
+
+    fn grab_keyboard(&mut self,
+                     this_object_id: ObjectId,
+                     _bundle: &mut Bundle,
+                     keyboard: ObjectId)
+                     -> Task {
+        self.proxy.borrow_mut().grab_input_method_keyboard(this_object_id, keyboard);
+        Task::Create {
+            id: keyboard,
+            object: InputMethodKeyboardGrab::new_object(keyboard, self.proxy.clone()),
+        }
+    }
+
+    fn destroy(&mut self, this_object_id: ObjectId, _bundle: &mut Bundle) -> Task {
+        self.proxy.borrow_mut().remove_input_method(this_object_id);
+        Task::Destroy { id: this_object_id }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Wayland `zwp_input_popup_surface_v2` object.
+///
+/// Pop-up is displayed below cursor rectangle of the active text input.
+struct InputPopupSurface {
+    proxy: ProxyRef,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl InputPopupSurface {
+    fn new(proxy_ref: ProxyRef) -> Self {
+        InputPopupSurface { proxy: proxy_ref }
+    }
+
+    fn new_object(proxy_ref: ProxyRef) -> Box<Object> {
+        Box::new(Handler::<_, zwp_input_popup_surface_v2::Dispatcher>::new(Self::new(proxy_ref)))
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl zwp_input_popup_surface_v2::Interface for InputPopupSurface {
+    fn destroy(&mut self, this_object_id: ObjectId, _bundle: &mut Bundle) -> Task {
+        self.proxy.borrow_mut().remove_input_popup(this_object_id);
+        Task::Destroy { id: this_object_id }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Wayland `zwp_input_method_keyboard_grab_v2` object.
+///
+/// While the grab exists key events are delivered to the input method instead of focused surface.
+/// Input method passes back keys it does not handle using `zwp_virtual_keyboard_v1`.
+struct InputMethodKeyboardGrab {
+    proxy: ProxyRef,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl InputMethodKeyboardGrab {
+    fn new(oid: ObjectId, proxy_ref: ProxyRef) -> Self {
+        {
+            let proxy = proxy_ref.borrow();
+            let socket = proxy.get_socket();
+            let keymap = proxy.get_settings().get_keymap();
+            send!(zwp_input_method_keyboard_grab_v2::keymap(&socket,
+                                                            oid,
+                                                            keymap.format,
+                                                            keymap.fd,
+                                                            keymap.size as u32));
+
+            let config = proxy.get_keyboard_config();
+            send!(zwp_input_method_keyboard_grab_v2::repeat_info(&socket,
+                                                                 oid,
+                                                                 config.repeat_rate as i32,
+                                                                 config.repeat_delay as i32));
+        }
+        InputMethodKeyboardGrab { proxy: proxy_ref }
+    }
+
+    fn new_object(oid: ObjectId, proxy_ref: ProxyRef) -> Box<Object> {
+        let grab = Self::new(oid, proxy_ref);
+        Box::new(Handler::<_, zwp_input_method_keyboard_grab_v2::Dispatcher>::new(grab))
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl zwp_input_method_keyboard_grab_v2::Interface for InputMethodKeyboardGrab {
+    fn release(&mut self, this_object_id: ObjectId, _bundle: &mut Bundle) -> Task {
+        self.proxy.borrow_mut().release_input_method_keyboard(this_object_id);
+        Task::Destroy { id: this_object_id }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
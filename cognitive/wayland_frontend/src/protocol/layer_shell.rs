@@ -0,0 +1,291 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! Implementations of Wayland `zwlr_layer_shell_v1` and `zwlr_layer_surface_v1` objects.
+
+use std::rc::Rc;
+
+use skylane::server as wl;
+use skylane::server::{Bundle, Object, ObjectId, Task};
+use skylane_protocols::server::wayland::wl_display;
+use wayland_protocols::server::Handler;
+use wayland_protocols::server::wlr_layer_shell_unstable_v1::{zwlr_layer_shell_v1,
+                                                             zwlr_layer_surface_v1};
+
+use qualia::{layer_anchor, Layer, LayerProperties, Margins, Size};
+
+use global::Global;
+use facade::Facade;
+use proxy::ProxyRef;
+
+// -------------------------------------------------------------------------------------------------
+
+/// Converts layer value used in protocol to `Layer`.
+fn layer_from_u32(layer: u32) -> Option<Layer> {
+    match layer {
+        zwlr_layer_shell_v1::layer::BACKGROUND => Some(Layer::Background),
+        zwlr_layer_shell_v1::layer::BOTTOM => Some(Layer::Bottom),
+        zwlr_layer_shell_v1::layer::TOP => Some(Layer::Top),
+        zwlr_layer_shell_v1::layer::OVERLAY => Some(Layer::Overlay),
+        _ => None,
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Wayland `zwlr_layer_shell_v1` object.
+struct LayerShell {
+    proxy: ProxyRef,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+pub fn get_global() -> Global {
+    Global::new(zwlr_layer_shell_v1::NAME,
+                zwlr_layer_shell_v1::VERSION,
+                Rc::new(LayerShell::new_object))
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl LayerShell {
+    fn new(proxy_ref: ProxyRef) -> Self {
+        LayerShell { proxy: proxy_ref }
+    }
+
+    fn new_object(_oid: ObjectId, _version: u32, proxy_ref: ProxyRef) -> Box<Object> {
+        Box::new(Handler::<_, zwlr_layer_shell_v1::Dispatcher>::new(Self::new(proxy_ref)))
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl zwlr_layer_shell_v1::Interface for LayerShell {
+    fn get_layer_surface(&mut self,
+                         this_object_id: ObjectId,
+                         bundle: &mut Bundle,
+                         id: ObjectId,
+                         surface: ObjectId,
+                         output: ObjectId,
+                         layer: u32,
+                         _namespace: String)
+                         -> Task {
+        let layer = if let Some(layer) = layer_from_u32(layer) {
+            layer
+        } else {
+            send!(wl_display::error(&bundle.get_socket(),
+                                    wl::DISPLAY_ID,
+                                    this_object_id,
+                                    zwlr_layer_shell_v1::error::INVALID_LAYER,
+                                    "Invalid layer"));
+            return Task::None;
+        };
+
+        let output = if output.is_null() { None } else { Some(output) };
+        let properties = LayerProperties::new(layer);
+        if self.proxy.borrow_mut().add_layer_surface(surface, id, output, properties) {
+            Task::Create {
+                id: id,
+                object: LayerSurface::new_object(surface, properties, self.proxy.clone()),
+            }
+        } else {
+            send!(wl_display::error(&bundle.get_socket(),
+                                    wl::DISPLAY_ID,
+                                    this_object_id,
+                                    zwlr_layer_shell_v1::error::ROLE,
+                                    "Surface already has a role"));
+            Task::None
+        }
+    }
+
+    fn destroy(&mut self, this_object_id: ObjectId, _bundle: &mut Bundle) -> Task {
+        Task::Destroy { id: this_object_id }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Wayland `zwlr_layer_surface_v1` object.
+///
+/// Keeps pending properties of the surface and passes them to `Proxy` on every change. They are
+/// applied by `Proxy` when the surface is committed.
+struct LayerSurface {
+    surface_oid: ObjectId,
+    properties: LayerProperties,
+    proxy: ProxyRef,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl LayerSurface {
+    fn new(surface_oid: ObjectId, properties: LayerProperties, proxy_ref: ProxyRef) -> Self {
+        LayerSurface {
+            surface_oid: surface_oid,
+            properties: properties,
+            proxy: proxy_ref,
+        }
+    }
+
+    fn new_object(surface_oid: ObjectId,
+                  properties: LayerProperties,
+                  proxy_ref: ProxyRef)
+                  -> Box<Object> {
+        Box::new(Handler::<_, zwlr_layer_surface_v1::Dispatcher>::new(Self::new(surface_oid,
+                                                                                 properties,
+                                                                                 proxy_ref)))
+    }
+
+    /// Passes updated properties to `Proxy`.
+    fn update(&self) {
+        self.proxy.borrow_mut().set_layer_properties(self.surface_oid, self.properties);
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl zwlr_layer_surface_v1::Interface for LayerSurface {
+    fn set_size(&mut self,
+                _this_object_id: ObjectId,
+                _bundle: &mut Bundle,
+                width: u32,
+                height: u32)
+                -> Task {
+        self.properties.size = Size::new(width as usize, height as usize);
+        self.update();
+        Task::None
+    }
+
+    fn set_anchor(&mut self, this_object_id: ObjectId, bundle: &mut Bundle, anchor: u32) -> Task {
+        if let Some(anchor) = layer_anchor::LayerAnchor::from_bits(anchor) {
+            self.properties.anchor = anchor;
+            self.update();
+        } else {
+            send!(wl_display::error(&bundle.get_socket(),
+                                    wl::DISPLAY_ID,
+                                    this_object_id,
+                                    zwlr_layer_surface_v1::error::INVALID_ANCHOR,
+                                    "Invalid anchor"));
+        }
+        Task::None
+    }
+
+    fn set_exclusive_zone(&mut self,
+                          _this_object_id: ObjectId,
+                          _bundle: &mut Bundle,
+                          zone: i32)
+                          -> Task {
+        self.properties.exclusive_zone = zone;
+        self.update();
+        Task::None
+    }
+
+    fn set_margin(&mut self,
+                  _this_object_id: ObjectId,
+                  _bundle: &mut Bundle,
+                  top: i32,
+                  right: i32,
+                  bottom: i32,
+                  left: i32)
+                  -> Task {
+        // TODO: Support negative margins.
+        let clamp = |margin: i32| if margin > 0 { margin as usize } else { 0 };
+        self.properties.margins =
+            Margins::new(clamp(top), clamp(right), clamp(bottom), clamp(left));
+        self.update();
+        Task::None
+    }
+
+    fn set_keyboard_interactivity(&mut self,
+                                  this_object_id: ObjectId,
+                                  bundle: &mut Bundle,
+                                  keyboard_interactivity: u32)
+                                  -> Task {
+        // TODO: Distinguish between exclusive and on-demand keyboard interactivity.
+        match keyboard_interactivity {
+            zwlr_layer_surface_v1::keyboard_interactivity::NONE => {
+                self.properties.keyboard_interactive = false;
+                self.update();
+            }
+            zwlr_layer_surface_v1::keyboard_interactivity::EXCLUSIVE |
+            zwlr_layer_surface_v1::keyboard_interactivity::ON_DEMAND => {
+                self.properties.keyboard_interactive = true;
+                self.update();
+            }
+            _ => {
+                let code = zwlr_layer_surface_v1::error::INVALID_KEYBOARD_INTERACTIVITY;
+                send!(wl_display::error(&bundle.get_socket(),
+                                        wl::DISPLAY_ID,
+                                        this_object_id,
+                                        code,
+                                        "Invalid keyboard interactivity"));
+            }
+        }
+        Task::None
+    }
+
+    fn get_popup(&mut self,
+                 _this_object_id: ObjectId,
+                 _bundle: &mut Bundle,
+                 popup: ObjectId)
+                 -> Task {
+        if !self.proxy.borrow_mut().adopt_popup(popup, self.surface_oid) {
+            log_warn3!("Pop-up {} is unknown or already has a parent", popup);
+        }
+        Task::None
+    }
+
+    fn ack_configure(&mut self,
+                     _this_object_id: ObjectId,
+                     _bundle: &mut Bundle,
+                     _serial: u32)
+                     -> Task {
+        // Nothing to do so far
+        Task::None
+    }
+
+    fn destroy(&mut self, this_object_id: ObjectId, _bundle: &mut Bundle) -> Task {
+        self.proxy.borrow_mut().remove_layer_surface(self.surface_oid);
+        Task::Destroy { id: this_object_id }
+    }
+
+    fn set_layer(&mut self, this_object_id: ObjectId, bundle: &mut Bundle, layer: u32) -> Task {
+        if let Some(layer) = layer_from_u32(layer) {
+            self.properties.layer = layer;
+            self.update();
+        } else {
+            send!(wl_display::error(&bundle.get_socket(),
+                                    wl::DISPLAY_ID,
+                                    this_object_id,
+                                    zwlr_layer_shell_v1::error::INVALID_LAYER,
+                                    "Invalid layer"));
+        }
+        Task::None
+    }
+
+    fn set_exclusive_edge(&mut self,
+                          this_object_id: ObjectId,
+                          bundle: &mut Bundle,
+                          edge: u32)
+                          -> Task {
+        let edge = layer_anchor::LayerAnchor::from_bits(edge).unwrap_or(layer_anchor::NONE);
+        let is_single_edge = [layer_anchor::NONE,
+                              layer_anchor::TOP,
+                              layer_anchor::BOTTOM,
+                              layer_anchor::LEFT,
+                              layer_anchor::RIGHT]
+            .contains(&edge);
+        if is_single_edge && self.properties.anchor.contains(edge) {
+            self.properties.exclusive_edge = edge;
+            self.update();
+        } else {
+            send!(wl_display::error(&bundle.get_socket(),
+                                    wl::DISPLAY_ID,
+                                    this_object_id,
+                                    zwlr_layer_surface_v1::error::INVALID_EXCLUSIVE_EDGE,
+                                    "Exclusive edge must be one of anchored edges"));
+        }
+        Task::None
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
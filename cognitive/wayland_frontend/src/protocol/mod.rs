@@ -10,11 +10,25 @@ pub mod shm;
 pub mod compositor;
 pub mod shell;
 pub mod xdg_shell_v6;
+pub mod xdg_shell;
+pub mod layer_shell;
+pub mod xdg_decoration;
 
 pub mod data_device_manager;
+pub mod primary_selection_device_manager;
 pub mod seat;
+pub mod relative_pointer;
+pub mod pointer_constraints;
+pub mod idle_inhibit;
+pub mod idle_notify;
+pub mod text_input;
+pub mod input_method;
+pub mod virtual_keyboard;
 pub mod subcompositor;
+pub mod viewporter;
+pub mod presentation;
 pub mod output;
+pub mod xdg_output;
 
 pub mod weston_screenshooter;
 
@@ -5,7 +5,7 @@
 
 use std::rc::Rc;
 
-use skylane::server::{Bundle, Object, ObjectId, Task};
+use skylane::server::{Bundle, Object, ObjectId, Socket, Task};
 use skylane_protocols::server::Handler;
 use skylane_protocols::server::wayland::wl_output;
 
@@ -18,52 +18,85 @@ use facade::Facade;
 // -------------------------------------------------------------------------------------------------
 
 /// Wayland `wl_output` object.
-struct Output {}
+struct Output {
+    proxy: ProxyRef,
+}
 
 // -------------------------------------------------------------------------------------------------
 
-pub fn get_global(info: OutputInfo) -> Global {
+pub fn get_global(id: i32) -> Global {
     Global::new(wl_output::NAME,
                 wl_output::VERSION,
-                Rc::new(move |oid, _version, proxy| Output::new_object(oid, proxy, info.clone())))
+                Rc::new(move |oid, version, proxy| Output::new_object(oid, version, proxy, id)))
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Sends all information about output. Should be followed by `send_done` and sent again every time
+/// the information changes.
+pub fn send_info(socket: &Socket, oid: ObjectId, version: u32, info: &OutputInfo) {
+    send!(wl_output::geometry(socket,
+                              oid,
+                              info.area.pos.x as i32,
+                              info.area.pos.y as i32,
+                              info.physical_size.width as i32,
+                              info.physical_size.height as i32,
+                              info.subpixel as i32,
+                              &info.make,
+                              &info.model,
+                              info.transform as i32));
+
+    for mode in info.modes.iter() {
+        let mut flags = 0;
+        if mode.is_current {
+            flags |= wl_output::mode::CURRENT;
+        }
+        if mode.is_preferred {
+            flags |= wl_output::mode::PREFERRED;
+        }
+
+        send!(wl_output::mode(socket,
+                              oid,
+                              flags,
+                              mode.size.width as i32,
+                              mode.size.height as i32,
+                              mode.refresh_rate as i32));
+    }
+
+    // FIXME: Add support for versions in `skylane`.
+    if version >= 2 {
+        send!(wl_output::scale(socket, oid, info.scale as i32));
+    }
+}
+
+/// Sends `done` event marking end of set of information about output.
+pub fn send_done(socket: &Socket, oid: ObjectId, version: u32) {
+    // FIXME: Add support for versions in `skylane`.
+    if version >= 2 {
+        send!(wl_output::done(socket, oid));
+    }
 }
 
 // -------------------------------------------------------------------------------------------------
 
 impl Output {
-    fn new(oid: ObjectId, proxy_ref: ProxyRef, info: OutputInfo) -> Self {
+    fn new(oid: ObjectId, version: u32, proxy_ref: ProxyRef, id: i32) -> Self {
         {
             let mut proxy = proxy_ref.borrow_mut();
-            let socket = proxy.get_socket();
-            send!(wl_output::geometry(&socket,
-                                      oid,
-                                      info.area.pos.x as i32,
-                                      info.area.pos.y as i32,
-                                      info.physical_size.width as i32,
-                                      info.physical_size.height as i32,
-                                      wl_output::subpixel::UNKNOWN as i32,
-                                      &info.make,
-                                      &info.model,
-                                      wl_output::transform::NORMAL as i32));
-
-            send!(wl_output::mode(&socket,
-                                  oid,
-                                  wl_output::mode::CURRENT as u32,
-                                  info.area.size.width as i32,
-                                  info.area.size.height as i32,
-                                  info.refresh_rate as i32));
-
-            send!(wl_output::scale(&socket, oid, 1));
-            send!(wl_output::done(&socket, oid));
-
-            proxy.relate_output_oid_with_id(oid, info.id);
+            if let Some(info) = proxy.get_output_info(id) {
+                let socket = proxy.get_socket();
+                send_info(&socket, oid, version, &info);
+                send_done(&socket, oid, version);
+            }
+            proxy.relate_output_oid_with_id(oid, id, version);
         }
 
-        Output {}
+        Output { proxy: proxy_ref }
     }
 
-    fn new_object(oid: ObjectId, proxy_ref: ProxyRef, info: OutputInfo) -> Box<Object> {
-        Box::new(Handler::<_, wl_output::Dispatcher>::new(Self::new(oid, proxy_ref, info)))
+    fn new_object(oid: ObjectId, version: u32, proxy_ref: ProxyRef, id: i32) -> Box<Object> {
+        let output = Self::new(oid, version, proxy_ref, id);
+        Box::new(Handler::<_, wl_output::Dispatcher>::new(output))
     }
 }
 
@@ -71,6 +104,7 @@ impl Output {
 
 impl wl_output::Interface for Output {
     fn release(&mut self, this_object_id: ObjectId, _bundle: &mut Bundle) -> Task {
+        self.proxy.borrow_mut().unrelate_output_oid(this_object_id);
         Task::Destroy { id: this_object_id }
     }
 }
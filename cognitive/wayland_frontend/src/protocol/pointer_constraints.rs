@@ -0,0 +1,217 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! Implementations of Wayland `zwp_pointer_constraints_v1`, `zwp_locked_pointer_v1` and
+//! `zwp_confined_pointer_v1` objects.
+
+use std::rc::Rc;
+
+use skylane::server as wl;
+use skylane::server::{Bundle, Object, ObjectId, Task};
+use skylane_protocols::server::wayland::wl_display;
+use wayland_protocols::server::Handler;
+use wayland_protocols::server::pointer_constraints_unstable_v1::{zwp_pointer_constraints_v1,
+                                                                 zwp_locked_pointer_v1,
+                                                                 zwp_confined_pointer_v1};
+
+use global::Global;
+use facade::{Facade, PointerConstraintOid};
+use proxy::ProxyRef;
+
+use self::zwp_pointer_constraints_v1::lifetime::PERSISTENT;
+
+// -------------------------------------------------------------------------------------------------
+
+/// Wayland `zwp_pointer_constraints_v1` object.
+struct PointerConstraints {
+    proxy: ProxyRef,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+pub fn get_global() -> Global {
+    Global::new(zwp_pointer_constraints_v1::NAME,
+                zwp_pointer_constraints_v1::VERSION,
+                Rc::new(PointerConstraints::new_object))
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl PointerConstraints {
+    fn new(proxy_ref: ProxyRef) -> Self {
+        PointerConstraints { proxy: proxy_ref }
+    }
+
+    fn new_object(_oid: ObjectId, _version: u32, proxy_ref: ProxyRef) -> Box<Object> {
+        Box::new(Handler::<_, zwp_pointer_constraints_v1::Dispatcher>::new(Self::new(proxy_ref)))
+    }
+
+    /// Requests constraint and sends error if the surface is already constrained.
+    fn constrain(&mut self,
+                 this_object_id: ObjectId,
+                 bundle: &mut Bundle,
+                 surface: ObjectId,
+                 constraint_oid: PointerConstraintOid,
+                 region: ObjectId,
+                 lifetime: u32)
+                 -> bool {
+        let is_persistent = lifetime == PERSISTENT;
+        let mut proxy = self.proxy.borrow_mut();
+        if proxy.constrain_pointer(surface, constraint_oid, region, is_persistent) {
+            true
+        } else {
+            send!(wl_display::error(&bundle.get_socket(),
+                                    wl::DISPLAY_ID,
+                                    this_object_id,
+                                    zwp_pointer_constraints_v1::error::ALREADY_CONSTRAINED,
+                                    "Surface already has pointer constraint"));
+            false
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl zwp_pointer_constraints_v1::Interface for PointerConstraints {
+    fn destroy(&mut self, this_object_id: ObjectId, _bundle: &mut Bundle) -> Task {
+        Task::Destroy { id: this_object_id }
+    }
+
+    fn lock_pointer(&mut self,
+                    this_object_id: ObjectId,
+                    bundle: &mut Bundle,
+                    id: ObjectId,
+                    surface: ObjectId,
+                    _pointer: ObjectId,
+                    region: ObjectId,
+                    lifetime: u32)
+                    -> Task {
+        let constraint_oid = PointerConstraintOid::Locked(id);
+        if self.constrain(this_object_id, bundle, surface, constraint_oid, region, lifetime) {
+            Task::Create {
+                id: id,
+                object: LockedPointer::new_object(id, self.proxy.clone()),
+            }
+        } else {
+            Task::None
+        }
+    }
+
+    fn confine_pointer(&mut self,
+                       this_object_id: ObjectId,
+                       bundle: &mut Bundle,
+                       id: ObjectId,
+                       surface: ObjectId,
+                       _pointer: ObjectId,
+                       region: ObjectId,
+                       lifetime: u32)
+                       -> Task {
+        let constraint_oid = PointerConstraintOid::Confined(id);
+        if self.constrain(this_object_id, bundle, surface, constraint_oid, region, lifetime) {
+            Task::Create {
+                id: id,
+                object: ConfinedPointer::new_object(id, self.proxy.clone()),
+            }
+        } else {
+            Task::None
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Wayland `zwp_locked_pointer_v1` object.
+///
+/// Changes of region are applied immediately instead of on next commit of the surface.
+struct LockedPointer {
+    constraint_oid: PointerConstraintOid,
+    proxy: ProxyRef,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl LockedPointer {
+    fn new(oid: ObjectId, proxy_ref: ProxyRef) -> Self {
+        LockedPointer {
+            constraint_oid: PointerConstraintOid::Locked(oid),
+            proxy: proxy_ref,
+        }
+    }
+
+    fn new_object(oid: ObjectId, proxy_ref: ProxyRef) -> Box<Object> {
+        Box::new(Handler::<_, zwp_locked_pointer_v1::Dispatcher>::new(Self::new(oid, proxy_ref)))
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl zwp_locked_pointer_v1::Interface for LockedPointer {
+    fn destroy(&mut self, this_object_id: ObjectId, _bundle: &mut Bundle) -> Task {
+        self.proxy.borrow_mut().unconstrain_pointer(self.constraint_oid);
+        Task::Destroy { id: this_object_id }
+    }
+
+    fn set_cursor_position_hint(&mut self,
+                                _this_object_id: ObjectId,
+                                _bundle: &mut Bundle,
+                                _surface_x: f32,
+                                _surface_y: f32)
+                                -> Task {
+        // TODO: Warp pointer to the hinted position on unlock.
+        Task::None
+    }
+
+    fn set_region(&mut self,
+                  _this_object_id: ObjectId,
+                  _bundle: &mut Bundle,
+                  region: ObjectId)
+                  -> Task {
+        self.proxy.borrow_mut().set_pointer_constraint_region(self.constraint_oid, region);
+        Task::None
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Wayland `zwp_confined_pointer_v1` object.
+///
+/// Changes of region are applied immediately instead of on next commit of the surface.
+struct ConfinedPointer {
+    constraint_oid: PointerConstraintOid,
+    proxy: ProxyRef,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl ConfinedPointer {
+    fn new(oid: ObjectId, proxy_ref: ProxyRef) -> Self {
+        ConfinedPointer {
+            constraint_oid: PointerConstraintOid::Confined(oid),
+            proxy: proxy_ref,
+        }
+    }
+
+    fn new_object(oid: ObjectId, proxy_ref: ProxyRef) -> Box<Object> {
+        Box::new(Handler::<_, zwp_confined_pointer_v1::Dispatcher>::new(Self::new(oid, proxy_ref)))
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl zwp_confined_pointer_v1::Interface for ConfinedPointer {
+    fn destroy(&mut self, this_object_id: ObjectId, _bundle: &mut Bundle) -> Task {
+        self.proxy.borrow_mut().unconstrain_pointer(self.constraint_oid);
+        Task::Destroy { id: this_object_id }
+    }
+
+    fn set_region(&mut self,
+                  _this_object_id: ObjectId,
+                  _bundle: &mut Bundle,
+                  region: ObjectId)
+                  -> Task {
+        self.proxy.borrow_mut().set_pointer_constraint_region(self.constraint_oid, region);
+        Task::None
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
@@ -0,0 +1,71 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! Implementations of Wayland `wp_presentation` object.
+
+use std::rc::Rc;
+
+use skylane::server::{Bundle, Object, ObjectId, Task};
+use wayland_protocols::server::Handler;
+use wayland_protocols::server::presentation_time::wp_presentation;
+
+use global::Global;
+use facade::Facade;
+use proxy::ProxyRef;
+
+// -------------------------------------------------------------------------------------------------
+
+/// ID of `CLOCK_MONOTONIC` clock used to timestamp page flips.
+const CLOCK_MONOTONIC: u32 = 1;
+
+// -------------------------------------------------------------------------------------------------
+
+/// Wayland `wp_presentation` object.
+///
+/// Presentation feedbacks are not represented as objects; they are tracked by `Proxy` and
+/// destroyed right after sending `presented` or `discarded` event.
+struct Presentation {
+    proxy: ProxyRef,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+pub fn get_global() -> Global {
+    Global::new(wp_presentation::NAME,
+                wp_presentation::VERSION,
+                Rc::new(Presentation::new_object))
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl Presentation {
+    fn new(oid: ObjectId, proxy_ref: ProxyRef) -> Self {
+        let socket = proxy_ref.borrow().get_socket();
+        send!(wp_presentation::clock_id(&socket, oid, CLOCK_MONOTONIC));
+        Presentation { proxy: proxy_ref }
+    }
+
+    fn new_object(oid: ObjectId, _version: u32, proxy_ref: ProxyRef) -> Box<Object> {
+        Box::new(Handler::<_, wp_presentation::Dispatcher>::new(Self::new(oid, proxy_ref)))
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl wp_presentation::Interface for Presentation {
+    fn destroy(&mut self, this_object_id: ObjectId, _bundle: &mut Bundle) -> Task {
+        Task::Destroy { id: this_object_id }
+    }
+
+    fn feedback(&mut self,
+                _this_object_id: ObjectId,
+                _bundle: &mut Bundle,
+                surface: ObjectId,
+                callback: ObjectId)
+                -> Task {
+        self.proxy.borrow_mut().add_presentation_feedback(surface, callback);
+        Task::None
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
@@ -0,0 +1,218 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! Implementations of Wayland `zwp_primary_selection_device_manager_v1`,
+//! `zwp_primary_selection_device_v1`, `zwp_primary_selection_source_v1` and
+//! `zwp_primary_selection_offer_v1` objects.
+
+use std::os::unix::io::RawFd;
+
+use std::rc::Rc;
+
+use skylane::server::{Bundle, Object, ObjectId, Task};
+use wayland_protocols::server::Handler;
+use wayland_protocols::server::primary_selection::zwp_primary_selection_device_manager_v1;
+use wayland_protocols::server::primary_selection::zwp_primary_selection_source_v1;
+use wayland_protocols::server::primary_selection::zwp_primary_selection_device_v1;
+use wayland_protocols::server::primary_selection::zwp_primary_selection_offer_v1;
+
+use qualia::Transfer;
+
+use global::Global;
+use proxy::ProxyRef;
+use facade::Facade;
+
+// -------------------------------------------------------------------------------------------------
+
+/// Wayland `zwp_primary_selection_device_manager_v1` object.
+struct PrimarySelectionDeviceManager {
+    proxy_ref: ProxyRef,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+pub fn get_global() -> Global {
+    Global::new(zwp_primary_selection_device_manager_v1::NAME,
+                zwp_primary_selection_device_manager_v1::VERSION,
+                Rc::new(PrimarySelectionDeviceManager::new_object))
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl PrimarySelectionDeviceManager {
+    /// Creates new `PrimarySelectionDeviceManager`.
+    fn new(_oid: ObjectId, proxy_ref: ProxyRef) -> Self {
+        PrimarySelectionDeviceManager { proxy_ref: proxy_ref }
+    }
+
+    fn new_object(oid: ObjectId, _version: u32, proxy_ref: ProxyRef) -> Box<Object> {
+        let manager = Self::new(oid, proxy_ref);
+        Box::new(Handler::<_, zwp_primary_selection_device_manager_v1::Dispatcher>::new(manager))
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl zwp_primary_selection_device_manager_v1::Interface for PrimarySelectionDeviceManager {
+    fn create_source(&mut self,
+                     _this_object_id: ObjectId,
+                     _bundle: &mut Bundle,
+                     new_source_id: ObjectId)
+                     -> Task {
+        Task::Create {
+            id: new_source_id,
+            object: PrimarySelectionSource::new_object(new_source_id, self.proxy_ref.clone()),
+        }
+    }
+
+    fn get_device(&mut self,
+                  _this_object_id: ObjectId,
+                  _bundle: &mut Bundle,
+                  new_device_id: ObjectId,
+                  _seat: ObjectId)
+                  -> Task {
+        Task::Create {
+            id: new_device_id,
+            object: PrimarySelectionDevice::new_object(new_device_id, self.proxy_ref.clone()),
+        }
+    }
+
+    fn destroy(&mut self, this_object_id: ObjectId, _bundle: &mut Bundle) -> Task {
+        Task::Destroy { id: this_object_id }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Wayland `zwp_primary_selection_source_v1` object.
+struct PrimarySelectionSource {
+    proxy_ref: ProxyRef,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl PrimarySelectionSource {
+    /// Creates new `PrimarySelectionSource`.
+    fn new(oid: ObjectId, proxy_ref: ProxyRef) -> Self {
+        {
+            let mut proxy = proxy_ref.borrow_mut();
+            proxy.set_primary_transfer(oid, Transfer::new());
+        }
+
+        PrimarySelectionSource { proxy_ref: proxy_ref }
+    }
+
+    fn new_object(oid: ObjectId, proxy_ref: ProxyRef) -> Box<Object> {
+        let source = Self::new(oid, proxy_ref);
+        Box::new(Handler::<_, zwp_primary_selection_source_v1::Dispatcher>::new(source))
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl zwp_primary_selection_source_v1::Interface for PrimarySelectionSource {
+    fn offer(&mut self, this_object_id: ObjectId, _bundle: &mut Bundle, mime_type: String) -> Task {
+        let mut proxy = self.proxy_ref.borrow_mut();
+        if let Some(ref mut transfer) = proxy.get_primary_transfer(this_object_id) {
+            transfer.add_mime_type(mime_type);
+            proxy.set_primary_transfer(this_object_id, transfer.clone());
+        }
+        Task::None
+    }
+
+    fn destroy(&mut self, this_object_id: ObjectId, _bundle: &mut Bundle) -> Task {
+        let mut proxy = self.proxy_ref.borrow_mut();
+        proxy.remove_primary_transfer(this_object_id);
+        Task::Destroy { id: this_object_id }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Wayland `zwp_primary_selection_device_v1` object.
+struct PrimarySelectionDevice {
+    proxy_ref: ProxyRef,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl PrimarySelectionDevice {
+    /// Creates new `PrimarySelectionDevice`.
+    fn new(oid: ObjectId, proxy_ref: ProxyRef) -> Self {
+        {
+            let mut proxy = proxy_ref.borrow_mut();
+            proxy.add_primary_device_oid(oid);
+        }
+
+        PrimarySelectionDevice { proxy_ref: proxy_ref }
+    }
+
+    fn new_object(oid: ObjectId, proxy_ref: ProxyRef) -> Box<Object> {
+        let device = Self::new(oid, proxy_ref);
+        Box::new(Handler::<_, zwp_primary_selection_device_v1::Dispatcher>::new(device))
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl zwp_primary_selection_device_v1::Interface for PrimarySelectionDevice {
+    fn set_selection(&mut self,
+                     _this_object_id: ObjectId,
+                     _bundle: &mut Bundle,
+                     source_oid: ObjectId,
+                     _serial: u32)
+                     -> Task {
+        let source_oid = if source_oid.is_null() { None } else { Some(source_oid) };
+        let mut proxy = self.proxy_ref.borrow_mut();
+        proxy.select_primary_transfer(source_oid);
+        Task::None
+    }
+
+    fn destroy(&mut self, this_object_id: ObjectId, _bundle: &mut Bundle) -> Task {
+        let mut proxy = self.proxy_ref.borrow_mut();
+        proxy.remove_primary_device_oid(this_object_id);
+        Task::Destroy { id: this_object_id }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Wayland `zwp_primary_selection_offer_v1` object.
+pub struct PrimarySelectionOffer {
+    proxy_ref: ProxyRef,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl PrimarySelectionOffer {
+    /// Creates new `PrimarySelectionOffer`.
+    fn new(_oid: ObjectId, proxy_ref: ProxyRef) -> Self {
+        PrimarySelectionOffer { proxy_ref: proxy_ref }
+    }
+
+    pub fn new_object(oid: ObjectId, proxy_ref: ProxyRef) -> Box<Object> {
+        let offer = Self::new(oid, proxy_ref);
+        Box::new(Handler::<_, zwp_primary_selection_offer_v1::Dispatcher>::new(offer))
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl zwp_primary_selection_offer_v1::Interface for PrimarySelectionOffer {
+    fn receive(&mut self,
+               _this_object_id: ObjectId,
+               _bundle: &mut Bundle,
+               mime_type: String,
+               fd: RawFd)
+               -> Task {
+        let mut proxy = self.proxy_ref.borrow_mut();
+        proxy.request_primary_transfer(mime_type, fd);
+        Task::None
+    }
+
+    fn destroy(&mut self, this_object_id: ObjectId, _bundle: &mut Bundle) -> Task {
+        Task::Destroy { id: this_object_id }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
@@ -0,0 +1,97 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! Implementations of Wayland `zwp_relative_pointer_manager_v1` and `zwp_relative_pointer_v1`
+//! objects.
+
+use std::rc::Rc;
+
+use skylane::server::{Bundle, Object, ObjectId, Task};
+use wayland_protocols::server::Handler;
+use wayland_protocols::server::relative_pointer_unstable_v1::{zwp_relative_pointer_manager_v1,
+                                                              zwp_relative_pointer_v1};
+
+use global::Global;
+use facade::Facade;
+use proxy::ProxyRef;
+
+// -------------------------------------------------------------------------------------------------
+
+/// Wayland `zwp_relative_pointer_manager_v1` object.
+struct RelativePointerManager {
+    proxy: ProxyRef,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+pub fn get_global() -> Global {
+    Global::new(zwp_relative_pointer_manager_v1::NAME,
+                zwp_relative_pointer_manager_v1::VERSION,
+                Rc::new(RelativePointerManager::new_object))
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl RelativePointerManager {
+    fn new(proxy_ref: ProxyRef) -> Self {
+        RelativePointerManager { proxy: proxy_ref }
+    }
+
+    fn new_object(_oid: ObjectId, _version: u32, proxy_ref: ProxyRef) -> Box<Object> {
+        let manager = Self::new(proxy_ref);
+        Box::new(Handler::<_, zwp_relative_pointer_manager_v1::Dispatcher>::new(manager))
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl zwp_relative_pointer_manager_v1::Interface for RelativePointerManager {
+    fn destroy(&mut self, this_object_id: ObjectId, _bundle: &mut Bundle) -> Task {
+        Task::Destroy { id: this_object_id }
+    }
+
+    fn get_relative_pointer(&mut self,
+                            _this_object_id: ObjectId,
+                            _bundle: &mut Bundle,
+                            id: ObjectId,
+                            _pointer: ObjectId)
+                            -> Task {
+        self.proxy.borrow_mut().add_relative_pointer_oid(id);
+        Task::Create {
+            id: id,
+            object: RelativePointer::new_object(self.proxy.clone()),
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Wayland `zwp_relative_pointer_v1` object.
+///
+/// Relative motion events are sent by `Proxy` to all relative pointers of the client.
+struct RelativePointer {
+    proxy: ProxyRef,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl RelativePointer {
+    fn new(proxy_ref: ProxyRef) -> Self {
+        RelativePointer { proxy: proxy_ref }
+    }
+
+    fn new_object(proxy_ref: ProxyRef) -> Box<Object> {
+        Box::new(Handler::<_, zwp_relative_pointer_v1::Dispatcher>::new(Self::new(proxy_ref)))
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl zwp_relative_pointer_v1::Interface for RelativePointer {
+    fn destroy(&mut self, this_object_id: ObjectId, _bundle: &mut Bundle) -> Task {
+        self.proxy.borrow_mut().remove_relative_pointer_oid(this_object_id);
+        Task::Destroy { id: this_object_id }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
@@ -21,6 +21,7 @@ use proxy::ProxyRef;
 /// Wayland `wl_seat` object.
 struct Seat {
     proxy: ProxyRef,
+    version: u32,
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -37,7 +38,8 @@ impl Seat {
             // TODO: Add more capabilities
             let proxy = proxy_ref.borrow();
             let socket = proxy.get_socket();
-            let caps = wl_seat::capability::POINTER | wl_seat::capability::KEYBOARD;
+            let caps = wl_seat::capability::POINTER | wl_seat::capability::KEYBOARD |
+                       wl_seat::capability::TOUCH;
             send!(wl_seat::capabilities(&socket, oid, caps));
 
             // FIXME: Add support for versions in `skylane`.
@@ -45,7 +47,10 @@ impl Seat {
                 send!(wl_seat::name(&socket, oid, "seat0"));
             }
         }
-        Seat { proxy: proxy_ref }
+        Seat {
+            proxy: proxy_ref,
+            version: version,
+        }
     }
 
     fn new_object(oid: ObjectId, version: u32, proxy_ref: ProxyRef) -> Box<Object> {
@@ -74,7 +79,7 @@ impl wl_seat::Interface for Seat {
                     -> Task {
         Task::Create {
             id: new_keyboard_id,
-            object: Keyboard::new_object(new_keyboard_id, self.proxy.clone()),
+            object: Keyboard::new_object(new_keyboard_id, self.version, self.proxy.clone()),
         }
     }
 
@@ -145,20 +150,30 @@ struct Keyboard {
 // -------------------------------------------------------------------------------------------------
 
 impl Keyboard {
-    fn new(oid: ObjectId, proxy_ref: ProxyRef) -> Self {
+    fn new(oid: ObjectId, version: u32, proxy_ref: ProxyRef) -> Self {
         {
             let mut proxy = proxy_ref.borrow_mut();
             let socket = proxy.get_socket();
             let keymap = proxy.get_settings().get_keymap();
             proxy.add_keyboard_oid(oid);
             send!(wl_keyboard::keymap(&socket, oid, keymap.format, keymap.fd, keymap.size as u32));
+
+            // FIXME: Add support for versions in `skylane`.
+            if version >= 4 {
+                let config = proxy.get_keyboard_config();
+                send!(wl_keyboard::repeat_info(&socket,
+                                               oid,
+                                               config.repeat_rate as i32,
+                                               config.repeat_delay as i32));
+            }
         }
 
         Keyboard { proxy: proxy_ref }
     }
 
-    fn new_object(oid: ObjectId, proxy_ref: ProxyRef) -> Box<Object> {
-        Box::new(Handler::<_, wl_keyboard::Dispatcher>::new(Self::new(oid, proxy_ref)))
+    fn new_object(oid: ObjectId, version: u32, proxy_ref: ProxyRef) -> Box<Object> {
+        let keyboard = Self::new(oid, version, proxy_ref);
+        Box::new(Handler::<_, wl_keyboard::Dispatcher>::new(keyboard))
     }
 }
 
@@ -174,7 +189,6 @@ impl wl_keyboard::Interface for Keyboard {
 // -------------------------------------------------------------------------------------------------
 
 /// Wayland `wl_touch` object.
-#[allow(dead_code)]
 struct Touch {
     proxy: ProxyRef,
 }
@@ -182,12 +196,13 @@ struct Touch {
 // -------------------------------------------------------------------------------------------------
 
 impl Touch {
-    fn new(proxy_ref: ProxyRef) -> Self {
+    fn new(oid: ObjectId, proxy_ref: ProxyRef) -> Self {
+        proxy_ref.borrow_mut().add_touch_oid(oid);
         Touch { proxy: proxy_ref }
     }
 
-    fn new_object(_oid: ObjectId, proxy_ref: ProxyRef) -> Box<Object> {
-        Box::new(Handler::<_, wl_touch::Dispatcher>::new(Self::new(proxy_ref)))
+    fn new_object(oid: ObjectId, proxy_ref: ProxyRef) -> Box<Object> {
+        Box::new(Handler::<_, wl_touch::Dispatcher>::new(Self::new(oid, proxy_ref)))
     }
 }
 
@@ -195,6 +210,7 @@ impl Touch {
 
 impl wl_touch::Interface for Touch {
     fn release(&mut self, this_object_id: ObjectId, _bundle: &mut Bundle) -> Task {
+        self.proxy.borrow_mut().remove_touch_oid(this_object_id);
         Task::Destroy { id: this_object_id }
     }
 }
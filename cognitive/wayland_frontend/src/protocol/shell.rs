@@ -10,17 +10,20 @@ use skylane_protocols::server::Handler;
 use skylane_protocols::server::wayland::wl_shell;
 use skylane_protocols::server::wayland::wl_shell_surface;
 
-use qualia::show_reason;
+use qualia::{resize_edge, show_reason};
 
-use facade::{Facade, ShellSurfaceOid};
+use facade::{Facade, PopupOid, ShellSurfaceOid};
 use global::Global;
 use proxy::ProxyRef;
 
 // -------------------------------------------------------------------------------------------------
 
+#[derive(Clone, Copy, PartialEq)]
 enum SurfaceType {
     None,
     Toplevel,
+    Maximized,
+    Fullscreen,
     Popup,
 }
 
@@ -91,6 +94,57 @@ impl Surface {
         let surface = Self::new(oid, surface_oid, proxy_ref);
         Box::new(Handler::<_, wl_shell_surface::Dispatcher>::new(surface))
     }
+
+    /// Shows the surface as toplevel with given type. Maximized and fullscreen states of previous
+    /// type are cleared if new type does not keep them.
+    fn show_as_toplevel(&mut self,
+                        this_object_id: ObjectId,
+                        surface_type: SurfaceType,
+                        output_oid: Option<ObjectId>) {
+        let mut proxy = self.proxy.borrow_mut();
+
+        // NOTE: Workaround for Qt. It first sets menus as toplevel and later as pop-up.
+        //       Here opposite situation added for symmetry.
+        match self.surface_type {
+            SurfaceType::Popup => {
+                proxy.ungrab_popup(self.surface_oid);
+                proxy.unrelate(self.surface_oid);
+            }
+            SurfaceType::Maximized if surface_type != SurfaceType::Maximized => {
+                proxy.set_maximized(self.surface_oid, false)
+            }
+            SurfaceType::Fullscreen if surface_type != SurfaceType::Fullscreen => {
+                proxy.set_fullscreen(self.surface_oid, false, None)
+            }
+            _ => {}
+        }
+
+        proxy.show(self.surface_oid, ShellSurfaceOid::Shell(this_object_id), show_reason::IN_SHELL);
+
+        match surface_type {
+            SurfaceType::Maximized => proxy.set_maximized(self.surface_oid, true),
+            SurfaceType::Fullscreen => proxy.set_fullscreen(self.surface_oid, true, output_oid),
+            _ => {}
+        }
+        self.surface_type = surface_type;
+    }
+
+    /// Hides the surface if it is shown as toplevel. Maximized and fullscreen surfaces are first
+    /// brought back to frame layout.
+    fn hide_toplevel(&mut self) {
+        let mut proxy = self.proxy.borrow_mut();
+        match self.surface_type {
+            SurfaceType::Maximized => proxy.set_maximized(self.surface_oid, false),
+            SurfaceType::Fullscreen => proxy.set_fullscreen(self.surface_oid, false, None),
+            _ => {}
+        }
+        match self.surface_type {
+            SurfaceType::Toplevel |
+            SurfaceType::Maximized |
+            SurfaceType::Fullscreen => proxy.hide(self.surface_oid, show_reason::IN_SHELL),
+            _ => {}
+        }
+    }
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -107,6 +161,7 @@ impl wl_shell_surface::Interface for Surface {
              seat: ObjectId,
              serial: u32)
              -> Task {
+        self.proxy.borrow().request_move(self.surface_oid, serial);
         Task::None
     }
 
@@ -117,21 +172,13 @@ impl wl_shell_surface::Interface for Surface {
               serial: u32,
               edges: u32)
               -> Task {
+        let edges = resize_edge::ResizeEdge::from_bits_truncate(edges);
+        self.proxy.borrow().request_resize(self.surface_oid, serial, edges);
         Task::None
     }
 
     fn set_toplevel(&mut self, this_object_id: ObjectId, bundle: &mut Bundle) -> Task {
-        let mut proxy = self.proxy.borrow_mut();
-
-        // NOTE: Workaround for Qt. It first sets menus as toplevel and later as pop-up.
-        //       Here opposite situation added for symmetry.
-        match self.surface_type {
-            SurfaceType::Popup => proxy.unrelate(self.surface_oid),
-            _ => {}
-        }
-        self.surface_type = SurfaceType::Toplevel;
-
-        proxy.show(self.surface_oid, ShellSurfaceOid::Shell(this_object_id), show_reason::IN_SHELL);
+        self.show_as_toplevel(this_object_id, SurfaceType::Toplevel, None);
         Task::None
     }
 
@@ -144,16 +191,12 @@ impl wl_shell_surface::Interface for Surface {
                      y: i32,
                      flags: u32)
                      -> Task {
-        let mut proxy = self.proxy.borrow_mut();
-
         // NOTE: Workaround for Qt. It first sets menus as toplevel and later as pop-up.
         //       Here opposite situation added for symmetry.
-        match self.surface_type {
-            SurfaceType::Toplevel => proxy.hide(self.surface_oid, show_reason::IN_SHELL),
-            _ => {}
-        }
+        self.hide_toplevel();
         self.surface_type = SurfaceType::Popup;
 
+        let mut proxy = self.proxy.borrow_mut();
         proxy.relate(self.surface_oid, parent_surface_oid);
         proxy.set_relative_position(self.surface_oid, x as isize, y as isize);
         Task::None
@@ -166,6 +209,8 @@ impl wl_shell_surface::Interface for Surface {
                       framerate: u32,
                       output: ObjectId)
                       -> Task {
+        let output = if output.is_null() { None } else { Some(output) };
+        self.show_as_toplevel(this_object_id, SurfaceType::Fullscreen, output);
         Task::None
     }
 
@@ -179,17 +224,14 @@ impl wl_shell_surface::Interface for Surface {
                  y: i32,
                  flags: u32)
                  -> Task {
-        let mut proxy = self.proxy.borrow_mut();
-
         // NOTE: Workaround for Qt. It first sets menus as toplevel and later as pop-up.
-        match self.surface_type {
-            SurfaceType::Toplevel => proxy.hide(self.surface_oid, show_reason::IN_SHELL),
-            _ => {}
-        }
+        self.hide_toplevel();
         self.surface_type = SurfaceType::Popup;
 
+        let mut proxy = self.proxy.borrow_mut();
         proxy.relate(self.surface_oid, parent_surface_oid);
         proxy.set_relative_position(self.surface_oid, x as isize, y as isize);
+        proxy.grab_popup(self.surface_oid, parent_surface_oid, PopupOid::Shell(this_object_id));
         Task::None
     }
 
@@ -198,14 +240,17 @@ impl wl_shell_surface::Interface for Surface {
                      bundle: &mut Bundle,
                      output: ObjectId)
                      -> Task {
+        self.show_as_toplevel(this_object_id, SurfaceType::Maximized, None);
         Task::None
     }
 
     fn set_title(&mut self, this_object_id: ObjectId, bundle: &mut Bundle, title: String) -> Task {
+        self.proxy.borrow().set_title(self.surface_oid, title);
         Task::None
     }
 
     fn set_class(&mut self, this_object_id: ObjectId, bundle: &mut Bundle, class: String) -> Task {
+        self.proxy.borrow().set_app_id(self.surface_oid, class);
         Task::None
     }
 }
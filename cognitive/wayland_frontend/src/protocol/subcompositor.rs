@@ -3,8 +3,6 @@
 
 //! Implementations of Wayland `wl_subcompositor` and `wl_subsurface` objects.
 
-// TODO: Finish implementation of subcompositor.
-
 use std::rc::Rc;
 
 use skylane::server::{Bundle, Object, ObjectId, Task};
@@ -80,6 +78,7 @@ impl Subsurface {
         {
             let proxy = proxy_ref.borrow_mut();
             proxy.relate(surface_oid, parent_surface_oid);
+            proxy.set_synchronized(surface_oid, true);
         }
         Subsurface {
             surface_oid: surface_oid,
@@ -98,9 +97,8 @@ impl Subsurface {
 
 // -------------------------------------------------------------------------------------------------
 
-#[allow(unused_variables)]
 impl wl_subsurface::Interface for Subsurface {
-    fn destroy(&mut self, this_object_id: ObjectId, bundle: &mut Bundle) -> Task {
+    fn destroy(&mut self, this_object_id: ObjectId, _bundle: &mut Bundle) -> Task {
         let proxy = self.proxy.borrow_mut();
         proxy.unrelate(self.surface_oid);
         Task::Destroy { id: this_object_id }
@@ -122,22 +120,30 @@ impl wl_subsurface::Interface for Subsurface {
                    _bundle: &mut Bundle,
                    sibling: ObjectId)
                    -> Task {
+        let proxy = self.proxy.borrow();
+        proxy.place_above(self.surface_oid, sibling);
         Task::None
     }
 
     fn place_below(&mut self,
-                   this_object_id: ObjectId,
-                   bundle: &mut Bundle,
+                   _this_object_id: ObjectId,
+                   _bundle: &mut Bundle,
                    sibling: ObjectId)
                    -> Task {
+        let proxy = self.proxy.borrow();
+        proxy.place_below(self.surface_oid, sibling);
         Task::None
     }
 
-    fn set_sync(&mut self, this_object_id: ObjectId, bundle: &mut Bundle) -> Task {
+    fn set_sync(&mut self, _this_object_id: ObjectId, _bundle: &mut Bundle) -> Task {
+        let proxy = self.proxy.borrow();
+        proxy.set_synchronized(self.surface_oid, true);
         Task::None
     }
 
-    fn set_desync(&mut self, this_object_id: ObjectId, bundle: &mut Bundle) -> Task {
+    fn set_desync(&mut self, _this_object_id: ObjectId, _bundle: &mut Bundle) -> Task {
+        let proxy = self.proxy.borrow();
+        proxy.set_synchronized(self.surface_oid, false);
         Task::None
     }
 }
@@ -0,0 +1,192 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! Implementations of Wayland `zwp_text_input_manager_v3` and `zwp_text_input_v3` objects.
+
+use std::rc::Rc;
+
+use skylane::server::{Bundle, Object, ObjectId, Task};
+use wayland_protocols::server::Handler;
+use wayland_protocols::server::text_input_unstable_v3::{zwp_text_input_manager_v3,
+                                                        zwp_text_input_v3};
+
+use qualia::{Area, Position, Size};
+
+use global::Global;
+use facade::{Facade, TextInputState};
+use proxy::ProxyRef;
+
+// -------------------------------------------------------------------------------------------------
+
+/// Generated request handlers read strings byte by byte turning every byte into separate
+/// character. This function restores original UTF-8 text.
+pub fn decode_text(text: String) -> String {
+    let bytes: Vec<u8> = text.chars().map(|c| c as u8).collect();
+    match String::from_utf8(bytes) {
+        Ok(text) => text,
+        Err(err) => String::from_utf8_lossy(&err.into_bytes()).into_owned(),
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Wayland `zwp_text_input_manager_v3` object.
+struct TextInputManager {
+    proxy: ProxyRef,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+pub fn get_global() -> Global {
+    Global::new(zwp_text_input_manager_v3::NAME,
+                zwp_text_input_manager_v3::VERSION,
+                Rc::new(TextInputManager::new_object))
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl TextInputManager {
+    fn new(proxy_ref: ProxyRef) -> Self {
+        TextInputManager { proxy: proxy_ref }
+    }
+
+    fn new_object(_oid: ObjectId, _version: u32, proxy_ref: ProxyRef) -> Box<Object> {
+        Box::new(Handler::<_, zwp_text_input_manager_v3::Dispatcher>::new(Self::new(proxy_ref)))
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl zwp_text_input_manager_v3::Interface for TextInputManager {
+    fn destroy(&mut self, this_object_id: ObjectId, _bundle: &mut Bundle) -> Task {
+        Task::Destroy { id: this_object_id }
+    }
+
+    fn get_text_input(&mut self,
+                      _this_object_id: ObjectId,
+                      _bundle: &mut Bundle,
+                      id: ObjectId,
+                      _seat: ObjectId)
+                      -> Task {
+        self.proxy.borrow_mut().add_text_input(id);
+        Task::Create {
+            id: id,
+            object: TextInput::new_object(self.proxy.clone()),
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Wayland `zwp_text_input_v3` object.
+///
+/// State is double-buffered and passed to the rest of frontend on commit. Actions and input panel
+/// requests from version 2 are ignored.
+struct TextInput {
+    pending: TextInputState,
+    proxy: ProxyRef,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl TextInput {
+    fn new(proxy_ref: ProxyRef) -> Self {
+        TextInput {
+            pending: TextInputState::new(),
+            proxy: proxy_ref,
+        }
+    }
+
+    fn new_object(proxy_ref: ProxyRef) -> Box<Object> {
+        Box::new(Handler::<_, zwp_text_input_v3::Dispatcher>::new(Self::new(proxy_ref)))
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl zwp_text_input_v3::Interface for TextInput {
+    fn destroy(&mut self, this_object_id: ObjectId, _bundle: &mut Bundle) -> Task {
+        self.proxy.borrow_mut().remove_text_input(this_object_id);
+        Task::Destroy { id: this_object_id }
+    }
+
+    fn enable(&mut self, _this_object_id: ObjectId, _bundle: &mut Bundle) -> Task {
+        // Enabling resets state to initial values.
+        self.pending = TextInputState::new();
+        self.pending.is_enabled = true;
+        Task::None
+    }
+
+    fn disable(&mut self, _this_object_id: ObjectId, _bundle: &mut Bundle) -> Task {
+        self.pending.is_enabled = false;
+        Task::None
+    }
+
+    fn set_surrounding_text(&mut self,
+                            _this_object_id: ObjectId,
+                            _bundle: &mut Bundle,
+                            text: String,
+                            cursor: i32,
+                            anchor: i32)
+                            -> Task {
+        self.pending.surrounding_text = Some((decode_text(text), cursor, anchor));
+        Task::None
+    }
+
+    fn set_text_change_cause(&mut self,
+                             _this_object_id: ObjectId,
+                             _bundle: &mut Bundle,
+                             cause: u32)
+                             -> Task {
+        self.pending.change_cause = cause;
+        Task::None
+    }
+
+    fn set_content_type(&mut self,
+                        _this_object_id: ObjectId,
+                        _bundle: &mut Bundle,
+                        hint: u32,
+                        purpose: u32)
+                        -> Task {
+        self.pending.content_hint = hint;
+        self.pending.content_purpose = purpose;
+        Task::None
+    }
+
+    fn set_cursor_rectangle(&mut self,
+                            _this_object_id: ObjectId,
+                            _bundle: &mut Bundle,
+                            x: i32,
+                            y: i32,
+                            width: i32,
+                            height: i32)
+                            -> Task {
+        let position = Position::new(x as isize, y as isize);
+        let size = Size::new(width.max(0) as usize, height.max(0) as usize);
+        self.pending.cursor_rectangle = Area::new(position, size);
+        Task::None
+    }
+
+    fn commit(&mut self, this_object_id: ObjectId, _bundle: &mut Bundle) -> Task {
+        self.proxy.borrow_mut().commit_text_input(this_object_id, self.pending.clone());
+        Task::None
+    }
+
+    fn set_available_actions(&mut self,
+                             _this_object_id: ObjectId,
+                             _bundle: &mut Bundle,
+                             _available_actions: Vec<u32>)
+                             -> Task {
+        Task::None
+    }
+
+    fn show_input_panel(&mut self, _this_object_id: ObjectId, _bundle: &mut Bundle) -> Task {
+        Task::None
+    }
+
+    fn hide_input_panel(&mut self, _this_object_id: ObjectId, _bundle: &mut Bundle) -> Task {
+        Task::None
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
@@ -0,0 +1,158 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! Implementations of Wayland `wp_viewporter` and `wp_viewport` objects.
+
+use std::rc::Rc;
+
+use skylane::server as wl;
+use skylane::server::{Bundle, Object, ObjectId, Task};
+use skylane_protocols::server::wayland::wl_display;
+use wayland_protocols::server::Handler;
+use wayland_protocols::server::viewporter::{wp_viewporter, wp_viewport};
+
+use qualia::{Size, ViewportSource};
+
+use global::Global;
+use facade::Facade;
+use proxy::ProxyRef;
+
+// -------------------------------------------------------------------------------------------------
+
+/// Value used by clients to unset source rectangle or destination size.
+const UNSET: i32 = -1;
+
+// -------------------------------------------------------------------------------------------------
+
+/// Wayland `wp_viewporter` object.
+struct Viewporter {
+    proxy: ProxyRef,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+pub fn get_global() -> Global {
+    Global::new(wp_viewporter::NAME,
+                wp_viewporter::VERSION,
+                Rc::new(Viewporter::new_object))
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl Viewporter {
+    fn new(proxy_ref: ProxyRef) -> Self {
+        Viewporter { proxy: proxy_ref }
+    }
+
+    fn new_object(_oid: ObjectId, _version: u32, proxy_ref: ProxyRef) -> Box<Object> {
+        Box::new(Handler::<_, wp_viewporter::Dispatcher>::new(Self::new(proxy_ref)))
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl wp_viewporter::Interface for Viewporter {
+    fn destroy(&mut self, this_object_id: ObjectId, _bundle: &mut Bundle) -> Task {
+        Task::Destroy { id: this_object_id }
+    }
+
+    fn get_viewport(&mut self,
+                    this_object_id: ObjectId,
+                    bundle: &mut Bundle,
+                    new_viewport_oid: ObjectId,
+                    surface_oid: ObjectId)
+                    -> Task {
+        if self.proxy.borrow_mut().add_viewport(surface_oid, new_viewport_oid) {
+            Task::Create {
+                id: new_viewport_oid,
+                object: Viewport::new_object(surface_oid, self.proxy.clone()),
+            }
+        } else {
+            send!(wl_display::error(&bundle.get_socket(),
+                                    wl::DISPLAY_ID,
+                                    this_object_id,
+                                    wp_viewporter::error::VIEWPORT_EXISTS,
+                                    "Surface already has a viewport"));
+            Task::None
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Wayland `wp_viewport` object.
+struct Viewport {
+    surface_oid: ObjectId,
+    proxy: ProxyRef,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl Viewport {
+    fn new(surface_oid: ObjectId, proxy_ref: ProxyRef) -> Self {
+        Viewport {
+            surface_oid: surface_oid,
+            proxy: proxy_ref,
+        }
+    }
+
+    fn new_object(surface_oid: ObjectId, proxy_ref: ProxyRef) -> Box<Object> {
+        Box::new(Handler::<_, wp_viewport::Dispatcher>::new(Self::new(surface_oid, proxy_ref)))
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl wp_viewport::Interface for Viewport {
+    fn destroy(&mut self, this_object_id: ObjectId, _bundle: &mut Bundle) -> Task {
+        self.proxy.borrow_mut().remove_viewport(self.surface_oid);
+        Task::Destroy { id: this_object_id }
+    }
+
+    fn set_source(&mut self,
+                  this_object_id: ObjectId,
+                  bundle: &mut Bundle,
+                  x: f32,
+                  y: f32,
+                  width: f32,
+                  height: f32)
+                  -> Task {
+        let unset = UNSET as f32;
+        if (x == unset) && (y == unset) && (width == unset) && (height == unset) {
+            self.proxy.borrow().set_viewport_source(self.surface_oid, None);
+        } else if (x < 0.0) || (y < 0.0) || (width <= 0.0) || (height <= 0.0) {
+            send!(wl_display::error(&bundle.get_socket(),
+                                    wl::DISPLAY_ID,
+                                    this_object_id,
+                                    wp_viewport::error::BAD_VALUE,
+                                    "Invalid source rectangle"));
+        } else {
+            let source = ViewportSource::new(x, y, width, height);
+            self.proxy.borrow().set_viewport_source(self.surface_oid, Some(source));
+        }
+        Task::None
+    }
+
+    fn set_destination(&mut self,
+                       this_object_id: ObjectId,
+                       bundle: &mut Bundle,
+                       width: i32,
+                       height: i32)
+                       -> Task {
+        if (width == UNSET) && (height == UNSET) {
+            self.proxy.borrow().set_viewport_destination(self.surface_oid, None);
+        } else if (width <= 0) || (height <= 0) {
+            send!(wl_display::error(&bundle.get_socket(),
+                                    wl::DISPLAY_ID,
+                                    this_object_id,
+                                    wp_viewport::error::BAD_VALUE,
+                                    "Invalid destination size"));
+        } else {
+            let size = Size::new(width as usize, height as usize);
+            self.proxy.borrow().set_viewport_destination(self.surface_oid, Some(size));
+        }
+        Task::None
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
@@ -0,0 +1,173 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! Implementations of Wayland `zwp_virtual_keyboard_manager_v1` and `zwp_virtual_keyboard_v1`
+//! objects.
+
+use std::os::unix::io::RawFd;
+use std::rc::Rc;
+use std::time::Duration;
+use nix;
+
+use skylane::server as wl;
+use skylane::server::{Bundle, Object, ObjectId, Task};
+use skylane_protocols::server::wayland::wl_display;
+use wayland_protocols::server::Handler;
+use wayland_protocols::server::virtual_keyboard_unstable_v1::{zwp_virtual_keyboard_manager_v1,
+                                                              zwp_virtual_keyboard_v1};
+
+use qualia::{Key, Milliseconds};
+use inputs::KeyMods;
+
+use global::Global;
+use facade::Facade;
+use proxy::ProxyRef;
+
+// -------------------------------------------------------------------------------------------------
+
+/// Wayland `zwp_virtual_keyboard_manager_v1` object.
+struct VirtualKeyboardManager {
+    proxy: ProxyRef,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+pub fn get_global() -> Global {
+    Global::new(zwp_virtual_keyboard_manager_v1::NAME,
+                zwp_virtual_keyboard_manager_v1::VERSION,
+                Rc::new(VirtualKeyboardManager::new_object))
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl VirtualKeyboardManager {
+    fn new(proxy_ref: ProxyRef) -> Self {
+        VirtualKeyboardManager { proxy: proxy_ref }
+    }
+
+    fn new_object(_oid: ObjectId, _version: u32, proxy_ref: ProxyRef) -> Box<Object> {
+        let manager = Self::new(proxy_ref);
+        Box::new(Handler::<_, zwp_virtual_keyboard_manager_v1::Dispatcher>::new(manager))
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl zwp_virtual_keyboard_manager_v1::Interface for VirtualKeyboardManager {
+    fn create_virtual_keyboard(&mut self,
+                               this_object_id: ObjectId,
+                               bundle: &mut Bundle,
+                               _seat: ObjectId,
+                               id: ObjectId)
+                               -> Task {
+        if self.proxy.borrow_mut().add_virtual_keyboard() {
+            Task::Create {
+                id: id,
+                object: VirtualKeyboard::new_object(self.proxy.clone()),
+            }
+        } else {
+            send!(wl_display::error(&bundle.get_socket(),
+                                    wl::DISPLAY_ID,
+                                    this_object_id,
+                                    zwp_virtual_keyboard_manager_v1::error::UNAUTHORIZED,
+                                    "Only input method may create virtual keyboard"));
+            Task::None
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Wayland `zwp_virtual_keyboard_v1` object.
+///
+/// Emulated keys are passed to the client having keyboard focus. Keymap provided by input method is
+/// ignored; input method is expected to use the keymap it received with keyboard grab.
+struct VirtualKeyboard {
+    has_keymap: bool,
+    proxy: ProxyRef,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl VirtualKeyboard {
+    fn new(proxy_ref: ProxyRef) -> Self {
+        VirtualKeyboard {
+            has_keymap: false,
+            proxy: proxy_ref,
+        }
+    }
+
+    fn new_object(proxy_ref: ProxyRef) -> Box<Object> {
+        Box::new(Handler::<_, zwp_virtual_keyboard_v1::Dispatcher>::new(Self::new(proxy_ref)))
+    }
+
+    /// Sends protocol error if keymap was not set yet. Returns `true` if it was.
+    fn check_keymap(&self, this_object_id: ObjectId, bundle: &mut Bundle) -> bool {
+        if !self.has_keymap {
+            send!(wl_display::error(&bundle.get_socket(),
+                                    wl::DISPLAY_ID,
+                                    this_object_id,
+                                    zwp_virtual_keyboard_v1::error::NO_KEYMAP,
+                                    "Keymap was not set"));
+        }
+        self.has_keymap
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl zwp_virtual_keyboard_v1::Interface for VirtualKeyboard {
+    fn keymap(&mut self,
+              _this_object_id: ObjectId,
+              _bundle: &mut Bundle,
+              _format: u32,
+              fd: RawFd,
+              _size: u32)
+              -> Task {
+        if let Err(err) = nix::unistd::close(fd) {
+            log_warn3!("Failed to close virtual keymap file: {:?}", err);
+        }
+        self.has_keymap = true;
+        Task::None
+    }
+
+    fn key(&mut self,
+           this_object_id: ObjectId,
+           bundle: &mut Bundle,
+           time: u32,
+           key: u32,
+           state: u32)
+           -> Task {
+        if self.check_keymap(this_object_id, bundle) {
+            let milliseconds = Milliseconds::from_duration(&Duration::from_millis(time as u64));
+            let key = Key::new(key as u16, state as i32, milliseconds);
+            self.proxy.borrow_mut().emit_virtual_key(key);
+        }
+        Task::None
+    }
+
+    fn modifiers(&mut self,
+                 this_object_id: ObjectId,
+                 bundle: &mut Bundle,
+                 mods_depressed: u32,
+                 mods_latched: u32,
+                 mods_locked: u32,
+                 group: u32)
+                 -> Task {
+        if self.check_keymap(this_object_id, bundle) {
+            self.proxy.borrow_mut().emit_virtual_modifiers(KeyMods {
+                depressed: mods_depressed,
+                latched: mods_latched,
+                locked: mods_locked,
+                effective: group,
+            });
+        }
+        Task::None
+    }
+
+    fn destroy(&mut self, this_object_id: ObjectId, _bundle: &mut Bundle) -> Task {
+        Task::Destroy { id: this_object_id }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
@@ -0,0 +1,146 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! Implementations of Wayland `zxdg_decoration_manager_v1` and `zxdg_toplevel_decoration_v1`
+//! objects.
+
+use std::rc::Rc;
+
+use skylane::server::{Bundle, Object, ObjectId, Task};
+use wayland_protocols::server::Handler;
+use wayland_protocols::server::xdg_shell::xdg_surface;
+use wayland_protocols::server::xdg_decoration_unstable_v1::{zxdg_decoration_manager_v1,
+                                                            zxdg_toplevel_decoration_v1};
+
+use global::Global;
+use facade::Facade;
+use proxy::ProxyRef;
+
+use self::zxdg_toplevel_decoration_v1::mode::SERVER_SIDE;
+
+// -------------------------------------------------------------------------------------------------
+
+/// Sends server-side decoration mode. Mode takes effect only after `xdg_surface` is configured so
+/// `configure` is sent to it as well.
+fn send_configure(bundle: &mut Bundle, oid: ObjectId, shell_surface_oid: Option<ObjectId>) {
+    let socket = bundle.get_socket();
+    send!(zxdg_toplevel_decoration_v1::configure(&socket, oid, SERVER_SIDE));
+    if let Some(shell_surface_oid) = shell_surface_oid {
+        let serial = socket.get_next_serial();
+        send!(xdg_surface::configure(&socket, shell_surface_oid, serial));
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Wayland `zxdg_decoration_manager_v1` object.
+struct XdgDecorationManager {
+    proxy: ProxyRef,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+pub fn get_global() -> Global {
+    Global::new(zxdg_decoration_manager_v1::NAME,
+                zxdg_decoration_manager_v1::VERSION,
+                Rc::new(XdgDecorationManager::new_object))
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl XdgDecorationManager {
+    fn new(proxy_ref: ProxyRef) -> Self {
+        XdgDecorationManager { proxy: proxy_ref }
+    }
+
+    fn new_object(_oid: ObjectId, _version: u32, proxy_ref: ProxyRef) -> Box<Object> {
+        Box::new(Handler::<_, zxdg_decoration_manager_v1::Dispatcher>::new(Self::new(proxy_ref)))
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl zxdg_decoration_manager_v1::Interface for XdgDecorationManager {
+    fn destroy(&mut self, this_object_id: ObjectId, _bundle: &mut Bundle) -> Task {
+        Task::Destroy { id: this_object_id }
+    }
+
+    fn get_toplevel_decoration(&mut self,
+                               _this_object_id: ObjectId,
+                               bundle: &mut Bundle,
+                               id: ObjectId,
+                               toplevel: ObjectId)
+                               -> Task {
+        let shell_surface_oid = self.proxy.borrow_mut().set_toplevel_decorated(toplevel, true);
+        if shell_surface_oid.is_none() {
+            log_warn2!("Requested decoration for unknown toplevel {:?}", toplevel);
+        }
+
+        // Compositor always prefers to draw decorations itself.
+        send_configure(bundle, id, shell_surface_oid);
+
+        Task::Create {
+            id: id,
+            object: XdgToplevelDecoration::new_object(toplevel,
+                                                      shell_surface_oid,
+                                                      self.proxy.clone()),
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Wayland `zxdg_toplevel_decoration_v1` object.
+///
+/// Only server-side mode is supported. Requests to change the mode are answered with server-side
+/// mode again.
+struct XdgToplevelDecoration {
+    toplevel_oid: ObjectId,
+    shell_surface_oid: Option<ObjectId>,
+    proxy: ProxyRef,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl XdgToplevelDecoration {
+    fn new(toplevel_oid: ObjectId,
+           shell_surface_oid: Option<ObjectId>,
+           proxy_ref: ProxyRef)
+           -> Self {
+        XdgToplevelDecoration {
+            toplevel_oid: toplevel_oid,
+            shell_surface_oid: shell_surface_oid,
+            proxy: proxy_ref,
+        }
+    }
+
+    fn new_object(toplevel_oid: ObjectId,
+                  shell_surface_oid: Option<ObjectId>,
+                  proxy_ref: ProxyRef)
+                  -> Box<Object> {
+        let decoration = Self::new(toplevel_oid, shell_surface_oid, proxy_ref);
+        Box::new(Handler::<_, zxdg_toplevel_decoration_v1::Dispatcher>::new(decoration))
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl zxdg_toplevel_decoration_v1::Interface for XdgToplevelDecoration {
+    fn destroy(&mut self, this_object_id: ObjectId, _bundle: &mut Bundle) -> Task {
+        // Without decoration object client is responsible for drawing decorations.
+        self.proxy.borrow_mut().set_toplevel_decorated(self.toplevel_oid, false);
+        Task::Destroy { id: this_object_id }
+    }
+
+    fn set_mode(&mut self, this_object_id: ObjectId, bundle: &mut Bundle, _mode: u32) -> Task {
+        send_configure(bundle, this_object_id, self.shell_surface_oid);
+        Task::None
+    }
+
+    fn unset_mode(&mut self, this_object_id: ObjectId, bundle: &mut Bundle) -> Task {
+        send_configure(bundle, this_object_id, self.shell_surface_oid);
+        Task::None
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
@@ -0,0 +1,167 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! Implementations of Wayland `zxdg_output_manager_v1` and `zxdg_output_v1` objects.
+
+use std::rc::Rc;
+
+use skylane::server::{Bundle, Object, ObjectId, Socket, Task};
+use wayland_protocols::server::Handler;
+use wayland_protocols::server::xdg_output::zxdg_output_manager_v1;
+use wayland_protocols::server::xdg_output::zxdg_output_v1;
+
+use qualia::OutputInfo;
+
+use global::Global;
+use proxy::ProxyRef;
+use facade::Facade;
+use protocol::output;
+
+// -------------------------------------------------------------------------------------------------
+
+/// Version of `zxdg_output_v1` since which `done` event is deprecated in favour of
+/// `wl_output.done`.
+const DONE_DEPRECATED_SINCE: u32 = 3;
+
+// -------------------------------------------------------------------------------------------------
+
+/// Wayland `zxdg_output_manager_v1` object.
+struct XdgOutputManager {
+    proxy_ref: ProxyRef,
+    version: u32,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+pub fn get_global() -> Global {
+    Global::new(zxdg_output_manager_v1::NAME,
+                zxdg_output_manager_v1::VERSION,
+                Rc::new(XdgOutputManager::new_object))
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Sends logical geometry, name and description of output. Does not send `done` event.
+pub fn send_info(socket: &Socket, oid: ObjectId, version: u32, info: &OutputInfo) {
+    send!(zxdg_output_v1::logical_position(socket,
+                                           oid,
+                                           info.area.pos.x as i32,
+                                           info.area.pos.y as i32));
+    send!(zxdg_output_v1::logical_size(socket,
+                                       oid,
+                                       info.area.size.width as i32,
+                                       info.area.size.height as i32));
+
+    // FIXME: Add support for versions in `skylane`.
+    if version >= 2 {
+        send!(zxdg_output_v1::name(socket, oid, &info.name));
+        send!(zxdg_output_v1::description(socket, oid, &make_description(info)));
+    }
+}
+
+/// Sends `done` event if it was not yet deprecated in given version. Newer clients should receive
+/// `wl_output.done` instead.
+pub fn send_done(socket: &Socket, oid: ObjectId, version: u32) {
+    if version < DONE_DEPRECATED_SINCE {
+        send!(zxdg_output_v1::done(socket, oid));
+    }
+}
+
+/// Makes human readable description of output.
+fn make_description(info: &OutputInfo) -> String {
+    if info.make.is_empty() || info.make == info.name {
+        info.name.clone()
+    } else {
+        format!("{} {} ({})", info.make, info.model, info.name)
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl XdgOutputManager {
+    /// Creates new `XdgOutputManager`.
+    fn new(_oid: ObjectId, version: u32, proxy_ref: ProxyRef) -> Self {
+        XdgOutputManager {
+            proxy_ref: proxy_ref,
+            version: version,
+        }
+    }
+
+    fn new_object(oid: ObjectId, version: u32, proxy_ref: ProxyRef) -> Box<Object> {
+        let manager = Self::new(oid, version, proxy_ref);
+        Box::new(Handler::<_, zxdg_output_manager_v1::Dispatcher>::new(manager))
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl zxdg_output_manager_v1::Interface for XdgOutputManager {
+    fn destroy(&mut self, this_object_id: ObjectId, _bundle: &mut Bundle) -> Task {
+        Task::Destroy { id: this_object_id }
+    }
+
+    fn get_xdg_output(&mut self,
+                      _this_object_id: ObjectId,
+                      _bundle: &mut Bundle,
+                      new_xdg_output_id: ObjectId,
+                      output: ObjectId)
+                      -> Task {
+        let object = XdgOutput::new_object(new_xdg_output_id,
+                                           output,
+                                           self.version,
+                                           self.proxy_ref.clone());
+        Task::Create {
+            id: new_xdg_output_id,
+            object: object,
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Wayland `zxdg_output_v1` object.
+struct XdgOutput {
+    proxy_ref: ProxyRef,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl XdgOutput {
+    /// Creates new `XdgOutput` and sends information about related output.
+    fn new(oid: ObjectId, output_oid: ObjectId, version: u32, proxy_ref: ProxyRef) -> Self {
+        {
+            let mut proxy = proxy_ref.borrow_mut();
+            if let Some((info, output_version)) = proxy.get_output_info_for_oid(output_oid) {
+                let socket = proxy.get_socket();
+                send_info(&socket, oid, version, &info);
+                send_done(&socket, oid, version);
+                if version >= DONE_DEPRECATED_SINCE {
+                    output::send_done(&socket, output_oid, output_version);
+                }
+            }
+            proxy.bind_xdg_output(oid, output_oid, version);
+        }
+
+        XdgOutput { proxy_ref: proxy_ref }
+    }
+
+    fn new_object(oid: ObjectId,
+                  output_oid: ObjectId,
+                  version: u32,
+                  proxy_ref: ProxyRef)
+                  -> Box<Object> {
+        let xdg_output = Self::new(oid, output_oid, version, proxy_ref);
+        Box::new(Handler::<_, zxdg_output_v1::Dispatcher>::new(xdg_output))
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl zxdg_output_v1::Interface for XdgOutput {
+    fn destroy(&mut self, this_object_id: ObjectId, _bundle: &mut Bundle) -> Task {
+        self.proxy_ref.borrow_mut().unbind_xdg_output(this_object_id);
+        Task::Destroy { id: this_object_id }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
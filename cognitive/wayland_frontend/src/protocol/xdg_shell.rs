@@ -0,0 +1,644 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! Implementations of Wayland `xdg_wm_base`, `xdg_positioner`, `xdg_surface`, `xdg_toplevel` and
+//! `xdg_popup` objects.
+
+use std::rc::Rc;
+
+use skylane::server::{Bundle, Object, ObjectId, Task};
+use wayland_protocols::server::Handler;
+use wayland_protocols::server::xdg_shell::xdg_wm_base;
+use wayland_protocols::server::xdg_shell::xdg_positioner;
+use wayland_protocols::server::xdg_shell::xdg_surface;
+use wayland_protocols::server::xdg_shell::xdg_toplevel;
+use wayland_protocols::server::xdg_shell::xdg_popup;
+
+use qualia::{resize_edge, show_reason, Area};
+
+use facade::{Facade, Placement, PopupOid, PositionerInfo, ShellSurfaceOid};
+use global::Global;
+use proxy::ProxyRef;
+
+// -------------------------------------------------------------------------------------------------
+
+/// Highest version of `xdg_wm_base` implemented here.
+const VERSION: u32 = 3;
+
+// -------------------------------------------------------------------------------------------------
+
+/// Wayland `xdg_wm_base` object.
+#[allow(dead_code)]
+struct XdgWmBase {
+    oid: ObjectId,
+    proxy: ProxyRef,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+pub fn get_global() -> Global {
+    Global::new(xdg_wm_base::NAME, VERSION, Rc::new(XdgWmBase::new_object))
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl XdgWmBase {
+    fn new(oid: ObjectId, proxy_ref: ProxyRef) -> Self {
+        XdgWmBase {
+            oid: oid,
+            proxy: proxy_ref,
+        }
+    }
+
+    fn new_object(oid: ObjectId, _version: u32, proxy_ref: ProxyRef) -> Box<Object> {
+        Box::new(Handler::<_, xdg_wm_base::Dispatcher>::new(Self::new(oid, proxy_ref)))
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+#[allow(unused_variables)]
+impl xdg_wm_base::Interface for XdgWmBase {
+    fn destroy(&mut self, this_object_id: ObjectId, bundle: &mut Bundle) -> Task {
+        Task::Destroy { id: this_object_id }
+    }
+
+    fn create_positioner(&mut self,
+                         this_object_id: ObjectId,
+                         bundle: &mut Bundle,
+                         new_positioner_oid: ObjectId)
+                         -> Task {
+        let positioner = XdgPositioner::new_object(new_positioner_oid, self.proxy.clone());
+        Task::Create {
+            id: new_positioner_oid,
+            object: positioner,
+        }
+    }
+
+    fn get_xdg_surface(&mut self,
+                       this_object_id: ObjectId,
+                       bundle: &mut Bundle,
+                       new_surface_oid: ObjectId,
+                       surface: ObjectId)
+                       -> Task {
+        let surface = XdgSurface::new_object(new_surface_oid, surface, self.proxy.clone());
+        Task::Create {
+            id: new_surface_oid,
+            object: surface,
+        }
+    }
+
+    fn pong(&mut self, this_object_id: ObjectId, bundle: &mut Bundle, serial: u32) -> Task {
+        Task::None
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Wayland `xdg_positioner` object.
+struct XdgPositioner {
+    proxy: ProxyRef,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl XdgPositioner {
+    fn new(oid: ObjectId, proxy_ref: ProxyRef) -> Self {
+        {
+            let mut proxy = proxy_ref.borrow_mut();
+            proxy.set_positioner(oid, PositionerInfo::new());
+        }
+        XdgPositioner { proxy: proxy_ref }
+    }
+
+    fn new_object(oid: ObjectId, proxy: ProxyRef) -> Box<Object> {
+        Box::new(Handler::<_, xdg_positioner::Dispatcher>::new(Self::new(oid, proxy)))
+    }
+
+    /// Converts value of `anchor` or `gravity` enum (they have the same values) to horizontal and
+    /// vertical placement.
+    fn edges_to_placement(edges: u32) -> (Placement, Placement) {
+        match edges {
+            xdg_positioner::anchor::TOP => (Placement::Center, Placement::Start),
+            xdg_positioner::anchor::BOTTOM => (Placement::Center, Placement::End),
+            xdg_positioner::anchor::LEFT => (Placement::Start, Placement::Center),
+            xdg_positioner::anchor::RIGHT => (Placement::End, Placement::Center),
+            xdg_positioner::anchor::TOP_LEFT => (Placement::Start, Placement::Start),
+            xdg_positioner::anchor::BOTTOM_LEFT => (Placement::Start, Placement::End),
+            xdg_positioner::anchor::TOP_RIGHT => (Placement::End, Placement::Start),
+            xdg_positioner::anchor::BOTTOM_RIGHT => (Placement::End, Placement::End),
+            _ => (Placement::Center, Placement::Center),
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+#[allow(unused_variables)]
+impl xdg_positioner::Interface for XdgPositioner {
+    fn destroy(&mut self, this_object_id: ObjectId, bundle: &mut Bundle) -> Task {
+        let mut proxy = self.proxy.borrow_mut();
+        proxy.remove_positioner(this_object_id);
+        Task::Destroy { id: this_object_id }
+    }
+
+    fn set_size(&mut self,
+                this_object_id: ObjectId,
+                bundle: &mut Bundle,
+                width: i32,
+                height: i32)
+                -> Task {
+        let mut proxy = self.proxy.borrow_mut();
+        if let Some(mut positioner) = proxy.get_positioner(this_object_id) {
+            positioner.size.width = width as usize;
+            positioner.size.height = height as usize;
+            proxy.set_positioner(this_object_id, positioner);
+        }
+        Task::None
+    }
+
+    fn set_anchor_rect(&mut self,
+                       this_object_id: ObjectId,
+                       bundle: &mut Bundle,
+                       x: i32,
+                       y: i32,
+                       width: i32,
+                       height: i32)
+                       -> Task {
+        let mut proxy = self.proxy.borrow_mut();
+        if let Some(mut positioner) = proxy.get_positioner(this_object_id) {
+            positioner.anchor.pos.x = x as isize;
+            positioner.anchor.pos.y = y as isize;
+            positioner.anchor.size.width = width as usize;
+            positioner.anchor.size.height = height as usize;
+            proxy.set_positioner(this_object_id, positioner);
+        }
+        Task::None
+    }
+
+    fn set_anchor(&mut self, this_object_id: ObjectId, bundle: &mut Bundle, anchor: u32) -> Task {
+        let mut proxy = self.proxy.borrow_mut();
+        if let Some(mut positioner) = proxy.get_positioner(this_object_id) {
+            positioner.anchor_placement = Self::edges_to_placement(anchor);
+            proxy.set_positioner(this_object_id, positioner);
+        }
+        Task::None
+    }
+
+    fn set_gravity(&mut self, this_object_id: ObjectId, bundle: &mut Bundle, gravity: u32) -> Task {
+        let mut proxy = self.proxy.borrow_mut();
+        if let Some(mut positioner) = proxy.get_positioner(this_object_id) {
+            positioner.gravity = Self::edges_to_placement(gravity);
+            proxy.set_positioner(this_object_id, positioner);
+        }
+        Task::None
+    }
+
+    fn set_constraint_adjustment(&mut self,
+                                 this_object_id: ObjectId,
+                                 bundle: &mut Bundle,
+                                 constraint_adjustment: u32)
+                                 -> Task {
+        let mut proxy = self.proxy.borrow_mut();
+        if let Some(mut positioner) = proxy.get_positioner(this_object_id) {
+            positioner.constraint_adjustment = constraint_adjustment;
+            proxy.set_positioner(this_object_id, positioner);
+        }
+        Task::None
+    }
+
+    fn set_offset(&mut self,
+                  this_object_id: ObjectId,
+                  _bundle: &mut Bundle,
+                  x: i32,
+                  y: i32)
+                  -> Task {
+        let mut proxy = self.proxy.borrow_mut();
+        if let Some(mut positioner) = proxy.get_positioner(this_object_id) {
+            positioner.offset.x = x as isize;
+            positioner.offset.y = y as isize;
+            proxy.set_positioner(this_object_id, positioner);
+        }
+        Task::None
+    }
+
+    fn set_reactive(&mut self, this_object_id: ObjectId, bundle: &mut Bundle) -> Task {
+        Task::None
+    }
+
+    fn set_parent_size(&mut self,
+                       this_object_id: ObjectId,
+                       bundle: &mut Bundle,
+                       parent_width: i32,
+                       parent_height: i32)
+                       -> Task {
+        Task::None
+    }
+
+    fn set_parent_configure(&mut self,
+                            this_object_id: ObjectId,
+                            bundle: &mut Bundle,
+                            serial: u32)
+                            -> Task {
+        Task::None
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Wayland `xdg_surface` object.
+struct XdgSurface {
+    oid: ObjectId,
+    surface_oid: ObjectId,
+    proxy: ProxyRef,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl XdgSurface {
+    fn new(oid: ObjectId, surface_oid: ObjectId, proxy_ref: ProxyRef) -> Self {
+        XdgSurface {
+            oid: oid,
+            surface_oid: surface_oid,
+            proxy: proxy_ref,
+        }
+    }
+
+    fn new_object(oid: ObjectId, surface_oid: ObjectId, proxy: ProxyRef) -> Box<Object> {
+        Box::new(Handler::<_, xdg_surface::Dispatcher>::new(Self::new(oid, surface_oid, proxy)))
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+#[allow(unused_variables)]
+impl xdg_surface::Interface for XdgSurface {
+    fn destroy(&mut self, this_object_id: ObjectId, bundle: &mut Bundle) -> Task {
+        let mut proxy = self.proxy.borrow_mut();
+        proxy.hide(self.surface_oid, show_reason::IN_SHELL);
+        Task::Destroy { id: this_object_id }
+    }
+
+    fn get_toplevel(&mut self,
+                    this_object_id: ObjectId,
+                    bundle: &mut Bundle,
+                    new_toplevel_id: ObjectId)
+                    -> Task {
+        let toplevel = XdgToplevel::new_object(new_toplevel_id,
+                                               self.surface_oid,
+                                               self.oid,
+                                               self.proxy.clone());
+        Task::Create {
+            id: new_toplevel_id,
+            object: toplevel,
+        }
+    }
+
+    fn get_popup(&mut self,
+                 this_object_id: ObjectId,
+                 bundle: &mut Bundle,
+                 new_popup_oid: ObjectId,
+                 parent_shell_surface_oid: ObjectId,
+                 positioner_oid: ObjectId)
+                 -> Task {
+        let area = {
+            let mut proxy = self.proxy.borrow_mut();
+            if let Some(positioner) = proxy.get_positioner(positioner_oid) {
+                let bounds = proxy.get_surface_oid_for_shell(parent_shell_surface_oid)
+                    .and_then(|parent_surface_oid| proxy.get_popup_bounds(parent_surface_oid));
+                if let Some(bounds) = bounds {
+                    positioner.get_constrained_area(bounds)
+                } else {
+                    positioner.get_area()
+                }
+            } else {
+                Area::default()
+            }
+        };
+
+        let popup = XdgPopup::new_object(new_popup_oid,
+                                         self.surface_oid,
+                                         this_object_id,
+                                         parent_shell_surface_oid,
+                                         area,
+                                         self.proxy.clone());
+
+        // Clients do not map pop-ups without configuring them.
+        let serial = bundle.get_socket().get_next_serial();
+        send!(xdg_popup::configure(&bundle.get_socket(),
+                                   new_popup_oid,
+                                   area.pos.x as i32,
+                                   area.pos.y as i32,
+                                   area.size.width as i32,
+                                   area.size.height as i32));
+        send!(xdg_surface::configure(&bundle.get_socket(), this_object_id, serial));
+
+        Task::Create {
+            id: new_popup_oid,
+            object: popup,
+        }
+    }
+
+    fn set_window_geometry(&mut self,
+                           this_object_id: ObjectId,
+                           bundle: &mut Bundle,
+                           x: i32,
+                           y: i32,
+                           width: i32,
+                           height: i32)
+                           -> Task {
+        if width > 0 && height > 0 {
+            let area = Area::create(x as isize, y as isize, width as usize, height as usize);
+            self.proxy.borrow().set_window_geometry(self.surface_oid, area);
+        } else {
+            log_wayl3!("Received window geometry with non-positive width or height");
+        }
+        Task::None
+    }
+
+    fn ack_configure(&mut self,
+                     this_object_id: ObjectId,
+                     bundle: &mut Bundle,
+                     serial: u32)
+                     -> Task {
+        Task::None
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Wayland `xdg_toplevel` object.
+struct XdgToplevel {
+    surface_oid: ObjectId,
+    proxy: ProxyRef,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl XdgToplevel {
+    fn new(oid: ObjectId,
+           surface_oid: ObjectId,
+           shell_surface_oid: ObjectId,
+           proxy_ref: ProxyRef)
+           -> Self {
+        {
+            let mut proxy = proxy_ref.borrow_mut();
+            proxy.show(surface_oid,
+                       ShellSurfaceOid::XdgToplevel(shell_surface_oid, oid),
+                       show_reason::IN_SHELL);
+        }
+
+        XdgToplevel {
+            surface_oid: surface_oid,
+            proxy: proxy_ref,
+        }
+    }
+
+    fn new_object(oid: ObjectId,
+                  surface_oid: ObjectId,
+                  shell_surface_oid: ObjectId,
+                  proxy_ref: ProxyRef)
+                  -> Box<Object> {
+        let toplevel = Self::new(oid, surface_oid, shell_surface_oid, proxy_ref);
+        Box::new(Handler::<_, xdg_toplevel::Dispatcher>::new(toplevel))
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+#[allow(unused_variables)]
+impl xdg_toplevel::Interface for XdgToplevel {
+    fn destroy(&mut self, this_object_id: ObjectId, bundle: &mut Bundle) -> Task {
+        Task::Destroy { id: this_object_id }
+    }
+
+    fn set_parent(&mut self,
+                  this_object_id: ObjectId,
+                  bundle: &mut Bundle,
+                  parent: ObjectId)
+                  -> Task {
+        Task::None
+    }
+
+    fn set_title(&mut self, this_object_id: ObjectId, bundle: &mut Bundle, title: String) -> Task {
+        self.proxy.borrow().set_title(self.surface_oid, title);
+        Task::None
+    }
+
+    fn set_app_id(&mut self,
+                  this_object_id: ObjectId,
+                  bundle: &mut Bundle,
+                  app_id: String)
+                  -> Task {
+        self.proxy.borrow().set_app_id(self.surface_oid, app_id);
+        Task::None
+    }
+
+    fn show_window_menu(&mut self,
+                        this_object_id: ObjectId,
+                        bundle: &mut Bundle,
+                        seat: ObjectId,
+                        serial: u32,
+                        x: i32,
+                        y: i32)
+                        -> Task {
+        Task::None
+    }
+
+    fn move_(&mut self,
+             this_object_id: ObjectId,
+             bundle: &mut Bundle,
+             seat: ObjectId,
+             serial: u32)
+             -> Task {
+        self.proxy.borrow().request_move(self.surface_oid, serial);
+        Task::None
+    }
+
+    fn resize(&mut self,
+              this_object_id: ObjectId,
+              bundle: &mut Bundle,
+              seat: ObjectId,
+              serial: u32,
+              edges: u32)
+              -> Task {
+        let edges = resize_edge::ResizeEdge::from_bits_truncate(edges);
+        self.proxy.borrow().request_resize(self.surface_oid, serial, edges);
+        Task::None
+    }
+
+    fn set_max_size(&mut self,
+                    this_object_id: ObjectId,
+                    bundle: &mut Bundle,
+                    width: i32,
+                    height: i32)
+                    -> Task {
+        Task::None
+    }
+
+    fn set_min_size(&mut self,
+                    this_object_id: ObjectId,
+                    bundle: &mut Bundle,
+                    width: i32,
+                    height: i32)
+                    -> Task {
+        Task::None
+    }
+
+    fn set_maximized(&mut self, this_object_id: ObjectId, bundle: &mut Bundle) -> Task {
+        self.proxy.borrow().set_maximized(self.surface_oid, true);
+        Task::None
+    }
+
+    fn unset_maximized(&mut self, this_object_id: ObjectId, bundle: &mut Bundle) -> Task {
+        self.proxy.borrow().set_maximized(self.surface_oid, false);
+        Task::None
+    }
+
+    fn set_fullscreen(&mut self,
+                      this_object_id: ObjectId,
+                      bundle: &mut Bundle,
+                      output: ObjectId)
+                      -> Task {
+        let output = if output.is_null() { None } else { Some(output) };
+        self.proxy.borrow().set_fullscreen(self.surface_oid, true, output);
+        Task::None
+    }
+
+    fn unset_fullscreen(&mut self, this_object_id: ObjectId, bundle: &mut Bundle) -> Task {
+        self.proxy.borrow().set_fullscreen(self.surface_oid, false, None);
+        Task::None
+    }
+
+    fn set_minimized(&mut self, this_object_id: ObjectId, bundle: &mut Bundle) -> Task {
+        self.proxy.borrow().set_minimized(self.surface_oid);
+        Task::None
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Wayland `xdg_popup` object.
+struct XdgPopup {
+    surface_oid: ObjectId,
+    parent_surface_oid: Option<ObjectId>,
+    shell_surface_oid: ObjectId,
+    proxy: ProxyRef,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl XdgPopup {
+    fn new(oid: ObjectId,
+           surface_oid: ObjectId,
+           shell_surface_oid: ObjectId,
+           parent_shell_surface_oid: ObjectId,
+           area: Area,
+           proxy_ref: ProxyRef)
+           -> Self {
+        let parent_surface_oid = {
+            let mut proxy = proxy_ref.borrow_mut();
+            let parent_surface_oid = proxy.get_surface_oid_for_shell(parent_shell_surface_oid);
+            if let Some(parent_surface_oid) = parent_surface_oid {
+                proxy.relate(surface_oid, parent_surface_oid);
+                proxy.set_relative_position(surface_oid, area.pos.x, area.pos.y);
+            } else {
+                // Parent may be assigned later by other protocol (e.g. layer shell).
+                proxy.add_parentless_popup(oid, surface_oid, area.pos);
+            }
+            parent_surface_oid
+        };
+
+        XdgPopup {
+            surface_oid: surface_oid,
+            parent_surface_oid: parent_surface_oid,
+            shell_surface_oid: shell_surface_oid,
+            proxy: proxy_ref,
+        }
+    }
+
+    fn new_object(oid: ObjectId,
+                  surface_oid: ObjectId,
+                  shell_surface_oid: ObjectId,
+                  parent_shell_surface_oid: ObjectId,
+                  area: Area,
+                  proxy_ref: ProxyRef)
+                  -> Box<Object> {
+        let popup = Self::new(oid,
+                              surface_oid,
+                              shell_surface_oid,
+                              parent_shell_surface_oid,
+                              area,
+                              proxy_ref);
+        Box::new(Handler::<_, xdg_popup::Dispatcher>::new(popup))
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+#[allow(unused_variables)]
+impl xdg_popup::Interface for XdgPopup {
+    fn destroy(&mut self, this_object_id: ObjectId, bundle: &mut Bundle) -> Task {
+        let mut proxy = self.proxy.borrow_mut();
+        proxy.ungrab_popup(self.surface_oid);
+        proxy.remove_parentless_popup(this_object_id);
+        proxy.unrelate(self.surface_oid);
+        Task::Destroy { id: this_object_id }
+    }
+
+    fn grab(&mut self,
+            this_object_id: ObjectId,
+            bundle: &mut Bundle,
+            seat: ObjectId,
+            serial: u32)
+            -> Task {
+        let parent_surface_oid = self.parent_surface_oid
+            .or_else(|| self.proxy.borrow().get_parent_surface_oid(self.surface_oid));
+        if let Some(parent_surface_oid) = parent_surface_oid {
+            let popup_oid = PopupOid::XdgPopup(this_object_id);
+            self.proxy.borrow_mut().grab_popup(self.surface_oid, parent_surface_oid, popup_oid);
+        } else {
+            // Pop-up without known parent can not be a part of grab chain.
+            send!(xdg_popup::popup_done(&bundle.get_socket(), this_object_id));
+        }
+        Task::None
+    }
+
+    fn reposition(&mut self,
+                  this_object_id: ObjectId,
+                  bundle: &mut Bundle,
+                  positioner_oid: ObjectId,
+                  token: u32)
+                  -> Task {
+        let area = {
+            let mut proxy = self.proxy.borrow_mut();
+            if let Some(positioner) = proxy.get_positioner(positioner_oid) {
+                let bounds = self.parent_surface_oid
+                    .or_else(|| proxy.get_parent_surface_oid(self.surface_oid))
+                    .and_then(|parent_surface_oid| proxy.get_popup_bounds(parent_surface_oid));
+                let area = if let Some(bounds) = bounds {
+                    positioner.get_constrained_area(bounds)
+                } else {
+                    positioner.get_area()
+                };
+                proxy.set_relative_position(self.surface_oid, area.pos.x, area.pos.y);
+                area
+            } else {
+                return Task::None;
+            }
+        };
+
+        let serial = bundle.get_socket().get_next_serial();
+        send!(xdg_popup::repositioned(&bundle.get_socket(), this_object_id, token));
+        send!(xdg_popup::configure(&bundle.get_socket(),
+                                   this_object_id,
+                                   area.pos.x as i32,
+                                   area.pos.y as i32,
+                                   area.size.width as i32,
+                                   area.size.height as i32));
+        send!(xdg_surface::configure(&bundle.get_socket(), self.shell_surface_oid, serial));
+        Task::None
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
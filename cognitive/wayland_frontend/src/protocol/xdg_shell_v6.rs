@@ -16,9 +16,9 @@ use skylane_protocols::server::xdg_shell_unstable_v6::zxdg_surface_v6;
 use skylane_protocols::server::xdg_shell_unstable_v6::zxdg_toplevel_v6;
 use skylane_protocols::server::xdg_shell_unstable_v6::zxdg_popup_v6;
 
-use qualia::{show_reason, Area};
+use qualia::{resize_edge, show_reason, Area};
 
-use facade::{Facade, PositionerInfo, ShellSurfaceOid};
+use facade::{Facade, Placement, PopupOid, PositionerInfo, ShellSurfaceOid};
 use global::Global;
 use proxy::ProxyRef;
 
@@ -111,6 +111,26 @@ impl ZxdgPositionerV6 {
     fn new_object(oid: ObjectId, proxy: ProxyRef) -> Box<Object> {
         Box::new(Handler::<_, zxdg_positioner_v6::Dispatcher>::new(Self::new(oid, proxy)))
     }
+
+    /// Converts bit field of edges (the same for anchor and gravity) to horizontal and vertical
+    /// placement.
+    fn edges_to_placement(edges: u32) -> (Placement, Placement) {
+        let horizontal = if edges & zxdg_positioner_v6::anchor::LEFT != 0 {
+            Placement::Start
+        } else if edges & zxdg_positioner_v6::anchor::RIGHT != 0 {
+            Placement::End
+        } else {
+            Placement::Center
+        };
+        let vertical = if edges & zxdg_positioner_v6::anchor::TOP != 0 {
+            Placement::Start
+        } else if edges & zxdg_positioner_v6::anchor::BOTTOM != 0 {
+            Placement::End
+        } else {
+            Placement::Center
+        };
+        (horizontal, vertical)
+    }
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -158,10 +178,20 @@ impl zxdg_positioner_v6::Interface for ZxdgPositionerV6 {
     }
 
     fn set_anchor(&mut self, this_object_id: ObjectId, bundle: &mut Bundle, anchor: u32) -> Task {
+        let mut proxy = self.proxy.borrow_mut();
+        if let Some(mut positioner) = proxy.get_positioner(this_object_id) {
+            positioner.anchor_placement = Self::edges_to_placement(anchor);
+            proxy.set_positioner(this_object_id, positioner);
+        }
         Task::None
     }
 
     fn set_gravity(&mut self, this_object_id: ObjectId, bundle: &mut Bundle, gravity: u32) -> Task {
+        let mut proxy = self.proxy.borrow_mut();
+        if let Some(mut positioner) = proxy.get_positioner(this_object_id) {
+            positioner.gravity = Self::edges_to_placement(gravity);
+            proxy.set_positioner(this_object_id, positioner);
+        }
         Task::None
     }
 
@@ -284,6 +314,12 @@ impl zxdg_surface_v6::Interface for ZxdgSurfaceV6 {
                            width: i32,
                            height: i32)
                            -> Task {
+        if width > 0 && height > 0 {
+            let area = Area::create(x as isize, y as isize, width as usize, height as usize);
+            self.proxy.borrow().set_window_geometry(self.surface_oid, area);
+        } else {
+            log_wayl3!("Received window geometry with non-positive width or height");
+        }
         Task::None
     }
 
@@ -299,7 +335,10 @@ impl zxdg_surface_v6::Interface for ZxdgSurfaceV6 {
 // -------------------------------------------------------------------------------------------------
 
 /// Wayland `zxdg_toplevel_v6` object.
-struct ZxdgToplevelV6 {}
+struct ZxdgToplevelV6 {
+    surface_oid: ObjectId,
+    proxy: ProxyRef,
+}
 
 // -------------------------------------------------------------------------------------------------
 
@@ -316,7 +355,10 @@ impl ZxdgToplevelV6 {
                        show_reason::IN_SHELL);
         }
 
-        ZxdgToplevelV6 {}
+        ZxdgToplevelV6 {
+            surface_oid: surface_oid,
+            proxy: proxy_ref,
+        }
     }
 
     fn new_object(oid: ObjectId,
@@ -346,6 +388,7 @@ impl zxdg_toplevel_v6::Interface for ZxdgToplevelV6 {
     }
 
     fn set_title(&mut self, this_object_id: ObjectId, bundle: &mut Bundle, title: String) -> Task {
+        self.proxy.borrow().set_title(self.surface_oid, title);
         Task::None
     }
 
@@ -354,6 +397,7 @@ impl zxdg_toplevel_v6::Interface for ZxdgToplevelV6 {
                   bundle: &mut Bundle,
                   app_id: String)
                   -> Task {
+        self.proxy.borrow().set_app_id(self.surface_oid, app_id);
         Task::None
     }
 
@@ -374,6 +418,7 @@ impl zxdg_toplevel_v6::Interface for ZxdgToplevelV6 {
              seat: ObjectId,
              serial: u32)
              -> Task {
+        self.proxy.borrow().request_move(self.surface_oid, serial);
         Task::None
     }
 
@@ -384,6 +429,8 @@ impl zxdg_toplevel_v6::Interface for ZxdgToplevelV6 {
               serial: u32,
               edges: u32)
               -> Task {
+        let edges = resize_edge::ResizeEdge::from_bits_truncate(edges);
+        self.proxy.borrow().request_resize(self.surface_oid, serial, edges);
         Task::None
     }
 
@@ -406,10 +453,12 @@ impl zxdg_toplevel_v6::Interface for ZxdgToplevelV6 {
     }
 
     fn set_maximized(&mut self, this_object_id: ObjectId, bundle: &mut Bundle) -> Task {
+        self.proxy.borrow().set_maximized(self.surface_oid, true);
         Task::None
     }
 
     fn unset_maximized(&mut self, this_object_id: ObjectId, bundle: &mut Bundle) -> Task {
+        self.proxy.borrow().set_maximized(self.surface_oid, false);
         Task::None
     }
 
@@ -418,14 +467,18 @@ impl zxdg_toplevel_v6::Interface for ZxdgToplevelV6 {
                       bundle: &mut Bundle,
                       output: ObjectId)
                       -> Task {
+        let output = if output.is_null() { None } else { Some(output) };
+        self.proxy.borrow().set_fullscreen(self.surface_oid, true, output);
         Task::None
     }
 
     fn unset_fullscreen(&mut self, this_object_id: ObjectId, bundle: &mut Bundle) -> Task {
+        self.proxy.borrow().set_fullscreen(self.surface_oid, false, None);
         Task::None
     }
 
     fn set_minimized(&mut self, this_object_id: ObjectId, bundle: &mut Bundle) -> Task {
+        self.proxy.borrow().set_minimized(self.surface_oid);
         Task::None
     }
 }
@@ -435,6 +488,7 @@ impl zxdg_toplevel_v6::Interface for ZxdgToplevelV6 {
 /// Wayland `zxdg_popup_v6` object.
 struct ZxdgPopupV6 {
     surface_oid: ObjectId,
+    parent_surface_oid: Option<ObjectId>,
     proxy: ProxyRef,
 }
 
@@ -446,17 +500,19 @@ impl ZxdgPopupV6 {
            area: Area,
            proxy_ref: ProxyRef)
            -> Self {
-        {
+        let parent_surface_oid = {
             let proxy = proxy_ref.borrow();
             let parent_surface_oid = proxy.get_surface_oid_for_shell(parent_shell_surface_oid);
             if let Some(parent_surface_oid) = parent_surface_oid {
                 proxy.relate(surface_oid, parent_surface_oid);
                 proxy.set_relative_position(surface_oid, area.pos.x, area.pos.y);
             }
-        }
+            parent_surface_oid
+        };
 
         ZxdgPopupV6 {
             surface_oid: surface_oid,
+            parent_surface_oid: parent_surface_oid,
             proxy: proxy_ref,
         }
     }
@@ -476,7 +532,8 @@ impl ZxdgPopupV6 {
 #[allow(unused_variables)]
 impl zxdg_popup_v6::Interface for ZxdgPopupV6 {
     fn destroy(&mut self, this_object_id: ObjectId, bundle: &mut Bundle) -> Task {
-        let proxy = self.proxy.borrow();
+        let mut proxy = self.proxy.borrow_mut();
+        proxy.ungrab_popup(self.surface_oid);
         proxy.unrelate(self.surface_oid);
         Task::Destroy { id: this_object_id }
     }
@@ -487,6 +544,13 @@ impl zxdg_popup_v6::Interface for ZxdgPopupV6 {
             seat: ObjectId,
             serial: u32)
             -> Task {
+        if let Some(parent_surface_oid) = self.parent_surface_oid {
+            let popup_oid = PopupOid::ZxdgPopupV6(this_object_id);
+            self.proxy.borrow_mut().grab_popup(self.surface_oid, parent_surface_oid, popup_oid);
+        } else {
+            // Pop-up without known parent can not be a part of grab chain.
+            send!(zxdg_popup_v6::popup_done(&bundle.get_socket(), this_object_id));
+        }
         Task::None
     }
 }
@@ -9,30 +9,51 @@ use std;
 use std::collections::{BTreeMap, HashMap, HashSet};
 use std::path::PathBuf;
 use std::os::unix::io::RawFd;
+use std::time::{Duration, Instant};
 
 use dharma;
 use skylane::server as wl;
 use skylane_protocols::server::wayland::{wl_display, wl_callback, wl_buffer};
-use skylane_protocols::server::wayland::{wl_keyboard, wl_pointer};
+use skylane_protocols::server::wayland::{wl_keyboard, wl_pointer, wl_touch};
 use skylane_protocols::server::wayland::{wl_data_source, wl_data_device, wl_data_offer};
 use skylane_protocols::server::wayland::wl_shell_surface;
 use skylane_protocols::server::xdg_shell_unstable_v6::{zxdg_toplevel_v6, zxdg_surface_v6};
+use skylane_protocols::server::xdg_shell_unstable_v6::zxdg_popup_v6;
 use skylane_protocols::server::weston_screenshooter::weston_screenshooter;
+use wayland_protocols::server::xdg_shell::{xdg_toplevel, xdg_surface, xdg_popup};
+use wayland_protocols::server::primary_selection::zwp_primary_selection_device_v1;
+use wayland_protocols::server::primary_selection::zwp_primary_selection_source_v1;
+use wayland_protocols::server::primary_selection::zwp_primary_selection_offer_v1;
+use wayland_protocols::server::presentation_time::wp_presentation_feedback;
+use wayland_protocols::server::viewporter::wp_viewport;
+use wayland_protocols::server::wlr_layer_shell_unstable_v1::zwlr_layer_surface_v1;
+use wayland_protocols::server::relative_pointer_unstable_v1::zwp_relative_pointer_v1;
+use wayland_protocols::server::pointer_constraints_unstable_v1::{zwp_confined_pointer_v1,
+                                                                 zwp_locked_pointer_v1};
+use wayland_protocols::server::ext_idle_notify_v1::ext_idle_notification_v1;
+use wayland_protocols::server::text_input_unstable_v3::zwp_text_input_v3;
+use wayland_protocols::server::input_method_unstable_v2::{zwp_input_method_keyboard_grab_v2,
+                                                           zwp_input_method_v2,
+                                                           zwp_input_popup_surface_v2};
 
 use cognitive_graphics::attributes::{EglAttributes, DmabufAttributes};
-use qualia::{Settings, Transfer, DrmBundle, Memory};
-use qualia::{Area, Axis, Button, Key, Milliseconds};
-use qualia::{OutputInfo, PixelFormat, Position, Size, Vector};
+use qualia::{KeyboardConfig, Settings, Transfer, DrmBundle, Memory};
+use qualia::{Area, Axis, Button, Key, LayerProperties, Milliseconds, PresentationTime};
+use qualia::{OutputInfo, PixelFormat, Position, Size, Transform, Vector, ViewportSource};
 use qualia::{DmabufId, EglImageId, MemoryPoolId, MemoryViewId};
-use qualia::{show_reason, surface_state, SurfaceId};
-use qualia::FrontendsCoordinationTrait;
+use qualia::{dnd_action, show_reason, resize_edge, surface_state, SurfaceId};
+use qualia::{FrontendsCoordinationTrait, PointerConstraint, PointerConstraintKind};
 use inputs::KeyMods;
 
 use protocol;
-use facade::{Facade, PositionerInfo, ShellSurfaceOid};
+use protocol::primary_selection_device_manager::PrimarySelectionOffer;
+use facade::{Facade, PointerConstraintOid, PopupOid, PositionerInfo, ShellSurfaceOid};
+use facade::{InputMethodState, TextInputState};
 use gateway::Gateway;
 use global::Global;
-use mediator::MediatorRef;
+use mediator::{ActiveTextInput, Drag, DragSource, InputMethod, MediatorRef};
+use popup_grabs::{PopupGrab, PopupGrabChain};
+use mediator::VirtualKeyboardEvent;
 
 // -------------------------------------------------------------------------------------------------
 
@@ -84,6 +105,18 @@ struct SurfaceInfo {
 
     // For send reconfiguration events in `on_surface_reconfigured`
     shell_surface_oid: Option<ShellSurfaceOid>,
+
+    // Presentation feedbacks requested since last commit
+    pending_feedback_oids: Vec<wl::ObjectId>,
+
+    // Presentation feedbacks for committed contents waiting to be drawn
+    committed_feedback_oids: Vec<wl::ObjectId>,
+
+    // For sending presentation feedback in `on_surface_presented`
+    drawn_feedback_oids: Vec<wl::ObjectId>,
+
+    // Idle inhibitors of the surface are active only if it was drawn in the last frame
+    is_drawn: bool,
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -95,6 +128,10 @@ impl SurfaceInfo {
             shell_surface_oid: None,
             buffer_oid: None,
             frame_oid: None,
+            pending_feedback_oids: Vec::new(),
+            committed_feedback_oids: Vec::new(),
+            drawn_feedback_oids: Vec::new(),
+            is_drawn: false,
         }
     }
 }
@@ -133,6 +170,104 @@ impl BufferInfo {
 
 // -------------------------------------------------------------------------------------------------
 
+/// Helper structure for aggregating information about data offered to this client by
+/// drag-and-drop.
+struct DragOffer {
+    /// Data offers created for every data device of the client together with their versions.
+    data_offer_oids: HashMap<wl::ObjectId, u32>,
+
+    /// Source of the data.
+    source: Option<DragSource>,
+
+    /// Mime type accepted by the client.
+    mime_type: Option<String>,
+
+    /// Action negotiated between source and the client.
+    dnd_action: dnd_action::DndAction,
+
+    /// Tells if the data was dropped and client is now expected to transfer and finish.
+    is_dropped: bool,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl DragOffer {
+    pub fn new(source: Option<DragSource>) -> Self {
+        DragOffer {
+            data_offer_oids: HashMap::new(),
+            source: source,
+            mime_type: None,
+            dnd_action: dnd_action::NONE,
+            is_dropped: false,
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Helper structure for remembering pointer button held by user.
+#[derive(Clone, Copy, Debug)]
+struct ButtonPress {
+    code: u16,
+    serial: u32,
+    sid: SurfaceId,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Helper structure for aggregating information about pointer constraint requested by client.
+#[derive(Clone, Copy, Debug)]
+struct PointerConstraintInfo {
+    constraint_oid: PointerConstraintOid,
+    is_persistent: bool,
+    is_active: bool,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl PointerConstraintInfo {
+    pub fn new(constraint_oid: PointerConstraintOid, is_persistent: bool) -> Self {
+        PointerConstraintInfo {
+            constraint_oid: constraint_oid,
+            is_persistent: is_persistent,
+            is_active: false,
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Helper structure for aggregating information about idle notification requested by client.
+#[derive(Clone, Copy, Debug)]
+struct IdleNotificationInfo {
+    timeout: Duration,
+    respects_inhibitors: bool,
+    is_idle: bool,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl IdleNotificationInfo {
+    pub fn new(timeout: Duration, respects_inhibitors: bool) -> Self {
+        IdleNotificationInfo {
+            timeout: timeout,
+            respects_inhibitors: respects_inhibitors,
+            is_idle: false,
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Helper structure for aggregating information about bound `zxdg_output_v1`.
+#[derive(Clone, Copy, Debug)]
+struct XdgOutputInfo {
+    output_oid: wl::ObjectId,
+    version: u32,
+}
+
+// -------------------------------------------------------------------------------------------------
+
 /// `Proxy` holds information common between handlers of one client. It constitutes for them facade
 /// for rest of the crate/application and gateway from `Engine` to clients.
 ///
@@ -143,6 +278,7 @@ pub struct Proxy {
     client_id: dharma::EventHandlerId,
     coordinator: Box<FrontendsCoordinationTrait>,
     settings: Settings,
+    keyboard_config: KeyboardConfig,
 
     mediator: MediatorRef,
     socket: wl::Socket,
@@ -153,21 +289,64 @@ pub struct Proxy {
     /// globals in wrong order may crash clients
     globals: BTreeMap<u32, Global>,
 
-    regions: HashMap<wl::ObjectId, Area>,
+    regions: HashMap<wl::ObjectId, Vec<Area>>,
     positioners: HashMap<wl::ObjectId, PositionerInfo>,
     transfers: HashMap<wl::ObjectId, Transfer>,
     pointer_oids: HashSet<wl::ObjectId>,
+    button_press: Option<ButtonPress>,
+    relative_pointer_oids: HashSet<wl::ObjectId>,
     keyboard_oids: HashSet<wl::ObjectId>,
-    data_device_oids: HashSet<wl::ObjectId>,
+    touch_oids: HashSet<wl::ObjectId>,
+    is_touch_frame_pending: bool,
+    data_device_oids: HashMap<wl::ObjectId, u32>,
+    data_source_oid_to_version: HashMap<wl::ObjectId, u32>,
     data_source_oid: Option<wl::ObjectId>,
+    primary_transfers: HashMap<wl::ObjectId, Transfer>,
+    primary_device_oids: HashSet<wl::ObjectId>,
+    primary_source_oid: Option<wl::ObjectId>,
     memory_pools: HashSet<MemoryPoolId>,
     surface_oid_to_sid_dict: HashMap<wl::ObjectId, SurfaceId>,
     sid_to_surface_info_dict: HashMap<SurfaceId, SurfaceInfo>,
 
+    /// Surfaces having `wp_viewport` object assigned.
+    viewports: HashMap<SurfaceId, wl::ObjectId>,
+
+    /// Properties of surfaces placed in layers together with IDs of requested displays.
+    layer_surfaces: HashMap<SurfaceId, (LayerProperties, Option<i32>)>,
+
+    /// Pointer constraints requested for surfaces. There may be at most one per surface.
+    pointer_constraints: HashMap<SurfaceId, PointerConstraintInfo>,
+
+    /// Surfaces of idle inhibitors.
+    idle_inhibitors: HashMap<wl::ObjectId, SurfaceId>,
+
+    /// Drawn surfaces with idle inhibitors. Only these surfaces inhibit idleness.
+    inhibiting_sids: HashSet<SurfaceId>,
+
+    /// Requested notifications about user being idle.
+    idle_notifications: HashMap<wl::ObjectId, IdleNotificationInfo>,
+
+    /// Text inputs together with number of their commits.
+    text_inputs: HashMap<wl::ObjectId, u32>,
+
+    /// Chain of pop-ups holding explicit grab. Every pop-up is a child of the previous one and the
+    /// last one is the topmost.
+    popup_grabs: PopupGrabChain,
+
+    /// Surfaces and positions of `xdg_popup`s created without parent. Layer surfaces may become
+    /// their parents.
+    parentless_popups: HashMap<wl::ObjectId, (wl::ObjectId, Position)>,
+
+    /// Data offered to this client by ongoing or just dropped drag-and-drop.
+    drag_offer: Option<DragOffer>,
+
     /// Surface object does not know if its buffer is memory shared or hardware image. It can only
     /// tell its buffer object ID, so we map this ID to structure defining how it can be attached.
     buffer_oid_to_info_dict: HashMap<wl::ObjectId, BufferInfo>,
     output_oid_to_id: HashMap<wl::ObjectId, i32>,
+    output_oid_to_version: HashMap<wl::ObjectId, u32>,
+    output_infos: HashMap<i32, OutputInfo>,
+    xdg_outputs: HashMap<wl::ObjectId, XdgOutputInfo>,
     screenshooter_oid: Option<wl::ObjectId>,
     screenshot_memory: Option<Memory>,
     last_global_id: u32,
@@ -182,6 +361,7 @@ impl Proxy {
     pub fn new(client_id: dharma::EventHandlerId,
                coordinator: Box<FrontendsCoordinationTrait>,
                settings: Settings,
+               keyboard_config: KeyboardConfig,
                mediator: MediatorRef,
                socket: wl::Socket)
                -> Self {
@@ -190,20 +370,42 @@ impl Proxy {
             coordinator: coordinator,
             mediator: mediator,
             settings: settings,
+            keyboard_config: keyboard_config,
             socket: socket,
             globals: BTreeMap::new(),
             regions: HashMap::new(),
             positioners: HashMap::new(),
             transfers: HashMap::new(),
             pointer_oids: HashSet::new(),
+            button_press: None,
+            relative_pointer_oids: HashSet::new(),
             keyboard_oids: HashSet::new(),
-            data_device_oids: HashSet::new(),
+            touch_oids: HashSet::new(),
+            is_touch_frame_pending: false,
+            data_device_oids: HashMap::new(),
+            data_source_oid_to_version: HashMap::new(),
             data_source_oid: None,
+            primary_transfers: HashMap::new(),
+            primary_device_oids: HashSet::new(),
+            primary_source_oid: None,
             memory_pools: HashSet::new(),
             surface_oid_to_sid_dict: HashMap::new(),
             sid_to_surface_info_dict: HashMap::new(),
+            viewports: HashMap::new(),
+            layer_surfaces: HashMap::new(),
+            pointer_constraints: HashMap::new(),
+            idle_inhibitors: HashMap::new(),
+            inhibiting_sids: HashSet::new(),
+            idle_notifications: HashMap::new(),
+            text_inputs: HashMap::new(),
+            popup_grabs: PopupGrabChain::new(),
+            parentless_popups: HashMap::new(),
+            drag_offer: None,
             buffer_oid_to_info_dict: HashMap::new(),
             output_oid_to_id: HashMap::new(),
+            output_oid_to_version: HashMap::new(),
+            output_infos: HashMap::new(),
+            xdg_outputs: HashMap::new(),
             screenshooter_oid: None,
             screenshot_memory: None,
             last_global_id: 0,
@@ -215,6 +417,24 @@ impl Proxy {
         self.settings.clone()
     }
 
+    /// Returns keyboard configuration.
+    pub fn get_keyboard_config(&self) -> &KeyboardConfig {
+        &self.keyboard_config
+    }
+
+    /// Returns information about output with given ID.
+    pub fn get_output_info(&self, id: i32) -> Option<OutputInfo> {
+        self.output_infos.get(&id).cloned()
+    }
+
+    /// Returns information about output bound as `wl_output` with given object ID together with
+    /// version of the `wl_output`.
+    pub fn get_output_info_for_oid(&self, oid: wl::ObjectId) -> Option<(OutputInfo, u32)> {
+        let id = self.output_oid_to_id.get(&oid)?;
+        let version = self.output_oid_to_version.get(&oid).cloned().unwrap_or(1);
+        self.output_infos.get(id).map(|info| (info.clone(), version))
+    }
+
     /// Returns client connection socket.
     pub fn get_socket(&self) -> wl::Socket {
         self.socket.clone()
@@ -242,6 +462,90 @@ impl Proxy {
             self.mediator.borrow_mut().remove(*sid);
             self.coordinator.destroy_surface(*sid);
         }
+
+        self.idle_inhibitors.clear();
+        for sid in self.inhibiting_sids.drain() {
+            self.mediator.borrow_mut().get_idle_tracker_mut().uninhibit(sid, Instant::now());
+        }
+
+        self.deactivate_text_input();
+        if self.is_input_method_client() {
+            self.mediator.borrow_mut().register_input_method(None);
+        }
+
+        self.popup_grabs.truncate(0);
+        self.update_popup_grabber();
+
+        let is_origin = match self.mediator.borrow().get_drag() {
+            Some(drag) => drag.origin_cid == self.client_id,
+            None => false,
+        };
+        if is_origin {
+            self.mediator.borrow_mut().end_drag();
+            self.coordinator.set_surface_as_drag_icon(SurfaceId::invalid());
+            self.coordinator.set_drag_transfer(None);
+        }
+
+        if self.mediator.borrow().get_primary_transfer_offerer() == Some(self.client_id) {
+            self.mediator.borrow_mut().register_primary_transfer_offerer(None);
+            self.coordinator.set_primary_transfer(None);
+        }
+    }
+
+    /// Checks if given surface is one of pop-ups holding explicit grab.
+    pub fn is_grabbing_popup(&self, sid: SurfaceId) -> bool {
+        self.popup_grabs.contains(sid)
+    }
+
+    /// Dismisses all pop-ups holding explicit grab, e.g. after user clicked outside of them.
+    pub fn dismiss_popup_grabs(&mut self) {
+        let old_oid = self.get_keyboard_focused_oid();
+        self.truncate_popup_grabs(0, None);
+        let new_oid = self.get_keyboard_focused_oid();
+        self.send_keyboard_focus_change(old_oid, new_oid);
+    }
+
+    /// Sends keyboard input to keyboard grab of input method.
+    pub fn on_input_method_keyboard_input(&mut self, key: Key, mods: Option<KeyMods>) {
+        let grab_oid = match self.mediator.borrow().get_input_method() {
+            Some(input_method) => input_method.keyboard_grab_oid,
+            None => None,
+        };
+
+        if let Some(grab_oid) = grab_oid {
+            let mut serial = self.socket.get_next_serial();
+            send!(zwp_input_method_keyboard_grab_v2::key(&self.socket,
+                                                         grab_oid,
+                                                         serial,
+                                                         key.time.get_value() as u32,
+                                                         key.code as u32,
+                                                         key.value as u32));
+
+            if let Some(mods) = mods {
+                serial = self.socket.get_next_serial();
+                send!(zwp_input_method_keyboard_grab_v2::modifiers(&self.socket,
+                                                                   grab_oid,
+                                                                   serial,
+                                                                   mods.depressed,
+                                                                   mods.latched,
+                                                                   mods.locked,
+                                                                   mods.effective));
+            }
+        }
+    }
+
+    /// Sends modifiers to all keyboards of this client.
+    pub fn on_keyboard_modifiers(&mut self, mods: KeyMods) {
+        for &keyboard_oid in self.keyboard_oids.iter() {
+            let serial = self.socket.get_next_serial();
+            send!(wl_keyboard::modifiers(&self.socket,
+                                         keyboard_oid,
+                                         serial,
+                                         mods.depressed,
+                                         mods.latched,
+                                         mods.locked,
+                                         mods.effective));
+        }
     }
 }
 
@@ -268,6 +572,13 @@ impl Proxy {
     fn relate_sid_with_frame(&mut self, sid: SurfaceId, oid: wl::ObjectId) {
         relate_sid_with!(frame_oid, self.sid_to_surface_info_dict, sid, oid);
     }
+
+    /// Helper method for informing client that contents related to given presentation feedback
+    /// will never be presented.
+    fn discard_presentation_feedback(socket: &wl::Socket, feedback_oid: wl::ObjectId) {
+        send!(wp_presentation_feedback::discarded(socket, feedback_oid));
+        send!(wl_display::delete_id(socket, wl::DISPLAY_ID, feedback_oid.get_value()));
+    }
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -281,6 +592,271 @@ impl Proxy {
 
 // -------------------------------------------------------------------------------------------------
 
+/// Pointer helpers.
+impl Proxy {
+    /// Checks if given serial comes from button press over given surface (or its subsurface)
+    /// which is still held. Interactive move and resize can be started only with such serial.
+    fn is_button_press_valid(&self, sid: SurfaceId, serial: u32) -> bool {
+        if let Some(press) = self.button_press {
+            let mut pressed_sid = press.sid;
+            while press.serial == serial && pressed_sid.is_valid() {
+                if pressed_sid == sid {
+                    return true;
+                }
+                pressed_sid = match self.coordinator.get_surface(pressed_sid) {
+                    Some(info) => info.parent_sid,
+                    None => SurfaceId::invalid(),
+                };
+            }
+        }
+        false
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Pointer constraint helpers.
+impl Proxy {
+    /// Returns ID of surface constrained by given pointer constraint.
+    fn find_pointer_constraint(&self, constraint_oid: PointerConstraintOid) -> Option<SurfaceId> {
+        self.pointer_constraints
+            .iter()
+            .find(|&(_, info)| info.constraint_oid == constraint_oid)
+            .map(|(&sid, _)| sid)
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Viewport helpers.
+impl Proxy {
+    /// Checks if committed viewport of given surface fits its buffer and sends protocol error if
+    /// it does not.
+    fn check_viewport(&self, sid: SurfaceId) {
+        let viewport_oid = if let Some(&viewport_oid) = self.viewports.get(&sid) {
+            viewport_oid
+        } else {
+            return;
+        };
+
+        if let Some(info) = self.coordinator.get_surface(sid) {
+            if !info.is_viewport_source_in_buffer() {
+                send!(wl_display::error(&self.socket,
+                                        wl::DISPLAY_ID,
+                                        viewport_oid,
+                                        wp_viewport::error::OUT_OF_BUFFER,
+                                        "Source rectangle extends outside of the buffer"));
+            } else if let (Some(source), None) = (info.viewport_source, info.viewport_destination) {
+                if (source.width.fract() != 0.0) || (source.height.fract() != 0.0) {
+                    send!(wl_display::error(&self.socket,
+                                            wl::DISPLAY_ID,
+                                            viewport_oid,
+                                            wp_viewport::error::BAD_SIZE,
+                                            "Source size is not integer and destination is unset"));
+                }
+            }
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Idle inhibition helpers.
+impl Proxy {
+    /// Inhibits idleness if given surface has any idle inhibitor and is drawn.
+    fn inhibit_idleness(&mut self, sid: SurfaceId) {
+        let is_drawn = self.sid_to_surface_info_dict.get(&sid).map_or(false, |info| info.is_drawn);
+        let has_inhibitor = self.idle_inhibitors.values().any(|&inhibited| inhibited == sid);
+        if is_drawn && has_inhibitor && self.inhibiting_sids.insert(sid) {
+            self.mediator.borrow_mut().get_idle_tracker_mut().inhibit(sid);
+        }
+    }
+
+    /// Stops inhibiting idleness by given surface.
+    fn uninhibit_idleness(&mut self, sid: SurfaceId) {
+        if self.inhibiting_sids.remove(&sid) {
+            self.mediator.borrow_mut().get_idle_tracker_mut().uninhibit(sid, Instant::now());
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Text input and input method helpers.
+impl Proxy {
+    /// Informs text inputs about keyboard focus moving between surfaces of this client. Text input
+    /// enabled on surface losing focus gets deactivated.
+    fn send_text_input_focus_change(&mut self, old_sid: SurfaceId, new_sid: SurfaceId) {
+        let is_active = match self.mediator.borrow().get_active_text_input() {
+            Some(text_input) => text_input.cid == self.client_id && text_input.sid == old_sid,
+            None => false,
+        };
+        if is_active {
+            self.deactivate_text_input();
+        }
+
+        let old_oid = self.sid_to_surface_info_dict.get(&old_sid).and_then(|info| info.surface_oid);
+        let new_oid = self.sid_to_surface_info_dict.get(&new_sid).and_then(|info| info.surface_oid);
+        for text_input_oid in self.text_inputs.keys() {
+            if let Some(old_oid) = old_oid {
+                send!(zwp_text_input_v3::leave(&self.socket, *text_input_oid, old_oid));
+            }
+            if let Some(new_oid) = new_oid {
+                send!(zwp_text_input_v3::enter(&self.socket, *text_input_oid, new_oid));
+            }
+        }
+    }
+
+    /// Deactivates input method if the active text input belongs to this client.
+    fn deactivate_text_input(&mut self) {
+        let is_own = match self.mediator.borrow().get_active_text_input() {
+            Some(text_input) => text_input.cid == self.client_id,
+            None => false,
+        };
+        if !is_own {
+            return;
+        }
+
+        self.hide_input_popups();
+        self.mediator.borrow_mut().set_active_text_input(None);
+        if let Some(input_method) = self.mediator.borrow().get_input_method() {
+            let oid = input_method.input_method_oid;
+            send!(zwp_input_method_v2::deactivate(&input_method.socket, oid));
+            send!(zwp_input_method_v2::done(&input_method.socket, oid));
+        }
+    }
+
+    /// Places pop-ups of input method directly below cursor rectangle of the active text input.
+    fn place_input_popups(&self) {
+        let (input_method, text_input) = {
+            let mediator = self.mediator.borrow();
+            (mediator.get_input_method(), mediator.get_active_text_input())
+        };
+
+        if let (Some(input_method), Some(text_input)) = (input_method, text_input) {
+            let rectangle = text_input.cursor_rectangle;
+            let height = rectangle.size.height;
+            let position = Position::new(rectangle.pos.x, rectangle.pos.y + height as isize);
+            for (&popup_oid, &sid) in input_method.popups.iter() {
+                self.coordinator.relate_surfaces(sid, text_input.sid);
+                self.coordinator.set_surface_relative_position(sid, position);
+
+                // Cursor rectangle is reported relative to the pop-up.
+                send!(zwp_input_popup_surface_v2::text_input_rectangle(&input_method.socket,
+                                                                       popup_oid,
+                                                                       0,
+                                                                       -(height as i32),
+                                                                       rectangle.size.width as i32,
+                                                                       height as i32));
+            }
+        }
+    }
+
+    /// Hides all pop-ups of input method.
+    fn hide_input_popups(&self) {
+        if let Some(input_method) = self.mediator.borrow().get_input_method() {
+            for sid in input_method.popups.values() {
+                self.coordinator.unrelate_surface(*sid);
+            }
+        }
+    }
+
+    /// Checks if this client is the input method.
+    fn is_input_method_client(&self) -> bool {
+        match self.mediator.borrow().get_input_method() {
+            Some(input_method) => input_method.cid == self.client_id,
+            None => false,
+        }
+    }
+
+    /// Checks if this client is the input method and given object is its `zwp_input_method_v2`.
+    fn is_input_method(&self, input_method_oid: wl::ObjectId) -> bool {
+        match self.mediator.borrow().get_input_method() {
+            Some(input_method) => {
+                input_method.cid == self.client_id &&
+                input_method.input_method_oid == input_method_oid
+            }
+            None => false,
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Pop-up grab helpers.
+impl Proxy {
+    /// Returns object ID of surface of this client which should have keyboard focus: the topmost
+    /// grabbing pop-up or the surface focused by compositor.
+    fn get_keyboard_focused_oid(&self) -> Option<wl::ObjectId> {
+        if let Some(grab) = self.popup_grabs.get_topmost() {
+            Some(grab.surface_oid)
+        } else {
+            let sid = self.coordinator.get_keyboard_focused_sid();
+            self.sid_to_surface_info_dict.get(&sid).and_then(|info| info.surface_oid)
+        }
+    }
+
+    /// Moves keyboard focus between surfaces of this client without involving compositor.
+    fn send_keyboard_focus_change(&self,
+                                  old_oid: Option<wl::ObjectId>,
+                                  new_oid: Option<wl::ObjectId>) {
+        if old_oid == new_oid {
+            return;
+        }
+
+        for keyboard_oid in self.keyboard_oids.iter() {
+            if let Some(old_oid) = old_oid {
+                let serial = self.socket.get_next_serial();
+                send!(wl_keyboard::leave(&self.socket, *keyboard_oid, serial, old_oid));
+            }
+            if let Some(new_oid) = new_oid {
+                let serial = self.socket.get_next_serial();
+                let keys: [u32; 0] = [0; 0];
+                send!(wl_keyboard::enter(&self.socket, *keyboard_oid, serial, new_oid, &keys[..]));
+            }
+        }
+    }
+
+    /// Informs client its pop-up was dismissed.
+    fn send_popup_done(&self, popup_oid: PopupOid) {
+        match popup_oid {
+            PopupOid::Shell(oid) => {
+                send!(wl_shell_surface::popup_done(&self.socket, oid));
+            }
+            PopupOid::ZxdgPopupV6(oid) => {
+                send!(zxdg_popup_v6::popup_done(&self.socket, oid));
+            }
+            PopupOid::XdgPopup(oid) => {
+                send!(xdg_popup::popup_done(&self.socket, oid));
+            }
+        }
+    }
+
+    /// Removes pop-ups starting from given position in grab chain. Pop-ups are dismissed from the
+    /// topmost one down, except the one with surface `ungrabbed_oid` which is already gone from
+    /// the client point of view.
+    fn truncate_popup_grabs(&mut self, index: usize, ungrabbed_oid: Option<wl::ObjectId>) {
+        for grab in self.popup_grabs.truncate(index) {
+            if Some(grab.surface_oid) != ungrabbed_oid {
+                self.send_popup_done(grab.popup_oid);
+            }
+        }
+        self.update_popup_grabber();
+    }
+
+    /// Informs `Mediator` if this client holds pop-up grab.
+    fn update_popup_grabber(&self) {
+        let mut mediator = self.mediator.borrow_mut();
+        if !self.popup_grabs.is_empty() {
+            mediator.register_popup_grabber(Some(self.client_id));
+        } else if mediator.get_popup_grabber() == Some(self.client_id) {
+            mediator.register_popup_grabber(None);
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
 // Other functions (which should be probably refactored).
 impl Proxy {
     pub fn get_surface_oid_for_shell(&self,
@@ -289,7 +865,8 @@ impl Proxy {
         for info in self.sid_to_surface_info_dict.values() {
             if let Some(shell_surface_oid) = info.shell_surface_oid {
                 match shell_surface_oid {
-                    ShellSurfaceOid::ZxdgToplevelV6(shell_surface_oid, _) => {
+                    ShellSurfaceOid::ZxdgToplevelV6(shell_surface_oid, _) |
+                    ShellSurfaceOid::XdgToplevel(shell_surface_oid, _) => {
                         if shell_surface_oid == parent_shell_surface_oid {
                             return info.surface_oid;
                         }
@@ -301,9 +878,144 @@ impl Proxy {
         None
     }
 
+    /// Sends drag-and-drop `enter` event to given surface with data offers created for every data
+    /// device of this client.
+    pub fn enter_drag(&mut self,
+                      connection: &mut wl::Connection,
+                      proxy_ref: ProxyRef,
+                      sid: SurfaceId,
+                      position: Position,
+                      source: Option<DragSource>) {
+        let surface_oid = if let Some(info) = self.sid_to_surface_info_dict.get(&sid) {
+            if let Some(surface_oid) = info.surface_oid {
+                surface_oid
+            } else {
+                return;
+            }
+        } else {
+            return;
+        };
+
+        let transfer = if source.is_some() { self.coordinator.get_drag_transfer() } else { None };
+        let mut offer = DragOffer::new(source);
+        for (data_device_oid, &version) in self.data_device_oids.iter() {
+            let data_offer_oid = if let Some(ref transfer) = transfer {
+                let data_offer_oid = connection.get_next_available_server_object_id();
+                let data_offer =
+                    protocol::data_device_manager::DataOffer::new_drag_object(data_offer_oid,
+                                                                              proxy_ref.clone());
+                connection.add_object(data_offer_oid, data_offer);
+
+                send!(wl_data_device::data_offer(&self.socket, *data_device_oid, data_offer_oid));
+                for mime_type in transfer.get_mime_types() {
+                    send!(wl_data_offer::offer(&self.socket, data_offer_oid, mime_type));
+                }
+                // FIXME: Add support for versions in `skylane`.
+                if version >= 3 {
+                    send!(wl_data_offer::source_actions(&self.socket,
+                                                        data_offer_oid,
+                                                        transfer.get_dnd_actions().bits()));
+                }
+                offer.data_offer_oids.insert(data_offer_oid, version);
+                data_offer_oid
+            } else {
+                wl::ObjectId::new(0)
+            };
+
+            let serial = self.socket.get_next_serial();
+            send!(wl_data_device::enter(&self.socket,
+                                        *data_device_oid,
+                                        serial,
+                                        surface_oid,
+                                        position.x as f32,
+                                        position.y as f32,
+                                        data_offer_oid));
+        }
+        self.drag_offer = Some(offer);
+    }
+
+    /// Sends drag-and-drop `motion` event.
+    pub fn move_drag(&self, position: Position, milliseconds: Milliseconds) {
+        if let Some(ref offer) = self.drag_offer {
+            if !offer.is_dropped {
+                for data_device_oid in self.data_device_oids.keys() {
+                    send!(wl_data_device::motion(&self.socket,
+                                                 *data_device_oid,
+                                                 milliseconds.get_value() as u32,
+                                                 position.x as f32,
+                                                 position.y as f32));
+                }
+            }
+        }
+    }
+
+    /// Sends drag-and-drop `leave` event.
+    pub fn leave_drag(&mut self) {
+        let is_entered = match self.drag_offer {
+            Some(ref offer) => !offer.is_dropped,
+            None => false,
+        };
+
+        if is_entered {
+            for data_device_oid in self.data_device_oids.keys() {
+                send!(wl_data_device::leave(&self.socket, *data_device_oid));
+            }
+            self.drag_offer = None;
+        }
+    }
+
+    /// Drops dragged data if this client accepted it or leaves otherwise. Returns `true` if data
+    /// was dropped.
+    pub fn drop_drag(&mut self) -> bool {
+        let is_accepted = match self.drag_offer {
+            Some(ref offer) if !offer.is_dropped => {
+                if offer.source.is_some() {
+                    // Actions are negotiated only with data devices since version 3.
+                    let is_negotiated = offer.data_offer_oids.values().all(|&v| v >= 3);
+                    offer.mime_type.is_some() &&
+                    (!is_negotiated || offer.dnd_action != dnd_action::NONE)
+                } else {
+                    // Drag without source happens within one client, there is nothing to accept.
+                    true
+                }
+            }
+            _ => false,
+        };
+
+        if is_accepted {
+            for data_device_oid in self.data_device_oids.keys() {
+                send!(wl_data_device::drop(&self.socket, *data_device_oid));
+            }
+            if let Some(ref mut offer) = self.drag_offer {
+                offer.is_dropped = true;
+            }
+        } else {
+            self.leave_drag();
+        }
+        is_accepted
+    }
+
+    /// Informs source about the result of drag-and-drop started by this client and cleans up.
+    pub fn end_drag(&mut self, drag: Drag, is_dropped: bool) {
+        if let Some(source) = drag.source {
+            if is_dropped {
+                // FIXME: Add support for versions in `skylane`.
+                if source.version >= 3 {
+                    send!(wl_data_source::dnd_drop_performed(&source.socket,
+                                                             source.data_source_oid));
+                }
+            } else {
+                send!(wl_data_source::cancelled(&source.socket, source.data_source_oid));
+            }
+        }
+
+        self.coordinator.set_surface_as_drag_icon(SurfaceId::invalid());
+        self.coordinator.set_drag_transfer(None);
+    }
+
     pub fn make_data_offer(&mut self, connection: &mut wl::Connection, proxy_ref: ProxyRef) {
         if let Some(transfer) = self.coordinator.get_transfer() {
-            for data_device_oid in self.data_device_oids.iter() {
+            for data_device_oid in self.data_device_oids.keys() {
                 let data_offer_oid = connection.get_next_available_server_object_id();
                 let data_offer =
                     protocol::data_device_manager::DataOffer::new_object(data_offer_oid,
@@ -320,6 +1032,37 @@ impl Proxy {
             }
         }
     }
+
+    /// Sends current primary selection to the client. Client is informed about primary selection
+    /// being unset as well.
+    pub fn make_primary_offer(&mut self, connection: &mut wl::Connection, proxy_ref: ProxyRef) {
+        let transfer = self.coordinator.get_primary_transfer();
+        for device_oid in self.primary_device_oids.iter() {
+            if let Some(ref transfer) = transfer {
+                let offer_oid = connection.get_next_available_server_object_id();
+                let offer = PrimarySelectionOffer::new_object(offer_oid, proxy_ref.clone());
+                connection.add_object(offer_oid, offer);
+
+                send!(zwp_primary_selection_device_v1::data_offer(&self.socket,
+                                                                  *device_oid,
+                                                                  offer_oid));
+
+                for mime_type in transfer.get_mime_types() {
+                    send!(zwp_primary_selection_offer_v1::offer(&self.socket,
+                                                                offer_oid,
+                                                                mime_type));
+                }
+
+                send!(zwp_primary_selection_device_v1::selection(&self.socket,
+                                                                 *device_oid,
+                                                                 offer_oid));
+            } else {
+                send!(zwp_primary_selection_device_v1::selection(&self.socket,
+                                                                 *device_oid,
+                                                                 wl::ObjectId::new(0)));
+            }
+        }
+    }
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -395,7 +1138,7 @@ impl Facade for Proxy {
         self.coordinator.destroy_dmabuf(dbid);
     }
 
-    fn define_region(&mut self, region_oid: wl::ObjectId, region: Area) {
+    fn define_region(&mut self, region_oid: wl::ObjectId, region: Vec<Area>) {
         self.regions.insert(region_oid, region);
     }
 
@@ -411,169 +1154,1029 @@ impl Facade for Proxy {
         self.pointer_oids.remove(&pointer_oid);
     }
 
-    fn add_keyboard_oid(&mut self, keyboard_oid: wl::ObjectId) {
-        self.keyboard_oids.insert(keyboard_oid);
+    fn add_relative_pointer_oid(&mut self, relative_pointer_oid: wl::ObjectId) {
+        self.relative_pointer_oids.insert(relative_pointer_oid);
+    }
+
+    fn remove_relative_pointer_oid(&mut self, relative_pointer_oid: wl::ObjectId) {
+        self.relative_pointer_oids.remove(&relative_pointer_oid);
+    }
+
+    fn add_keyboard_oid(&mut self, keyboard_oid: wl::ObjectId) {
+        self.keyboard_oids.insert(keyboard_oid);
+    }
+
+    fn remove_keyboard_oid(&mut self, keyboard_oid: wl::ObjectId) {
+        self.keyboard_oids.remove(&keyboard_oid);
+    }
+
+    fn add_touch_oid(&mut self, touch_oid: wl::ObjectId) {
+        self.touch_oids.insert(touch_oid);
+    }
+
+    fn remove_touch_oid(&mut self, touch_oid: wl::ObjectId) {
+        self.touch_oids.remove(&touch_oid);
+    }
+
+    fn add_data_device_oid(&mut self, data_device_oid: wl::ObjectId, version: u32) {
+        self.data_device_oids.insert(data_device_oid, version);
+    }
+
+    fn remove_data_device_oid(&mut self, data_device_oid: wl::ObjectId) {
+        self.data_device_oids.remove(&data_device_oid);
+    }
+
+    fn add_primary_device_oid(&mut self, primary_device_oid: wl::ObjectId) {
+        self.primary_device_oids.insert(primary_device_oid);
+    }
+
+    fn remove_primary_device_oid(&mut self, primary_device_oid: wl::ObjectId) {
+        self.primary_device_oids.remove(&primary_device_oid);
+    }
+
+    fn set_positioner(&mut self, oid: wl::ObjectId, positioner: PositionerInfo) {
+        self.positioners.insert(oid, positioner);
+    }
+
+    fn get_positioner(&mut self, oid: wl::ObjectId) -> Option<PositionerInfo> {
+        if let Some(positioner) = self.positioners.get(&oid) {
+            Some(*positioner)
+        } else {
+            None
+        }
+    }
+
+    fn get_popup_bounds(&self, parent_surface_oid: wl::ObjectId) -> Option<Area> {
+        let sid = self.surface_oid_to_sid_dict.get(&parent_surface_oid)?;
+        let position = self.coordinator.get_surface(*sid)?.global_position?;
+        self.output_infos
+            .values()
+            .find(|info| info.area.contains(&position))
+            .map(|info| Area::new(info.area.pos - position, info.area.size))
+    }
+
+    fn remove_positioner(&mut self, oid: wl::ObjectId) {
+        self.positioners.remove(&oid);
+    }
+
+    fn set_transfer(&mut self, oid: wl::ObjectId, transfer: Transfer) {
+        self.transfers.insert(oid, transfer);
+    }
+
+    fn relate_data_source_oid_with_version(&mut self, oid: wl::ObjectId, version: u32) {
+        self.data_source_oid_to_version.insert(oid, version);
+    }
+
+    fn get_transfer(&mut self, oid: wl::ObjectId) -> Option<Transfer> {
+        if let Some(transfer) = self.transfers.get(&oid) {
+            Some(transfer.clone())
+        } else {
+            None
+        }
+    }
+
+    fn select_transfer(&mut self, data_source_oid: wl::ObjectId) {
+        if let Some(transfer) = self.transfers.get(&data_source_oid) {
+            self.data_source_oid = Some(data_source_oid);
+            self.mediator.borrow_mut().register_transfer_offerer(Some(self.client_id));
+            self.coordinator.set_transfer(Some(transfer.clone()));
+        } else {
+            log_warn2!("Transfer cannot be selected");
+        }
+    }
+
+    fn remove_transfer(&mut self, oid: wl::ObjectId) {
+        self.transfers.remove(&oid);
+        self.data_source_oid_to_version.remove(&oid);
+
+        // Drag-and-drop can not continue with destroyed source. Current target will be left.
+        if self.mediator.borrow_mut().cancel_drag_source(self.client_id, oid) {
+            self.coordinator.set_drag_transfer(None);
+        }
+    }
+
+    fn request_transfer(&mut self, mime_type: String, fd: RawFd) {
+        self.coordinator.request_transfer(mime_type, fd);
+    }
+
+    fn set_primary_transfer(&mut self, oid: wl::ObjectId, transfer: Transfer) {
+        self.primary_transfers.insert(oid, transfer);
+    }
+
+    fn get_primary_transfer(&mut self, oid: wl::ObjectId) -> Option<Transfer> {
+        self.primary_transfers.get(&oid).cloned()
+    }
+
+    fn select_primary_transfer(&mut self, oid: Option<wl::ObjectId>) {
+        let transfer = if let Some(oid) = oid {
+            if let Some(transfer) = self.primary_transfers.get(&oid) {
+                Some(transfer.clone())
+            } else {
+                log_warn2!("Primary selection transfer cannot be selected");
+                return;
+            }
+        } else {
+            None
+        };
+
+        // Previous source of this client is cancelled here. Sources of other clients are
+        // cancelled when they are informed about the new offer.
+        if let Some(old_oid) = self.primary_source_oid {
+            if Some(old_oid) != oid {
+                send!(zwp_primary_selection_source_v1::cancelled(&self.socket, old_oid));
+            }
+        }
+
+        self.primary_source_oid = oid;
+        let offerer = if oid.is_some() { Some(self.client_id) } else { None };
+        self.mediator.borrow_mut().register_primary_transfer_offerer(offerer);
+        self.coordinator.set_primary_transfer(transfer);
+    }
+
+    fn remove_primary_transfer(&mut self, oid: wl::ObjectId) {
+        self.primary_transfers.remove(&oid);
+        if self.primary_source_oid == Some(oid) {
+            self.primary_source_oid = None;
+            self.mediator.borrow_mut().register_primary_transfer_offerer(None);
+            self.coordinator.set_primary_transfer(None);
+        }
+    }
+
+    fn request_primary_transfer(&mut self, mime_type: String, fd: RawFd) {
+        self.coordinator.request_primary_transfer(mime_type, fd);
+    }
+
+    fn start_drag(&mut self,
+                  data_source_oid: Option<wl::ObjectId>,
+                  origin_surface_oid: wl::ObjectId,
+                  icon_surface_oid: Option<wl::ObjectId>) {
+        // Only surface holding pointer focus can start dragging.
+        let pfsid = self.coordinator.get_pointer_focused_sid();
+        let origin_sid = self.surface_oid_to_sid_dict.get(&origin_surface_oid).cloned();
+        if origin_sid != Some(pfsid) || self.mediator.borrow().is_dragging() {
+            log_warn3!("Refusing to start drag from surface {}", origin_surface_oid);
+            if let Some(data_source_oid) = data_source_oid {
+                send!(wl_data_source::cancelled(&self.socket, data_source_oid));
+            }
+            return;
+        }
+
+        let source = if let Some(data_source_oid) = data_source_oid {
+            let transfer = self.transfers.get(&data_source_oid).cloned();
+            self.coordinator.set_drag_transfer(transfer);
+            let version = self.data_source_oid_to_version.get(&data_source_oid).cloned();
+            Some(DragSource {
+                socket: self.socket.clone(),
+                data_source_oid: data_source_oid,
+                version: version.unwrap_or(1),
+            })
+        } else {
+            None
+        };
+
+        if let Some(icon_surface_oid) = icon_surface_oid {
+            if let Some(&icon_sid) = self.surface_oid_to_sid_dict.get(&icon_surface_oid) {
+                self.coordinator.set_surface_as_drag_icon(icon_sid);
+            }
+        }
+
+        // Pointer events are not sent to clients while dragging.
+        self.on_pointer_focus_changed(pfsid, SurfaceId::invalid(), Position::default());
+
+        self.mediator.borrow_mut().start_drag(Drag {
+            origin_cid: self.client_id,
+            source: source,
+            target_sid: SurfaceId::invalid(),
+            position: Position::default(),
+            button: self.button_press.map(|press| press.code),
+        });
+    }
+
+    fn accept_drag_mime_type(&mut self, data_offer_oid: wl::ObjectId, mime_type: Option<String>) {
+        if let Some(ref mut offer) = self.drag_offer {
+            if offer.data_offer_oids.contains_key(&data_offer_oid) {
+                if let Some(ref source) = offer.source {
+                    let accepted = mime_type.as_ref().map_or("", |mime_type| mime_type.as_str());
+                    send!(wl_data_source::target(&source.socket, source.data_source_oid, accepted));
+                }
+                offer.mime_type = mime_type;
+            }
+        }
+    }
+
+    fn set_drag_actions(&mut self,
+                        data_offer_oid: wl::ObjectId,
+                        dnd_actions: dnd_action::DndAction,
+                        preferred_action: dnd_action::DndAction) {
+        let transfer = self.coordinator.get_drag_transfer();
+        if let Some(ref mut offer) = self.drag_offer {
+            if offer.is_dropped || !offer.data_offer_oids.contains_key(&data_offer_oid) {
+                return;
+            }
+
+            offer.dnd_action = if let Some(transfer) = transfer {
+                transfer.choose_dnd_action(dnd_actions, preferred_action)
+            } else {
+                dnd_action::NONE
+            };
+
+            // FIXME: Add support for versions in `skylane`.
+            let bits = offer.dnd_action.bits();
+            for (data_offer_oid, &version) in offer.data_offer_oids.iter() {
+                if version >= 3 {
+                    send!(wl_data_offer::action(&self.socket, *data_offer_oid, bits));
+                }
+            }
+            if let Some(ref source) = offer.source {
+                if source.version >= 3 {
+                    send!(wl_data_source::action(&source.socket, source.data_source_oid, bits));
+                }
+            }
+        }
+    }
+
+    fn request_drag_transfer(&mut self,
+                             data_offer_oid: wl::ObjectId,
+                             mime_type: String,
+                             fd: RawFd) {
+        if let Some(ref offer) = self.drag_offer {
+            if offer.data_offer_oids.contains_key(&data_offer_oid) {
+                if let Some(ref source) = offer.source {
+                    send!(wl_data_source::send(&source.socket,
+                                               source.data_source_oid,
+                                               &mime_type,
+                                               fd));
+                }
+            }
+        }
+    }
+
+    fn finish_drag(&mut self, data_offer_oid: wl::ObjectId) {
+        let is_finished = match self.drag_offer {
+            Some(ref offer) => {
+                offer.is_dropped && offer.data_offer_oids.contains_key(&data_offer_oid)
+            }
+            None => false,
+        };
+
+        if is_finished {
+            if let Some(offer) = self.drag_offer.take() {
+                if let Some(source) = offer.source {
+                    // FIXME: Add support for versions in `skylane`.
+                    if source.version >= 3 {
+                        send!(wl_data_source::dnd_finished(&source.socket,
+                                                           source.data_source_oid));
+                    }
+                }
+            }
+        }
+    }
+
+    fn remove_drag_offer(&mut self, data_offer_oid: wl::ObjectId) {
+        if let Some(ref mut offer) = self.drag_offer {
+            offer.data_offer_oids.remove(&data_offer_oid);
+        }
+    }
+
+    fn set_input_region(&self, sid: SurfaceId, region_oid: wl::ObjectId) {
+        let region = self.regions.get(&region_oid).cloned();
+        self.coordinator.set_surface_input_region(sid, region);
+    }
+
+    fn set_opaque_region(&self, sid: SurfaceId, region_oid: wl::ObjectId) {
+        let region = self.regions.get(&region_oid).cloned().unwrap_or_else(Vec::new);
+        self.coordinator.set_surface_opaque_region(sid, region);
+    }
+
+    fn create_surface(&mut self, oid: wl::ObjectId) -> SurfaceId {
+        let sid = self.coordinator.create_surface();
+        self.relate_sid_with_surface(sid, oid);
+        self.mediator.borrow_mut().relate_sid_to_client(sid, self.client_id);
+        sid
+    }
+
+    fn destroy_surface(&mut self, sid: SurfaceId) {
+        let surface_oid = self.sid_to_surface_info_dict.get(&sid).and_then(|info| info.surface_oid);
+        if let Some(surface_oid) = surface_oid {
+            if let Some(index) = self.popup_grabs.find_related(surface_oid) {
+                // Do not send keyboard events to destroyed surface.
+                let filter = |oid| if oid == Some(surface_oid) { None } else { oid };
+                let old_oid = filter(self.get_keyboard_focused_oid());
+                self.truncate_popup_grabs(index, Some(surface_oid));
+                let new_oid = filter(self.get_keyboard_focused_oid());
+                self.send_keyboard_focus_change(old_oid, new_oid);
+            }
+        }
+        self.viewports.remove(&sid);
+        self.layer_surfaces.remove(&sid);
+        self.pointer_constraints.remove(&sid);
+        let inhibitor_oids: Vec<wl::ObjectId> = self.idle_inhibitors
+            .iter()
+            .filter(|&(_, inhibited_sid)| *inhibited_sid == sid)
+            .map(|(&oid, _)| oid)
+            .collect();
+        for inhibitor_oid in inhibitor_oids {
+            self.remove_idle_inhibitor(inhibitor_oid);
+        }
+        let is_active = match self.mediator.borrow().get_active_text_input() {
+            Some(text_input) => text_input.sid == sid,
+            None => false,
+        };
+        if is_active {
+            self.deactivate_text_input();
+        }
+        if let Some(input_method) = self.mediator.borrow_mut().get_input_method_mut() {
+            input_method.popups.retain(|_, popup_sid| *popup_sid != sid);
+        }
+        if let Some(info) = self.sid_to_surface_info_dict.get_mut(&sid) {
+            let feedback_oids = info.pending_feedback_oids
+                .drain(..)
+                .chain(info.committed_feedback_oids.drain(..))
+                .chain(info.drawn_feedback_oids.drain(..));
+            for feedback_oid in feedback_oids {
+                Self::discard_presentation_feedback(&self.socket, feedback_oid);
+            }
+        }
+        self.coordinator.destroy_surface(sid)
+    }
+
+    fn attach(&mut self, buffer_oid: wl::ObjectId, sid: SurfaceId, _x: i32, _y: i32) {
+        if buffer_oid.is_null() {
+            // Client wants to unmap this surface
+            // TODO: This should be done on commit
+            self.coordinator.unrelate_surface(sid);
+            self.coordinator.detach_surface(sid)
+        } else if let Some(info) = self.buffer_oid_to_info_dict.get(&buffer_oid).cloned() {
+            self.relate_sid_with_buffer(sid, buffer_oid);
+            match info {
+                BufferInfo::Shm { mpid, mvid } => {
+                    self.coordinator.attach_shm(mvid, sid);
+                }
+                BufferInfo::EglImage { eiid } => {
+                    self.coordinator.attach_egl_image(eiid, sid);
+                }
+                BufferInfo::Dmabuf { dbid } => {
+                    self.coordinator.attach_dmabuf(dbid, sid);
+                }
+            }
+        } else {
+            log_error!("Unknown buffer object ID: {}", buffer_oid);
+        }
+    }
+
+    fn set_buffer_scale(&self, sid: SurfaceId, scale: u32) {
+        self.coordinator.set_surface_buffer_scale(sid, scale);
+    }
+
+    fn set_buffer_transform(&self, sid: SurfaceId, transform: Transform) {
+        self.coordinator.set_surface_buffer_transform(sid, transform);
+    }
+
+    fn damage(&self, sid: SurfaceId, area: Area) {
+        self.coordinator.damage_surface(sid, area);
+    }
+
+    fn damage_buffer(&self, sid: SurfaceId, area: Area) {
+        self.coordinator.damage_surface_buffer(sid, area);
+    }
+
+    fn commit(&mut self, sid: SurfaceId) {
+        if let Some(info) = self.sid_to_surface_info_dict.get_mut(&sid) {
+            // Contents related to previously committed feedbacks will never be presented.
+            for feedback_oid in info.committed_feedback_oids.drain(..) {
+                Self::discard_presentation_feedback(&self.socket, feedback_oid);
+            }
+            std::mem::swap(&mut info.committed_feedback_oids, &mut info.pending_feedback_oids);
+        }
+        if let Some(&(properties, display_id)) = self.layer_surfaces.get(&sid) {
+            if !properties.is_size_valid() {
+                let shell_surface_oid =
+                    self.sid_to_surface_info_dict.get(&sid).and_then(|info| info.shell_surface_oid);
+                if let Some(ShellSurfaceOid::Layer(layer_surface_oid)) = shell_surface_oid {
+                    send!(wl_display::error(&self.socket,
+                                            wl::DISPLAY_ID,
+                                            layer_surface_oid,
+                                            zwlr_layer_surface_v1::error::INVALID_SIZE,
+                                            "Zero size requires anchoring to opposite edges"));
+                }
+                return;
+            }
+            self.coordinator.set_surface_layer(sid, properties, display_id);
+        }
+        self.coordinator.commit_surface(sid);
+        self.check_viewport(sid);
+    }
+
+    fn set_frame(&mut self, sid: SurfaceId, frame_oid: wl::ObjectId) {
+        self.relate_sid_with_frame(sid, frame_oid);
+    }
+
+    fn add_presentation_feedback(&mut self,
+                                 surface_oid: wl::ObjectId,
+                                 feedback_oid: wl::ObjectId) {
+        let sid = self.surface_oid_to_sid_dict.get(&surface_oid).cloned();
+        if let Some(info) = sid.and_then(|sid| self.sid_to_surface_info_dict.get_mut(&sid)) {
+            info.pending_feedback_oids.push(feedback_oid);
+        } else {
+            Self::discard_presentation_feedback(&self.socket, feedback_oid);
+        }
+    }
+
+    fn show(&mut self,
+            surface_oid: wl::ObjectId,
+            shell_surface_oid: ShellSurfaceOid,
+            reason: show_reason::ShowReason) {
+        if let Some(&sid) = self.surface_oid_to_sid_dict.get(&surface_oid) {
+            self.relate_sid_with_shell_surface(sid, shell_surface_oid);
+            self.coordinator.show_surface(sid, reason);
+        } else {
+            log_error!("Unknown surface object ID: {}", surface_oid);
+        }
+    }
+
+    fn hide(&mut self, surface_oid: wl::ObjectId, reason: show_reason::ShowReason) {
+        if let Some(&sid) = self.surface_oid_to_sid_dict.get(&surface_oid) {
+            self.coordinator.hide_surface(sid, reason);
+            self.unrelate_sid_with_shell_surface(sid);
+        } else {
+            log_error!("Unknown surface object ID: {}", surface_oid);
+        }
+    }
+
+    fn set_offset(&self, sid: SurfaceId, offset: Vector) {
+        self.coordinator.set_surface_offset(sid, offset);
+    }
+
+    fn set_requested_size(&self, sid: SurfaceId, size: Size) {
+        self.coordinator.set_surface_requested_size(sid, size);
+    }
+
+    fn set_relative_position(&self, surface_oid: wl::ObjectId, x: isize, y: isize) {
+        if let Some(&sid) = self.surface_oid_to_sid_dict.get(&surface_oid) {
+            let position = Position::new(x, y);
+            self.coordinator.set_surface_relative_position(sid, position);
+        }
+    }
+
+    fn relate(&self, surface_oid: wl::ObjectId, parent_surface_oid: wl::ObjectId) {
+        if let Some(&sid) = self.surface_oid_to_sid_dict.get(&surface_oid) {
+            if let Some(&parent_sid) = self.surface_oid_to_sid_dict.get(&parent_surface_oid) {
+                self.coordinator.relate_surfaces(sid, parent_sid);
+            }
+            self.coordinator.set_surface_relative_position(sid, Position::default());
+        }
+    }
+
+    fn unrelate(&self, surface_oid: wl::ObjectId) {
+        if let Some(&sid) = self.surface_oid_to_sid_dict.get(&surface_oid) {
+            self.coordinator.unrelate_surface(sid);
+        }
+    }
+
+    fn place_above(&self, surface_oid: wl::ObjectId, sibling_oid: wl::ObjectId) {
+        if let Some(&sid) = self.surface_oid_to_sid_dict.get(&surface_oid) {
+            if let Some(&sibling_sid) = self.surface_oid_to_sid_dict.get(&sibling_oid) {
+                self.coordinator.place_surface_above(sid, sibling_sid);
+            }
+        }
+    }
+
+    fn place_below(&self, surface_oid: wl::ObjectId, sibling_oid: wl::ObjectId) {
+        if let Some(&sid) = self.surface_oid_to_sid_dict.get(&surface_oid) {
+            if let Some(&sibling_sid) = self.surface_oid_to_sid_dict.get(&sibling_oid) {
+                self.coordinator.place_surface_below(sid, sibling_sid);
+            }
+        }
+    }
+
+    fn set_synchronized(&self, surface_oid: wl::ObjectId, is_synchronized: bool) {
+        if let Some(&sid) = self.surface_oid_to_sid_dict.get(&surface_oid) {
+            self.coordinator.set_surface_synchronized(sid, is_synchronized);
+        }
+    }
+
+    fn add_viewport(&mut self, surface_oid: wl::ObjectId, viewport_oid: wl::ObjectId) -> bool {
+        if let Some(&sid) = self.surface_oid_to_sid_dict.get(&surface_oid) {
+            if self.viewports.contains_key(&sid) {
+                false
+            } else {
+                self.viewports.insert(sid, viewport_oid);
+                true
+            }
+        } else {
+            true
+        }
+    }
+
+    fn remove_viewport(&mut self, surface_oid: wl::ObjectId) {
+        if let Some(&sid) = self.surface_oid_to_sid_dict.get(&surface_oid) {
+            if self.viewports.remove(&sid).is_some() {
+                self.coordinator.set_surface_viewport_source(sid, None);
+                self.coordinator.set_surface_viewport_destination(sid, None);
+            }
+        }
+    }
+
+    fn set_viewport_source(&self, surface_oid: wl::ObjectId, source: Option<ViewportSource>) {
+        if let Some(&sid) = self.surface_oid_to_sid_dict.get(&surface_oid) {
+            self.coordinator.set_surface_viewport_source(sid, source);
+        }
+    }
+
+    fn set_viewport_destination(&self, surface_oid: wl::ObjectId, destination: Option<Size>) {
+        if let Some(&sid) = self.surface_oid_to_sid_dict.get(&surface_oid) {
+            self.coordinator.set_surface_viewport_destination(sid, destination);
+        }
+    }
+
+    fn set_window_geometry(&self, surface_oid: wl::ObjectId, area: Area) {
+        if let Some(&sid) = self.surface_oid_to_sid_dict.get(&surface_oid) {
+            self.coordinator.set_surface_offset(sid, area.pos);
+            self.coordinator.set_surface_requested_size(sid, area.size);
+        }
+    }
+
+    fn set_title(&self, surface_oid: wl::ObjectId, title: String) {
+        if let Some(&sid) = self.surface_oid_to_sid_dict.get(&surface_oid) {
+            self.coordinator.set_surface_title(sid, title);
+        }
+    }
+
+    fn set_app_id(&self, surface_oid: wl::ObjectId, app_id: String) {
+        if let Some(&sid) = self.surface_oid_to_sid_dict.get(&surface_oid) {
+            self.coordinator.set_surface_app_id(sid, app_id);
+        }
+    }
+
+    fn request_move(&self, surface_oid: wl::ObjectId, serial: u32) {
+        if let Some(&sid) = self.surface_oid_to_sid_dict.get(&surface_oid) {
+            if self.is_button_press_valid(sid, serial) {
+                self.coordinator.request_surface_move(sid);
+            } else {
+                log_warn3!("Refusing to move surface {} with serial {}", surface_oid, serial);
+            }
+        }
+    }
+
+    fn request_resize(&self,
+                      surface_oid: wl::ObjectId,
+                      serial: u32,
+                      edges: resize_edge::ResizeEdge) {
+        if let Some(&sid) = self.surface_oid_to_sid_dict.get(&surface_oid) {
+            if self.is_button_press_valid(sid, serial) {
+                self.coordinator.request_surface_resize(sid, edges);
+            } else {
+                log_warn3!("Refusing to resize surface {} with serial {}", surface_oid, serial);
+            }
+        }
+    }
+
+    fn set_maximized(&self, surface_oid: wl::ObjectId, maximized: bool) {
+        if let Some(&sid) = self.surface_oid_to_sid_dict.get(&surface_oid) {
+            self.coordinator.request_surface_maximization(sid, maximized);
+        }
+    }
+
+    fn set_fullscreen(&self,
+                      surface_oid: wl::ObjectId,
+                      fullscreen: bool,
+                      output_oid: Option<wl::ObjectId>) {
+        if let Some(&sid) = self.surface_oid_to_sid_dict.get(&surface_oid) {
+            let display_id = if let Some(output_oid) = output_oid {
+                self.output_oid_to_id.get(&output_oid).cloned()
+            } else {
+                None
+            };
+            self.coordinator.request_surface_fullscreen(sid, fullscreen, display_id);
+        }
+    }
+
+    fn set_minimized(&self, surface_oid: wl::ObjectId) {
+        if let Some(&sid) = self.surface_oid_to_sid_dict.get(&surface_oid) {
+            self.coordinator.request_surface_minimization(sid);
+        }
+    }
+
+    fn add_layer_surface(&mut self,
+                         surface_oid: wl::ObjectId,
+                         layer_surface_oid: wl::ObjectId,
+                         output_oid: Option<wl::ObjectId>,
+                         properties: LayerProperties)
+                         -> bool {
+        if let Some(&sid) = self.surface_oid_to_sid_dict.get(&surface_oid) {
+            let has_role = self.sid_to_surface_info_dict
+                .get(&sid)
+                .map_or(false, |info| info.shell_surface_oid.is_some());
+            if has_role {
+                return false;
+            }
+
+            let display_id = output_oid.and_then(|oid| self.output_oid_to_id.get(&oid).cloned());
+            self.layer_surfaces.insert(sid, (properties, display_id));
+            let shell_surface_oid = ShellSurfaceOid::Layer(layer_surface_oid);
+            self.show(surface_oid, shell_surface_oid, show_reason::IN_SHELL);
+        }
+        true
+    }
+
+    fn set_layer_properties(&mut self, surface_oid: wl::ObjectId, properties: LayerProperties) {
+        if let Some(&sid) = self.surface_oid_to_sid_dict.get(&surface_oid) {
+            if let Some(entry) = self.layer_surfaces.get_mut(&sid) {
+                entry.0 = properties;
+            }
+        }
+    }
+
+    fn remove_layer_surface(&mut self, surface_oid: wl::ObjectId) {
+        if let Some(&sid) = self.surface_oid_to_sid_dict.get(&surface_oid) {
+            if self.layer_surfaces.remove(&sid).is_some() {
+                self.hide(surface_oid, show_reason::IN_SHELL);
+            }
+        }
+    }
+
+    fn set_toplevel_decorated(&mut self,
+                              toplevel_oid: wl::ObjectId,
+                              is_decorated: bool)
+                              -> Option<wl::ObjectId> {
+        let found = self.sid_to_surface_info_dict.iter().find(|&(_, info)| {
+            match info.shell_surface_oid {
+                Some(ShellSurfaceOid::XdgToplevel(_, oid)) => oid == toplevel_oid,
+                _ => false,
+            }
+        });
+
+        if let Some((&sid, info)) = found {
+            self.coordinator.set_surface_decorated(sid, is_decorated);
+            match info.shell_surface_oid {
+                Some(ShellSurfaceOid::XdgToplevel(shell_surface_oid, _)) => Some(shell_surface_oid),
+                _ => None,
+            }
+        } else {
+            None
+        }
+    }
+
+    fn add_idle_inhibitor(&mut self, inhibitor_oid: wl::ObjectId, surface_oid: wl::ObjectId) {
+        if let Some(&sid) = self.surface_oid_to_sid_dict.get(&surface_oid) {
+            self.idle_inhibitors.insert(inhibitor_oid, sid);
+            self.inhibit_idleness(sid);
+        } else {
+            log_error!("Unknown surface object ID: {}", surface_oid);
+        }
+    }
+
+    fn remove_idle_inhibitor(&mut self, inhibitor_oid: wl::ObjectId) {
+        if let Some(sid) = self.idle_inhibitors.remove(&inhibitor_oid) {
+            if !self.idle_inhibitors.values().any(|&inhibited_sid| inhibited_sid == sid) {
+                self.uninhibit_idleness(sid);
+            }
+        }
+    }
+
+    fn add_idle_notification(&mut self,
+                             notification_oid: wl::ObjectId,
+                             timeout: Duration,
+                             respects_inhibitors: bool) {
+        let info = IdleNotificationInfo::new(timeout, respects_inhibitors);
+        self.idle_notifications.insert(notification_oid, info);
     }
 
-    fn remove_keyboard_oid(&mut self, keyboard_oid: wl::ObjectId) {
-        self.keyboard_oids.remove(&keyboard_oid);
+    fn remove_idle_notification(&mut self, notification_oid: wl::ObjectId) {
+        self.idle_notifications.remove(&notification_oid);
     }
 
-    fn add_data_device_oid(&mut self, data_device_oid: wl::ObjectId) {
-        self.data_device_oids.insert(data_device_oid);
+    fn constrain_pointer(&mut self,
+                         surface_oid: wl::ObjectId,
+                         constraint_oid: PointerConstraintOid,
+                         region_oid: wl::ObjectId,
+                         is_persistent: bool)
+                         -> bool {
+        let sid = if let Some(&sid) = self.surface_oid_to_sid_dict.get(&surface_oid) {
+            sid
+        } else {
+            log_error!("Unknown surface object ID: {}", surface_oid);
+            return true;
+        };
+
+        if self.pointer_constraints.contains_key(&sid) {
+            return false;
+        }
+
+        let info = PointerConstraintInfo::new(constraint_oid, is_persistent);
+        self.pointer_constraints.insert(sid, info);
+        self.set_pointer_constraint_region(constraint_oid, region_oid);
+        true
+    }
+
+    fn set_pointer_constraint_region(&mut self,
+                                     constraint_oid: PointerConstraintOid,
+                                     region_oid: wl::ObjectId) {
+        if let Some(sid) = self.find_pointer_constraint(constraint_oid) {
+            let kind = match constraint_oid {
+                PointerConstraintOid::Locked(_) => PointerConstraintKind::Lock,
+                PointerConstraintOid::Confined(_) => PointerConstraintKind::Confine,
+            };
+            let region = self.regions.get(&region_oid).cloned();
+            let constraint = PointerConstraint::new(kind, region);
+            self.coordinator.set_surface_pointer_constraint(sid, Some(constraint));
+        }
     }
 
-    fn remove_data_device_oid(&mut self, data_device_oid: wl::ObjectId) {
-        self.data_device_oids.remove(&data_device_oid);
+    fn unconstrain_pointer(&mut self, constraint_oid: PointerConstraintOid) {
+        if let Some(sid) = self.find_pointer_constraint(constraint_oid) {
+            self.pointer_constraints.remove(&sid);
+            self.coordinator.set_surface_pointer_constraint(sid, None);
+        }
     }
 
-    fn set_positioner(&mut self, oid: wl::ObjectId, positioner: PositionerInfo) {
-        self.positioners.insert(oid, positioner);
+    fn add_text_input(&mut self, text_input_oid: wl::ObjectId) {
+        self.text_inputs.insert(text_input_oid, 0);
+
+        let sid = self.coordinator.get_keyboard_focused_sid();
+        let surface_oid = self.sid_to_surface_info_dict.get(&sid).and_then(|info| info.surface_oid);
+        if let Some(surface_oid) = surface_oid {
+            send!(zwp_text_input_v3::enter(&self.socket, text_input_oid, surface_oid));
+        }
     }
 
-    fn get_positioner(&mut self, oid: wl::ObjectId) -> Option<PositionerInfo> {
-        if let Some(positioner) = self.positioners.get(&oid) {
-            Some(*positioner)
+    fn commit_text_input(&mut self, text_input_oid: wl::ObjectId, state: TextInputState) {
+        let commit_count = if let Some(count) = self.text_inputs.get_mut(&text_input_oid) {
+            *count += 1;
+            *count
         } else {
-            None
+            log_error!("Unknown text input object ID: {}", text_input_oid);
+            return;
+        };
+
+        let is_active = match self.mediator.borrow().get_active_text_input() {
+            Some(text_input) => {
+                text_input.cid == self.client_id && text_input.text_input_oid == text_input_oid
+            }
+            None => false,
+        };
+
+        if !state.is_enabled {
+            if is_active {
+                self.deactivate_text_input();
+            }
+            return;
         }
-    }
 
-    fn remove_positioner(&mut self, oid: wl::ObjectId) {
-        self.positioners.remove(&oid);
-    }
+        // Text input may be enabled only on focused surface.
+        let sid = self.coordinator.get_keyboard_focused_sid();
+        if !self.sid_to_surface_info_dict.contains_key(&sid) {
+            return;
+        }
 
-    fn set_transfer(&mut self, oid: wl::ObjectId, transfer: Transfer) {
-        self.transfers.insert(oid, transfer);
+        self.mediator.borrow_mut().set_active_text_input(Some(ActiveTextInput {
+            cid: self.client_id,
+            socket: self.socket.clone(),
+            text_input_oid: text_input_oid,
+            sid: sid,
+            commit_count: commit_count,
+            cursor_rectangle: state.cursor_rectangle,
+        }));
+
+        if let Some(input_method) = self.mediator.borrow().get_input_method() {
+            let socket = &input_method.socket;
+            let oid = input_method.input_method_oid;
+            if !is_active {
+                send!(zwp_input_method_v2::activate(socket, oid));
+            }
+            if let Some((ref text, cursor, anchor)) = state.surrounding_text {
+                let cursor = cursor.max(0) as u32;
+                let anchor = anchor.max(0) as u32;
+                send!(zwp_input_method_v2::surrounding_text(socket, oid, text, cursor, anchor));
+            }
+            send!(zwp_input_method_v2::text_change_cause(socket, oid, state.change_cause));
+            send!(zwp_input_method_v2::content_type(socket,
+                                                    oid,
+                                                    state.content_hint,
+                                                    state.content_purpose));
+            send!(zwp_input_method_v2::done(socket, oid));
+        }
+
+        self.place_input_popups();
     }
 
-    fn get_transfer(&mut self, oid: wl::ObjectId) -> Option<Transfer> {
-        if let Some(transfer) = self.transfers.get(&oid) {
-            Some(transfer.clone())
-        } else {
-            None
+    fn remove_text_input(&mut self, text_input_oid: wl::ObjectId) {
+        let is_active = match self.mediator.borrow().get_active_text_input() {
+            Some(text_input) => {
+                text_input.cid == self.client_id && text_input.text_input_oid == text_input_oid
+            }
+            None => false,
+        };
+        if is_active {
+            self.deactivate_text_input();
         }
+        self.text_inputs.remove(&text_input_oid);
     }
 
-    fn select_transfer(&mut self, data_source_oid: wl::ObjectId) {
-        if let Some(transfer) = self.transfers.get(&data_source_oid) {
-            self.data_source_oid = Some(data_source_oid);
-            self.mediator.borrow_mut().register_transfer_offerer(Some(self.client_id));
-            self.coordinator.set_transfer(Some(transfer.clone()));
-        } else {
-            log_warn2!("Transfer cannot be selected");
+    fn add_input_method(&mut self, input_method_oid: wl::ObjectId) -> bool {
+        if self.mediator.borrow().get_input_method().is_some() {
+            return false;
+        }
+
+        self.mediator.borrow_mut().register_input_method(Some(InputMethod {
+            cid: self.client_id,
+            socket: self.socket.clone(),
+            input_method_oid: input_method_oid,
+            popups: HashMap::new(),
+            keyboard_grab_oid: None,
+        }));
+
+        // Text input may have been enabled before input method appeared.
+        if self.mediator.borrow().get_active_text_input().is_some() {
+            send!(zwp_input_method_v2::activate(&self.socket, input_method_oid));
+            send!(zwp_input_method_v2::done(&self.socket, input_method_oid));
         }
+        true
     }
 
-    fn remove_transfer(&mut self, oid: wl::ObjectId) {
-        self.transfers.remove(&oid);
+    fn commit_input_method(&mut self, input_method_oid: wl::ObjectId, state: InputMethodState) {
+        if !self.is_input_method(input_method_oid) {
+            return;
+        }
+
+        if let Some(text_input) = self.mediator.borrow().get_active_text_input() {
+            let socket = &text_input.socket;
+            let oid = text_input.text_input_oid;
+            if let Some((ref text, begin, end)) = state.preedit_string {
+                send!(zwp_text_input_v3::preedit_string(socket, oid, text, begin, end));
+            }
+            let (before_length, after_length) = state.delete_surrounding_text;
+            if before_length != 0 || after_length != 0 {
+                send!(zwp_text_input_v3::delete_surrounding_text(socket,
+                                                                 oid,
+                                                                 before_length,
+                                                                 after_length));
+            }
+            if let Some(ref text) = state.commit_string {
+                send!(zwp_text_input_v3::commit_string(socket, oid, text));
+            }
+            send!(zwp_text_input_v3::done(socket, oid, text_input.commit_count));
+        }
     }
 
-    fn request_transfer(&mut self, mime_type: String, fd: RawFd) {
-        self.coordinator.request_transfer(mime_type, fd);
+    fn remove_input_method(&mut self, input_method_oid: wl::ObjectId) {
+        if self.is_input_method(input_method_oid) {
+            self.hide_input_popups();
+            self.mediator.borrow_mut().register_input_method(None);
+        }
     }
 
-    fn set_input_region(&self, sid: SurfaceId, region_oid: wl::ObjectId) {
-        if let Some(region) = self.regions.get(&region_oid) {
-            self.coordinator.set_surface_offset(sid, region.pos);
-            self.coordinator.set_surface_requested_size(sid, region.size);
+    fn add_input_popup(&mut self, popup_oid: wl::ObjectId, surface_oid: wl::ObjectId) -> bool {
+        let sid = if let Some(&sid) = self.surface_oid_to_sid_dict.get(&surface_oid) {
+            sid
         } else {
-            // TODO: Implement reseting input region.
+            return false;
+        };
+
+        let has_role = self.sid_to_surface_info_dict
+            .get(&sid)
+            .map_or(false, |info| info.shell_surface_oid.is_some());
+        if has_role {
+            return false;
         }
-    }
 
-    fn create_surface(&mut self, oid: wl::ObjectId) -> SurfaceId {
-        let sid = self.coordinator.create_surface();
-        self.relate_sid_with_surface(sid, oid);
-        self.mediator.borrow_mut().relate_sid_to_client(sid, self.client_id);
-        sid
+        self.relate_sid_with_shell_surface(sid, ShellSurfaceOid::InputPopup(popup_oid));
+        let cid = self.client_id;
+        if let Some(input_method) = self.mediator.borrow_mut().get_input_method_mut() {
+            if input_method.cid == cid {
+                input_method.popups.insert(popup_oid, sid);
+            }
+        }
+        self.place_input_popups();
+        true
     }
 
-    fn destroy_surface(&self, sid: SurfaceId) {
-        self.coordinator.destroy_surface(sid)
+    fn grab_input_method_keyboard(&mut self,
+                                  input_method_oid: wl::ObjectId,
+                                  grab_oid: wl::ObjectId) {
+        if self.is_input_method(input_method_oid) {
+            if let Some(input_method) = self.mediator.borrow_mut().get_input_method_mut() {
+                input_method.keyboard_grab_oid = Some(grab_oid);
+            }
+        }
     }
 
-    fn attach(&mut self, buffer_oid: wl::ObjectId, sid: SurfaceId, _x: i32, _y: i32) {
-        if buffer_oid.is_null() {
-            // Client wants to unmap this surface
-            // TODO: This should be done on commit
-            self.coordinator.unrelate_surface(sid);
-            self.coordinator.detach_surface(sid)
-        } else if let Some(info) = self.buffer_oid_to_info_dict.get(&buffer_oid).cloned() {
-            self.relate_sid_with_buffer(sid, buffer_oid);
-            match info {
-                BufferInfo::Shm { mpid, mvid } => {
-                    self.coordinator.attach_shm(mvid, sid);
-                }
-                BufferInfo::EglImage { eiid } => {
-                    self.coordinator.attach_egl_image(eiid, sid);
-                }
-                BufferInfo::Dmabuf { dbid } => {
-                    self.coordinator.attach_dmabuf(dbid, sid);
-                }
+    fn release_input_method_keyboard(&mut self, grab_oid: wl::ObjectId) {
+        let cid = self.client_id;
+        if let Some(input_method) = self.mediator.borrow_mut().get_input_method_mut() {
+            if input_method.cid == cid && input_method.keyboard_grab_oid == Some(grab_oid) {
+                input_method.keyboard_grab_oid = None;
             }
-        } else {
-            log_error!("Unknown buffer object ID: {}", buffer_oid);
         }
     }
 
-    fn commit(&self, sid: SurfaceId) {
-        self.coordinator.commit_surface(sid);
+    fn add_virtual_keyboard(&mut self) -> bool {
+        // Other clients could use virtual keyboard to inject keys into any surface.
+        self.is_input_method_client()
     }
 
-    fn set_frame(&mut self, sid: SurfaceId, frame_oid: wl::ObjectId) {
-        self.relate_sid_with_frame(sid, frame_oid);
+    fn emit_virtual_key(&mut self, key: Key) {
+        if self.is_input_method_client() {
+            self.mediator.borrow_mut().push_virtual_keyboard_event(VirtualKeyboardEvent::Key(key));
+        }
     }
 
-    fn show(&mut self,
-            surface_oid: wl::ObjectId,
-            shell_surface_oid: ShellSurfaceOid,
-            reason: show_reason::ShowReason) {
-        if let Some(&sid) = self.surface_oid_to_sid_dict.get(&surface_oid) {
-            self.relate_sid_with_shell_surface(sid, shell_surface_oid);
-            self.coordinator.show_surface(sid, reason);
-        } else {
-            log_error!("Unknown surface object ID: {}", surface_oid);
+    fn emit_virtual_modifiers(&mut self, mods: KeyMods) {
+        if self.is_input_method_client() {
+            let event = VirtualKeyboardEvent::Modifiers(mods);
+            self.mediator.borrow_mut().push_virtual_keyboard_event(event);
         }
     }
 
-    fn hide(&mut self, surface_oid: wl::ObjectId, reason: show_reason::ShowReason) {
-        if let Some(&sid) = self.surface_oid_to_sid_dict.get(&surface_oid) {
-            self.coordinator.hide_surface(sid, reason);
+    fn remove_input_popup(&mut self, popup_oid: wl::ObjectId) {
+        let cid = self.client_id;
+        let sid = match self.mediator.borrow_mut().get_input_method_mut() {
+            Some(ref mut input_method) if input_method.cid == cid => {
+                input_method.popups.remove(&popup_oid)
+            }
+            _ => None,
+        };
+        if let Some(sid) = sid {
+            if self.mediator.borrow().get_active_text_input().is_some() {
+                self.coordinator.unrelate_surface(sid);
+            }
             self.unrelate_sid_with_shell_surface(sid);
+        }
+    }
+
+    fn grab_popup(&mut self,
+                  surface_oid: wl::ObjectId,
+                  parent_surface_oid: wl::ObjectId,
+                  popup_oid: PopupOid) {
+        let sid = if let Some(&sid) = self.surface_oid_to_sid_dict.get(&surface_oid) {
+            sid
         } else {
             log_error!("Unknown surface object ID: {}", surface_oid);
+            return;
+        };
+
+        if self.is_grabbing_popup(sid) {
+            return;
+        }
+
+        let is_grab_free = match self.mediator.borrow().get_popup_grabber() {
+            Some(client_id) => client_id == self.client_id,
+            None => true,
+        };
+
+        let old_oid = self.get_keyboard_focused_oid();
+        let grab = PopupGrab {
+            sid: sid,
+            surface_oid: surface_oid,
+            parent_surface_oid: parent_surface_oid,
+            popup_oid: popup_oid,
+        };
+
+        if is_grab_free && self.popup_grabs.push(grab) {
+            self.update_popup_grabber();
+            self.send_keyboard_focus_change(old_oid, Some(surface_oid));
+        } else {
+            log_warn3!("Refusing grab for pop-up {:?}", popup_oid);
+            self.send_popup_done(popup_oid);
         }
     }
 
-    fn set_offset(&self, sid: SurfaceId, offset: Vector) {
-        self.coordinator.set_surface_offset(sid, offset);
+    fn ungrab_popup(&mut self, surface_oid: wl::ObjectId) {
+        if let Some(index) = self.popup_grabs.find(surface_oid) {
+            let old_oid = self.get_keyboard_focused_oid();
+            self.truncate_popup_grabs(index, Some(surface_oid));
+            let new_oid = self.get_keyboard_focused_oid();
+            self.send_keyboard_focus_change(old_oid, new_oid);
+        }
     }
 
-    fn set_requested_size(&self, sid: SurfaceId, size: Size) {
-        self.coordinator.set_surface_requested_size(sid, size);
+    fn add_parentless_popup(&mut self,
+                            popup_oid: wl::ObjectId,
+                            surface_oid: wl::ObjectId,
+                            position: Position) {
+        self.parentless_popups.insert(popup_oid, (surface_oid, position));
     }
 
-    fn set_relative_position(&self, surface_oid: wl::ObjectId, x: isize, y: isize) {
-        if let Some(&sid) = self.surface_oid_to_sid_dict.get(&surface_oid) {
-            let position = Position::new(x, y);
-            self.coordinator.set_surface_relative_position(sid, position);
-        }
+    fn remove_parentless_popup(&mut self, popup_oid: wl::ObjectId) {
+        self.parentless_popups.remove(&popup_oid);
     }
 
-    fn relate(&self, surface_oid: wl::ObjectId, parent_surface_oid: wl::ObjectId) {
-        if let Some(&sid) = self.surface_oid_to_sid_dict.get(&surface_oid) {
-            if let Some(&parent_sid) = self.surface_oid_to_sid_dict.get(&parent_surface_oid) {
-                self.coordinator.relate_surfaces(sid, parent_sid);
-            }
-            self.coordinator.set_surface_relative_position(sid, Position::default());
+    fn adopt_popup(&mut self, popup_oid: wl::ObjectId, parent_surface_oid: wl::ObjectId) -> bool {
+        if let Some((surface_oid, position)) = self.parentless_popups.remove(&popup_oid) {
+            self.relate(surface_oid, parent_surface_oid);
+            self.set_relative_position(surface_oid, position.x, position.y);
+            true
+        } else {
+            false
         }
     }
 
-    fn unrelate(&self, surface_oid: wl::ObjectId) {
-        if let Some(&sid) = self.surface_oid_to_sid_dict.get(&surface_oid) {
-            self.coordinator.unrelate_surface(sid);
-        }
+    fn get_parent_surface_oid(&self, surface_oid: wl::ObjectId) -> Option<wl::ObjectId> {
+        self.surface_oid_to_sid_dict
+            .get(&surface_oid)
+            .and_then(|&sid| self.coordinator.get_surface(sid))
+            .and_then(|info| self.sid_to_surface_info_dict.get(&info.parent_sid))
+            .and_then(|info| info.surface_oid)
     }
 
     fn set_as_cursor(&self, surface_oid: wl::ObjectId, hotspot_x: isize, hotspot_y: isize) {
@@ -584,8 +2187,26 @@ impl Facade for Proxy {
         }
     }
 
-    fn relate_output_oid_with_id(&mut self, oid: wl::ObjectId, id: i32) {
+    fn relate_output_oid_with_id(&mut self, oid: wl::ObjectId, id: i32, version: u32) {
         self.output_oid_to_id.insert(oid, id);
+        self.output_oid_to_version.insert(oid, version);
+    }
+
+    fn unrelate_output_oid(&mut self, oid: wl::ObjectId) {
+        self.output_oid_to_id.remove(&oid);
+        self.output_oid_to_version.remove(&oid);
+    }
+
+    fn bind_xdg_output(&mut self, oid: wl::ObjectId, output_oid: wl::ObjectId, version: u32) {
+        self.xdg_outputs.insert(oid,
+                                XdgOutputInfo {
+                                    output_oid: output_oid,
+                                    version: version,
+                                });
+    }
+
+    fn unbind_xdg_output(&mut self, oid: wl::ObjectId) {
+        self.xdg_outputs.remove(&oid);
     }
 
     fn take_screenshot(&mut self,
@@ -633,7 +2254,28 @@ impl Gateway for Proxy {
     fn on_output_found(&mut self, _bundle: DrmBundle) {}
 
     fn on_display_created(&mut self, output_info: OutputInfo) {
-        self.register_global(protocol::output::get_global(output_info));
+        self.register_global(protocol::output::get_global(output_info.id));
+        self.output_infos.insert(output_info.id, output_info);
+    }
+
+    fn on_display_updated(&mut self, output_info: OutputInfo) {
+        for (oid, id) in self.output_oid_to_id.iter() {
+            if *id == output_info.id {
+                let version = self.output_oid_to_version.get(oid).cloned().unwrap_or(1);
+                protocol::output::send_info(&self.socket, *oid, version, &output_info);
+                for (xdg_oid, xdg_info) in self.xdg_outputs.iter() {
+                    if xdg_info.output_oid == *oid {
+                        protocol::xdg_output::send_info(&self.socket,
+                                                        *xdg_oid,
+                                                        xdg_info.version,
+                                                        &output_info);
+                        protocol::xdg_output::send_done(&self.socket, *xdg_oid, xdg_info.version);
+                    }
+                }
+                protocol::output::send_done(&self.socket, *oid, version);
+            }
+        }
+        self.output_infos.insert(output_info.id, output_info);
     }
 
     fn on_keyboard_input(&mut self, key: Key, mods: Option<KeyMods>) {
@@ -671,10 +2313,63 @@ impl Gateway for Proxy {
                 send!(wl_buffer::release(&self.socket, buffer_oid));
             }
             info.buffer_oid = None;
+
+            let feedback_oids = info.committed_feedback_oids.drain(..);
+            info.drawn_feedback_oids.extend(feedback_oids);
+            info.is_drawn = true;
+        }
+        self.inhibit_idleness(sid);
+    }
+
+    fn on_surface_presented(&mut self, sid: SurfaceId, display_id: i32, time: PresentationTime) {
+        if let Some(info) = self.sid_to_surface_info_dict.get_mut(&sid) {
+            let refresh = self.output_infos
+                .get(&display_id)
+                .and_then(|output_info| if output_info.refresh_rate > 0 {
+                    Some(1_000_000_000 / output_info.refresh_rate as u32)
+                } else {
+                    None
+                })
+                .unwrap_or(0);
+            let flags = wp_presentation_feedback::kind::VSYNC |
+                        wp_presentation_feedback::kind::HW_CLOCK |
+                        wp_presentation_feedback::kind::HW_COMPLETION;
+
+            for feedback_oid in info.drawn_feedback_oids.drain(..) {
+                for (output_oid, id) in self.output_oid_to_id.iter() {
+                    if *id == display_id {
+                        send!(wp_presentation_feedback::sync_output(&self.socket,
+                                                                    feedback_oid,
+                                                                    *output_oid));
+                    }
+                }
+                send!(wp_presentation_feedback::presented(&self.socket,
+                                                          feedback_oid,
+                                                          (time.seconds >> 32) as u32,
+                                                          time.seconds as u32,
+                                                          time.nanoseconds,
+                                                          refresh,
+                                                          (time.sequence >> 32) as u32,
+                                                          time.sequence as u32,
+                                                          flags));
+                send!(wl_display::delete_id(&self.socket,
+                                            wl::DISPLAY_ID,
+                                            feedback_oid.get_value()));
+            }
+        }
+    }
+
+    fn on_surface_discarded(&mut self, sid: SurfaceId) {
+        if let Some(info) = self.sid_to_surface_info_dict.get_mut(&sid) {
+            for feedback_oid in info.committed_feedback_oids.drain(..) {
+                Self::discard_presentation_feedback(&self.socket, feedback_oid);
+            }
+            info.is_drawn = false;
         }
+        self.uninhibit_idleness(sid);
     }
 
-    fn on_pointer_focus_changed(&self,
+    fn on_pointer_focus_changed(&mut self,
                                 old_sid: SurfaceId,
                                 new_sid: SurfaceId,
                                 position: Position) {
@@ -706,7 +2401,7 @@ impl Gateway for Proxy {
         }
     }
 
-    fn on_pointer_relative_motion(&self,
+    fn on_pointer_relative_motion(&mut self,
                                   _sid: SurfaceId,
                                   position: Position,
                                   milliseconds: Milliseconds) {
@@ -719,11 +2414,67 @@ impl Gateway for Proxy {
         }
     }
 
-    fn on_pointer_button(&self, btn: Button) {
+    fn on_pointer_delta(&mut self, _sid: SurfaceId, vector: Vector, milliseconds: Milliseconds) {
+        // There is no pointer acceleration so accelerated and unaccelerated motions are equal.
+        let utime = 1000 * milliseconds.get_value();
+        for relative_pointer_oid in self.relative_pointer_oids.iter() {
+            send!(zwp_relative_pointer_v1::relative_motion(&self.socket,
+                                                           *relative_pointer_oid,
+                                                           (utime >> 32) as u32,
+                                                           utime as u32,
+                                                           vector.x as f32,
+                                                           vector.y as f32,
+                                                           vector.x as f32,
+                                                           vector.y as f32));
+        }
+    }
+
+    fn on_pointer_constraint_changed(&mut self, sid: SurfaceId, is_active: bool) {
+        let is_defunct = if let Some(info) = self.pointer_constraints.get_mut(&sid) {
+            if info.is_active == is_active {
+                return;
+            }
+
+            info.is_active = is_active;
+            match (info.constraint_oid, is_active) {
+                (PointerConstraintOid::Locked(oid), true) => {
+                    send!(zwp_locked_pointer_v1::locked(&self.socket, oid));
+                }
+                (PointerConstraintOid::Locked(oid), false) => {
+                    send!(zwp_locked_pointer_v1::unlocked(&self.socket, oid));
+                }
+                (PointerConstraintOid::Confined(oid), true) => {
+                    send!(zwp_confined_pointer_v1::confined(&self.socket, oid));
+                }
+                (PointerConstraintOid::Confined(oid), false) => {
+                    send!(zwp_confined_pointer_v1::unconfined(&self.socket, oid));
+                }
+            }
+            !is_active && !info.is_persistent
+        } else {
+            false
+        };
+
+        // One-shot constraint can not be activated again.
+        if is_defunct {
+            self.pointer_constraints.remove(&sid);
+            self.coordinator.set_surface_pointer_constraint(sid, None);
+        }
+    }
+
+    fn on_pointer_button(&mut self, btn: Button) {
         let serial = self.socket.get_next_serial();
         let state = if btn.value == 0 {
+            if self.button_press.map_or(false, |press| press.code == btn.code) {
+                self.button_press = None;
+            }
             wl_pointer::button_state::RELEASED
         } else {
+            self.button_press = Some(ButtonPress {
+                code: btn.code,
+                serial: serial,
+                sid: self.coordinator.get_pointer_focused_sid(),
+            });
             wl_pointer::button_state::PRESSED
         };
 
@@ -793,7 +2544,77 @@ impl Gateway for Proxy {
         }
     }
 
+    fn on_touch_down(&mut self, sid: SurfaceId, id: i32, position: Position, time: Milliseconds) {
+        if let Some(surface_info) = self.sid_to_surface_info_dict.get(&sid) {
+            if let Some(surface_oid) = surface_info.surface_oid {
+                for touch_oid in self.touch_oids.iter() {
+                    let serial = self.socket.get_next_serial();
+                    send!(wl_touch::down(&self.socket,
+                                         *touch_oid,
+                                         serial,
+                                         time.get_value() as u32,
+                                         surface_oid,
+                                         id,
+                                         position.x as f32,
+                                         position.y as f32));
+                }
+                self.is_touch_frame_pending = true;
+            }
+        }
+    }
+
+    fn on_touch_motion(&mut self,
+                       _sid: SurfaceId,
+                       id: i32,
+                       position: Position,
+                       time: Milliseconds) {
+        for touch_oid in self.touch_oids.iter() {
+            send!(wl_touch::motion(&self.socket,
+                                   *touch_oid,
+                                   time.get_value() as u32,
+                                   id,
+                                   position.x as f32,
+                                   position.y as f32));
+        }
+        self.is_touch_frame_pending = true;
+    }
+
+    fn on_touch_up(&mut self, _sid: SurfaceId, id: i32, time: Milliseconds) {
+        for touch_oid in self.touch_oids.iter() {
+            let serial = self.socket.get_next_serial();
+            send!(wl_touch::up(&self.socket, *touch_oid, serial, time.get_value() as u32, id));
+        }
+        self.is_touch_frame_pending = true;
+    }
+
+    fn on_touch_frame(&mut self) {
+        if self.is_touch_frame_pending {
+            self.is_touch_frame_pending = false;
+            for touch_oid in self.touch_oids.iter() {
+                send!(wl_touch::frame(&self.socket, *touch_oid));
+            }
+        }
+    }
+
     fn on_keyboard_focus_changed(&mut self, old_sid: SurfaceId, new_sid: SurfaceId) {
+        self.send_text_input_focus_change(old_sid, new_sid);
+
+        let old_sid = if self.popup_grabs.is_empty() {
+            old_sid
+        } else {
+            // Focus change breaks the grab. Previously focused surface was already left when
+            // pop-ups took keyboard focus so only the topmost pop-up has to be left.
+            let old_oid = self.get_keyboard_focused_oid();
+            self.truncate_popup_grabs(0, None);
+            self.send_keyboard_focus_change(old_oid, None);
+            if let Some(window_info) = self.coordinator.get_surface(old_sid) {
+                self.on_surface_reconfigured(old_sid,
+                                             window_info.desired_size,
+                                             window_info.state_flags);
+            }
+            SurfaceId::invalid()
+        };
+
         if old_sid != SurfaceId::invalid() {
             if let Some(surface_info) = self.sid_to_surface_info_dict.get(&old_sid) {
                 if let Some(surface_oid) = surface_info.surface_oid {
@@ -846,10 +2667,63 @@ impl Gateway for Proxy {
         }
     }
 
+    /// Cancels primary selection source of this client if other client took over the selection.
+    fn on_primary_transfer_offered(&mut self) {
+        if self.mediator.borrow().get_primary_transfer_offerer() != Some(self.client_id) {
+            if let Some(source_oid) = self.primary_source_oid.take() {
+                send!(zwp_primary_selection_source_v1::cancelled(&self.socket, source_oid));
+            }
+        }
+    }
+
+    fn on_primary_transfer_requested(&mut self, mime_type: String, fd: RawFd) {
+        if let Some(source_oid) = self.primary_source_oid {
+            send!(zwp_primary_selection_source_v1::send(&self.socket, source_oid, &mime_type, fd));
+        }
+    }
+
+    fn on_system_activity(&mut self) {
+        for (notification_oid, info) in self.idle_notifications.iter_mut() {
+            if info.is_idle {
+                info.is_idle = false;
+                send!(ext_idle_notification_v1::resumed(&self.socket, *notification_oid));
+            }
+        }
+    }
+
+    fn on_timer_500(&mut self) {
+        if self.idle_notifications.is_empty() {
+            return;
+        }
+
+        let (idle_time, input_idle_time) = {
+            let now = Instant::now();
+            let mediator = self.mediator.borrow();
+            let tracker = mediator.get_idle_tracker();
+            (tracker.get_idle_time(now), tracker.get_input_idle_time(now))
+        };
+
+        for (notification_oid, info) in self.idle_notifications.iter_mut() {
+            let time = if info.respects_inhibitors { idle_time } else { input_idle_time };
+            if !info.is_idle && (time >= info.timeout) {
+                info.is_idle = true;
+                send!(ext_idle_notification_v1::idled(&self.socket, *notification_oid));
+            }
+        }
+    }
+
     fn on_surface_reconfigured(&self,
                                sid: SurfaceId,
                                size: Size,
                                state_flags: surface_state::SurfaceState) {
+        // Clients are asked only for size of contents, without decorations drawn by compositor.
+        let size = if let Some(surface) = self.coordinator.get_surface(sid) {
+            let margins = surface.get_decoration_margins();
+            Area::new(Position::default(), size).shrunk(&margins).size
+        } else {
+            size
+        };
+
         if let Some(info) = self.sid_to_surface_info_dict.get(&sid) {
             if let Some(shell_surface) = info.shell_surface_oid {
                 match shell_surface {
@@ -862,11 +2736,19 @@ impl Gateway for Proxy {
                     }
                     ShellSurfaceOid::ZxdgToplevelV6(shell_surface_oid, shell_toplevel_oid) => {
                         let mut pos = 0;
-                        let mut states: [u32; 2] = [0; 2];
+                        let mut states: [u32; 4] = [0; 4];
                         if state_flags.intersects(surface_state::MAXIMIZED) {
                             states[pos] = zxdg_toplevel_v6::state::MAXIMIZED;
                             pos += 1;
                         }
+                        if state_flags.intersects(surface_state::FULLSCREEN) {
+                            states[pos] = zxdg_toplevel_v6::state::FULLSCREEN;
+                            pos += 1;
+                        }
+                        if state_flags.intersects(surface_state::RESIZING) {
+                            states[pos] = zxdg_toplevel_v6::state::RESIZING;
+                            pos += 1;
+                        }
                         if sid == self.coordinator.get_keyboard_focused_sid() {
                             states[pos] = zxdg_toplevel_v6::state::ACTIVATED;
                             pos += 1;
@@ -879,6 +2761,44 @@ impl Gateway for Proxy {
                         let serial = self.socket.get_next_serial();
                         send!(zxdg_surface_v6::configure(&self.socket, shell_surface_oid, serial));
                     }
+                    ShellSurfaceOid::XdgToplevel(shell_surface_oid, shell_toplevel_oid) => {
+                        let mut pos = 0;
+                        let mut states: [u32; 4] = [0; 4];
+                        if state_flags.intersects(surface_state::MAXIMIZED) {
+                            states[pos] = xdg_toplevel::state::MAXIMIZED;
+                            pos += 1;
+                        }
+                        if state_flags.intersects(surface_state::FULLSCREEN) {
+                            states[pos] = xdg_toplevel::state::FULLSCREEN;
+                            pos += 1;
+                        }
+                        if state_flags.intersects(surface_state::RESIZING) {
+                            states[pos] = xdg_toplevel::state::RESIZING;
+                            pos += 1;
+                        }
+                        if sid == self.coordinator.get_keyboard_focused_sid() {
+                            states[pos] = xdg_toplevel::state::ACTIVATED;
+                            pos += 1;
+                        }
+                        send!(xdg_toplevel::configure(&self.socket,
+                                                      shell_toplevel_oid,
+                                                      size.width as i32,
+                                                      size.height as i32,
+                                                      &states[0..pos]));
+                        let serial = self.socket.get_next_serial();
+                        send!(xdg_surface::configure(&self.socket, shell_surface_oid, serial));
+                    }
+                    ShellSurfaceOid::Layer(layer_surface_oid) => {
+                        let serial = self.socket.get_next_serial();
+                        send!(zwlr_layer_surface_v1::configure(&self.socket,
+                                                               layer_surface_oid,
+                                                               serial,
+                                                               size.width as u32,
+                                                               size.height as u32));
+                    }
+                    ShellSurfaceOid::InputPopup(_) => {
+                        // Input method pop-ups are not configurable
+                    }
                 }
             } else {
                 log_warn3!("Received reconfiguration request for surface {:?} \
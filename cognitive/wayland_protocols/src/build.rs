@@ -0,0 +1,54 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! Generates server-side bindings for protocols listed in `PROTOCOLS`.
+
+extern crate skylane_scanner;
+
+use std::env;
+use std::fs::File;
+use std::io::Write;
+use std::path::PathBuf;
+
+/// Names of XML files (without extension) from `protocols` directory.
+const PROTOCOLS: &'static [&'static str] = &["xdg-shell",
+                                              "primary-selection-unstable-v1",
+                                              "xdg-output-unstable-v1",
+                                              "viewporter",
+                                              "presentation-time",
+                                              "wlr-layer-shell-unstable-v1",
+                                              "xdg-decoration-unstable-v1",
+                                              "relative-pointer-unstable-v1",
+                                              "pointer-constraints-unstable-v1",
+                                              "idle-inhibit-unstable-v1",
+                                              "ext-idle-notify-v1",
+                                              "text-input-unstable-v3",
+                                              "input-method-unstable-v2",
+                                              "virtual-keyboard-unstable-v1"];
+
+fn main() {
+    let out_dir = env::var("OUT_DIR").expect("Read OUT_DIR variable");
+    let src_dir = env::current_dir().expect("Get current directory");
+
+    for protocol in PROTOCOLS {
+        let mut src_path = src_dir.clone();
+        src_path.push("protocols");
+        src_path.push(protocol);
+        src_path.set_extension("xml");
+        println!("cargo:rerun-if-changed={}", src_path.display());
+
+        let mut scanner =
+            skylane_scanner::Scanner::new(&src_path)
+                .expect(format!("Initialize scanner for file {:?}", &src_path).as_str());
+        let protocol_name = scanner.get_protocol_name().expect("Extract protocol name");
+
+        let mut dst_server_path = PathBuf::new();
+        dst_server_path.push(&out_dir);
+        dst_server_path.push(format!("{}_server", protocol_name));
+        dst_server_path.set_extension("rs");
+
+        let mut server_file = File::create(&dst_server_path).expect("Create file");
+        server_file.write_all(scanner.generate_server_interface(0).as_bytes())
+            .expect(format!("Write to file: {:?}", &dst_server_path).as_str());
+    }
+}
@@ -0,0 +1,155 @@
+// This Source Code Form is subject to the terms of the Mozilla Public License, v. 2.0. If a copy of
+// the MPL was not distributed with this file, You can obtain one at http://mozilla.org/MPL/2.0/
+
+//! This crate supplements `skylane_protocols` with server-side bindings for Wayland protocols it
+//! does not provide yet. Bindings are generated from XML files in `protocols` directory using
+//! `skylane_scanner`, exactly the same way as in `skylane_protocols`.
+//!
+//! `Dispatcher` trait is private in `skylane_protocols` so this crate provides its own `Handler`
+//! binding `Dispatcher`s generated here with objects implementing their `Interface`s.
+
+extern crate skylane;
+extern crate byteorder;
+
+// -------------------------------------------------------------------------------------------------
+
+mod private {
+    use std::io::Cursor;
+    use skylane::server::{Bundle, Header, Object, SkylaneError, Task};
+
+    /// This trait is implemented by `Dispatcher`s in protocol definitions generated from XML
+    /// files. Every object defined in protocol has its own `Dispatcher` which takes buffer
+    /// data, parses it and calls appropriate method of given object which implements attached
+    /// interface.
+    pub trait Dispatcher<I> {
+        /// Constructs new `Dispatcher`.
+        fn new() -> Self;
+
+        /// Demarshals message and call appropriate callback in passed `object`.
+        fn dispatch(&mut self,
+                    object: &mut I,
+                    bundle: &mut Bundle,
+                    header: &Header,
+                    bytes_buf: &mut Cursor<&[u8]>,
+                    fds_buf: &mut Cursor<&[u8]>)
+                    -> Result<Task, SkylaneError>;
+    }
+
+    /// Binds `Dispatcher` with object implementing `Interface` trait from protocol definition.
+    pub struct Handler<I, D>
+        where D: Dispatcher<I>
+    {
+        object: I,
+        dispatcher: D,
+    }
+
+    impl<I, D> Handler<I, D>
+        where D: Dispatcher<I>
+    {
+        /// Constructs new `Handler`.
+        pub fn new(object: I) -> Self {
+            Handler {
+                object: object,
+                dispatcher: D::new(),
+            }
+        }
+    }
+
+    impl<I, D> Object for Handler<I, D>
+        where D: Dispatcher<I>
+    {
+        fn dispatch(&mut self,
+                    bundle: &mut Bundle,
+                    header: &Header,
+                    bytes_buf: &mut Cursor<&[u8]>,
+                    fds_buf: &mut Cursor<&[u8]>)
+                    -> Result<Task, SkylaneError> {
+            self.dispatcher.dispatch(&mut self.object, bundle, header, bytes_buf, fds_buf)
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Server-side protocols.
+// Generated code uses `std::mem::uninitialized` for output buffers.
+#[allow(deprecated, invalid_value)]
+pub mod server {
+    use skylane::server::{Bundle, Header, ObjectId, SkylaneError, Socket, Task};
+    use private::Dispatcher;
+    pub use private::Handler;
+
+    /// Protocol generated from `xdg-shell.xml`
+    pub mod xdg_shell {
+        include!(concat!(env!("OUT_DIR"), "/xdg_shell_server.rs"));
+    }
+
+    /// Protocol generated from `primary-selection-unstable-v1.xml`
+    pub mod primary_selection {
+        include!(concat!(env!("OUT_DIR"), "/wp_primary_selection_unstable_v1_server.rs"));
+    }
+
+    /// Protocol generated from `xdg-output-unstable-v1.xml`
+    pub mod xdg_output {
+        include!(concat!(env!("OUT_DIR"), "/xdg_output_unstable_v1_server.rs"));
+    }
+
+    /// Protocol generated from `viewporter.xml`
+    pub mod viewporter {
+        include!(concat!(env!("OUT_DIR"), "/viewporter_server.rs"));
+    }
+
+    /// Protocol generated from `presentation-time.xml`
+    pub mod presentation_time {
+        include!(concat!(env!("OUT_DIR"), "/presentation_time_server.rs"));
+    }
+
+    /// Protocol generated from `wlr-layer-shell-unstable-v1.xml`
+    pub mod wlr_layer_shell_unstable_v1 {
+        include!(concat!(env!("OUT_DIR"), "/wlr_layer_shell_unstable_v1_server.rs"));
+    }
+
+    /// Protocol generated from `xdg-decoration-unstable-v1.xml`
+    pub mod xdg_decoration_unstable_v1 {
+        include!(concat!(env!("OUT_DIR"), "/xdg_decoration_unstable_v1_server.rs"));
+    }
+
+    /// Protocol generated from `relative-pointer-unstable-v1.xml`
+    pub mod relative_pointer_unstable_v1 {
+        include!(concat!(env!("OUT_DIR"), "/relative_pointer_unstable_v1_server.rs"));
+    }
+
+    /// Protocol generated from `pointer-constraints-unstable-v1.xml`
+    pub mod pointer_constraints_unstable_v1 {
+        include!(concat!(env!("OUT_DIR"), "/pointer_constraints_unstable_v1_server.rs"));
+    }
+
+    /// Protocol generated from `idle-inhibit-unstable-v1.xml`
+    // Generated code imports `NativeEndian` even though no request of this protocol has arguments.
+    #[allow(unused_imports)]
+    pub mod idle_inhibit_unstable_v1 {
+        include!(concat!(env!("OUT_DIR"), "/idle_inhibit_unstable_v1_server.rs"));
+    }
+
+    /// Protocol generated from `ext-idle-notify-v1.xml`
+    pub mod ext_idle_notify_v1 {
+        include!(concat!(env!("OUT_DIR"), "/ext_idle_notify_v1_server.rs"));
+    }
+
+    /// Protocol generated from `text-input-unstable-v3.xml`
+    pub mod text_input_unstable_v3 {
+        include!(concat!(env!("OUT_DIR"), "/text_input_unstable_v3_server.rs"));
+    }
+
+    /// Protocol generated from `input-method-unstable-v2.xml`
+    pub mod input_method_unstable_v2 {
+        include!(concat!(env!("OUT_DIR"), "/input_method_unstable_v2_server.rs"));
+    }
+
+    /// Protocol generated from `virtual-keyboard-unstable-v1.xml`
+    pub mod virtual_keyboard_unstable_v1 {
+        include!(concat!(env!("OUT_DIR"), "/virtual_keyboard_unstable_v1_server.rs"));
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
@@ -7,6 +7,7 @@
 
 // -------------------------------------------------------------------------------------------------
 
+use std::collections::HashMap;
 use std::os::unix::io::RawFd;
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
@@ -14,17 +15,18 @@ use std::time::Duration;
 use dharma;
 
 use cognitive_graphics::attributes::{EglAttributes, DmabufAttributes};
-use qualia::{Position, Size, Vector, DmabufId, EglImageId, MemoryPoolId, MemoryViewId};
+use qualia::{Area, Position, Size, Transform, Vector};
+use qualia::{DmabufId, EglImageId, MemoryPoolId, MemoryViewId};
 use qualia::{Buffer, Memory, PixelFormat, GraphicsManagement, WorkspaceState};
-use qualia::{perceptron, Perceptron, Transfer, DrmBundle};
-use qualia::{SurfaceContext, SurfaceId, SurfaceInfo, DataSource};
+use qualia::{perceptron, Perceptron, PresentationTime, Transfer, DrmBundle};
+use qualia::{SurfaceContext, SurfaceId, SurfaceInfo, DataSource, LayerProperties, ViewportSource};
 use qualia::{SurfaceManagement, SurfaceControl, SurfaceViewer};
 use qualia::{SurfaceAccess, SurfaceListing, SurfaceFocusing};
 use qualia::{AppearanceManagement, DataTransferring, EventHandling, StatePublishing};
 use qualia::{MemoryManagement, HwGraphics, WindowManagement, Screenshooting};
 use qualia::{AestheticsCoordinationTrait, ExhibitorCoordinationTrait};
 use qualia::FrontendsCoordinationTrait;
-use qualia::{show_reason, surface_state};
+use qualia::{show_reason, resize_edge, surface_state, PointerConstraint};
 
 use resource_storage::ResourceStorage;
 
@@ -51,6 +53,12 @@ struct InnerCoordinator {
     /// Represents possible data transfer between clients (e.g. copy-paste)
     transfer: Option<Transfer>,
 
+    /// Represents data transfer between clients by drag-and-drop
+    drag_transfer: Option<Transfer>,
+
+    /// Represents data transfer between clients by primary selection (e.g. middle-click paste)
+    primary_transfer: Option<Transfer>,
+
     /// State of workspaces.
     workspace_state: WorkspaceState,
 }
@@ -69,6 +77,8 @@ impl InnerCoordinator {
             kfsid: SurfaceId::invalid(),
             pfsid: SurfaceId::invalid(),
             transfer: None,
+            drag_transfer: None,
+            primary_transfer: None,
             workspace_state: WorkspaceState::empty(),
         }
     }
@@ -114,6 +124,13 @@ impl InnerCoordinator {
         self.signaler.emit(perceptron::NOTIFY, Perceptron::Notify);
     }
 
+    /// Informs other parts of application about request from client to change drag icon surface.
+    pub fn set_surface_as_drag_icon(&mut self, sid: SurfaceId) {
+        self.signaler.emit(perceptron::DRAG_ICON_SURFACE_CHANGE,
+                           Perceptron::DragIconSurfaceChange(sid));
+        self.signaler.emit(perceptron::NOTIFY, Perceptron::Notify);
+    }
+
     /// Emits given signal.
     fn emit(&mut self, id: dharma::SignalId, package: Perceptron) {
         self.signaler.emit(id, package);
@@ -156,8 +173,8 @@ impl InnerCoordinator {
     }
 
     /// Notifies about page flip.
-    fn emit_page_flip(&mut self, display_id: i32) {
-        self.signaler.emit(perceptron::PAGE_FLIP, Perceptron::PageFlip(display_id));
+    fn emit_page_flip(&mut self, display_id: i32, time: PresentationTime) {
+        self.signaler.emit(perceptron::PAGE_FLIP, Perceptron::PageFlip(display_id, time));
     }
 
     /// Sets data transfer information.
@@ -178,6 +195,34 @@ impl InnerCoordinator {
                            Perceptron::TransferRequested(mime_type, fd));
     }
 
+    /// Sets drag-and-drop transfer information.
+    pub fn set_drag_transfer(&mut self, transfer: Option<Transfer>) {
+        self.drag_transfer = transfer;
+    }
+
+    /// Returns drag-and-drop transfer information.
+    pub fn get_drag_transfer(&self) -> Option<Transfer> {
+        self.drag_transfer.clone()
+    }
+
+    /// Sets primary selection data transfer information.
+    pub fn set_primary_transfer(&mut self, transfer: Option<Transfer>) {
+        self.primary_transfer = transfer;
+        self.signaler.emit(perceptron::PRIMARY_TRANSFER_OFFERED,
+                           Perceptron::PrimaryTransferOffered);
+    }
+
+    /// Returns primary selection data transfer information.
+    pub fn get_primary_transfer(&self) -> Option<Transfer> {
+        self.primary_transfer.clone()
+    }
+
+    /// Requests begin of primary selection data transfer to requesting client.
+    pub fn request_primary_transfer(&mut self, mime_type: String, fd: RawFd) {
+        self.signaler.emit(perceptron::PRIMARY_TRANSFER_REQUESTED,
+                           Perceptron::PrimaryTransferRequested(mime_type, fd));
+    }
+
     /// Adds new event handler.
     pub fn add_event_handler(&mut self,
                              event_handler: Box<dharma::EventHandler + Send>,
@@ -309,6 +354,54 @@ impl SurfaceManagement for Coordinator {
         mine.attach_dmabuf(dbid, sid);
     }
 
+    /// Lock and call corresponding method from `InnerCoordinator`.
+    fn set_surface_buffer_scale(&self, sid: SurfaceId, scale: u32) {
+        let mut mine = self.resources.lock().unwrap();
+        mine.set_surface_buffer_scale(sid, scale);
+    }
+
+    /// Lock and call corresponding method from `InnerCoordinator`.
+    fn set_surface_buffer_transform(&self, sid: SurfaceId, transform: Transform) {
+        let mut mine = self.resources.lock().unwrap();
+        mine.set_surface_buffer_transform(sid, transform);
+    }
+
+    /// Lock and call corresponding method from `InnerCoordinator`.
+    fn set_surface_viewport_source(&self, sid: SurfaceId, source: Option<ViewportSource>) {
+        let mut mine = self.resources.lock().unwrap();
+        mine.set_surface_viewport_source(sid, source);
+    }
+
+    /// Lock and call corresponding method from `InnerCoordinator`.
+    fn set_surface_viewport_destination(&self, sid: SurfaceId, destination: Option<Size>) {
+        let mut mine = self.resources.lock().unwrap();
+        mine.set_surface_viewport_destination(sid, destination);
+    }
+
+    /// Lock and call corresponding method from `InnerCoordinator`.
+    fn set_surface_opaque_region(&self, sid: SurfaceId, region: Vec<Area>) {
+        let mut mine = self.resources.lock().unwrap();
+        mine.set_surface_opaque_region(sid, region);
+    }
+
+    /// Lock and call corresponding method from `InnerCoordinator`.
+    fn set_surface_input_region(&self, sid: SurfaceId, region: Option<Vec<Area>>) {
+        let mut mine = self.resources.lock().unwrap();
+        mine.set_surface_input_region(sid, region);
+    }
+
+    /// Lock and call corresponding method from `InnerCoordinator`.
+    fn damage_surface(&self, sid: SurfaceId, area: Area) {
+        let mut mine = self.resources.lock().unwrap();
+        mine.damage_surface(sid, area);
+    }
+
+    /// Lock and call corresponding method from `InnerCoordinator`.
+    fn damage_surface_buffer(&self, sid: SurfaceId, area: Area) {
+        let mut mine = self.resources.lock().unwrap();
+        mine.damage_surface_buffer(sid, area);
+    }
+
     /// Lock and call corresponding method from `InnerCoordinator`.
     fn detach_surface(&self, sid: SurfaceId) {
         let mut mine = self.resources.lock().unwrap();
@@ -343,6 +436,15 @@ impl SurfaceControl for Coordinator {
         mine.dock_surface(sid, size, display_id)
     }
 
+    /// Lock and call corresponding method from `InnerCoordinator`.
+    fn set_surface_layer(&self,
+                         sid: SurfaceId,
+                         properties: LayerProperties,
+                         display_id: Option<i32>) {
+        let mut mine = self.resources.lock().unwrap();
+        mine.set_surface_layer(sid, properties, display_id)
+    }
+
     /// Lock and call corresponding method from `InnerCoordinator`.
     fn hide_surface(&self, sid: SurfaceId, reason: show_reason::ShowReason) {
         let mut mine = self.resources.lock().unwrap();
@@ -378,6 +480,89 @@ impl SurfaceControl for Coordinator {
         let mut mine = self.resources.lock().unwrap();
         mine.unrelate_surface(sid)
     }
+
+    /// Lock and call corresponding method from `InnerCoordinator`.
+    fn place_surface_above(&self, sid: SurfaceId, sibling_sid: SurfaceId) {
+        let mut mine = self.resources.lock().unwrap();
+        mine.place_surface_above(sid, sibling_sid)
+    }
+
+    /// Lock and call corresponding method from `InnerCoordinator`.
+    fn place_surface_below(&self, sid: SurfaceId, sibling_sid: SurfaceId) {
+        let mut mine = self.resources.lock().unwrap();
+        mine.place_surface_below(sid, sibling_sid)
+    }
+
+    /// Lock and call corresponding method from `InnerCoordinator`.
+    fn set_surface_synchronized(&self, sid: SurfaceId, is_synchronized: bool) {
+        let mut mine = self.resources.lock().unwrap();
+        mine.set_surface_synchronized(sid, is_synchronized)
+    }
+
+    /// Lock and call corresponding method from `InnerCoordinator`.
+    fn set_surface_title(&self, sid: SurfaceId, title: String) {
+        let mut mine = self.resources.lock().unwrap();
+        mine.set_surface_title(sid, title)
+    }
+
+    /// Lock and call corresponding method from `InnerCoordinator`.
+    fn set_surface_app_id(&self, sid: SurfaceId, app_id: String) {
+        let mut mine = self.resources.lock().unwrap();
+        mine.set_surface_app_id(sid, app_id)
+    }
+
+    /// Lock and call corresponding method from `InnerCoordinator`.
+    fn set_surface_decorated(&self, sid: SurfaceId, is_decorated: bool) {
+        let mut mine = self.resources.lock().unwrap();
+        mine.set_surface_decorated(sid, is_decorated)
+    }
+
+    /// Lock and call corresponding method from `InnerCoordinator`.
+    fn set_surface_pointer_constraint(&self,
+                                      sid: SurfaceId,
+                                      constraint: Option<PointerConstraint>) {
+        let mut mine = self.resources.lock().unwrap();
+        mine.set_surface_pointer_constraint(sid, constraint)
+    }
+
+    /// Lock and call corresponding method from `InnerCoordinator`.
+    fn set_surface_global_position(&self, sid: SurfaceId, position: Position) {
+        let mut mine = self.resources.lock().unwrap();
+        mine.set_surface_global_position(sid, position)
+    }
+
+    /// Lock and call corresponding method from `InnerCoordinator`.
+    fn request_surface_move(&self, sid: SurfaceId) {
+        let mut mine = self.resources.lock().unwrap();
+        mine.request_surface_move(sid)
+    }
+
+    /// Lock and call corresponding method from `InnerCoordinator`.
+    fn request_surface_resize(&self, sid: SurfaceId, edges: resize_edge::ResizeEdge) {
+        let mut mine = self.resources.lock().unwrap();
+        mine.request_surface_resize(sid, edges)
+    }
+
+    /// Lock and call corresponding method from `InnerCoordinator`.
+    fn request_surface_maximization(&self, sid: SurfaceId, maximized: bool) {
+        let mut mine = self.resources.lock().unwrap();
+        mine.request_surface_maximization(sid, maximized)
+    }
+
+    /// Lock and call corresponding method from `InnerCoordinator`.
+    fn request_surface_fullscreen(&self,
+                                  sid: SurfaceId,
+                                  fullscreen: bool,
+                                  display_id: Option<i32>) {
+        let mut mine = self.resources.lock().unwrap();
+        mine.request_surface_fullscreen(sid, fullscreen, display_id)
+    }
+
+    /// Lock and call corresponding method from `InnerCoordinator`.
+    fn request_surface_minimization(&self, sid: SurfaceId) {
+        let mut mine = self.resources.lock().unwrap();
+        mine.request_surface_minimization(sid)
+    }
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -401,6 +586,12 @@ impl SurfaceAccess for Coordinator {
         let mut mine = self.resources.lock().unwrap();
         mine.reconfigure(sid, size, state_flags);
     }
+
+    /// Lock and call corresponding method from `InnerCoordinator`.
+    fn take_surfaces_damage(&mut self) -> HashMap<SurfaceId, Vec<Area>> {
+        let mut mine = self.resources.lock().unwrap();
+        mine.take_surfaces_damage()
+    }
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -455,6 +646,12 @@ impl AppearanceManagement for Coordinator {
         let mut mine = self.inner.lock().unwrap();
         mine.set_surface_as_background(sid);
     }
+
+    /// Lock and call corresponding method from `InnerCoordinator`.
+    fn set_surface_as_drag_icon(&self, sid: SurfaceId) {
+        let mut mine = self.inner.lock().unwrap();
+        mine.set_surface_as_drag_icon(sid);
+    }
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -477,6 +674,36 @@ impl DataTransferring for Coordinator {
         let mut mine = self.inner.lock().unwrap();
         mine.request_transfer(mime_type, fd);
     }
+
+    /// Lock and call corresponding method from `InnerCoordinator`.
+    fn set_drag_transfer(&mut self, transfer: Option<Transfer>) {
+        let mut mine = self.inner.lock().unwrap();
+        mine.set_drag_transfer(transfer);
+    }
+
+    /// Lock and call corresponding method from `InnerCoordinator`.
+    fn get_drag_transfer(&self) -> Option<Transfer> {
+        let mine = self.inner.lock().unwrap();
+        mine.get_drag_transfer()
+    }
+
+    /// Lock and call corresponding method from `InnerCoordinator`.
+    fn set_primary_transfer(&mut self, transfer: Option<Transfer>) {
+        let mut mine = self.inner.lock().unwrap();
+        mine.set_primary_transfer(transfer);
+    }
+
+    /// Lock and call corresponding method from `InnerCoordinator`.
+    fn get_primary_transfer(&self) -> Option<Transfer> {
+        let mine = self.inner.lock().unwrap();
+        mine.get_primary_transfer()
+    }
+
+    /// Lock and call corresponding method from `InnerCoordinator`.
+    fn request_primary_transfer(&mut self, mime_type: String, fd: RawFd) {
+        let mut mine = self.inner.lock().unwrap();
+        mine.request_primary_transfer(mime_type, fd);
+    }
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -550,9 +777,9 @@ impl StatePublishing for Coordinator {
     }
 
     /// Lock and call corresponding method from `InnerCoordinator`.
-    fn emit_page_flip(&mut self, display_id: i32) {
+    fn emit_page_flip(&mut self, display_id: i32, time: PresentationTime) {
         let mut mine = self.inner.lock().unwrap();
-        mine.emit_page_flip(display_id);
+        mine.emit_page_flip(display_id, time);
     }
 }
 
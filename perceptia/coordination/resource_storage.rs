@@ -11,11 +11,12 @@ use std;
 use dharma;
 
 use cognitive_graphics::attributes::{EglAttributes, DmabufAttributes};
-use qualia::{Position, Size, Vector, DmabufId, EglImageId, MemoryPoolId, MemoryViewId};
+use qualia::{Area, Position, Size, Transform, Vector};
+use qualia::{DmabufId, EglImageId, MemoryPoolId, MemoryViewId};
 use qualia::{Memory, MemoryPool, MemoryView, PixelFormat, GraphicsManagement};
 use qualia::{perceptron, Perceptron};
-use qualia::{SurfaceContext, SurfaceId, SurfaceInfo, DataSource};
-use qualia::{show_reason, surface_state};
+use qualia::{SurfaceContext, SurfaceId, SurfaceInfo, DataSource, LayerProperties, ViewportSource};
+use qualia::{decoration, show_reason, resize_edge, surface_state, PointerConstraint};
 
 use surfaces::Surface;
 
@@ -240,6 +241,54 @@ impl ResourceStorage {
         surface.attach_dmabuf(attrs.clone());
     }
 
+    /// Sets pending scale of buffers attached to given surface.
+    pub fn set_surface_buffer_scale(&mut self, sid: SurfaceId, scale: u32) {
+        let surface = try_get_surface!(self, sid);
+        surface.set_buffer_scale(scale);
+    }
+
+    /// Sets pending transform of buffers attached to given surface.
+    pub fn set_surface_buffer_transform(&mut self, sid: SurfaceId, transform: Transform) {
+        let surface = try_get_surface!(self, sid);
+        surface.set_buffer_transform(transform);
+    }
+
+    /// Sets pending viewport source rectangle of given surface.
+    pub fn set_surface_viewport_source(&mut self, sid: SurfaceId, source: Option<ViewportSource>) {
+        let surface = try_get_surface!(self, sid);
+        surface.set_viewport_source(source);
+    }
+
+    /// Sets pending viewport destination size of given surface.
+    pub fn set_surface_viewport_destination(&mut self, sid: SurfaceId, destination: Option<Size>) {
+        let surface = try_get_surface!(self, sid);
+        surface.set_viewport_destination(destination);
+    }
+
+    /// Sets pending opaque region of given surface.
+    pub fn set_surface_opaque_region(&mut self, sid: SurfaceId, region: Vec<Area>) {
+        let surface = try_get_surface!(self, sid);
+        surface.set_opaque_region(region);
+    }
+
+    /// Sets pending input region of given surface.
+    pub fn set_surface_input_region(&mut self, sid: SurfaceId, region: Option<Vec<Area>>) {
+        let surface = try_get_surface!(self, sid);
+        surface.set_input_region(region);
+    }
+
+    /// Adds given area (in surface coordinates) to pending damage of given surface.
+    pub fn damage_surface(&mut self, sid: SurfaceId, area: Area) {
+        let surface = try_get_surface!(self, sid);
+        surface.damage(area);
+    }
+
+    /// Adds given area (in buffer coordinates) to pending damage of given surface.
+    pub fn damage_surface_buffer(&mut self, sid: SurfaceId, area: Area) {
+        let surface = try_get_surface!(self, sid);
+        surface.damage_buffer(area);
+    }
+
     /// Informs other parts of application the surface is now not visible.
     pub fn detach_surface(&mut self, sid: SurfaceId) {
         self.signaler.emit(perceptron::SURFACE_DESTROYED, Perceptron::SurfaceDestroyed(sid));
@@ -247,14 +296,15 @@ impl ResourceStorage {
 
     /// Sets pending buffer of given surface as current. Corrects sizes adds `drawable` show reason.
     pub fn commit_surface(&mut self, sid: SurfaceId) {
-        let is_first_time_commited = {
+        let is_synchronized = self.is_surface_synchronized(sid);
+        {
             let surface = try_get_surface!(self, sid);
-            surface.commit()
-        };
-        if is_first_time_commited {
-            self.show_surface(sid, show_reason::DRAWABLE);
+            surface.cache();
+        }
+        if !is_synchronized {
+            self.apply_cached_surface_state(sid);
+            self.signaler.emit(perceptron::NOTIFY, Perceptron::Notify);
         }
-        self.signaler.emit(perceptron::NOTIFY, Perceptron::Notify);
     }
 
     /// Detaches and forgets given surface.
@@ -279,6 +329,15 @@ impl ResourceStorage {
                            Perceptron::DockSurface(sid, size, display_id));
     }
 
+    /// Requests showing given surface in layer on given display.
+    pub fn set_surface_layer(&mut self,
+                             sid: SurfaceId,
+                             properties: LayerProperties,
+                             display_id: Option<i32>) {
+        self.signaler.emit(perceptron::LAYER_SURFACE,
+                           Perceptron::LayerSurface(sid, properties, display_id));
+    }
+
     /// Subtracts given show reason flag from set of surfaces show reason. If not all reasons
     /// needed for surface to be drawn are meet, emit signal `surface destroyed`.
     pub fn hide_surface(&mut self, sid: SurfaceId, reason: show_reason::ShowReason) {
@@ -327,12 +386,131 @@ impl ResourceStorage {
             let mut surface = try_get_surface!(self, sid);
             let parent_sid = surface.get_parent_sid();
             surface.set_parent_sid(SurfaceId::invalid());
+            surface.set_synchronized(false);
             parent_sid
         };
         let mut parent_surface = try_get_surface!(self, parent_sid);
         parent_surface.remove_satellite(sid);
     }
 
+    /// Places given surface directly above its sibling or parent. Takes effect after commit of the
+    /// parent.
+    pub fn place_surface_above(&mut self, sid: SurfaceId, sibling_sid: SurfaceId) {
+        let parent_sid = try_get_surface!(self, sid).get_parent_sid();
+        let parent_surface = try_get_surface!(self, parent_sid);
+        if !parent_surface.place_satellite_above(sid, sibling_sid) {
+            log_warn2!("Failed to place surface {} above {}", sid, sibling_sid);
+        }
+    }
+
+    /// Places given surface directly below its sibling or parent. Takes effect after commit of the
+    /// parent.
+    pub fn place_surface_below(&mut self, sid: SurfaceId, sibling_sid: SurfaceId) {
+        let parent_sid = try_get_surface!(self, sid).get_parent_sid();
+        let parent_surface = try_get_surface!(self, parent_sid);
+        if !parent_surface.place_satellite_below(sid, sibling_sid) {
+            log_warn2!("Failed to place surface {} below {}", sid, sibling_sid);
+        }
+    }
+
+    /// Sets synchronization mode of given subsurface. State committed by synchronized surface is
+    /// applied only after commit of its parent. When surface becomes desynchronized its cached
+    /// state is applied immediately.
+    pub fn set_surface_synchronized(&mut self, sid: SurfaceId, is_synchronized: bool) {
+        {
+            let surface = try_get_surface!(self, sid);
+            surface.set_synchronized(is_synchronized);
+        }
+        if !self.is_surface_synchronized(sid) {
+            self.apply_cached_surface_state(sid);
+            self.signaler.emit(perceptron::NOTIFY, Perceptron::Notify);
+        }
+    }
+
+    /// Sets title of given surface. Informs other parts of application if it changed.
+    pub fn set_surface_title(&mut self, sid: SurfaceId, title: String) {
+        let changed = {
+            let surface = try_get_surface!(self, sid);
+            surface.set_title(title)
+        };
+        if changed {
+            self.signaler.emit(perceptron::SURFACE_DESCRIPTION_CHANGED,
+                               Perceptron::SurfaceDescriptionChanged(sid));
+        }
+    }
+
+    /// Sets application ID of given surface. Informs other parts of application if it changed.
+    pub fn set_surface_app_id(&mut self, sid: SurfaceId, app_id: String) {
+        let changed = {
+            let surface = try_get_surface!(self, sid);
+            surface.set_app_id(app_id)
+        };
+        if changed {
+            self.signaler.emit(perceptron::SURFACE_DESCRIPTION_CHANGED,
+                               Perceptron::SurfaceDescriptionChanged(sid));
+        }
+    }
+
+    /// Sets if given surface is decorated by compositor. Decorations take part of the area desired
+    /// for the surface so it is reconfigured if this changed.
+    pub fn set_surface_decorated(&mut self, sid: SurfaceId, is_decorated: bool) {
+        let changed = {
+            let surface = try_get_surface!(self, sid);
+            surface.set_decorated(is_decorated)
+        };
+        if changed {
+            self.signaler.emit(perceptron::SURFACE_RECONFIGURED,
+                               Perceptron::SurfaceReconfigured(sid));
+        }
+    }
+
+    /// Sets or removes constraint of pointer movement over given surface.
+    pub fn set_surface_pointer_constraint(&mut self,
+                                          sid: SurfaceId,
+                                          constraint: Option<PointerConstraint>) {
+        let surface = try_get_surface!(self, sid);
+        surface.set_pointer_constraint(constraint);
+    }
+
+    /// Sets position of given surface in global coordinates.
+    pub fn set_surface_global_position(&mut self, sid: SurfaceId, position: Position) {
+        let surface = try_get_surface!(self, sid);
+        surface.set_global_position(position);
+    }
+
+    /// Requests interactive move of given surface.
+    pub fn request_surface_move(&mut self, sid: SurfaceId) {
+        self.signaler.emit(perceptron::SURFACE_MOVE_REQUESTED,
+                           Perceptron::SurfaceMoveRequested(sid));
+    }
+
+    /// Requests interactive resize of given surface by dragging given edges.
+    pub fn request_surface_resize(&mut self, sid: SurfaceId, edges: resize_edge::ResizeEdge) {
+        self.signaler.emit(perceptron::SURFACE_RESIZE_REQUESTED,
+                           Perceptron::SurfaceResizeRequested(sid, edges));
+    }
+
+    /// Requests maximizing or unmaximizing given surface.
+    pub fn request_surface_maximization(&mut self, sid: SurfaceId, maximized: bool) {
+        self.signaler.emit(perceptron::SURFACE_MAXIMIZATION_REQUESTED,
+                           Perceptron::SurfaceMaximizationRequested(sid, maximized));
+    }
+
+    /// Requests making given surface fullscreen or bringing it back from fullscreen.
+    pub fn request_surface_fullscreen(&mut self,
+                                      sid: SurfaceId,
+                                      fullscreen: bool,
+                                      display_id: Option<i32>) {
+        self.signaler.emit(perceptron::SURFACE_FULLSCREEN_REQUESTED,
+                           Perceptron::SurfaceFullscreenRequested(sid, fullscreen, display_id));
+    }
+
+    /// Requests minimizing given surface.
+    pub fn request_surface_minimization(&mut self, sid: SurfaceId) {
+        self.signaler.emit(perceptron::SURFACE_MINIMIZATION_REQUESTED,
+                           Perceptron::SurfaceMinimizationRequested(sid));
+    }
+
     /// Returns information about surface.
     pub fn get_surface(&self, sid: SurfaceId) -> Option<SurfaceInfo> {
         let surface = try_get_surface_or_none!(self, sid);
@@ -353,6 +531,18 @@ impl ResourceStorage {
         }
     }
 
+    /// Returns damage of all surfaces committed since last call and forgets it.
+    pub fn take_surfaces_damage(&mut self) -> std::collections::HashMap<SurfaceId, Vec<Area>> {
+        let mut result = std::collections::HashMap::new();
+        for (sid, surface) in self.surfaces.iter_mut() {
+            let damage = surface.take_damage();
+            if damage.len() > 0 {
+                result.insert(*sid, damage);
+            }
+        }
+        result
+    }
+
     /// Returns surface context.
     pub fn get_renderer_context(&self, sid: SurfaceId) -> Option<Vec<SurfaceContext>> {
         let surface = try_get_surface_or_none!(self, sid);
@@ -366,6 +556,15 @@ impl ResourceStorage {
                 }
             }
         }
+
+        // Decorations are drawn above and left to the surface. Fullscreen surfaces are not
+        // decorated.
+        let is_fullscreen = surface.get_state_flags().contains(surface_state::FULLSCREEN);
+        if surface.is_decorated() && !is_fullscreen {
+            let margins = decoration::get_margins();
+            let vector = Vector::new(margins.left as isize, margins.top as isize);
+            result = result.iter().map(|context| context.moved(vector)).collect();
+        }
         Some(result)
     }
 }
@@ -529,6 +728,36 @@ impl ResourceStorage {
     fn generate_next_dmabuf_id(&mut self) -> DmabufId {
         self.last_dmabuf_id.increment()
     }
+
+    /// Checks if state committed by given surface should be cached until commit of its parent,
+    /// i.e. if the surface or any of its ancestors is synchronized.
+    fn is_surface_synchronized(&self, sid: SurfaceId) -> bool {
+        let mut sid = sid;
+        while let Some(surface) = self.surfaces.get(&sid) {
+            if surface.is_synchronized() {
+                return true;
+            }
+            sid = surface.get_parent_sid();
+        }
+        false
+    }
+
+    /// Applies cached state of given surface and recursively of its satellites. Surfaces committed
+    /// for the first time are marked as drawable.
+    fn apply_cached_surface_state(&mut self, sid: SurfaceId) {
+        let (is_first_time_commited, satellites) = {
+            let surface = try_get_surface!(self, sid);
+            (surface.apply_cached(), surface.get_satellites().clone())
+        };
+        if is_first_time_commited {
+            self.show_surface(sid, show_reason::DRAWABLE);
+        }
+        for satellite_sid in satellites {
+            if satellite_sid != sid {
+                self.apply_cached_surface_state(satellite_sid);
+            }
+        }
+    }
 }
 
 #[cfg(test)]
@@ -580,6 +809,145 @@ mod tests {
 
         assert_storage_size(&resources);
     }
+
+    /// Check if setting title and application ID is reflected in surface information and signal
+    /// is emitted only when any of them really changed.
+    #[test]
+    fn test_setting_surface_description() {
+        let mut signaler = super::dharma::Signaler::new();
+        let mut receiver = super::dharma::Receiver::new();
+        signaler.subscribe(super::perceptron::SURFACE_DESCRIPTION_CHANGED, &receiver);
+        let mut resources = super::ResourceStorage::new(signaler);
+
+        let sid = resources.create_surface();
+        resources.set_surface_title(sid, "title".to_owned());
+        resources.set_surface_app_id(sid, "app".to_owned());
+        resources.set_surface_title(sid, "title".to_owned());
+
+        let info = resources.get_surface(sid).unwrap();
+        assert_eq!(info.title, "title");
+        assert_eq!(info.app_id, "app");
+
+        for _ in 0..2 {
+            match receiver.try_recv() {
+                super::dharma::ReceiveResult::Defined(_, package) => {
+                    match package {
+                        super::Perceptron::SurfaceDescriptionChanged(s) => assert_eq!(s, sid),
+                        _ => panic!("Wrong perceptron: {:?}", package),
+                    }
+                }
+                _ => panic!("Expected signal not received"),
+            }
+        }
+        match receiver.try_recv() {
+            super::dharma::ReceiveResult::Empty => {}
+            _ => panic!("Unexpected signal received"),
+        }
+    }
+
+    /// Check if stacking order of subsurfaces changes only after commit of parent.
+    #[test]
+    fn test_stacking_subsurfaces() {
+        let signaler = super::dharma::Signaler::new();
+        let mut resources = super::ResourceStorage::new(signaler);
+
+        let parent_sid = resources.create_surface();
+        let sid1 = resources.create_surface();
+        let sid2 = resources.create_surface();
+        resources.relate_surfaces(sid1, parent_sid);
+        resources.relate_surfaces(sid2, parent_sid);
+
+        let get_order = |resources: &super::ResourceStorage| -> Vec<super::SurfaceId> {
+            let contexts = resources.get_renderer_context(parent_sid).unwrap();
+            contexts.iter().map(|context| context.id).collect()
+        };
+        assert_eq!(get_order(&resources), vec![parent_sid, sid1, sid2]);
+
+        resources.place_surface_below(sid2, parent_sid);
+        assert_eq!(get_order(&resources), vec![parent_sid, sid1, sid2]);
+        resources.commit_surface(parent_sid);
+        assert_eq!(get_order(&resources), vec![sid2, parent_sid, sid1]);
+
+        resources.place_surface_above(sid2, sid1);
+        resources.place_surface_above(parent_sid, sid1);
+        resources.commit_surface(parent_sid);
+        assert_eq!(get_order(&resources), vec![parent_sid, sid1, sid2]);
+    }
+
+    /// Check if state committed by synchronized subsurface is applied after commit of its parent
+    /// and state of desynchronized subsurface is applied immediately.
+    #[test]
+    fn test_committing_synchronized_subsurface() {
+        let signaler = super::dharma::Signaler::new();
+        let mut resources = super::ResourceStorage::new(signaler);
+
+        let width = 10;
+        let height = 10;
+        let format = super::PixelFormat::XRGB8888;
+        let stride = format.get_size() * width;
+        let data = vec![0; stride * height];
+        let mut buffer = qualia::Buffer::new(format, width, height, stride, data);
+        let mpid = resources.create_memory_pool(unsafe { buffer.as_memory() });
+        let mvid = resources.create_memory_view(mpid, format, 0, width, height, stride).unwrap();
+
+        let parent_sid = resources.create_surface();
+        let sid = resources.create_surface();
+        resources.relate_surfaces(sid, parent_sid);
+        resources.set_surface_synchronized(sid, true);
+
+        resources.attach_shm(mvid, sid);
+        resources.commit_surface(sid);
+        assert!(resources.get_surface(sid).unwrap().data_source.is_none());
+        resources.commit_surface(parent_sid);
+        assert!(!resources.get_surface(sid).unwrap().data_source.is_none());
+
+        let area = super::Area::create(1, 2, 3, 4);
+        resources.damage_surface(sid, area);
+        resources.commit_surface(sid);
+        assert!(resources.take_surfaces_damage().get(&sid).is_none());
+        resources.set_surface_synchronized(sid, false);
+        assert_eq!(resources.take_surfaces_damage().get(&sid), Some(&vec![area]));
+
+        resources.damage_surface(sid, area);
+        resources.commit_surface(sid);
+        assert_eq!(resources.take_surfaces_damage().get(&sid), Some(&vec![area]));
+    }
+
+    /// Check if viewport changes size of surface and if buffer damage is mapped through source
+    /// rectangle to surface coordinates.
+    #[test]
+    fn test_committing_surface_with_viewport() {
+        let signaler = super::dharma::Signaler::new();
+        let mut resources = super::ResourceStorage::new(signaler);
+
+        let width = 10;
+        let height = 10;
+        let format = super::PixelFormat::XRGB8888;
+        let stride = format.get_size() * width;
+        let data = vec![0; stride * height];
+        let mut buffer = qualia::Buffer::new(format, width, height, stride, data);
+        let mpid = resources.create_memory_pool(unsafe { buffer.as_memory() });
+        let mvid = resources.create_memory_view(mpid, format, 0, width, height, stride).unwrap();
+
+        let sid = resources.create_surface();
+        let source = super::ViewportSource::new(2.0, 2.0, 4.0, 4.0);
+        resources.attach_shm(mvid, sid);
+        resources.set_surface_viewport_source(sid, Some(source));
+        resources.commit_surface(sid);
+        let info = resources.get_surface(sid).unwrap();
+        assert_eq!(info.get_contents_size(), Some(super::Size::new(4, 4)));
+        assert_eq!(info.requested_size, super::Size::new(4, 4));
+
+        resources.set_surface_viewport_destination(sid, Some(super::Size::new(8, 8)));
+        resources.damage_surface_buffer(sid, super::Area::create(3, 3, 2, 2));
+        resources.damage_surface_buffer(sid, super::Area::create(0, 0, 1, 1));
+        resources.take_surfaces_damage();
+        resources.commit_surface(sid);
+        let info = resources.get_surface(sid).unwrap();
+        assert_eq!(info.get_contents_size(), Some(super::Size::new(8, 8)));
+        assert_eq!(resources.take_surfaces_damage().get(&sid),
+                   Some(&vec![super::Area::create(2, 2, 4, 4)]));
+    }
 }
 
 // -------------------------------------------------------------------------------------------------
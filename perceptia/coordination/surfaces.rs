@@ -6,8 +6,103 @@
 // -------------------------------------------------------------------------------------------------
 
 use cognitive_graphics::attributes::{DmabufAttributes, EglAttributes};
-use qualia::{DataSource, MemoryView, Position, Size, Vector};
-use qualia::{SurfaceContext, SurfaceId, SurfaceInfo, show_reason, surface_state};
+use std;
+
+use qualia::{Area, DataSource, MemoryView, Position, Size, Transform, Vector};
+use qualia::{SurfaceContext, SurfaceId, SurfaceInfo, ViewportSource, show_reason, surface_state};
+use qualia::PointerConstraint;
+
+// -------------------------------------------------------------------------------------------------
+
+/// Committed state of surface waiting to be applied. Synchronized subsurfaces keep it until their
+/// parent is committed.
+struct CachedState {
+    buffer: DataSource,
+    buffer_scale: u32,
+    buffer_transform: Transform,
+    viewport_source: Option<ViewportSource>,
+    viewport_destination: Option<Size>,
+    opaque_region: Vec<Area>,
+    input_region: Option<Vec<Area>>,
+    damage: Vec<Area>,
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl CachedState {
+    /// Returns size of buffer in surface coordinates before applying viewport (after applying
+    /// buffer transform and scale) or `None` if no buffer is attached.
+    fn get_scaled_buffer_size(&self) -> Option<Size> {
+        self.buffer.as_image().map(|image| {
+            let mut size = image.get_size();
+            if self.buffer_transform.swaps_dimensions() {
+                size = Size::new(size.height, size.width);
+            }
+            Size::new(size.width / self.buffer_scale as usize,
+                      size.height / self.buffer_scale as usize)
+        })
+    }
+
+    /// Returns size of surface in logical coordinates or `None` if no buffer is attached.
+    fn get_surface_size(&self) -> Option<Size> {
+        self.get_scaled_buffer_size().map(|size| {
+            match (self.viewport_destination, self.viewport_source) {
+                (Some(destination), _) => destination,
+                (None, Some(source)) => Size::new(source.width as usize, source.height as usize),
+                (None, None) => size,
+            }
+        })
+    }
+
+    /// Maps area in buffer coordinates to surface coordinates using buffer scale, transform and
+    /// viewport. Resulting area covers all surface pixels touched by the buffer area.
+    fn buffer_area_to_surface(&self, area: &Area) -> Area {
+        let (buffer_size, scaled_size, surface_size) =
+            match (self.buffer.as_image().map(|image| image.get_size()),
+                   self.get_scaled_buffer_size(),
+                   self.get_surface_size()) {
+                (Some(buffer_size), Some(scaled_size), Some(surface_size)) => {
+                    (buffer_size, scaled_size, surface_size)
+                }
+                _ => return Area::default(),
+            };
+
+        if buffer_size.is_zero() || scaled_size.is_zero() {
+            return Area::default();
+        }
+
+        let width = buffer_size.width as f32;
+        let height = buffer_size.height as f32;
+        let transform = self.buffer_transform.inverted();
+        let (x1, y1) = transform.map_normalized(area.pos.x as f32 / width,
+                                                area.pos.y as f32 / height);
+        let (x2, y2) =
+            transform.map_normalized((area.pos.x + area.size.width as isize) as f32 / width,
+                                     (area.pos.y + area.size.height as isize) as f32 / height);
+
+        // Map from normalized buffer coordinates through source rectangle to surface coordinates.
+        let source = self.viewport_source.unwrap_or_else(|| {
+            ViewportSource::new(0.0, 0.0, scaled_size.width as f32, scaled_size.height as f32)
+        });
+        let scale_x = surface_size.width as f32 / source.width;
+        let scale_y = surface_size.height as f32 / source.height;
+        let map_x = |x: f32| (x * scaled_size.width as f32 - source.x) * scale_x;
+        let map_y = |y: f32| (y * scaled_size.height as f32 - source.y) * scale_y;
+
+        let left = map_x(x1.min(x2)).floor();
+        let top = map_y(y1.min(y2)).floor();
+        let right = map_x(x1.max(x2)).ceil();
+        let bottom = map_y(y1.max(y2)).ceil();
+        if (right <= left) || (bottom <= top) {
+            return Area::default();
+        }
+
+        Area::create(left as isize,
+                     top as isize,
+                     (right - left) as usize,
+                     (bottom - top) as usize)
+    }
+}
 
 // -------------------------------------------------------------------------------------------------
 
@@ -28,9 +123,20 @@ pub struct Surface {
     /// ID of parent surface.
     parent_sid: SurfaceId,
 
-    /// List of IDs of satelliting surfaces.
+    /// List of IDs of satelliting surfaces (including this surface) in stacking order from bottom
+    /// to top.
     satellites: Vec<SurfaceId>,
 
+    /// Stacking order of satellites to be used after commit.
+    pending_satellites: Vec<SurfaceId>,
+
+    /// Flag indicating if state committed by this surface should be applied only after commit of
+    /// its parent. Used by subsurfaces.
+    is_synchronized: bool,
+
+    /// State committed but not yet applied.
+    cached: Option<CachedState>,
+
     /// Position requested by client relative to parent surface.
     /// For surfaces without parent this must be {0, 0}.
     relative_position: Position,
@@ -41,11 +147,72 @@ pub struct Surface {
     /// Data to be used after commit.
     pending_buffer: DataSource,
 
+    /// Factor by which buffer is bigger than surface in logical coordinates.
+    buffer_scale: u32,
+
+    /// Buffer scale to be used after commit.
+    pending_buffer_scale: u32,
+
+    /// Rotation and flipping of buffer contents.
+    buffer_transform: Transform,
+
+    /// Buffer transform to be used after commit.
+    pending_buffer_transform: Transform,
+
+    /// Areas of surface (in surface coordinates) which are fully opaque.
+    opaque_region: Vec<Area>,
+
+    /// Opaque region to be used after commit.
+    pending_opaque_region: Vec<Area>,
+
+    /// Part of the buffer (in surface coordinates before scaling) to be shown. `None` means whole
+    /// buffer.
+    viewport_source: Option<ViewportSource>,
+
+    /// Viewport source to be used after commit.
+    pending_viewport_source: Option<ViewportSource>,
+
+    /// Size to which contents of surface are scaled. `None` means size of viewport source.
+    viewport_destination: Option<Size>,
+
+    /// Viewport destination to be used after commit.
+    pending_viewport_destination: Option<Size>,
+
+    /// Areas of surface (in surface coordinates) accepting input. `None` means whole surface.
+    input_region: Option<Vec<Area>>,
+
+    /// Input region to be used after commit.
+    pending_input_region: Option<Vec<Area>>,
+
+    /// Committed damage in surface coordinates not yet taken for redraw.
+    damage: Vec<Area>,
+
+    /// Damage in surface coordinates to be used after commit.
+    pending_damage: Vec<Area>,
+
+    /// Damage in buffer coordinates to be used after commit.
+    pending_buffer_damage: Vec<Area>,
+
     /// Flags describing logical state of surface
     state_flags: surface_state::SurfaceState,
 
     /// Flags indicating if surface is ready to be shown.
     show_reasons: show_reason::ShowReason,
+
+    /// Title of the surface set by client.
+    title: String,
+
+    /// Application ID (or class) set by client.
+    app_id: String,
+
+    /// Indicates if the surface is decorated by compositor.
+    is_decorated: bool,
+
+    /// Constraint of pointer movement requested by client.
+    pointer_constraint: Option<PointerConstraint>,
+
+    /// Position in global coordinates as last drawn.
+    global_position: Option<Position>,
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -60,11 +227,34 @@ impl Surface {
             requested_size: Size::default(),
             parent_sid: SurfaceId::invalid(),
             satellites: vec![*id],
+            pending_satellites: vec![*id],
+            is_synchronized: false,
+            cached: None,
             relative_position: Position::default(),
             buffer: DataSource::None,
             pending_buffer: DataSource::None,
+            buffer_scale: 1,
+            pending_buffer_scale: 1,
+            buffer_transform: Transform::Normal,
+            pending_buffer_transform: Transform::Normal,
+            opaque_region: Vec::new(),
+            pending_opaque_region: Vec::new(),
+            viewport_source: None,
+            pending_viewport_source: None,
+            viewport_destination: None,
+            pending_viewport_destination: None,
+            input_region: None,
+            pending_input_region: None,
+            damage: Vec::new(),
+            pending_damage: Vec::new(),
+            pending_buffer_damage: Vec::new(),
             show_reasons: show_reason::NONE,
             state_flags: surface_state::REGULAR,
+            title: String::new(),
+            app_id: String::new(),
+            is_decorated: false,
+            pointer_constraint: None,
+            global_position: None,
         }
     }
 
@@ -92,33 +282,40 @@ impl Surface {
         self.parent_sid = sid
     }
 
-    /// Adds satellite.
+    /// Adds satellite on top of other satellites.
     #[inline]
     pub fn add_satellite(&mut self, sid: SurfaceId) {
-        let mut contains = false;
-        for satellite in self.satellites.iter() {
-            if *satellite == sid {
-                contains = true;
-            }
-        }
-        if !contains {
+        if !self.pending_satellites.contains(&sid) {
             self.satellites.push(sid);
+            self.pending_satellites.push(sid);
         }
     }
 
     /// Removes satellite.
     #[inline]
     pub fn remove_satellite(&mut self, sid: SurfaceId) {
-        let mut index = None;
-        for (i, satellite) in self.satellites.iter().enumerate() {
-            if *satellite == sid {
-                index = Some(i);
-                break;
-            }
-        }
-        if let Some(index) = index {
-            self.satellites.remove(index);
-        }
+        self.satellites.retain(|satellite| *satellite != sid);
+        self.pending_satellites.retain(|satellite| *satellite != sid);
+    }
+
+    /// Places satellite directly above given sibling (or this surface) in pending stacking order.
+    /// Returns `false` if any of given surfaces is not satellite of this surface.
+    #[inline]
+    pub fn place_satellite_above(&mut self, sid: SurfaceId, sibling_sid: SurfaceId) -> bool {
+        self.place_satellite(sid, sibling_sid, 1)
+    }
+
+    /// Places satellite directly below given sibling (or this surface) in pending stacking order.
+    /// Returns `false` if any of given surfaces is not satellite of this surface.
+    #[inline]
+    pub fn place_satellite_below(&mut self, sid: SurfaceId, sibling_sid: SurfaceId) -> bool {
+        self.place_satellite(sid, sibling_sid, 0)
+    }
+
+    /// Sets synchronization mode.
+    #[inline]
+    pub fn set_synchronized(&mut self, is_synchronized: bool) {
+        self.is_synchronized = is_synchronized;
     }
 
     /// Sets relative position.
@@ -133,6 +330,49 @@ impl Surface {
         self.state_flags = state_flags
     }
 
+    /// Sets title. Returns `true` if title changed, `false` otherwise.
+    #[inline]
+    pub fn set_title(&mut self, title: String) -> bool {
+        let changed = self.title != title;
+        self.title = title;
+        changed
+    }
+
+    /// Sets application ID. Returns `true` if application ID changed, `false` otherwise.
+    #[inline]
+    pub fn set_app_id(&mut self, app_id: String) -> bool {
+        let changed = self.app_id != app_id;
+        self.app_id = app_id;
+        changed
+    }
+
+    /// Checks if surface is decorated by compositor.
+    #[inline]
+    pub fn is_decorated(&self) -> bool {
+        self.is_decorated
+    }
+
+    /// Sets if surface is decorated by compositor. Returns `true` if it changed, `false`
+    /// otherwise.
+    #[inline]
+    pub fn set_decorated(&mut self, is_decorated: bool) -> bool {
+        let changed = self.is_decorated != is_decorated;
+        self.is_decorated = is_decorated;
+        changed
+    }
+
+    /// Sets or removes constraint of pointer movement.
+    #[inline]
+    pub fn set_pointer_constraint(&mut self, constraint: Option<PointerConstraint>) {
+        self.pointer_constraint = constraint;
+    }
+
+    /// Sets position in global coordinates.
+    #[inline]
+    pub fn set_global_position(&mut self, position: Position) {
+        self.global_position = Some(position);
+    }
+
     /// Adds given reason to show reasons. Returns updates set of reasons.
     ///
     /// Satelliting surface can not be shown in shell.
@@ -177,25 +417,116 @@ impl Surface {
         self.pending_buffer = DataSource::new_dmabuf(attrs);
     }
 
-    /// Sets pending buffer as current. If surface was committed for the first time and sizes are
-    /// not set, assign size of buffer as requested size. Return `true` if surface was committed for
-    /// the first time, `false` otherwise.
-    pub fn commit(&mut self) -> bool {
-        let is_first_time_committed = self.buffer.is_none();
-        self.buffer = self.pending_buffer.clone();
+    /// Sets pending buffer scale.
+    #[inline]
+    pub fn set_buffer_scale(&mut self, scale: u32) {
+        self.pending_buffer_scale = scale;
+    }
+
+    /// Sets pending buffer transform.
+    #[inline]
+    pub fn set_buffer_transform(&mut self, transform: Transform) {
+        self.pending_buffer_transform = transform;
+    }
+
+    /// Sets pending opaque region.
+    #[inline]
+    pub fn set_opaque_region(&mut self, region: Vec<Area>) {
+        self.pending_opaque_region = region;
+    }
+
+    /// Sets pending viewport source rectangle.
+    #[inline]
+    pub fn set_viewport_source(&mut self, source: Option<ViewportSource>) {
+        self.pending_viewport_source = source;
+    }
+
+    /// Sets pending viewport destination size.
+    #[inline]
+    pub fn set_viewport_destination(&mut self, destination: Option<Size>) {
+        self.pending_viewport_destination = destination;
+    }
+
+    /// Sets pending input region.
+    #[inline]
+    pub fn set_input_region(&mut self, region: Option<Vec<Area>>) {
+        self.pending_input_region = region;
+    }
+
+    /// Adds given area (in surface coordinates) to pending damage.
+    #[inline]
+    pub fn damage(&mut self, area: Area) {
+        self.pending_damage.push(area);
+    }
+
+    /// Adds given area (in buffer coordinates) to pending damage.
+    #[inline]
+    pub fn damage_buffer(&mut self, area: Area) {
+        self.pending_buffer_damage.push(area);
+    }
+
+    /// Returns committed damage and forgets it.
+    #[inline]
+    pub fn take_damage(&mut self) -> Vec<Area> {
+        std::mem::replace(&mut self.damage, Vec::new())
+    }
 
-        if let Some(ref image) = self.buffer.as_image() {
+    /// Stores pending buffer, buffer scale, buffer transform, viewport, opaque and input regions
+    /// and damage to be applied later. Damage is converted to surface coordinates and accumulated
+    /// with damage cached earlier.
+    pub fn cache(&mut self) {
+        let damage = self.cached.take().map_or(Vec::new(), |cached| cached.damage);
+        let mut state = CachedState {
+            buffer: self.pending_buffer.clone(),
+            buffer_scale: self.pending_buffer_scale,
+            buffer_transform: self.pending_buffer_transform,
+            viewport_source: self.pending_viewport_source,
+            viewport_destination: self.pending_viewport_destination,
+            opaque_region: self.pending_opaque_region.clone(),
+            input_region: self.pending_input_region.clone(),
+            damage: damage,
+        };
+
+        let pending_damage = self.take_pending_damage(&state);
+        state.damage.extend(pending_damage);
+        self.cached = Some(state);
+    }
+
+    /// Sets cached state and pending stacking order of satellites as current. If surface was
+    /// committed for the first time and sizes are not set, assign size of buffer in logical
+    /// coordinates as requested size. Return `true` if surface was committed for the first time,
+    /// `false` otherwise (also when there was no cached state).
+    pub fn apply_cached(&mut self) -> bool {
+        let cached = if let Some(cached) = self.cached.take() {
+            cached
+        } else {
+            return false;
+        };
+
+        let is_first_time_committed = self.buffer.is_none();
+        let size = cached.get_surface_size();
+        self.buffer = cached.buffer;
+        self.buffer_scale = cached.buffer_scale;
+        self.buffer_transform = cached.buffer_transform;
+        self.viewport_source = cached.viewport_source;
+        self.viewport_destination = cached.viewport_destination;
+        self.opaque_region = cached.opaque_region;
+        self.input_region = cached.input_region;
+        self.damage.extend(cached.damage);
+        self.satellites = self.pending_satellites.clone();
+
+        if let Some(size) = size {
             // If surface was just created...
             if is_first_time_committed {
                 // ... size was not yet requested by surface ...
                 if (self.requested_size.width == 0) || (self.requested_size.height == 0) {
                     // ... use its image size as requested size ...
-                    self.requested_size = image.get_size();
+                    self.requested_size = size;
                 }
                 // ... and if it is subsurface ...
                 if self.parent_sid.is_valid() {
                     // ... set its desired size.
-                    self.desired_size = image.get_size();
+                    self.desired_size = size;
                 }
             }
         }
@@ -213,6 +544,17 @@ impl Surface {
             requested_size: self.requested_size,
             state_flags: self.state_flags,
             data_source: self.buffer.clone(),
+            buffer_scale: self.buffer_scale,
+            buffer_transform: self.buffer_transform,
+            viewport_source: self.viewport_source,
+            viewport_destination: self.viewport_destination,
+            opaque_region: self.opaque_region.clone(),
+            input_region: self.input_region.clone(),
+            title: self.title.clone(),
+            app_id: self.app_id.clone(),
+            is_decorated: self.is_decorated,
+            pointer_constraint: self.pointer_constraint.clone(),
+            global_position: self.global_position,
         }
     }
 
@@ -248,6 +590,53 @@ impl Surface {
     pub fn get_satellites(&self) -> &Vec<SurfaceId> {
         &self.satellites
     }
+
+    /// Checks if state committed by this surface should be applied only after commit of its
+    /// parent.
+    pub fn is_synchronized(&self) -> bool {
+        self.is_synchronized
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+/// Private methods.
+impl Surface {
+    /// Moves satellite in pending stacking order to position relative to given sibling. Offset `1`
+    /// means "above", `0` - "below".
+    fn place_satellite(&mut self, sid: SurfaceId, sibling_sid: SurfaceId, offset: usize) -> bool {
+        if (sid == sibling_sid) || (sid == self.id) ||
+           !self.pending_satellites.contains(&sibling_sid) {
+            return false;
+        }
+
+        if let Some(index) = self.pending_satellites.iter().position(|s| *s == sid) {
+            self.pending_satellites.remove(index);
+        } else {
+            return false;
+        }
+
+        if let Some(index) = self.pending_satellites.iter().position(|s| *s == sibling_sid) {
+            self.pending_satellites.insert(index + offset, sid);
+        }
+        true
+    }
+
+    /// Converts pending damage to surface coordinates of given state, clips it to surface size
+    /// and returns it.
+    fn take_pending_damage(&mut self, state: &CachedState) -> Vec<Area> {
+        let mut damage = std::mem::replace(&mut self.pending_damage, Vec::new());
+        let buffer_damage = std::mem::replace(&mut self.pending_buffer_damage, Vec::new());
+        if let Some(size) = state.get_surface_size() {
+            let bounds = Area::new(Position::default(), size);
+            for area in buffer_damage.iter() {
+                damage.push(state.buffer_area_to_surface(area));
+            }
+            damage.iter().filter_map(|area| area.intersection(&bounds)).collect()
+        } else {
+            Vec::new()
+        }
+    }
 }
 
 // -------------------------------------------------------------------------------------------------
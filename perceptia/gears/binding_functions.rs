@@ -20,6 +20,11 @@ pub trait Executor: Send + Sync {
     /// Executes an action when key or button was released.
     fn release(&self, &mut InputContext) {}
 
+    /// Tells if action should be executed repeatedly while key is held.
+    fn is_repeatable(&self) -> bool {
+        false
+    }
+
     /// Clones this trait object.
     fn duplicate(&self) -> Box<Executor>;
 }
@@ -115,7 +120,8 @@ fn put_direction(context: &mut InputContext, direction: Direction) {
 
 // -------------------------------------------------------------------------------------------------
 
-/// Helper macro defining implementation of `Executor` without `release` method.
+/// Helper macro defining implementation of `Executor` without `release` method. Executors marked
+/// as `repeatable` are executed repeatedly while key is held.
 macro_rules! define_simple_executor {
     ($name:ident($context:ident) $callback:block) => {
         #[derive(Clone)]
@@ -129,6 +135,20 @@ macro_rules! define_simple_executor {
                 $callback
             }
         }
+    };
+    (repeatable $name:ident($context:ident) $callback:block) => {
+        #[derive(Clone)]
+        pub struct $name {}
+        impl $name {
+            pub fn new() -> Box<Self> { Box::new(Self{}) }
+        }
+        impl Executor for $name {
+            fn duplicate(&self) -> Box<Executor> { Self::new() }
+            fn activate(&self, $context: &mut InputContext) {
+                $callback
+            }
+            fn is_repeatable(&self) -> bool { true }
+        }
     }
 }
 
@@ -218,42 +238,42 @@ define_simple_executor!(PutResize(context) {
 // -------------------------------------------------------------------------------------------------
 
 /// Sets north direction in command but do not execute.
-define_simple_executor!(PutNorth(context) {
+define_simple_executor!(repeatable PutNorth(context) {
     put_direction(context, Direction::North);
 });
 
 // -------------------------------------------------------------------------------------------------
 
 /// Sets east direction in command but do not execute.
-define_simple_executor!(PutEast(context) {
+define_simple_executor!(repeatable PutEast(context) {
     put_direction(context, Direction::East);
 });
 
 // -------------------------------------------------------------------------------------------------
 
 /// Sets south direction in command but do not execute.
-define_simple_executor!(PutSouth(context) {
+define_simple_executor!(repeatable PutSouth(context) {
     put_direction(context, Direction::South);
 });
 
 // -------------------------------------------------------------------------------------------------
 
 /// Sets west direction in command but do not execute.
-define_simple_executor!(PutWest(context) {
+define_simple_executor!(repeatable PutWest(context) {
     put_direction(context, Direction::West);
 });
 
 // -------------------------------------------------------------------------------------------------
 
 /// Sets forward direction in command but do not execute.
-define_simple_executor!(PutForward(context) {
+define_simple_executor!(repeatable PutForward(context) {
     put_direction(context, Direction::Forward);
 });
 
 // -------------------------------------------------------------------------------------------------
 
 /// Sets backward direction in command but do not execute.
-define_simple_executor!(PutBackward(context) {
+define_simple_executor!(repeatable PutBackward(context) {
     put_direction(context, Direction::Backward);
 });
 
@@ -331,8 +351,44 @@ define_simple_executor!(ToggleAnchorization(context) {
 
 // -------------------------------------------------------------------------------------------------
 
+/// Executes command toggling maximization.
+define_simple_executor!(ToggleMaximization(context) {
+    context.set_action(Action::Maximize);
+    context.set_direction(Direction::None);
+    context.execute_command();
+});
+
+// -------------------------------------------------------------------------------------------------
+
+/// Executes command toggling fullscreen.
+define_simple_executor!(ToggleFullscreen(context) {
+    context.set_action(Action::Fullscreen);
+    context.set_direction(Direction::None);
+    context.execute_command();
+});
+
+// -------------------------------------------------------------------------------------------------
+
+/// Executes command minimizing selected surface.
+define_simple_executor!(Minimize(context) {
+    context.set_action(Action::Minimize);
+    context.set_direction(Direction::None);
+    context.execute_command();
+});
+
+// -------------------------------------------------------------------------------------------------
+
+/// Executes command restoring most recently used minimized surface.
+define_simple_executor!(RestoreMinimized(context) {
+    context.set_action(Action::Restore);
+    context.set_direction(Direction::None);
+    context.execute_command();
+});
+
+// -------------------------------------------------------------------------------------------------
+
 /// Executes command for circling surfaces forward.
-define_simple_executor!(CicleHistoryForward(context) {
+define_simple_executor!(repeatable CicleHistoryForward(context) {
     context.set_action(Action::Focus);
     context.set_direction(Direction::Forward);
     context.set_magnitude(1);
@@ -342,7 +398,7 @@ define_simple_executor!(CicleHistoryForward(context) {
 // -------------------------------------------------------------------------------------------------
 
 /// Executes command for circling surfaces backward.
-define_simple_executor!(CicleHistoryBackward(context) {
+define_simple_executor!(repeatable CicleHistoryBackward(context) {
     context.set_action(Action::Focus);
     context.set_direction(Direction::Backward);
     context.set_magnitude(1);
@@ -352,7 +408,7 @@ define_simple_executor!(CicleHistoryBackward(context) {
 // -------------------------------------------------------------------------------------------------
 
 /// Executes command focusing surface on the right.
-define_simple_executor!(FocusRight(context) {
+define_simple_executor!(repeatable FocusRight(context) {
     context.set_action(Action::Focus);
     context.set_direction(Direction::East);
     context.set_magnitude(1);
@@ -362,7 +418,7 @@ define_simple_executor!(FocusRight(context) {
 // -------------------------------------------------------------------------------------------------
 
 /// Executes command focusing surface below.
-define_simple_executor!(FocusDown(context) {
+define_simple_executor!(repeatable FocusDown(context) {
     context.set_action(Action::Focus);
     context.set_direction(Direction::South);
     context.set_magnitude(1);
@@ -372,7 +428,7 @@ define_simple_executor!(FocusDown(context) {
 // -------------------------------------------------------------------------------------------------
 
 /// Executes command focusing surface on the left.
-define_simple_executor!(FocusLeft(context) {
+define_simple_executor!(repeatable FocusLeft(context) {
     context.set_action(Action::Focus);
     context.set_direction(Direction::West);
     context.set_magnitude(1);
@@ -382,7 +438,7 @@ define_simple_executor!(FocusLeft(context) {
 // -------------------------------------------------------------------------------------------------
 
 /// Executes command focusing surface above.
-define_simple_executor!(FocusUp(context) {
+define_simple_executor!(repeatable FocusUp(context) {
     context.set_action(Action::Focus);
     context.set_direction(Direction::North);
     context.set_magnitude(1);
@@ -392,7 +448,7 @@ define_simple_executor!(FocusUp(context) {
 // -------------------------------------------------------------------------------------------------
 
 /// Executes command jumping over surface on the right.
-define_simple_executor!(JumpRight(context) {
+define_simple_executor!(repeatable JumpRight(context) {
     context.set_action(Action::Jump);
     context.set_direction(Direction::East);
     context.set_magnitude(1);
@@ -402,7 +458,7 @@ define_simple_executor!(JumpRight(context) {
 // -------------------------------------------------------------------------------------------------
 
 /// Executes command jumping over surface below.
-define_simple_executor!(JumpDown(context) {
+define_simple_executor!(repeatable JumpDown(context) {
     context.set_action(Action::Jump);
     context.set_direction(Direction::South);
     context.set_magnitude(1);
@@ -412,7 +468,7 @@ define_simple_executor!(JumpDown(context) {
 // -------------------------------------------------------------------------------------------------
 
 /// Executes command jumping over surface on the left.
-define_simple_executor!(JumpLeft(context) {
+define_simple_executor!(repeatable JumpLeft(context) {
     context.set_action(Action::Jump);
     context.set_direction(Direction::West);
     context.set_magnitude(1);
@@ -422,7 +478,7 @@ define_simple_executor!(JumpLeft(context) {
 // -------------------------------------------------------------------------------------------------
 
 /// Executes command jumping over surface above.
-define_simple_executor!(JumpUp(context) {
+define_simple_executor!(repeatable JumpUp(context) {
     context.set_action(Action::Jump);
     context.set_direction(Direction::North);
     context.set_magnitude(1);
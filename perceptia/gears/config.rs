@@ -16,8 +16,8 @@ use yaml_rust;
 use serde_yaml;
 use serde::ser::{Serialize, Serializer, SerializeMap};
 
-use qualia::{Binding, Directories, Illusion, modifier};
-use qualia::{AestheticsConfig, KeyboardConfig, ExhibitorConfig, InputConfig};
+use qualia::{Binding, Directories, Illusion, Transform, modifier};
+use qualia::{AestheticsConfig, KeyboardConfig, ExhibitorConfig, InputConfig, OutputConfig};
 
 use config_defaults::DefaultConfig;
 use binding_functions;
@@ -210,6 +210,34 @@ impl Config {
                 choose_floating: String
             }
 
+            if let yaml_rust::yaml::Yaml::Hash(ref hash) = yaml["exhibitor"]["outputs"] {
+                for (name, section) in hash.iter() {
+                    if let Some(name) = name.as_str() {
+                        let mut output = self.exhibitor
+                            .outputs
+                            .get(name)
+                            .cloned()
+                            .unwrap_or(OutputConfig::default());
+                        load_config!{output; section;
+                            scale: u32
+                        }
+                        // Rotations may be given as plain numbers
+                        let transform = match section["transform"] {
+                            yaml_rust::yaml::Yaml::Integer(value) => Some(value.to_string()),
+                            yaml_rust::yaml::Yaml::String(ref value) => Some(value.clone()),
+                            _ => None,
+                        };
+                        if let Some(value) = transform {
+                            match Self::string_to_transform(&value) {
+                                Some(transform) => output.transform = transform,
+                                None => log_warn1!("Unknown output transform: {}", value),
+                            }
+                        }
+                        self.exhibitor.outputs.insert(name.to_owned(), output);
+                    }
+                }
+            }
+
             load_config!{self.input; yaml["input"];
                 touchpad_scale: f32,
                 touchpad_pressure_threshold: i32,
@@ -218,7 +246,9 @@ impl Config {
 
             load_config!{self.keyboard; yaml["keyboard"];
                 layout: String,
-                variant: String
+                variant: String,
+                repeat_rate: u32,
+                repeat_delay: u32
             }
 
             if let yaml_rust::yaml::Yaml::Array(ref array) = yaml["keybindings"]["insert"] {
@@ -366,6 +396,10 @@ impl Config {
             "verticalize" => binding_functions::Verticalize::new(),
             "stackize" => binding_functions::Stackize::new(),
             "toggle_anchorization" => binding_functions::ToggleAnchorization::new(),
+            "toggle_maximization" => binding_functions::ToggleMaximization::new(),
+            "toggle_fullscreen" => binding_functions::ToggleFullscreen::new(),
+            "minimize" => binding_functions::Minimize::new(),
+            "restore_minimized" => binding_functions::RestoreMinimized::new(),
             "cicle_history_forward" => binding_functions::CicleHistoryForward::new(),
             "cicle_history_backward" => binding_functions::CicleHistoryBackward::new(),
             "focus_right" => binding_functions::FocusRight::new(),
@@ -399,6 +433,26 @@ impl Config {
 
 // -------------------------------------------------------------------------------------------------
 
+// Helper methods for parsing outputs configuration
+impl Config {
+    /// Translates string to output transform.
+    fn string_to_transform(value: &str) -> Option<Transform> {
+        match value.to_ascii_lowercase().as_ref() {
+            "normal" => Some(Transform::Normal),
+            "90" => Some(Transform::Rotated90),
+            "180" => Some(Transform::Rotated180),
+            "270" => Some(Transform::Rotated270),
+            "flipped" => Some(Transform::Flipped),
+            "flipped_90" => Some(Transform::Flipped90),
+            "flipped_180" => Some(Transform::Flipped180),
+            "flipped_270" => Some(Transform::Flipped270),
+            _ => None,
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
 // Return immutable sections.
 impl Config {
     /// Returns config for aesthetics.
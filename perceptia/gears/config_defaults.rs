@@ -5,11 +5,13 @@
 
 // -------------------------------------------------------------------------------------------------
 
+use std::collections::HashMap;
+
 use uinput_sys;
 
-use qualia::modifier;
+use qualia::{modifier, Transform};
 pub use qualia::{AestheticsConfig, CompositorConfig, KeyboardConfig};
-pub use qualia::{ExhibitorConfig, InputConfig, StrategistConfig};
+pub use qualia::{ExhibitorConfig, InputConfig, OutputConfig, StrategistConfig};
 
 use config::{BindingEntry, Config, KeybindingsConfig};
 use binding_functions;
@@ -59,6 +61,7 @@ impl DefaultConfig for ExhibitorConfig {
         ExhibitorConfig {
             compositor: CompositorConfig::default(),
             strategist: StrategistConfig::default(),
+            outputs: HashMap::new(),
         }
     }
 }
@@ -336,6 +339,18 @@ impl DefaultConfig for KeybindingsConfig {
                      BindingEntry::new(uinput_sys::KEY_SPACE,
                                        modifier::LMTA,
                                        binding_functions::ToggleAnchorization::new()),
+                     BindingEntry::new(uinput_sys::KEY_M,
+                                       modifier::LMTA,
+                                       binding_functions::ToggleMaximization::new()),
+                     BindingEntry::new(uinput_sys::KEY_F,
+                                       modifier::LMTA,
+                                       binding_functions::ToggleFullscreen::new()),
+                     BindingEntry::new(uinput_sys::KEY_N,
+                                       modifier::LMTA,
+                                       binding_functions::Minimize::new()),
+                     BindingEntry::new(uinput_sys::KEY_N,
+                                       modifier::LMTA | modifier::LSHF,
+                                       binding_functions::RestoreMinimized::new()),
                      BindingEntry::new(uinput_sys::BTN_LEFT,
                                        modifier::LMTA,
                                        binding_functions::SurfaceDrag::new()),
@@ -361,6 +376,19 @@ impl DefaultConfig for KeyboardConfig {
         KeyboardConfig {
             layout: "us".to_owned(),
             variant: "".to_owned(),
+            repeat_rate: 25,
+            repeat_delay: 600,
+        }
+    }
+}
+
+// -------------------------------------------------------------------------------------------------
+
+impl DefaultConfig for OutputConfig {
+    fn default() -> Self {
+        OutputConfig {
+            scale: 1,
+            transform: Transform::Normal,
         }
     }
 }
@@ -11,14 +11,14 @@
 
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use uinput_sys;
 
-use dharma::Signaler;
+use dharma::{Signaler, TimerController};
 use qualia::{Action, Command, Direction, OptionalPosition, InteractionMode, Slide, Vector};
 use qualia::{modifier, Axis, Binding, Button, CatchResult, Key, InputCode, InputValue, KeyState};
-use qualia::{InputForwarding, InputHandling};
-use qualia::{perceptron, Perceptron, Milliseconds};
+use qualia::{InputForwarding, InputHandling, KeyboardConfig};
+use qualia::{perceptron, Perceptron, Milliseconds, Touch};
 
 use config::KeybindingsConfig;
 use binding_functions::{self, Executor};
@@ -87,6 +87,14 @@ impl Mode {
 
 // -------------------------------------------------------------------------------------------------
 
+/// Key binding being repeated while its key is held.
+struct Repeat {
+    code: InputCode,
+    binding: Binding,
+}
+
+// -------------------------------------------------------------------------------------------------
+
 /// This structure manages key bindings and modes.
 ///
 /// Main task is to identify key sequences as bindings and execute assigned function.
@@ -97,19 +105,30 @@ struct InnerInputManager {
     command: Command,
     previous_modification: binding_functions::PreviousModification,
     signaler: Signaler<Perceptron>,
+    repeat: Option<Repeat>,
+    repeat_delay: Duration,
+    repeat_interval: Duration,
+    repeat_timer: Option<TimerController>,
+    modifiers: modifier::ModifierType,
 }
 
 // -------------------------------------------------------------------------------------------------
 
 impl InnerInputManager {
     /// Constructs new `InnerInputManager`.
-    pub fn new(config: &KeybindingsConfig, signaler: Signaler<Perceptron>) -> Self {
+    pub fn new(config: &KeybindingsConfig,
+               keyboard_config: &KeyboardConfig,
+               signaler: Signaler<Perceptron>)
+               -> Self {
         // Create default modes
         let common_mode = Mode::new(true, mode_name::COMMON.to_owned(), None);
         let insert_mode = Mode::new(true, mode_name::INSERT.to_owned(), None);
         let normal_mode =
             Mode::new(false, mode_name::NORMAL.to_owned(), Some(binding_functions::Nop::new()));
 
+        // Zero rate disables repeating
+        let repeat_interval = (1000 / keyboard_config.repeat_rate.max(1)).max(1) as u64;
+
         // Create manager
         let mut inner = InnerInputManager {
             modes: vec![common_mode, insert_mode, normal_mode],
@@ -117,6 +136,11 @@ impl InnerInputManager {
             command: Command::default(),
             previous_modification: binding_functions::PreviousModification::None,
             signaler: signaler,
+            repeat: None,
+            repeat_delay: Duration::from_millis(keyboard_config.repeat_delay.max(1) as u64),
+            repeat_interval: Duration::from_millis(repeat_interval),
+            repeat_timer: None,
+            modifiers: modifier::NONE,
         };
 
         inner.apply_configuration(config);
@@ -173,9 +197,39 @@ impl InnerInputManager {
                      value: InputValue,
                      modifiers: modifier::ModifierType)
                      -> CatchResult {
+        self.modifiers = modifiers;
+        self.update_repeat(code, value, modifiers);
         self.catch(code, value, modifiers)
     }
 
+    /// Sets timer used for repeating key bindings.
+    pub fn set_repeat_timer(&mut self, timer: TimerController) {
+        self.repeat_timer = Some(timer);
+    }
+
+    /// Executes once again key binding which key is held for longer than repeat delay. Repeating
+    /// stops if modifiers changed since the key was pressed.
+    pub fn repeat(&mut self) {
+        let repeated = if let Some(ref repeat) = self.repeat {
+            if Binding::create(repeat.code, self.modifiers) == repeat.binding {
+                Some((repeat.code, repeat.binding.clone()))
+            } else {
+                None
+            }
+        } else {
+            None
+        };
+
+        if let Some((code, binding)) = repeated {
+            if let Some(executor) = self.find_executor(&binding) {
+                self.code = code;
+                executor.activate(self);
+            }
+        } else {
+            self.stop_repeat();
+        }
+    }
+
     /// Tries for find executor matching to given button and state of modifiers and execute it if
     /// found.
     fn catch_button(&mut self,
@@ -186,6 +240,45 @@ impl InnerInputManager {
         self.catch(code, value, modifiers)
     }
 
+    /// Helper method for starting repeating key binding when its key was pressed and stopping when
+    /// released. Pressing any other key stops repeating as well.
+    fn update_repeat(&mut self,
+                     code: InputCode,
+                     value: InputValue,
+                     modifiers: modifier::ModifierType) {
+        if value == KeyState::Pressed as InputValue {
+            let binding = Binding::create(code, modifiers);
+            let is_repeatable = match self.find_executor(&binding) {
+                Some(ref executor) => executor.is_repeatable(),
+                None => false,
+            };
+
+            if is_repeatable {
+                self.repeat = Some(Repeat {
+                                       code: code,
+                                       binding: binding,
+                                   });
+                if let Some(ref timer) = self.repeat_timer {
+                    timer.arm(self.repeat_delay, self.repeat_interval);
+                }
+            } else {
+                self.stop_repeat();
+            }
+        } else if value == KeyState::Released as InputValue {
+            if self.repeat.as_ref().map_or(false, |repeat| repeat.code == code) {
+                self.stop_repeat();
+            }
+        }
+    }
+
+    /// Helper method for stopping repeating key binding.
+    fn stop_repeat(&mut self) {
+        self.repeat = None;
+        if let Some(ref timer) = self.repeat_timer {
+            timer.disarm();
+        }
+    }
+
     /// Helper method for searching and executing an executor.
     fn catch(&mut self,
              code: InputCode,
@@ -348,8 +441,24 @@ pub struct InputManager {
 
 impl InputManager {
     /// Constructs new `InputManager`.
-    pub fn new(config: &KeybindingsConfig, signaler: Signaler<Perceptron>) -> Self {
-        InputManager { inner: Arc::new(Mutex::new(InnerInputManager::new(config, signaler))) }
+    pub fn new(config: &KeybindingsConfig,
+               keyboard_config: &KeyboardConfig,
+               signaler: Signaler<Perceptron>)
+               -> Self {
+        let inner = InnerInputManager::new(config, keyboard_config, signaler);
+        InputManager { inner: Arc::new(Mutex::new(inner)) }
+    }
+
+    /// Lock and call corresponding method from `InnerInputManager`.
+    pub fn set_repeat_timer(&mut self, timer: TimerController) {
+        let mut mine = self.inner.lock().unwrap();
+        mine.set_repeat_timer(timer)
+    }
+
+    /// Lock and call corresponding method from `InnerInputManager`.
+    pub fn repeat(&mut self) {
+        let mut mine = self.inner.lock().unwrap();
+        mine.repeat()
     }
 
     /// Lock and call corresponding method from `InnerInputManager`.
@@ -473,10 +582,38 @@ impl InputForwarding for InputForwarder {
                            Perceptron::InputPointerPositionReset);
     }
 
+    /// Emits touch down event.
+    fn emit_touch_down(&mut self, id: i32, x: f32, y: f32) {
+        let touch = Touch::new(id, Slide::new(x, y), self.get_timestamp());
+        self.signaler.emit(perceptron::INPUT_TOUCH_DOWN, Perceptron::InputTouchDown(touch));
+    }
+
+    /// Emits touch motion event.
+    fn emit_touch_motion(&mut self, id: i32, x: f32, y: f32) {
+        let touch = Touch::new(id, Slide::new(x, y), self.get_timestamp());
+        self.signaler.emit(perceptron::INPUT_TOUCH_MOTION, Perceptron::InputTouchMotion(touch));
+    }
+
+    /// Emits touch up event.
+    fn emit_touch_up(&mut self, id: i32) {
+        self.signaler.emit(perceptron::INPUT_TOUCH_UP,
+                           Perceptron::InputTouchUp(id, self.get_timestamp()));
+    }
+
+    /// Emits touch frame event.
+    fn emit_touch_frame(&mut self) {
+        self.signaler.emit(perceptron::INPUT_TOUCH_FRAME, Perceptron::InputTouchFrame);
+    }
+
     /// Emits system activity event.
     fn emit_system_activity_event(&mut self) {
         self.signaler.emit(perceptron::NOTIFY, Perceptron::Notify);
     }
+
+    /// Emits user activity event.
+    fn emit_user_activity_event(&mut self) {
+        self.signaler.emit(perceptron::SYSTEM_ACTIVITY, Perceptron::SystemActivity);
+    }
 }
 
 // -------------------------------------------------------------------------------------------------
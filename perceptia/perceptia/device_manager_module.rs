@@ -5,7 +5,9 @@
 
 // -------------------------------------------------------------------------------------------------
 
-use dharma::{Module, ModuleConstructor, SignalId};
+use std::time::Duration;
+
+use dharma::{self, Module, ModuleConstructor, SignalId};
 use qualia::{Perceptron, perceptron};
 use coordination::{Context, Coordinator};
 use gears::{InputManager, InputForwarder};
@@ -24,10 +26,26 @@ impl DeviceManagerModule {
     pub fn new(context: &mut Context) -> Self {
         let coordinator = context.get_coordinator().clone();
         let signaler = context.get_signaler().clone();
-        let config = context.get_config();
+        let config = context.get_config().clone();
+        let keyboard_config = config.get_keyboard_config();
 
         // Construct `InputManager` implementing `InputHandling`.
-        let input_manager = InputManager::new(config.get_keybindings_config(), signaler.clone());
+        let mut input_manager = InputManager::new(config.get_keybindings_config(),
+                                                  keyboard_config,
+                                                  signaler.clone());
+
+        // Set up timer for repeating key bindings. It is armed only while repeatable binding is
+        // held.
+        if keyboard_config.repeat_rate > 0 {
+            let mut repeater = input_manager.clone();
+            let timer = dharma::Timer::new(Duration::from_millis(1000), move || {
+                repeater.repeat();
+            }).expect("creating key repeat timer");
+            let controller = timer.get_controller();
+            controller.disarm();
+            input_manager.set_repeat_timer(controller);
+            context.add_event_handler(Box::new(timer), dharma::event_kind::READ);
+        }
 
         // Construct `InputForwarder` implementing `InputForwarding`.
         let input_forwarder = InputForwarder::new(signaler, context.get_reference_time());
@@ -6,7 +6,7 @@
 // -------------------------------------------------------------------------------------------------
 
 use dharma::{Module, ModuleConstructor, SignalId};
-use qualia::{DrmBundle, perceptron, Perceptron};
+use qualia::{DrmBundle, ExhibitorConfig, perceptron, Perceptron};
 use outputs::DrmOutput;
 use coordination::{Context, Coordinator};
 use exhibitor::{Exhibitor, Strategist};
@@ -17,6 +17,7 @@ use exhibitor::{Exhibitor, Strategist};
 pub struct ExhibitorModule {
     last_output_id: i32,
     exhibitor: Exhibitor<Coordinator>,
+    config: ExhibitorConfig,
 }
 
 // -------------------------------------------------------------------------------------------------
@@ -32,6 +33,7 @@ impl ExhibitorModule {
                                       context.get_reference_time(),
                                       Strategist::new_from_config(config.strategist.clone()),
                                       config.compositor.clone()),
+            config: config.clone(),
         }
     }
 }
@@ -54,11 +56,23 @@ impl Module for ExhibitorModule {
              perceptron::INPUT_POINTER_POSITION,
              perceptron::INPUT_POINTER_BUTTON,
              perceptron::INPUT_POINTER_POSITION_RESET,
+             perceptron::INPUT_TOUCH_DOWN,
+             perceptron::INPUT_TOUCH_MOTION,
+             perceptron::INPUT_TOUCH_UP,
+             perceptron::INPUT_TOUCH_FRAME,
              perceptron::CURSOR_SURFACE_CHANGE,
              perceptron::BACKGROUND_SURFACE_CHANGE,
+             perceptron::DRAG_ICON_SURFACE_CHANGE,
              perceptron::SURFACE_READY,
              perceptron::DOCK_SURFACE,
+             perceptron::LAYER_SURFACE,
+             perceptron::SURFACE_MOVE_REQUESTED,
+             perceptron::SURFACE_RESIZE_REQUESTED,
+             perceptron::SURFACE_MAXIMIZATION_REQUESTED,
+             perceptron::SURFACE_FULLSCREEN_REQUESTED,
+             perceptron::SURFACE_MINIMIZATION_REQUESTED,
              perceptron::SURFACE_DESTROYED,
+             perceptron::SURFACE_DESCRIPTION_CHANGED,
              perceptron::KEYBOARD_FOCUS_CHANGED,
              perceptron::TAKE_SCREENSHOT]
     }
@@ -71,7 +85,7 @@ impl Module for ExhibitorModule {
         match *package {
             Perceptron::Notify => self.exhibitor.on_notify(),
             Perceptron::OutputFound(ref bundle) => self.on_output_found(bundle.clone()),
-            Perceptron::PageFlip(id) => self.exhibitor.on_pageflip(id),
+            Perceptron::PageFlip(id, time) => self.exhibitor.on_pageflip(id, time),
             Perceptron::Command(ref command) => self.exhibitor.on_command(command.clone()),
 
             Perceptron::InputPointerMotion(ref vector) => self.exhibitor.on_motion(vector.clone()),
@@ -79,13 +93,42 @@ impl Module for ExhibitorModule {
             Perceptron::InputPointerButton(ref btn) => self.exhibitor.on_button(btn.clone()),
             Perceptron::InputPointerPositionReset => self.exhibitor.on_position_reset(),
 
+            Perceptron::InputTouchDown(ref touch) => self.exhibitor.on_touch_down(touch.clone()),
+            Perceptron::InputTouchMotion(ref touch) => {
+                self.exhibitor.on_touch_motion(touch.clone());
+            }
+            Perceptron::InputTouchUp(id, time) => self.exhibitor.on_touch_up(id, time),
+            Perceptron::InputTouchFrame => self.exhibitor.on_touch_frame(),
+
             Perceptron::CursorSurfaceChange(sid) => self.exhibitor.on_cursor_surface_change(sid),
+            Perceptron::DragIconSurfaceChange(sid) => {
+                self.exhibitor.on_drag_icon_surface_change(sid);
+            }
 
             Perceptron::SurfaceReady(sid) => self.exhibitor.on_surface_ready(sid),
             Perceptron::DockSurface(sid, size, display_id) => {
                 self.exhibitor.on_dock_surface(sid, size, display_id);
             }
+            Perceptron::LayerSurface(sid, properties, display_id) => {
+                self.exhibitor.on_layer_surface(sid, properties, display_id);
+            }
+            Perceptron::SurfaceMoveRequested(sid) => self.exhibitor.on_surface_move_requested(sid),
+            Perceptron::SurfaceResizeRequested(sid, edges) => {
+                self.exhibitor.on_surface_resize_requested(sid, edges);
+            }
+            Perceptron::SurfaceMaximizationRequested(sid, maximized) => {
+                self.exhibitor.on_surface_maximization_requested(sid, maximized);
+            }
+            Perceptron::SurfaceFullscreenRequested(sid, fullscreen, display_id) => {
+                self.exhibitor.on_surface_fullscreen_requested(sid, fullscreen, display_id);
+            }
+            Perceptron::SurfaceMinimizationRequested(sid) => {
+                self.exhibitor.on_surface_minimization_requested(sid);
+            }
             Perceptron::SurfaceDestroyed(sid) => self.exhibitor.on_surface_destroyed(sid),
+            Perceptron::SurfaceDescriptionChanged(sid) => {
+                self.exhibitor.on_surface_description_changed(sid);
+            }
 
             Perceptron::KeyboardFocusChanged(_, sid) => {
                 self.exhibitor.on_keyboard_focus_changed(sid);
@@ -120,8 +163,13 @@ impl ExhibitorModule {
     fn on_output_found(&mut self, bundle: DrmBundle) {
         self.last_output_id += 1;
         match DrmOutput::new(bundle, self.last_output_id) {
-            Ok(output) => {
-                log_info2!("Created output: {}", output.get_info().make);
+            Ok(mut output) => {
+                let name = output.get_info().name;
+                log_info2!("Created output: {}", name);
+                if let Some(config) = self.config.outputs.get(&name) {
+                    output.set_scale(config.scale);
+                    output.set_transform(config.transform);
+                }
                 self.exhibitor.on_output_found(output);
             }
             Err(err) => {
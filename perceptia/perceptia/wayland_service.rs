@@ -71,17 +71,30 @@ impl WaylandService {
         // Subscribe for signals
         self.context.get_signaler().register(&self.receiver);
         for s in vec![perceptron::DISPLAY_CREATED,
+                      perceptron::DISPLAY_UPDATED,
                       perceptron::OUTPUT_FOUND,
                       perceptron::INPUT_KEYBOARD,
                       perceptron::INPUT_POINTER_BUTTON,
                       perceptron::INPUT_POINTER_AXIS,
                       perceptron::SURFACE_FRAME,
+                      perceptron::SURFACE_PRESENTED,
+                      perceptron::SURFACE_DISCARDED,
                       perceptron::POINTER_FOCUS_CHANGED,
                       perceptron::POINTER_RELATIVE_MOTION,
+                      perceptron::POINTER_DELTA,
+                      perceptron::POINTER_CONSTRAINT_CHANGED,
+                      perceptron::SYSTEM_ACTIVITY,
+                      perceptron::TIMER_500,
+                      perceptron::TOUCH_DOWN,
+                      perceptron::TOUCH_MOTION,
+                      perceptron::TOUCH_UP,
+                      perceptron::TOUCH_FRAME,
                       perceptron::KEYBOARD_FOCUS_CHANGED,
                       perceptron::SURFACE_RECONFIGURED,
                       perceptron::TRANSFER_OFFERED,
                       perceptron::TRANSFER_REQUESTED,
+                      perceptron::PRIMARY_TRANSFER_OFFERED,
+                      perceptron::PRIMARY_TRANSFER_REQUESTED,
                       perceptron::SCREENSHOT_DONE] {
             self.context.get_signaler().subscribe(s, &self.receiver);
         }
@@ -149,6 +162,9 @@ impl WaylandService {
             Perceptron::DisplayCreated(info) => {
                 self.engine.on_display_created(info);
             }
+            Perceptron::DisplayUpdated(info) => {
+                self.engine.on_display_updated(info);
+            }
             Perceptron::InputKeyboard(key) => {
                 self.engine.on_keyboard_input(key, None);
             }
@@ -161,12 +177,38 @@ impl WaylandService {
             Perceptron::SurfaceFrame(sid, milliseconds) => {
                 self.engine.on_surface_frame(sid, milliseconds);
             }
+            Perceptron::SurfacePresented(sid, display_id, time) => {
+                self.engine.on_surface_presented(sid, display_id, time);
+            }
+            Perceptron::SurfaceDiscarded(sid) => {
+                self.engine.on_surface_discarded(sid);
+            }
             Perceptron::PointerFocusChanged(old_sid, new_sid, pos) => {
                 self.engine.on_pointer_focus_changed(old_sid, new_sid, pos);
             }
             Perceptron::PointerRelativeMotion(sid, pos, time) => {
                 self.engine.on_pointer_relative_motion(sid, pos, time);
             }
+            Perceptron::PointerDelta(sid, vector, time) => {
+                self.engine.on_pointer_delta(sid, vector, time);
+            }
+            Perceptron::PointerConstraintChanged(sid, is_active) => {
+                self.engine.on_pointer_constraint_changed(sid, is_active);
+            }
+            Perceptron::SystemActivity => self.engine.on_system_activity(),
+            Perceptron::Timer500 => self.engine.on_timer_500(),
+            Perceptron::TouchDown(sid, id, pos, time) => {
+                self.engine.on_touch_down(sid, id, pos, time);
+            }
+            Perceptron::TouchMotion(sid, id, pos, time) => {
+                self.engine.on_touch_motion(sid, id, pos, time);
+            }
+            Perceptron::TouchUp(sid, id, time) => {
+                self.engine.on_touch_up(sid, id, time);
+            }
+            Perceptron::TouchFrame => {
+                self.engine.on_touch_frame();
+            }
             Perceptron::KeyboardFocusChanged(old_sid, new_sid) => {
                 self.engine.on_keyboard_focus_changed(old_sid, new_sid);
             }
@@ -176,6 +218,12 @@ impl WaylandService {
             Perceptron::TransferRequested(mime_type, fd) => {
                 self.engine.on_transfer_requested(mime_type, fd);
             }
+            Perceptron::PrimaryTransferOffered => {
+                self.engine.on_primary_transfer_offered();
+            }
+            Perceptron::PrimaryTransferRequested(mime_type, fd) => {
+                self.engine.on_primary_transfer_requested(mime_type, fd);
+            }
             Perceptron::SurfaceReconfigured(sid) => {
                 if let Some(info) = self.context.get_coordinator().get_surface(sid) {
                     self.engine.on_surface_reconfigured(sid, info.desired_size, info.state_flags);
@@ -82,14 +82,17 @@ impl wl_output::Interface for Output {
     fn mode(&mut self,
             _this_object_id: ObjectId,
             _bundle: &mut Bundle,
-            _flags: u32,
+            flags: u32,
             width: i32,
             height: i32,
             refresh: i32)
             -> Task {
-        self.width = width as usize;
-        self.height = height as usize;
-        self.refresh_rate = refresh as usize;
+        // Output may advertise many modes. Only the current one is interesting.
+        if (flags & wl_output::mode::CURRENT) != 0 {
+            self.width = width as usize;
+            self.height = height as usize;
+            self.refresh_rate = refresh as usize;
+        }
         Task::None
     }
 